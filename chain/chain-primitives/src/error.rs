@@ -4,7 +4,7 @@ use chrono::DateTime;
 use near_primitives::time::Utc;
 
 use near_primitives::block::BlockValidityError;
-use near_primitives::challenge::{ChunkProofs, ChunkState};
+use near_primitives::challenge::{ChallengeBody, ChunkProofs};
 use near_primitives::errors::{EpochError, StorageError};
 use near_primitives::shard_layout::ShardLayoutError;
 use near_primitives::sharding::{ChunkHash, ShardChunkHeader};
@@ -116,6 +116,9 @@ pub enum Error {
     /// Invalid Challenge Root (doesn't match actual challenge)
     #[error("Invalid Challenge Root")]
     InvalidChallengeRoot,
+    /// Challenges result in the header doesn't match the challenges of the previous block.
+    #[error("Invalid Challenges Result")]
+    InvalidChallengesResult,
     /// Invalid challenge (wrong signature or format).
     #[error("Invalid Challenge")]
     InvalidChallenge,
@@ -131,9 +134,9 @@ pub enum Error {
     /// One of the chunks has invalid proofs
     #[error("Invalid Chunk Proofs")]
     InvalidChunkProofs(Box<ChunkProofs>),
-    /// Invalid chunk state.
+    /// Invalid chunk state, with the body of the challenge which proves it.
     #[error("Invalid Chunk State")]
-    InvalidChunkState(Box<ChunkState>),
+    InvalidChunkState(Box<ChallengeBody>),
     /// Invalid chunk mask
     #[error("Invalid Chunk Mask")]
     InvalidChunkMask,
@@ -188,6 +191,9 @@ pub enum Error {
     /// Invalid block merkle root.
     #[error("Invalid Block Merkle Root")]
     InvalidBlockMerkleRoot,
+    /// Epoch sync data doesn't match the headers it was requested for.
+    #[error("Invalid Epoch Sync Data")]
+    InvalidEpochSyncData,
     /// Someone is not a validator. Usually happens in signature verification
     #[error("Not A Validator")]
     NotAValidator,
@@ -270,6 +276,7 @@ impl Error {
             | Error::InvalidReceiptsProof
             | Error::InvalidStatePayload
             | Error::InvalidTransactions
+            | Error::InvalidChallengesResult
             | Error::InvalidChallenge
             | Error::MaliciousChallenge
             | Error::IncorrectNumberOfChunkHeaders
@@ -288,6 +295,7 @@ impl Error {
             | Error::InvalidStateRequest(_)
             | Error::InvalidRandomnessBeaconOutput
             | Error::InvalidBlockMerkleRoot
+            | Error::InvalidEpochSyncData
             | Error::InvalidProtocolVersion
             | Error::NotAValidator
             | Error::InvalidChallengeRoot => true,
@@ -18,12 +18,16 @@ use near_chain_primitives::error::{BlockKnownError, Error, LogTransientStorageEr
 use near_primitives::block::{genesis_chunks, Tip};
 use near_primitives::challenge::{
     BlockDoubleSign, Challenge, ChallengeBody, ChallengesResult, ChunkProofs, ChunkState,
-    MaybeEncodedShardChunk, PartialState, SlashedValidator,
+    ChunkStateIncomingReceipts, ChunkStateV2, MaybeEncodedShardChunk, PartialState,
+    SlashedValidator,
 };
 use near_primitives::checked_feature;
-use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::epoch_manager::block_info::BlockInfo;
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
+use near_primitives::hash::{hash, CryptoHash, HashAlgorithm};
 use near_primitives::merkle::{
-    combine_hash, merklize, verify_path, Direction, MerklePath, MerklePathItem, PartialMerkleTree,
+    combine_hash_with, merkle_hash_algorithm, merklize, merklize_with, verify_path,
+    verify_path_with, Direction, MerklePath, MerklePathItem, PartialMerkleTree,
 };
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{
@@ -32,8 +36,9 @@ use near_primitives::sharding::{
 };
 use near_primitives::state_part::PartId;
 use near_primitives::syncing::{
-    get_num_state_parts, ReceiptProofResponse, RootProof, ShardStateSyncResponseHeader,
-    ShardStateSyncResponseHeaderV1, ShardStateSyncResponseHeaderV2, StateHeaderKey, StatePartKey,
+    get_num_state_parts, EpochSyncFinalizationResponse, EpochSyncResponse, ReceiptProofResponse,
+    RootProof, ShardStateSyncResponseHeader, ShardStateSyncResponseHeaderV1,
+    ShardStateSyncResponseHeaderV2, StateHeaderKey, StatePartKey,
 };
 use near_primitives::transaction::{
     ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof, SignedTransaction,
@@ -59,7 +64,7 @@ use crate::block_processing_utils::{
 };
 use crate::blocks_delay_tracker::BlocksDelayTracker;
 use crate::crypto_hash_timer::CryptoHashTimer;
-use crate::lightclient::get_epoch_block_producers_view;
+use crate::lightclient::{get_epoch_block_producers_view, light_client_block_hash};
 use crate::migrations::check_if_block_is_first_with_chunk_of_version;
 use crate::missing_chunks::{BlockLike, MissingChunksPool};
 use crate::store::{ChainStore, ChainStoreAccess, ChainStoreUpdate, GCMode};
@@ -587,6 +592,7 @@ impl Chain {
                         genesis.header(),
                         // genesis height is considered final
                         chain_genesis.height,
+                        chain_genesis.protocol_version,
                     ),
                 )?);
                 store_update.save_block_header(genesis.header().clone())?;
@@ -739,6 +745,154 @@ impl Chain {
         create_light_client_block_view(&final_block_header, chain_store, Some(next_block_producers))
     }
 
+    /// Returns the light client block of the last final block of `epoch_id` for Epoch Sync,
+    /// or `UpToDate` if `epoch_id` is the epoch of the head.
+    pub fn get_epoch_sync_response(&self, epoch_id: &EpochId) -> Result<EpochSyncResponse, Error> {
+        match self.store.get_epoch_light_client_block(&epoch_id.0) {
+            Ok(light_client_block_view) => Ok(EpochSyncResponse::Advance {
+                light_client_block_view: LightClientBlockView::clone(&light_client_block_view),
+            }),
+            Err(Error::DBNotFoundErr(_)) if &self.head()?.epoch_id == epoch_id => {
+                Ok(EpochSyncResponse::UpToDate)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the data necessary to finish Epoch Sync at the first block of `epoch_id`.
+    pub fn get_epoch_sync_finalization_response(
+        &self,
+        epoch_id: &EpochId,
+    ) -> Result<EpochSyncFinalizationResponse, Error> {
+        let block_in_epoch = match self.store.get_epoch_light_client_block(&epoch_id.0) {
+            Ok(light_client_block) => light_client_block_hash(&light_client_block),
+            Err(Error::DBNotFoundErr(_)) => {
+                let head = self.head()?;
+                if &head.epoch_id != epoch_id {
+                    return Err(Error::EpochOutOfBounds(epoch_id.clone()));
+                }
+                head.last_block_hash
+            }
+            Err(err) => return Err(err),
+        };
+        let epoch_start_height = self.runtime_adapter.get_epoch_start_height(&block_in_epoch)?;
+        let cur_epoch_header = self.get_block_header_by_height(epoch_start_height)?;
+        if cur_epoch_header.epoch_id() != epoch_id
+            || cur_epoch_header.height() == self.genesis.header().height()
+        {
+            return Err(Error::EpochOutOfBounds(epoch_id.clone()));
+        }
+
+        // Collect the headers from the last final block of the previous epoch, which the
+        // syncing node knows from its light client block, up to the last block of the epoch.
+        let prev_epoch_last_header = self.get_previous_header(&cur_epoch_header)?;
+        let prev_epoch_light_client_block =
+            self.store.get_epoch_light_client_block(&prev_epoch_last_header.epoch_id().0)?;
+        let prev_epoch_final_hash = light_client_block_hash(&prev_epoch_light_client_block);
+        let mut prev_epoch_headers = vec![prev_epoch_last_header.clone()];
+        while prev_epoch_headers.last().unwrap().hash() != &prev_epoch_final_hash {
+            let header = self.get_previous_header(prev_epoch_headers.last().unwrap())?;
+            if header.height() < prev_epoch_light_client_block.inner_lite.height {
+                return Err(Error::Other(format!(
+                    "light client block of epoch {:?} is not on the canonical chain",
+                    prev_epoch_last_header.epoch_id()
+                )));
+            }
+            prev_epoch_headers.push(header);
+        }
+        prev_epoch_headers.reverse();
+
+        let (
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            prev_epoch_info,
+            cur_epoch_info,
+            next_epoch_info,
+        ) = self.runtime_adapter.get_epoch_sync_data(
+            prev_epoch_last_header.hash(),
+            epoch_id,
+            cur_epoch_header.next_epoch_id(),
+        )?;
+        Ok(EpochSyncFinalizationResponse {
+            prev_epoch_last_header_tree: PartialMerkleTree::clone(
+                &self.store.get_block_merkle_tree(prev_epoch_last_header.hash())?,
+            ),
+            cur_epoch_header,
+            prev_epoch_headers,
+            prev_epoch_first_block_info: BlockInfo::clone(&prev_epoch_first_block_info),
+            prev_epoch_prev_last_block_info: BlockInfo::clone(&prev_epoch_prev_last_block_info),
+            prev_epoch_last_block_info: BlockInfo::clone(&prev_epoch_last_block_info),
+            prev_epoch_info: EpochInfo::clone(&prev_epoch_info),
+            cur_epoch_info: EpochInfo::clone(&cur_epoch_info),
+            next_epoch_info: EpochInfo::clone(&next_epoch_info),
+        })
+    }
+
+    /// Saves the headers, block merkle trees and epoch data received at the end of Epoch Sync
+    /// and sets the header head to the first block of the epoch synced to, so that Header Sync
+    /// continues from there.
+    /// Validity of the headers and epoch data is checked by Epoch Sync methods, except for the
+    /// block merkle tree which is checked here.
+    pub fn finalize_epoch_sync(
+        &mut self,
+        response: EpochSyncFinalizationResponse,
+    ) -> Result<(), Error> {
+        let EpochSyncFinalizationResponse {
+            cur_epoch_header,
+            prev_epoch_headers,
+            prev_epoch_last_header_tree,
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            prev_epoch_info,
+            cur_epoch_info,
+            next_epoch_info,
+        } = response;
+        let prev_epoch_last_hash = *cur_epoch_header.prev_hash();
+        let hash_algorithm = merkle_hash_algorithm(cur_epoch_info.protocol_version());
+        let mut cur_epoch_header_tree = prev_epoch_last_header_tree.clone();
+        cur_epoch_header_tree.insert_with(hash_algorithm, prev_epoch_last_hash);
+        if &cur_epoch_header_tree.root_with(hash_algorithm) != cur_epoch_header.block_merkle_root()
+        {
+            return Err(Error::InvalidBlockMerkleRoot);
+        }
+
+        let prev_epoch_id = prev_epoch_last_block_info.epoch_id().clone();
+        self.runtime_adapter.epoch_sync_init_epoch_manager(
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            &prev_epoch_id,
+            prev_epoch_info,
+            cur_epoch_header.epoch_id(),
+            cur_epoch_info,
+            cur_epoch_header.next_epoch_id(),
+            next_epoch_info,
+        )?;
+
+        let mut chain_store_update = self.store.store_update();
+        for header in prev_epoch_headers {
+            chain_store_update.save_block_header_no_update_tree(header)?;
+        }
+        chain_store_update
+            .save_block_merkle_tree(prev_epoch_last_hash, prev_epoch_last_header_tree);
+        chain_store_update.save_block_merkle_tree(*cur_epoch_header.hash(), cur_epoch_header_tree);
+        chain_store_update.save_block_header_no_update_tree(cur_epoch_header.clone())?;
+        chain_store_update.force_save_header_head(&Tip::from_header(&cur_epoch_header))?;
+
+        // Record the first block of the epoch in the epoch manager as Header Sync would.
+        let last_finalized_height =
+            chain_store_update.get_block_height(cur_epoch_header.last_final_block())?;
+        let epoch_protocol_version =
+            self.runtime_adapter.get_epoch_protocol_version(cur_epoch_header.epoch_id())?;
+        let epoch_manager_update = self.runtime_adapter.add_validator_proposals(
+            BlockHeaderInfo::new(&cur_epoch_header, last_finalized_height, epoch_protocol_version),
+        )?;
+        chain_store_update.merge(epoch_manager_update);
+        chain_store_update.commit()
+    }
+
     pub fn save_block(&mut self, block: MaybeValidated<Block>) -> Result<(), Error> {
         if self.store.get_block(block.hash()).is_ok() {
             return Ok(());
@@ -1104,7 +1258,9 @@ impl Chain {
                 }
             }
         }
-        block.check_validity().map_err(|e| e.into())
+        let protocol_version =
+            runtime_adapter.get_epoch_protocol_version(block.header().epoch_id())?;
+        block.check_validity(protocol_version).map_err(|e| e.into())
     }
 
     /// Verify header signature when the epoch is known, but not the whole chain.
@@ -1313,16 +1469,24 @@ impl Chain {
                 return Err(Error::InvalidFinalityInfo);
             }
 
+            let protocol_version =
+                self.runtime_adapter.get_epoch_protocol_version(header.epoch_id())?;
+            let hash_algorithm = merkle_hash_algorithm(protocol_version);
             let block_merkle_tree = self.store.get_block_merkle_tree(header.prev_hash())?;
             let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-            block_merkle_tree.insert(*header.prev_hash());
-            if &block_merkle_tree.root() != header.block_merkle_root() {
+            block_merkle_tree.insert_with(hash_algorithm, *header.prev_hash());
+            if &block_merkle_tree.root_with(hash_algorithm) != header.block_merkle_root() {
                 return Err(Error::InvalidBlockMerkleRoot);
             }
 
-            // Check that challenges root is empty to ensure later that block doesn't contain challenges.
-            // TODO (#2445): Enable challenges when they are working correctly.
-            if header.challenges_root() != &MerkleHash::default() {
+            // Check that challenges root is empty to ensure later that block doesn't contain
+            // challenges, unless they are enabled.
+            if !checked_feature!(
+                "protocol_feature_chunk_state_challenges",
+                ChunkStateChallenges,
+                protocol_version
+            ) && header.challenges_root() != &MerkleHash::default()
+            {
                 return Err(Error::InvalidChallengeRoot);
             }
         }
@@ -1580,7 +1744,7 @@ impl Chain {
         Ok(receipt_proofs_by_shard_id)
     }
 
-    fn shuffle_receipt_proofs<ReceiptProofType>(
+    pub(crate) fn shuffle_receipt_proofs<ReceiptProofType>(
         receipt_proofs: &mut Vec<ReceiptProofType>,
         block_hash: &CryptoHash,
     ) {
@@ -1738,14 +1902,19 @@ impl Chain {
 
                 self.validate_header(header, &Provenance::SYNC, challenges)?;
                 let mut chain_update = self.chain_update();
-                chain_update.chain_store_update.save_block_header(header.clone())?;
+                let epoch_protocol_version =
+                    chain_update.runtime_adapter.get_epoch_protocol_version(header.epoch_id())?;
+                chain_update.chain_store_update.save_block_header_with_merkle_hash_algorithm(
+                    header.clone(),
+                    merkle_hash_algorithm(epoch_protocol_version),
+                )?;
 
                 // Add validator proposals for given header.
                 let last_finalized_height =
                     chain_update.chain_store_update.get_block_height(header.last_final_block())?;
-                let epoch_manager_update = chain_update
-                    .runtime_adapter
-                    .add_validator_proposals(BlockHeaderInfo::new(header, last_finalized_height))?;
+                let epoch_manager_update = chain_update.runtime_adapter.add_validator_proposals(
+                    BlockHeaderInfo::new(header, last_finalized_height, epoch_protocol_version),
+                )?;
                 chain_update.chain_store_update.merge(epoch_manager_update);
                 chain_update.commit()?;
             }
@@ -2282,7 +2451,9 @@ impl Chain {
             if !self.partial_verify_orphan_header_signature(block.header())? {
                 return Err(Error::InvalidSignature);
             }
-            block.check_validity()?;
+            let protocol_version =
+                self.runtime_adapter.get_epoch_protocol_version(block.header().epoch_id())?;
+            block.check_validity(protocol_version)?;
             // TODO: enable after #3729 and #3863
             // self.verify_orphan_header_approvals(&block.header())?;
             return Err(Error::Orphan);
@@ -2370,6 +2541,18 @@ impl Chain {
             block.header().epoch_id(),
             block.header().prev_hash(),
         )?;
+        // Validators are slashed for the challenges of the previous block, so that the result of
+        // the challenges is known before the chunks of the block are produced.
+        if checked_feature!(
+            "protocol_feature_chunk_state_challenges",
+            ChunkStateChallenges,
+            protocol_version
+        ) && block.header().challenges_result()
+            != &self.get_block_extra(&prev_hash)?.challenges_result
+        {
+            byzantine_assert!(false);
+            return Err(Error::InvalidChallengesResult);
+        }
 
         let prev_block = self.get_block(&prev_hash)?;
 
@@ -2732,7 +2915,11 @@ impl Chain {
             let ReceiptProofResponse(block_hash, receipt_proofs) = receipt_response;
             let block_header = self.get_block_header(block_hash)?.clone();
             let block = self.get_block(block_hash)?;
-            let (block_receipts_root, block_receipts_proofs) = merklize(
+            let hash_algorithm = merkle_hash_algorithm(
+                self.runtime_adapter.get_epoch_protocol_version(block_header.epoch_id())?,
+            );
+            let (block_receipts_root, block_receipts_proofs) = merklize_with(
+                hash_algorithm,
                 &block
                     .chunks()
                     .iter()
@@ -2754,8 +2941,9 @@ impl Chain {
 
                 // Make sure we send something reasonable.
                 assert_eq!(block_header.chunk_receipts_root(), &block_receipts_root);
-                assert!(verify_path(root_proof, proof, &receipts_hash));
-                assert!(verify_path(
+                assert!(verify_path_with(hash_algorithm, root_proof, proof, &receipts_hash));
+                assert!(verify_path_with(
+                    hash_algorithm,
                     block_receipts_root,
                     &block_receipts_proofs[from_shard_id],
                     &root_proof,
@@ -2962,6 +3150,11 @@ impl Chain {
             hash_to_compare = *header.prev_hash();
 
             let block_header = self.get_block_header(block_hash)?;
+            // Receipts proofs only come from chunks included at this height, so
+            // both the chunk and the block trees use the block's hash function.
+            let hash_algorithm = merkle_hash_algorithm(
+                self.runtime_adapter.get_epoch_protocol_version(block_header.epoch_id())?,
+            );
             // 4c. Checking len of receipt_proofs for current block
             if receipt_proofs.len() != shard_state_header.root_proofs()[i].len()
                 || receipt_proofs.len() != block_header.chunks_included() as usize
@@ -2989,12 +3182,17 @@ impl Chain {
                 let RootProof(root, block_proof) = &shard_state_header.root_proofs()[i][j];
                 let receipts_hash = CryptoHash::hash_borsh(ReceiptList(shard_id, receipts));
                 // 4e. Proving the set of receipts is the subset of outgoing_receipts of shard `shard_id`
-                if !verify_path(*root, proof, &receipts_hash) {
+                if !verify_path_with(hash_algorithm, *root, proof, &receipts_hash) {
                     byzantine_assert!(false);
                     return Err(Error::Other("set_shard_state failed: invalid proofs".into()));
                 }
                 // 4f. Proving the outgoing_receipts_root matches that in the block
-                if !verify_path(*block_header.chunk_receipts_root(), block_proof, root) {
+                if !verify_path_with(
+                    hash_algorithm,
+                    *block_header.chunk_receipts_root(),
+                    block_proof,
+                    root,
+                ) {
                     byzantine_assert!(false);
                     return Err(Error::Other("set_shard_state failed: invalid proofs".into()));
                 }
@@ -3460,74 +3658,130 @@ impl Chain {
         Ok(res)
     }
 
+    /// Creates a challenge of the chunk whose header doesn't match the result of applying the
+    /// previous chunk of the same shard. The previous chunk is applied again recording the state it
+    /// reads, so that the challenge can be checked by the nodes which don't track the shard.
+    /// Before `ChunkStateChallenges` the challenge carries no state and can't be checked.
     pub fn create_chunk_state_challenge(
         &self,
         prev_block: &Block,
         block: &Block,
         chunk_header: &ShardChunkHeader,
-    ) -> Result<ChunkState, Error> {
+    ) -> Result<ChallengeBody, Error> {
         let chunk_shard_id = chunk_header.shard_id();
+        let protocol_version =
+            self.runtime_adapter.get_epoch_protocol_version(block.header().epoch_id())?;
+        if !checked_feature!(
+            "protocol_feature_chunk_state_challenges",
+            ChunkStateChallenges,
+            protocol_version
+        ) {
+            let prev_merkle_proofs =
+                Block::compute_chunk_headers_root(prev_block.chunks().iter()).1;
+            let merkle_proofs = Block::compute_chunk_headers_root(block.chunks().iter()).1;
+            let prev_chunk =
+                self.get_chunk_clone_from_header(&prev_block.chunks()[chunk_shard_id as usize])?;
+            return Ok(ChallengeBody::ChunkState(ChunkState {
+                prev_block_header: prev_block.header().try_to_vec()?,
+                block_header: block.header().try_to_vec()?,
+                prev_merkle_proof: prev_merkle_proofs[chunk_shard_id as usize].clone(),
+                merkle_proof: merkle_proofs[chunk_shard_id as usize].clone(),
+                prev_chunk,
+                chunk_header: chunk_header.clone(),
+                partial_state: PartialState(vec![]),
+            }));
+        }
+        let prev_chunk_header = &prev_block.chunks()[chunk_shard_id as usize];
+        if prev_chunk_header.height_included() != prev_block.header().height() {
+            // Only the transactions and receipts of a new chunk can be checked in a challenge.
+            return Err(Error::Other(format!(
+                "No new chunk for shard {} in block {}",
+                chunk_shard_id,
+                prev_block.hash()
+            )));
+        }
         let prev_merkle_proofs = Block::compute_chunk_headers_root(prev_block.chunks().iter()).1;
         let merkle_proofs = Block::compute_chunk_headers_root(block.chunks().iter()).1;
-        let prev_chunk = self
-            .get_chunk_clone_from_header(&prev_block.chunks()[chunk_shard_id as usize].clone())
-            .unwrap();
-
-        // TODO (#6316): enable storage proof generation
-        // let prev_chunk_header = &prev_block.chunks()[chunk_shard_id as usize];
-        // let receipt_proof_response: Vec<ReceiptProofResponse> =
-        //     self.chain_store_update.get_incoming_receipts_for_shard(
-        //         chunk_shard_id,
-        //         *prev_block.hash(),
-        //         prev_chunk_header.height_included(),
-        //     )?;
-        // let receipts = collect_receipts_from_response(&receipt_proof_response);
-        //
-        // let challenges_result = self.verify_challenges(
-        //     block.challenges(),
-        //     block.header().epoch_id(),
-        //     block.header().prev_hash(),
-        //     Some(block.hash()),
-        // )?;
-        // let prev_chunk_inner = prev_chunk.cloned_header().take_inner();
-        // let is_first_block_with_chunk_of_version = check_if_block_is_first_with_chunk_of_version(
-        //     &mut self.chain_store_update,
-        //     self.runtime_adapter.as_ref(),
-        //     prev_block.hash(),
-        //     chunk_shard_id,
-        // )?;
-        // let apply_result = self
-        //     .runtime_adapter
-        //     .apply_transactions_with_optional_storage_proof(
-        //         chunk_shard_id,
-        //         prev_chunk_inner.prev_state_root(),
-        //         prev_chunk.height_included(),
-        //         prev_block.header().raw_timestamp(),
-        //         prev_chunk_inner.prev_block_hash(),
-        //         prev_block.hash(),
-        //         &receipts,
-        //         prev_chunk.transactions(),
-        //         prev_chunk_inner.validator_proposals(),
-        //         prev_block.header().gas_price(),
-        //         prev_chunk_inner.gas_limit(),
-        //         &challenges_result,
-        //         *block.header().random_value(),
-        //         true,
-        //         true,
-        //         is_first_block_with_chunk_of_version,
-        //         None,
-        //     )
-        //     .unwrap();
-        // let partial_state = apply_result.proof.unwrap().nodes;
-        Ok(ChunkState {
+        let prev_chunk = self.get_chunk_clone_from_header(prev_chunk_header)?;
+        let prev_prev_block = self.get_block(prev_block.header().prev_hash())?;
+
+        let incoming_receipts = self.store().get_incoming_receipts_for_shard(
+            chunk_shard_id,
+            *prev_block.hash(),
+            prev_prev_block.chunks()[chunk_shard_id as usize].height_included(),
+        )?;
+        let receipts = collect_receipts_from_response(&incoming_receipts);
+        let is_first_block_with_chunk_of_version = check_if_block_is_first_with_chunk_of_version(
+            self.store(),
+            self.runtime_adapter.as_ref(),
+            prev_block.header().prev_hash(),
+            chunk_shard_id,
+        )?;
+        let apply_result = self.runtime_adapter.apply_transactions_with_optional_storage_proof(
+            chunk_shard_id,
+            &prev_chunk_header.prev_state_root(),
+            prev_chunk_header.height_included(),
+            prev_block.header().raw_timestamp(),
+            prev_chunk_header.prev_block_hash(),
+            prev_block.hash(),
+            &receipts,
+            prev_chunk.transactions(),
+            prev_chunk_header.validator_proposals(),
+            prev_prev_block.header().gas_price(),
+            prev_chunk_header.gas_limit(),
+            prev_block.header().challenges_result(),
+            *prev_block.header().random_value(),
+            true,
+            true,
+            is_first_block_with_chunk_of_version,
+            Default::default(),
+            false,
+        )?;
+        let partial_state = match apply_result.proof {
+            Some(proof) => proof.nodes,
+            None => {
+                return Err(Error::Other(
+                    "Storage proof of the previous chunk is missing".to_string(),
+                ))
+            }
+        };
+        // Attach the headers of the blocks with the receipts and of the chunks which sent them, so
+        // that the receipts can be checked without the blocks.
+        let mut challenge_incoming_receipts = Vec::with_capacity(incoming_receipts.len());
+        let mut last_chunk_block_hash = *prev_block.hash();
+        for ReceiptProofResponse(block_hash, receipt_proofs) in incoming_receipts.iter() {
+            let receipts_block = self.get_block(block_hash)?;
+            let chunk_merkle_proofs =
+                Block::compute_chunk_headers_root(receipts_block.chunks().iter()).1;
+            let receipt_proofs = receipt_proofs
+                .iter()
+                .map(|receipt_proof| {
+                    let from_shard_id = receipt_proof.1.from_shard_id as usize;
+                    (
+                        receipt_proof.clone(),
+                        receipts_block.chunks()[from_shard_id].clone(),
+                        chunk_merkle_proofs[from_shard_id].clone(),
+                    )
+                })
+                .collect();
+            challenge_incoming_receipts.push(ChunkStateIncomingReceipts {
+                block_header: receipts_block.header().try_to_vec()?,
+                receipt_proofs,
+            });
+            last_chunk_block_hash = *receipts_block.header().prev_hash();
+        }
+        let last_chunk_block_header = self.get_block_header(&last_chunk_block_hash)?;
+        Ok(ChallengeBody::ChunkStateV2(ChunkStateV2 {
             prev_block_header: prev_block.header().try_to_vec()?,
             block_header: block.header().try_to_vec()?,
             prev_merkle_proof: prev_merkle_proofs[chunk_shard_id as usize].clone(),
             merkle_proof: merkle_proofs[chunk_shard_id as usize].clone(),
             prev_chunk,
             chunk_header: chunk_header.clone(),
-            partial_state: PartialState(vec![]),
-        })
+            partial_state,
+            incoming_receipts: challenge_incoming_receipts,
+            last_chunk_block_header: last_chunk_block_header.try_to_vec()?,
+        }))
     }
 
     fn get_split_state_roots(
@@ -3660,10 +3914,13 @@ impl Chain {
                                                 chunk_header: {:#?}", e,block.header().prev_hash(),block.header().hash(),shard_id,prev_chunk_height_included,prev_chunk_extra,chunk_header);
                         byzantine_assert!(false);
                         match self.create_chunk_state_challenge(prev_block, block, chunk_header) {
-                            Ok(chunk_state) => {
-                                Error::InvalidChunkState(Box::new(chunk_state))
+                            Ok(challenge_body) => {
+                                Error::InvalidChunkState(Box::new(challenge_body))
+                            }
+                            Err(err) => {
+                                warn!(target: "chain", ?err, "Failed to create chunk state challenge");
+                                e
                             }
-                            Err(err) => err,
                         }
                     })?;
                     // we can't use hash from the current block here yet because the incoming receipts
@@ -3884,11 +4141,12 @@ impl Chain {
 /// Implement block merkle proof retrieval.
 impl Chain {
     fn combine_maybe_hashes(
+        hash_algorithm: HashAlgorithm,
         hash1: Option<MerkleHash>,
         hash2: Option<MerkleHash>,
     ) -> Option<MerkleHash> {
         match (hash1, hash2) {
-            (Some(h1), Some(h2)) => Some(combine_hash(&h1, &h2)),
+            (Some(h1), Some(h2)) => Some(combine_hash_with(hash_algorithm, &h1, &h2)),
             (Some(h1), None) => Some(h1),
             (None, Some(_)) => {
                 debug_assert!(false, "Inconsistent state in merkle proof computation: left node is None but right node exists");
@@ -3907,6 +4165,26 @@ impl Chain {
         )
     }
 
+    /// Get hash function of the node at given position (index, level). A complete node is hashed
+    /// with the function of the block whose insertion completed it, other nodes with the function
+    /// of the head block, `head_hash_algorithm`.
+    fn get_merkle_tree_node_hash_algorithm(
+        &self,
+        index: u64,
+        counter: u64,
+        tree_size: u64,
+        head_hash_algorithm: HashAlgorithm,
+    ) -> Result<HashAlgorithm, Error> {
+        let cur_tree_size = (index + 1) * counter;
+        if cur_tree_size >= tree_size {
+            return Ok(head_hash_algorithm);
+        }
+        let block_hash = self.store().get_block_hash_from_ordinal(cur_tree_size)?;
+        let epoch_id = self.get_block_header(&block_hash)?.epoch_id().clone();
+        let protocol_version = self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+        Ok(merkle_hash_algorithm(protocol_version))
+    }
+
     /// Get node at given position (index, level). If the node does not exist, return `None`.
     fn get_merkle_tree_node(
         &self,
@@ -3914,6 +4192,7 @@ impl Chain {
         level: u64,
         counter: u64,
         tree_size: u64,
+        head_hash_algorithm: HashAlgorithm,
         tree_nodes: &mut HashMap<(u64, u64), Option<MerkleHash>>,
     ) -> Result<Option<MerkleHash>, Error> {
         if let Some(hash) = tree_nodes.get(&(index, level)) {
@@ -3936,6 +4215,7 @@ impl Chain {
                             level - 1,
                             counter / 2,
                             tree_size,
+                            head_hash_algorithm,
                             tree_nodes,
                         )?;
                         let right_hash = self.reconstruct_merkle_tree_node(
@@ -3943,9 +4223,10 @@ impl Chain {
                             level - 1,
                             counter / 2,
                             tree_size,
+                            head_hash_algorithm,
                             tree_nodes,
                         )?;
-                        Self::combine_maybe_hashes(left_hash, right_hash)
+                        Self::combine_maybe_hashes(head_hash_algorithm, left_hash, right_hash)
                     } else {
                         None
                     }
@@ -3972,6 +4253,7 @@ impl Chain {
         level: u64,
         counter: u64,
         tree_size: u64,
+        head_hash_algorithm: HashAlgorithm,
         tree_nodes: &mut HashMap<(u64, u64), Option<MerkleHash>>,
     ) -> Result<Option<MerkleHash>, Error> {
         if let Some(hash) = tree_nodes.get(&(index, level)) {
@@ -3991,6 +4273,7 @@ impl Chain {
                     level - 1,
                     counter / 2,
                     tree_size,
+                    head_hash_algorithm,
                     tree_nodes,
                 )?;
                 let right_hash = self.reconstruct_merkle_tree_node(
@@ -3998,9 +4281,16 @@ impl Chain {
                     level - 1,
                     counter / 2,
                     tree_size,
+                    head_hash_algorithm,
                     tree_nodes,
                 )?;
-                let maybe_hash = Self::combine_maybe_hashes(left_hash, right_hash);
+                let hash_algorithm = self.get_merkle_tree_node_hash_algorithm(
+                    index,
+                    counter,
+                    tree_size,
+                    head_hash_algorithm,
+                )?;
+                let maybe_hash = Self::combine_maybe_hashes(hash_algorithm, left_hash, right_hash);
                 tree_nodes.insert((index, level), maybe_hash);
 
                 Ok(maybe_hash)
@@ -4008,28 +4298,33 @@ impl Chain {
         }
     }
 
-    /// Get merkle proof for block with hash `block_hash` in the merkle tree of `head_block_hash`.
+    /// Get merkle proof for block with hash `block_hash` in the merkle tree of `head_block_hash`,
+    /// along with the hash function of each item of the proof.
     pub fn get_block_proof(
         &self,
         block_hash: &CryptoHash,
         head_block_hash: &CryptoHash,
-    ) -> Result<MerklePath, Error> {
+    ) -> Result<(MerklePath, Vec<HashAlgorithm>), Error> {
         let leaf_index = self.store().get_block_merkle_tree(block_hash)?.size();
         let tree_size = self.store().get_block_merkle_tree(head_block_hash)?.size();
         if leaf_index >= tree_size {
             if block_hash == head_block_hash {
                 // special case if the block to prove is the same as head
-                return Ok(vec![]);
+                return Ok((vec![], vec![]));
             }
             return Err(Error::Other(format!(
                 "block {} is ahead of head block {}",
                 block_hash, head_block_hash
             )));
         }
+        let head_epoch_id = self.get_block_header(head_block_hash)?.epoch_id().clone();
+        let head_hash_algorithm =
+            merkle_hash_algorithm(self.runtime_adapter.get_epoch_protocol_version(&head_epoch_id)?);
         let mut level = 0;
         let mut counter = 1;
         let mut cur_index = leaf_index;
         let mut path = vec![];
+        let mut hash_algorithms = vec![];
         let mut tree_nodes = HashMap::new();
         let mut iter = tree_size;
         while iter > 1 {
@@ -4046,20 +4341,34 @@ impl Chain {
                     level,
                     counter,
                     tree_size,
+                    head_hash_algorithm,
                     &mut tree_nodes,
                 )?
             } else {
-                self.get_merkle_tree_node(cur_index, level, counter, tree_size, &mut tree_nodes)?
+                self.get_merkle_tree_node(
+                    cur_index,
+                    level,
+                    counter,
+                    tree_size,
+                    head_hash_algorithm,
+                    &mut tree_nodes,
+                )?
             };
             if let Some(hash) = maybe_hash {
                 path.push(MerklePathItem { hash, direction });
+                hash_algorithms.push(self.get_merkle_tree_node_hash_algorithm(
+                    cur_index / 2,
+                    counter * 2,
+                    tree_size,
+                    head_hash_algorithm,
+                )?);
             }
             cur_index /= 2;
             iter = (iter + 1) / 2;
             level += 1;
             counter *= 2;
         }
-        Ok(path)
+        Ok((path, hash_algorithms))
     }
 }
 
@@ -4785,8 +5094,14 @@ impl<'a> ChainUpdate<'a> {
                 apply_result,
                 apply_split_result_or_state_changes,
             }) => {
-                let (outcome_root, outcome_paths) =
-                    ApplyTransactionResult::compute_outcomes_proof(&apply_result.outcomes);
+                let epoch_id =
+                    self.runtime_adapter.get_epoch_id_from_prev_block(&prev_block_hash)?;
+                let protocol_version =
+                    self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+                let (outcome_root, outcome_paths) = ApplyTransactionResult::compute_outcomes_proof(
+                    protocol_version,
+                    &apply_result.outcomes,
+                );
                 let shard_id = shard_uid.shard_id();
 
                 // Save state root after applying transactions.
@@ -4928,7 +5243,12 @@ impl<'a> ChainUpdate<'a> {
             self.mark_block_as_challenged(&block_hash, Some(block.hash()))?;
         }
 
-        self.chain_store_update.save_block_header(block.header().clone())?;
+        let epoch_protocol_version =
+            self.runtime_adapter.get_epoch_protocol_version(block.header().epoch_id())?;
+        self.chain_store_update.save_block_header_with_merkle_hash_algorithm(
+            block.header().clone(),
+            merkle_hash_algorithm(epoch_protocol_version),
+        )?;
         self.update_header_head_if_not_challenged(block.header())?;
 
         // If block checks out, record validator proposals for given block.
@@ -4939,9 +5259,9 @@ impl<'a> ChainUpdate<'a> {
             self.chain_store_update.get_block_header(last_final_block)?.height()
         };
 
-        let epoch_manager_update = self
-            .runtime_adapter
-            .add_validator_proposals(BlockHeaderInfo::new(block.header(), last_finalized_height))?;
+        let epoch_manager_update = self.runtime_adapter.add_validator_proposals(
+            BlockHeaderInfo::new(block.header(), last_finalized_height, epoch_protocol_version),
+        )?;
         self.chain_store_update.merge(epoch_manager_update);
 
         // Add validated block to the db, even if it's not the canonical fork.
@@ -5211,8 +5531,12 @@ impl<'a> ChainUpdate<'a> {
             false,
         )?;
 
-        let (outcome_root, outcome_proofs) =
-            ApplyTransactionResult::compute_outcomes_proof(&apply_result.outcomes);
+        let protocol_version =
+            self.runtime_adapter.get_epoch_protocol_version(block_header.epoch_id())?;
+        let (outcome_root, outcome_proofs) = ApplyTransactionResult::compute_outcomes_proof(
+            protocol_version,
+            &apply_result.outcomes,
+        );
 
         self.chain_store_update.save_chunk(chunk);
 
@@ -1,9 +1,13 @@
 use near_chain_primitives::Error;
-use near_primitives::block::BlockHeader;
+use near_primitives::block::{Approval, ApprovalInner, BlockHeader};
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::types::EpochId;
+use near_primitives::merkle::combine_hash;
+use near_primitives::types::validator_stake::ValidatorStake;
+use near_primitives::types::{Balance, EpochId};
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
-use near_primitives::views::{BlockHeaderInnerLiteView, LightClientBlockView};
+use near_primitives::views::{
+    BlockHeaderInnerLiteView, LightClientBlockLiteView, LightClientBlockView,
+};
 
 use crate::{ChainStoreAccess, RuntimeAdapter};
 
@@ -70,3 +74,73 @@ pub fn create_light_client_block_view(
         approvals_after_next,
     })
 }
+
+/// Returns the hash of the block a `LightClientBlock` is created for.
+pub fn light_client_block_hash(light_client_block: &LightClientBlockView) -> CryptoHash {
+    LightClientBlockLiteView {
+        prev_block_hash: light_client_block.prev_block_hash,
+        inner_rest_hash: light_client_block.inner_rest_hash,
+        inner_lite: light_client_block.inner_lite.clone(),
+    }
+    .hash()
+}
+
+/// Validates the `LightClientBlock` of the last final block of an epoch.
+///
+/// # Arguments
+///  * `epoch_id` - the epoch the light client block is expected to be in
+///  * `block_producers` - the ordered list of block producers of that epoch
+///  * `light_client_block` - the light client block to validate
+///
+/// The block must be endorsed by more than 2/3 of the stake of `block_producers`
+/// and must carry the block producers of the next epoch, matching the hash in
+/// its header.  On success those can be used to validate the light client block
+/// of the next epoch.
+pub fn validate_light_client_block(
+    epoch_id: &EpochId,
+    block_producers: &[ValidatorStake],
+    light_client_block: &LightClientBlockView,
+) -> Result<(), Error> {
+    if light_client_block.inner_lite.epoch_id != epoch_id.0 {
+        return Err(Error::InvalidEpochHash);
+    }
+
+    let current_block_hash = light_client_block_hash(light_client_block);
+    let next_block_hash =
+        combine_hash(&light_client_block.next_block_inner_hash, &current_block_hash);
+    let approval_message = Approval::get_data_for_sig(
+        &ApprovalInner::Endorsement(next_block_hash),
+        light_client_block.inner_lite.height + 2,
+    );
+
+    // Approvals from the block producers of the next epoch, if any, follow the
+    // approvals from the block producers of this epoch and are not counted.
+    let mut total_stake: Balance = 0;
+    let mut approved_stake: Balance = 0;
+    for (i, block_producer) in block_producers.iter().enumerate() {
+        total_stake += block_producer.stake();
+        let signature = match light_client_block.approvals_after_next.get(i) {
+            Some(Some(signature)) => signature,
+            _ => continue,
+        };
+        if !signature.verify(&approval_message, block_producer.public_key()) {
+            return Err(Error::InvalidApprovals);
+        }
+        approved_stake += block_producer.stake();
+    }
+    if approved_stake * 3 <= total_stake * 2 {
+        return Err(Error::NotEnoughApprovals);
+    }
+
+    let next_bps = light_client_block.next_bps.as_ref().ok_or(Error::InvalidNextBPHash)?;
+    let next_bps = next_bps.iter().cloned().map(ValidatorStakeView::into_validator_stake);
+    // Chains that started before `BlockHeaderV3` hash the legacy encoding of
+    // the validator stakes.
+    let next_bp_hash = CryptoHash::hash_borsh_iter(next_bps.clone());
+    let legacy_next_bp_hash = CryptoHash::hash_borsh_iter(next_bps.map(|bp| bp.into_v1()));
+    let expected_next_bp_hash = light_client_block.inner_lite.next_bp_hash;
+    if next_bp_hash != expected_next_bp_hash && legacy_next_bp_hash != expected_next_bp_hash {
+        return Err(Error::InvalidNextBPHash);
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::store::ChainStoreAccess;
 use crate::types::RuntimeAdapter;
 use near_chain_primitives::error::Error;
+use near_primitives::block::BlockHeader;
 use near_primitives::hash::CryptoHash;
 use near_primitives::types::ShardId;
 
@@ -41,3 +42,19 @@ pub fn check_if_block_is_first_with_chunk_of_version(
         Ok(false)
     }
 }
+
+/// Same as `check_if_block_is_first_with_chunk_of_version`, given the header of the last block up
+/// to `prev_block_hash` which contains a new chunk for the shard instead of reading it from the store.
+/// Used to check challenges on the nodes which may not have the blocks.
+pub fn check_if_block_is_first_with_chunk_of_version_from_header(
+    runtime_adapter: &dyn RuntimeAdapter,
+    prev_block_hash: &CryptoHash,
+    last_block_with_chunk_header: &BlockHeader,
+) -> Result<bool, Error> {
+    if is_first_epoch_with_protocol_version(runtime_adapter, prev_block_hash)? {
+        let epoch_id = runtime_adapter.get_epoch_id_from_prev_block(prev_block_hash)?;
+        Ok(last_block_with_chunk_header.epoch_id() != &epoch_id)
+    } else {
+        Ok(false)
+    }
+}
@@ -9,7 +9,7 @@ use near_primitives::time::Utc;
 use near_chain_primitives::error::Error;
 use near_primitives::block::Tip;
 use near_primitives::errors::InvalidTxError;
-use near_primitives::hash::CryptoHash;
+use near_primitives::hash::{CryptoHash, HashAlgorithm};
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
 use near_primitives::receipt::Receipt;
 use near_primitives::shard_layout::{account_id_to_shard_id, get_block_shard_uid, ShardUId};
@@ -1614,17 +1614,20 @@ impl<'a> ChainStoreUpdate<'a> {
     }
 
     /// Save header head in Epoch Sync
-    /// Checking validity of header head is delegated to Epoch Sync methods
+    /// Checking validity of header head is delegated to Epoch Sync methods.
+    /// Unlike `save_header_head_if_not_challenged` it doesn't walk back to update the height
+    /// index of the previous headers, since those are not known after Epoch Sync.
     pub fn force_save_header_head(&mut self, t: &Tip) -> Result<(), Error> {
         self.try_save_latest_known(t.height)?;
 
-        // TODO #3488
-        // Bowen: It seems that height_to_hashes is used to update DBCol::BlockHeight, which stores blocks,
-        // not block headers, by height. Therefore I wonder whether this line here breaks some invariant
-        // since now we potentially don't have the corresponding block in storage.
-
-        //self.chain_tore_cache_update.height_to_hashes.insert(t.height, Some(t.last_block_hash));
-        //self.chain_store_cache_update.next_block_hashes.insert(t.prev_block_hash, t.last_block_hash);
+        let block_ordinal = self.get_block_merkle_tree(&t.last_block_hash)?.size();
+        self.chain_store_cache_update
+            .block_ordinal_to_hash
+            .insert(block_ordinal, t.last_block_hash);
+        self.chain_store_cache_update.height_to_hashes.insert(t.height, Some(t.last_block_hash));
+        self.chain_store_cache_update
+            .next_block_hashes
+            .insert(t.prev_block_hash, t.last_block_hash);
         self.header_head = Some(t.clone());
         Ok(())
     }
@@ -1739,14 +1742,18 @@ impl<'a> ChainStoreUpdate<'a> {
             .insert(block_hash, Arc::new(block_merkle_tree));
     }
 
-    fn update_and_save_block_merkle_tree(&mut self, header: &BlockHeader) -> Result<(), Error> {
+    fn update_and_save_block_merkle_tree(
+        &mut self,
+        header: &BlockHeader,
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<(), Error> {
         let prev_hash = *header.prev_hash();
         if prev_hash == CryptoHash::default() {
             self.save_block_merkle_tree(*header.hash(), PartialMerkleTree::default());
         } else {
             let old_merkle_tree = self.get_block_merkle_tree(&prev_hash)?;
             let mut new_merkle_tree = PartialMerkleTree::clone(&old_merkle_tree);
-            new_merkle_tree.insert(prev_hash);
+            new_merkle_tree.insert_with(hash_algorithm, prev_hash);
             self.save_block_merkle_tree(*header.hash(), new_merkle_tree);
         }
         Ok(())
@@ -1759,8 +1766,21 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    /// Saves the header and its block merkle tree built with SHA-256.  Only
+    /// suitable for genesis and for blocks of protocol versions which don’t
+    /// change the block merkle tree hash function.
     pub fn save_block_header(&mut self, header: BlockHeader) -> Result<(), Error> {
-        self.update_and_save_block_merkle_tree(&header)?;
+        self.save_block_header_with_merkle_hash_algorithm(header, HashAlgorithm::Sha256)
+    }
+
+    /// Saves the header and its block merkle tree.  `hash_algorithm` must be
+    /// the merkle hash algorithm of the header’s epoch protocol version.
+    pub fn save_block_header_with_merkle_hash_algorithm(
+        &mut self,
+        header: BlockHeader,
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<(), Error> {
+        self.update_and_save_block_merkle_tree(&header, hash_algorithm)?;
         self.chain_store_cache_update.headers.insert(*header.hash(), header);
         Ok(())
     }
@@ -911,13 +911,13 @@ impl RuntimeAdapter for KeyValueRuntime {
         _gas_limit: Gas,
         _challenges: &ChallengesResult,
         _random_seed: CryptoHash,
-        generate_storage_proof: bool,
+        _generate_storage_proof: bool,
         _is_new_chunk: bool,
         _is_first_block_with_chunk_of_version: bool,
         _state_patch: SandboxStatePatch,
         _use_flat_storage: bool,
     ) -> Result<ApplyTransactionResult, Error> {
-        assert!(!generate_storage_proof);
+        // The key-value runtime doesn't keep a trie, so there is no storage proof to generate.
         let mut tx_results = vec![];
 
         let mut state = self.state.read().unwrap().get(state_root).cloned().unwrap();
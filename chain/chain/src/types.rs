@@ -19,7 +19,7 @@ use near_primitives::epoch_manager::block_info::BlockInfo;
 use near_primitives::epoch_manager::epoch_info::EpochInfo;
 use near_primitives::errors::{EpochError, InvalidTxError};
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::merkle::{merklize, MerklePath};
+use near_primitives::merkle::{merkle_hash_algorithm, merklize_with, MerklePath};
 use near_primitives::receipt::Receipt;
 use near_primitives::shard_layout::{ShardLayout, ShardUId};
 use near_primitives::state_part::PartId;
@@ -111,15 +111,18 @@ pub struct ApplyTransactionResult {
 }
 
 impl ApplyTransactionResult {
-    /// Returns root and paths for all the outcomes in the result.
+    /// Returns root and paths for all the outcomes in the result.  The tree is
+    /// hashed with the algorithm of given protocol version of the block in
+    /// which the chunk was applied.
     pub fn compute_outcomes_proof(
+        protocol_version: ProtocolVersion,
         outcomes: &[ExecutionOutcomeWithId],
     ) -> (MerkleHash, Vec<MerklePath>) {
         let mut result = vec![];
         for outcome_with_id in outcomes.iter() {
             result.push(outcome_with_id.to_hashes());
         }
-        merklize(&result)
+        merklize_with(merkle_hash_algorithm(protocol_version), &result)
     }
 }
 
@@ -142,7 +145,22 @@ pub struct BlockHeaderInfo {
 }
 
 impl BlockHeaderInfo {
-    pub fn new(header: &BlockHeader, last_finalized_height: u64) -> Self {
+    /// `epoch_protocol_version` is the protocol version of the epoch of the block.  Validators
+    /// in `challenges_result` of the header are only slashed since `ChunkStateChallenges`.
+    pub fn new(
+        header: &BlockHeader,
+        last_finalized_height: u64,
+        epoch_protocol_version: ProtocolVersion,
+    ) -> Self {
+        let slashed_validators = if checked_feature!(
+            "protocol_feature_chunk_state_challenges",
+            ChunkStateChallenges,
+            epoch_protocol_version
+        ) {
+            header.challenges_result().clone()
+        } else {
+            vec![]
+        };
         Self {
             hash: *header.hash(),
             prev_hash: *header.prev_hash(),
@@ -151,7 +169,7 @@ impl BlockHeaderInfo {
             last_finalized_height,
             last_finalized_block_hash: *header.last_final_block(),
             proposals: header.validator_proposals().collect(),
-            slashed_validators: vec![],
+            slashed_validators,
             chunk_mask: header.chunk_mask().to_vec(),
             total_supply: header.total_supply(),
             latest_protocol_version: header.latest_protocol_version(),
@@ -388,7 +406,6 @@ pub trait RuntimeAdapter: EpochManagerAdapter + Send + Sync {
     /// Amount of tokens minted in given epoch.
     fn get_epoch_minted_amount(&self, epoch_id: &EpochId) -> Result<Balance, Error>;
 
-    // TODO #3488 this likely to be updated
     /// Data that is necessary for prove Epochs in Epoch Sync.
     fn get_epoch_sync_data(
         &self,
@@ -407,7 +424,6 @@ pub trait RuntimeAdapter: EpochManagerAdapter + Send + Sync {
         Error,
     >;
 
-    // TODO #3488 this likely to be updated
     /// Hash that is necessary for prove Epochs in Epoch Sync.
     fn get_epoch_sync_data_hash(
         &self,
@@ -660,7 +676,7 @@ mod tests {
     use near_crypto::KeyType;
     use near_primitives::block::{genesis_chunks, Approval};
     use near_primitives::hash::hash;
-    use near_primitives::merkle::verify_path;
+    use near_primitives::merkle::verify_path_with;
     use near_primitives::transaction::{ExecutionMetadata, ExecutionOutcome, ExecutionStatus};
     use near_primitives::validator_signer::InMemoryValidatorSigner;
     use near_primitives::version::PROTOCOL_VERSION;
@@ -732,9 +748,16 @@ mod tests {
             },
         };
         let outcomes = vec![outcome1, outcome2];
-        let (outcome_root, paths) = ApplyTransactionResult::compute_outcomes_proof(&outcomes);
+        let (outcome_root, paths) =
+            ApplyTransactionResult::compute_outcomes_proof(PROTOCOL_VERSION, &outcomes);
+        let hash_algorithm = merkle_hash_algorithm(PROTOCOL_VERSION);
         for (outcome_with_id, path) in outcomes.into_iter().zip(paths.into_iter()) {
-            assert!(verify_path(outcome_root, &path, &outcome_with_id.to_hashes()));
+            assert!(verify_path_with(
+                hash_algorithm,
+                outcome_root,
+                &path,
+                &outcome_with_id.to_hashes()
+            ));
         }
     }
 }
@@ -5,19 +5,26 @@ use borsh::BorshDeserialize;
 use near_crypto::PublicKey;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::challenge::{
-    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, ChunkState, MaybeEncodedShardChunk,
+    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, ChunkStateIncomingReceipts,
+    ChunkStateV2, MaybeEncodedShardChunk,
 };
+use near_primitives::checked_feature;
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::merklize;
+use near_primitives::merkle::{merkle_hash_algorithm, merklize, merklize_with, verify_path_with};
 use near_primitives::sharding::{
-    ShardChunk, ShardChunkHeader, ShardChunkHeaderV1, ShardChunkHeaderV2, ShardChunkHeaderV3,
+    ReceiptList, ReceiptProof, ShardChunk, ShardChunkHeader, ShardChunkHeaderV1,
+    ShardChunkHeaderV2, ShardChunkHeaderV3, ShardProof,
 };
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
-use near_primitives::types::{AccountId, BlockHeight, EpochId, Nonce};
+use near_primitives::types::{AccountId, BlockHeight, EpochId, Nonce, ShardId};
+use near_store::PartialStorage;
 
+use crate::chain::collect_receipts;
+use crate::migrations::check_if_block_is_first_with_chunk_of_version_from_header;
+use crate::types::ApplyTransactionResult;
 use crate::{byzantine_assert, Chain};
-use crate::{ChainStore, Error, RuntimeAdapter};
+use crate::{ChainStore, ChainStoreAccess, Error, RuntimeAdapter};
 
 /// Gas limit cannot be adjusted for more than 0.1% at a time.
 const GAS_LIMIT_ADJUSTMENT_FACTOR: u64 = 1000;
@@ -67,15 +74,16 @@ pub fn validate_chunk_proofs(
     if height_created == 0 {
         return Ok(receipts.len() == 0 && outgoing_receipts_root == CryptoHash::default());
     } else {
-        let shard_layout = {
-            let prev_block_hash = match chunk {
-                ShardChunk::V1(chunk) => &chunk.header.inner.prev_block_hash,
-                ShardChunk::V2(chunk) => chunk.header.prev_block_hash(),
-            };
-            runtime_adapter.get_shard_layout_from_prev_block(prev_block_hash)?
+        let prev_block_hash = match chunk {
+            ShardChunk::V1(chunk) => &chunk.header.inner.prev_block_hash,
+            ShardChunk::V2(chunk) => chunk.header.prev_block_hash(),
         };
+        let shard_layout = runtime_adapter.get_shard_layout_from_prev_block(prev_block_hash)?;
+        let epoch_id = runtime_adapter.get_epoch_id_from_prev_block(prev_block_hash)?;
+        let protocol_version = runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
         let outgoing_receipts_hashes = Chain::build_receipts_hashes(receipts, &shard_layout);
-        let (receipts_root, _) = merklize(&outgoing_receipts_hashes);
+        let (receipts_root, _) =
+            merklize_with(merkle_hash_algorithm(protocol_version), &outgoing_receipts_hashes);
         if receipts_root != outgoing_receipts_root {
             byzantine_assert!(false);
             return Ok(false);
@@ -164,7 +172,10 @@ pub fn validate_chunk_with_chunk_extra(
         let shard_layout = runtime_adapter.get_shard_layout_from_prev_block(prev_block_hash)?;
         Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout)
     };
-    let (outgoing_receipts_root, _) = merklize(&outgoing_receipts_hashes);
+    let epoch_id = runtime_adapter.get_epoch_id_from_prev_block(prev_block_hash)?;
+    let protocol_version = runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+    let (outgoing_receipts_root, _) =
+        merklize_with(merkle_hash_algorithm(protocol_version), &outgoing_receipts_hashes);
 
     if outgoing_receipts_root != chunk_header.outgoing_receipts_root() {
         return Err(Error::InvalidReceiptsProof);
@@ -302,82 +313,206 @@ fn validate_chunk_proofs_challenge(
     return Err(Error::MaliciousChallenge);
 }
 
+/// Checks that the incoming receipts of a chunk state challenge are exactly the receipts the
+/// previous chunk was applied with. They are checked against the block headers carried in the
+/// challenge, so that the nodes which don't have the blocks can check them too.
+fn validate_chunk_state_incoming_receipts(
+    runtime_adapter: &dyn RuntimeAdapter,
+    prev_block_header: &BlockHeader,
+    shard_id: ShardId,
+    block_headers: &[BlockHeader],
+    incoming_receipts: &[ChunkStateIncomingReceipts],
+    last_chunk_block_header: &BlockHeader,
+) -> Result<bool, Error> {
+    let has_new_chunk =
+        |header: &BlockHeader| header.chunk_mask().get(shard_id as usize) == Some(&true);
+    // The blocks have to lead from the previous block, which contains the previous chunk, back to
+    // the block with the chunk before it, without new chunks of the shard in between.
+    let mut block_hash = *prev_block_header.hash();
+    for (i, (block_header, block_receipts)) in
+        block_headers.iter().zip(incoming_receipts).enumerate()
+    {
+        if *block_header.hash() != block_hash || has_new_chunk(block_header) != (i == 0) {
+            return Ok(false);
+        }
+        let hash_algorithm = merkle_hash_algorithm(
+            runtime_adapter.get_epoch_protocol_version(block_header.epoch_id())?,
+        );
+        for (ReceiptProof(receipts, shard_proof), from_chunk, from_chunk_proof) in
+            block_receipts.receipt_proofs.iter()
+        {
+            let ShardProof { from_shard_id, to_shard_id, proof } = shard_proof;
+            let receipts_hash = CryptoHash::hash_borsh(ReceiptList(shard_id, receipts));
+            if *to_shard_id != shard_id
+                || from_chunk.shard_id() != *from_shard_id
+                || from_chunk.height_included() != block_header.height()
+                || !Block::validate_chunk_header_proof(
+                    from_chunk,
+                    block_header.chunk_headers_root(),
+                    from_chunk_proof,
+                )
+                || !verify_path_with(
+                    hash_algorithm,
+                    from_chunk.outgoing_receipts_root(),
+                    proof,
+                    &receipts_hash,
+                )
+            {
+                return Ok(false);
+            }
+        }
+        // There has to be a single proof from each new chunk, in the order they are applied in.
+        let receipt_proofs: Vec<_> = block_receipts
+            .receipt_proofs
+            .iter()
+            .map(|(receipt_proof, _, _)| receipt_proof)
+            .collect();
+        let mut expected_order = receipt_proofs.clone();
+        expected_order.sort_by_key(|ReceiptProof(_, shard_proof)| shard_proof.from_shard_id);
+        expected_order.dedup_by_key(|ReceiptProof(_, shard_proof)| shard_proof.from_shard_id);
+        Chain::shuffle_receipt_proofs(&mut expected_order, block_header.hash());
+        if expected_order.len() != block_header.chunks_included() as usize
+            || expected_order != receipt_proofs
+        {
+            return Ok(false);
+        }
+        block_hash = *block_header.prev_hash();
+    }
+    Ok(!incoming_receipts.is_empty()
+        && *last_chunk_block_header.hash() == block_hash
+        && has_new_chunk(last_chunk_block_header))
+}
+
 fn validate_chunk_state_challenge(
-    _runtime_adapter: &dyn RuntimeAdapter,
-    _chunk_state: &ChunkState,
+    runtime_adapter: &dyn RuntimeAdapter,
+    chunk_state: &ChunkStateV2,
 ) -> Result<(CryptoHash, Vec<AccountId>), Error> {
-    // TODO (#2445): Enable challenges when they are working correctly.
-    // let prev_block_header = BlockHeader::try_from_slice(&chunk_state.prev_block_header)?;
-    // let block_header = BlockHeader::try_from_slice(&chunk_state.block_header)?;
-    //
-    // // Validate previous chunk and block header.
-    // validate_header_authorship(runtime_adapter, &prev_block_header)?;
-    // let prev_chunk_header = chunk_state.prev_chunk.cloned_header();
-    // let _ = validate_chunk_authorship(runtime_adapter, &prev_chunk_header)?;
-    // if !Block::validate_chunk_header_proof(
-    //     &prev_chunk_header,
-    //     prev_block_header.chunk_headers_root(),
-    //     &chunk_state.prev_merkle_proof,
-    // ) {
-    //     return Err(ErrorKind::MaliciousChallenge.into());
-    // }
-    //
-    // // Validate current chunk and block header.
-    // validate_header_authorship(runtime_adapter, &block_header)?;
-    // let chunk_producer = validate_chunk_authorship(runtime_adapter, &chunk_state.chunk_header)?;
-    // if !Block::validate_chunk_header_proof(
-    //     &chunk_state.chunk_header,
-    //     block_header.chunk_headers_root(),
-    //     &chunk_state.merkle_proof,
-    // ) {
-    //     return Err(ErrorKind::MaliciousChallenge.into());
-    // }
+    let prev_block_header = BlockHeader::try_from_slice(&chunk_state.prev_block_header)?;
+    let block_header = BlockHeader::try_from_slice(&chunk_state.block_header)?;
+    if block_header.prev_hash() != prev_block_header.hash() {
+        return Err(Error::MaliciousChallenge);
+    }
+
+    // Validate previous chunk and block header.
+    validate_header_authorship(runtime_adapter, &prev_block_header)?;
+    let prev_chunk_header = chunk_state.prev_chunk.cloned_header();
+    let _ = validate_chunk_authorship(runtime_adapter, &prev_chunk_header)?;
+    if !Block::validate_chunk_header_proof(
+        &prev_chunk_header,
+        prev_block_header.chunk_headers_root(),
+        &chunk_state.prev_merkle_proof,
+    ) || prev_chunk_header.height_included() != prev_block_header.height()
+        || !validate_chunk_proofs(&chunk_state.prev_chunk, runtime_adapter)?
+    {
+        return Err(Error::MaliciousChallenge);
+    }
+
+    // Validate current chunk and block header.
+    validate_header_authorship(runtime_adapter, &block_header)?;
+    let chunk_header = &chunk_state.chunk_header;
+    let chunk_producer = validate_chunk_authorship(runtime_adapter, chunk_header)?;
+    if !Block::validate_chunk_header_proof(
+        chunk_header,
+        block_header.chunk_headers_root(),
+        &chunk_state.merkle_proof,
+    ) || chunk_header.height_included() != block_header.height()
+        || chunk_header.shard_id() != prev_chunk_header.shard_id()
+        || chunk_header.prev_block_hash() != prev_block_header.hash()
+    {
+        return Err(Error::MaliciousChallenge);
+    }
+
+    let shard_id = chunk_header.shard_id();
+    let receipts_block_headers = chunk_state
+        .incoming_receipts
+        .iter()
+        .map(|receipts| BlockHeader::try_from_slice(&receipts.block_header))
+        .collect::<Result<Vec<_>, _>>()?;
+    let last_chunk_block_header =
+        BlockHeader::try_from_slice(&chunk_state.last_chunk_block_header)?;
+    if !validate_chunk_state_incoming_receipts(
+        runtime_adapter,
+        &prev_block_header,
+        shard_id,
+        &receipts_block_headers,
+        &chunk_state.incoming_receipts,
+        &last_chunk_block_header,
+    )? {
+        return Err(Error::MaliciousChallenge);
+    }
+    let receipts = collect_receipts(
+        chunk_state
+            .incoming_receipts
+            .iter()
+            .flat_map(|receipts| receipts.receipt_proofs.iter())
+            .map(|(receipt_proof, _, _)| receipt_proof),
+    );
+    // The blocks of the incoming receipts lead back from the previous block to the one with the
+    // chunk before the previous chunk, so the block before the previous one is among them.
+    let prev_prev_block_header = receipts_block_headers.get(1).unwrap_or(&last_chunk_block_header);
+    let is_first_block_with_chunk_of_version =
+        check_if_block_is_first_with_chunk_of_version_from_header(
+            runtime_adapter,
+            prev_block_header.prev_hash(),
+            &last_chunk_block_header,
+        )?;
 
     // Apply state transition and check that the result state and other data doesn't match.
-    // TODO (#6316): enable storage proof generation
-    // let partial_storage = PartialStorage { nodes: chunk_state.partial_state.clone() };
-    // let result = runtime_adapter
-    //     .check_state_transition(
-    //         partial_storage,
-    //         prev_chunk_header.shard_id(),
-    //         &prev_chunk_header.prev_state_root(),
-    //         block_header.height(),
-    //         block_header.raw_timestamp(),
-    //         block_header.prev_hash(),
-    //         block_header.hash(),
-    //         chunk_state.prev_chunk.receipts(),
-    //         chunk_state.prev_chunk.transactions(),
-    //         ValidatorStakeIter::empty(),
-    //         prev_block_header.gas_price(),
-    //         prev_chunk_header.gas_limit(),
-    //         &ChallengesResult::default(),
-    //         *block_header.random_value(),
-    //         // TODO: set it properly when challenges are enabled
-    //         true,
-    //         false,
-    //     )
-    //     .map_err(|_| Error::from(ErrorKind::MaliciousChallenge))?;
-    // let outcome_root = ApplyTransactionResult::compute_outcomes_proof(&result.outcomes).0;
-    // let proposals_match = result.validator_proposals.len()
-    //     == chunk_state.chunk_header.validator_proposals().len()
-    //     && result
-    //         .validator_proposals
-    //         .iter()
-    //         .zip(chunk_state.chunk_header.validator_proposals())
-    //         .all(|(x, y)| x == &y);
-    // if result.new_root != chunk_state.chunk_header.prev_state_root()
-    //     || outcome_root != chunk_state.chunk_header.outcome_root()
-    //     || !proposals_match
-    //     || result.total_gas_burnt != chunk_state.chunk_header.gas_used()
-    // {
-    //     Ok((*block_header.hash(), vec![chunk_producer]))
-    // } else {
-    //     // If all the data matches, this is actually valid chunk and challenge is malicious.
-    //     Err(ErrorKind::MaliciousChallenge.into())
-    // }
-    // Ok((*block_header.hash(), vec![chunk_producer]))
-
-    Err(Error::MaliciousChallenge)
+    // If the partial state misses some of the nodes the chunk reads, the challenge is malicious.
+    let partial_storage = PartialStorage { nodes: chunk_state.partial_state.clone() };
+    let result = runtime_adapter
+        .check_state_transition(
+            partial_storage,
+            shard_id,
+            &prev_chunk_header.prev_state_root(),
+            prev_chunk_header.height_included(),
+            prev_block_header.raw_timestamp(),
+            prev_chunk_header.prev_block_hash(),
+            prev_block_header.hash(),
+            &receipts,
+            chunk_state.prev_chunk.transactions(),
+            prev_chunk_header.validator_proposals(),
+            prev_prev_block_header.gas_price(),
+            prev_chunk_header.gas_limit(),
+            prev_block_header.challenges_result(),
+            *prev_block_header.random_value(),
+            true,
+            is_first_block_with_chunk_of_version,
+        )
+        .map_err(|_| Error::MaliciousChallenge)?;
+
+    let protocol_version =
+        runtime_adapter.get_epoch_protocol_version(prev_block_header.epoch_id())?;
+    let (outcome_root, _) =
+        ApplyTransactionResult::compute_outcomes_proof(protocol_version, &result.outcomes);
+    let outgoing_receipts_root = {
+        let shard_layout =
+            runtime_adapter.get_shard_layout_from_prev_block(block_header.prev_hash())?;
+        let epoch_id = runtime_adapter.get_epoch_id_from_prev_block(block_header.prev_hash())?;
+        let protocol_version = runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+        let outgoing_receipts_hashes =
+            Chain::build_receipts_hashes(&result.outgoing_receipts, &shard_layout);
+        merklize_with(merkle_hash_algorithm(protocol_version), &outgoing_receipts_hashes).0
+    };
+    let proposals_match = result.validator_proposals.len()
+        == chunk_header.validator_proposals().len()
+        && result
+            .validator_proposals
+            .iter()
+            .zip(chunk_header.validator_proposals())
+            .all(|(x, y)| x == &y);
+    if result.new_root != chunk_header.prev_state_root()
+        || outcome_root != chunk_header.outcome_root()
+        || !proposals_match
+        || result.total_gas_burnt != chunk_header.gas_used()
+        || result.total_balance_burnt != chunk_header.balance_burnt()
+        || outgoing_receipts_root != chunk_header.outgoing_receipts_root()
+    {
+        Ok((*block_header.hash(), vec![chunk_producer]))
+    } else {
+        // If all the data matches, this is actually valid chunk and challenge is malicious.
+        Err(Error::MaliciousChallenge)
+    }
 }
 
 /// Returns `Some(block_hash, vec![account_id])` of invalid block and who to
@@ -405,7 +540,17 @@ pub fn validate_challenge(
         ChallengeBody::ChunkProofs(chunk_proofs) => {
             validate_chunk_proofs_challenge(runtime_adapter, chunk_proofs)
         }
-        ChallengeBody::ChunkState(chunk_state) => {
+        // The challenge doesn't carry the incoming receipts of the chunk, so it can't be checked.
+        ChallengeBody::ChunkState(_) => Err(Error::MaliciousChallenge),
+        ChallengeBody::ChunkStateV2(chunk_state) => {
+            let protocol_version = runtime_adapter.get_epoch_protocol_version(epoch_id)?;
+            if !checked_feature!(
+                "protocol_feature_chunk_state_challenges",
+                ChunkStateChallenges,
+                protocol_version
+            ) {
+                return Err(Error::InvalidChallenge);
+            }
             validate_chunk_state_challenge(runtime_adapter, chunk_state)
         }
     }
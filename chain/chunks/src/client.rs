@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 
 use actix::Message;
+use near_chain_configs::TransactionPoolConfig;
 use near_network::types::MsgRecipient;
 use near_o11y::{WithSpanContext, WithSpanContextExt};
+use near_pool::types::InsertTransactionResult;
 use near_pool::{PoolIteratorWrapper, TransactionPool};
 use near_primitives::{
     epoch_manager::RngSeed,
+    hash::CryptoHash,
     sharding::{EncodedShardChunk, PartialEncodedChunk, ShardChunk},
     transaction::SignedTransaction,
     types::ShardId,
@@ -49,20 +52,26 @@ pub struct ShardedTransactionPool {
     /// Useful to make tests deterministic and reproducible,
     /// while keeping the security of randomization of transactions in pool
     rng_seed: RngSeed,
+
+    /// Capacity and eviction policy of the pool of each shard.
+    config: TransactionPoolConfig,
 }
 
 impl ShardedTransactionPool {
-    pub fn new(rng_seed: RngSeed) -> Self {
+    pub fn new(rng_seed: RngSeed, config: TransactionPoolConfig) -> Self {
         TransactionPool::init_metrics();
-        Self { tx_pools: HashMap::new(), rng_seed }
+        Self { tx_pools: HashMap::new(), rng_seed, config }
     }
 
     pub fn get_pool_iterator(&mut self, shard_id: ShardId) -> Option<PoolIteratorWrapper<'_>> {
         self.tx_pools.get_mut(&shard_id).map(|pool| pool.pool_iterator())
     }
 
-    /// Returns true if transaction is not in the pool before call
-    pub fn insert_transaction(&mut self, shard_id: ShardId, tx: SignedTransaction) -> bool {
+    pub fn insert_transaction(
+        &mut self,
+        shard_id: ShardId,
+        tx: SignedTransaction,
+    ) -> InsertTransactionResult {
         self.pool_for_shard(shard_id).insert_transaction(tx)
     }
 
@@ -72,6 +81,21 @@ impl ShardedTransactionPool {
         }
     }
 
+    /// Hashes of the transactions in the pools of all shards.
+    pub fn transaction_hashes(&self) -> Vec<CryptoHash> {
+        let mut shard_ids: Vec<_> = self.tx_pools.keys().collect();
+        shard_ids.sort();
+        shard_ids
+            .into_iter()
+            .flat_map(|shard_id| self.tx_pools[shard_id].transaction_hashes().copied())
+            .collect()
+    }
+
+    /// Returns the transaction with the given hash from the pool of any shard.
+    pub fn get_transaction(&self, tx_hash: &CryptoHash) -> Option<&SignedTransaction> {
+        self.tx_pools.values().find_map(|pool| pool.get_transaction(tx_hash))
+    }
+
     /// Computes a deterministic random seed for given `shard_id`.
     /// This seed is used to randomize the transaction pool.
     /// For better security we want the seed to different in each shard.
@@ -84,9 +108,9 @@ impl ShardedTransactionPool {
     }
 
     fn pool_for_shard(&mut self, shard_id: ShardId) -> &mut TransactionPool {
-        self.tx_pools
-            .entry(shard_id)
-            .or_insert_with(|| TransactionPool::new(Self::random_seed(&self.rng_seed, shard_id)))
+        self.tx_pools.entry(shard_id).or_insert_with(|| {
+            TransactionPool::new(Self::random_seed(&self.rng_seed, shard_id), self.config.clone())
+        })
     }
 
     pub fn reintroduce_transactions(
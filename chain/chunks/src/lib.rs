@@ -98,8 +98,8 @@ use tracing::{debug, error, warn};
 use near_chain::{byzantine_assert, RuntimeAdapter};
 use near_network::types::{NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest};
 use near_primitives::block::Tip;
-use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::{verify_path, MerklePath};
+use near_primitives::hash::{CryptoHash, HashAlgorithm};
+use near_primitives::merkle::{merkle_hash_algorithm, verify_path, verify_path_with, MerklePath};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{
     ChunkHash, EncodedShardChunk, EncodedShardChunkBody, PartialEncodedChunk,
@@ -115,7 +115,7 @@ use near_primitives::types::{
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
 use near_primitives::validator_signer::ValidatorSigner;
-use near_primitives::version::ProtocolVersion;
+use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 
 use crate::chunk_cache::{EncodedChunksCache, EncodedChunksCacheEntry};
 use crate::logic::cares_about_shard_this_or_next_epoch;
@@ -1272,6 +1272,31 @@ impl ShardsManager {
         }
     }
 
+    /// Returns the hash functions receipts proofs of the chunk may be built with.
+    ///
+    /// The receipts Merkle tree is hashed with the algorithm of the chunk's
+    /// epoch.  If the previous block of the chunk hasn't been processed yet,
+    /// that epoch isn't known, so proofs built with either SHA-256 or the hash
+    /// function of the latest protocol version are accepted.
+    fn receipts_hash_algorithms(
+        &self,
+        header: &ShardChunkHeader,
+    ) -> Result<Vec<HashAlgorithm>, Error> {
+        match self.runtime_adapter.get_epoch_id_from_prev_block(header.prev_block_hash()) {
+            Ok(epoch_id) => {
+                let protocol_version =
+                    self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+                Ok(vec![merkle_hash_algorithm(protocol_version)])
+            }
+            Err(_) => {
+                let mut algorithms =
+                    vec![HashAlgorithm::Sha256, merkle_hash_algorithm(PROTOCOL_VERSION)];
+                algorithms.dedup();
+                Ok(algorithms)
+            }
+        }
+    }
+
     /// Add a part to current encoded chunk stored in memory. It's present only if One Part was present and signed correctly.
     fn validate_part(
         &mut self,
@@ -1655,6 +1680,7 @@ impl ShardsManager {
         }
 
         // 1.e Checking receipts validity
+        let hash_algorithms = self.receipts_hash_algorithms(header)?;
         for proof in partial_encoded_chunk.receipts.iter() {
             // TODO: only validate receipts we care about
             // https://github.com/near/nearcore/issues/5885
@@ -1663,7 +1689,10 @@ impl ShardsManager {
             let shard_id = proof.1.to_shard_id;
             let ReceiptProof(shard_receipts, receipt_proof) = proof;
             let receipt_hash = CryptoHash::hash_borsh(ReceiptList(shard_id, shard_receipts));
-            if !verify_path(header.outgoing_receipts_root(), &receipt_proof.proof, &receipt_hash) {
+            let receipts_root = header.outgoing_receipts_root();
+            if !hash_algorithms.iter().any(|&hash_algorithm| {
+                verify_path_with(hash_algorithm, receipts_root, &receipt_proof.proof, &receipt_hash)
+            }) {
                 byzantine_assert!(false);
                 return Err(Error::ChainError(near_chain::Error::InvalidReceiptsProof));
             }
@@ -2190,7 +2219,7 @@ mod test {
     use near_o11y::testonly::init_test_logger;
     use near_primitives::block::Tip;
     use near_primitives::hash::{hash, CryptoHash};
-    use near_primitives::merkle::merklize;
+    use near_primitives::merkle::merklize_with;
     use near_primitives::sharding::ReedSolomonWrapper;
     use near_primitives::types::EpochId;
     use near_primitives::validator_signer::InMemoryValidatorSigner;
@@ -2288,7 +2317,11 @@ mod test {
             vec![],
             vec![],
             &[],
-            merklize(&Chain::build_receipts_hashes(&[], &shard_layout)).0,
+            merklize_with(
+                merkle_hash_algorithm(PROTOCOL_VERSION),
+                &Chain::build_receipts_hashes(&[], &shard_layout),
+            )
+            .0,
             CryptoHash::default(),
             &signer,
             &mut rs,
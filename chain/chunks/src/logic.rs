@@ -2,7 +2,7 @@ use near_chain::{validate::validate_chunk_proofs, Chain, ChainStore, RuntimeAdap
 use near_chunks_primitives::Error;
 use near_primitives::{
     hash::CryptoHash,
-    merkle::{merklize, MerklePath},
+    merkle::{merkle_hash_algorithm, merklize_with, MerklePath},
     receipt::Receipt,
     sharding::{
         EncodedShardChunk, PartialEncodedChunk, PartialEncodedChunkPart, PartialEncodedChunkV1,
@@ -54,8 +54,11 @@ pub fn make_outgoing_receipts_proofs(
     let shard_layout =
         runtime_adapter.get_shard_layout_from_prev_block(chunk_header.prev_block_hash())?;
 
+    let epoch_id = runtime_adapter.get_epoch_id_from_prev_block(chunk_header.prev_block_hash())?;
+    let protocol_version = runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+
     let hashes = Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout);
-    let (root, proofs) = merklize(&hashes);
+    let (root, proofs) = merklize_with(merkle_hash_algorithm(protocol_version), &hashes);
     assert_eq!(chunk_header.outgoing_receipts_root(), root);
 
     let mut receipts_by_shard =
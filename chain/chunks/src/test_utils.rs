@@ -243,7 +243,8 @@ impl ChunkTestFixture {
         let receipts = Vec::new();
         let shard_layout = mock_runtime.get_shard_layout(&EpochId::default()).unwrap();
         let receipts_hashes = Chain::build_receipts_hashes(&receipts, &shard_layout);
-        let (receipts_root, _) = merkle::merklize(&receipts_hashes);
+        let hash_algorithm = merkle::merkle_hash_algorithm(PROTOCOL_VERSION);
+        let (receipts_root, _) = merkle::merklize_with(hash_algorithm, &receipts_hashes);
         let (mock_chunk, mock_merkle_paths) = ShardsManager::create_encoded_shard_chunk(
             mock_parent_hash,
             Default::default(),
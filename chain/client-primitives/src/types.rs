@@ -8,10 +8,11 @@ use chrono::DateTime;
 use near_primitives::time::Utc;
 
 use near_chain_configs::ProtocolConfigView;
-use near_primitives::hash::CryptoHash;
+use near_primitives::hash::{CryptoHash, HashAlgorithm};
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
 use near_primitives::network::PeerId;
 use near_primitives::sharding::ChunkHash;
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{
     AccountId, BlockHeight, BlockReference, EpochId, EpochReference, MaybeBlockId, ShardId,
     TransactionOrReceiptId,
@@ -163,9 +164,8 @@ pub enum SyncStatus {
     AwaitingPeers,
     /// Not syncing / Done syncing.
     NoSync,
-    /// Syncing using light-client headers to a recent epoch
-    // TODO #3488
-    // Bowen: why do we use epoch ordinal instead of epoch id?
+    /// Syncing using light-client headers to a recent epoch.
+    /// `epoch_ord` is the number of epochs synced so far.
     EpochSync { epoch_ord: u64 },
     /// Downloading block headers for fast sync.
     HeaderSync {
@@ -521,6 +521,39 @@ impl Message for GetNetworkInfo {
     type Result = Result<NetworkInfoResponse, String>;
 }
 
+/// Lists the hashes of the transactions in the transaction pools of the node.
+pub struct GetMempoolTransactionHashes {}
+
+impl Message for GetMempoolTransactionHashes {
+    type Result = Vec<CryptoHash>;
+}
+
+/// Returns a transaction from the transaction pools of the node.
+pub struct GetMempoolTransaction {
+    pub transaction_hash: CryptoHash,
+}
+
+impl Message for GetMempoolTransaction {
+    type Result = Option<SignedTransaction>;
+}
+
+/// Change of the chain observed by the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainEvent {
+    /// The head of the chain moved to the given block.
+    NewHead { block_hash: CryptoHash, height: BlockHeight },
+    /// The given block became final. Sent for every final block, in order.
+    FinalBlock { block_hash: CryptoHash, height: BlockHeight },
+}
+
+/// Subscribes to the `ChainEvent`s of the client. A subscriber which falls
+/// behind by more than the capacity of the channel misses the oldest events.
+pub struct SubscribeChainEvents {}
+
+impl Message for SubscribeChainEvents {
+    type Result = tokio::sync::broadcast::Receiver<ChainEvent>;
+}
+
 pub struct GetGasPrice {
     pub block_id: MaybeBlockId,
 }
@@ -797,6 +830,8 @@ pub struct GetBlockProof {
 pub struct GetBlockProofResponse {
     pub block_header_lite: LightClientBlockLiteView,
     pub proof: MerklePath,
+    /// Hash function of each item of `proof`.
+    pub proof_hash_algorithms: Vec<HashAlgorithm>,
 }
 
 #[derive(thiserror::Error, Debug)]
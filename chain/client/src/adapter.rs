@@ -6,12 +6,14 @@ use near_network::types::{
     PartialEncodedChunkResponseMsg, ReasonForBan, StateResponseInfo,
 };
 use near_o11y::WithSpanContextExt;
+use near_pool::types::InsertTransactionResult;
 use near_primitives::block::{Approval, Block, BlockHeader};
 use near_primitives::challenge::Challenge;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::PartialEncodedChunk;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -57,6 +59,29 @@ pub(crate) struct BlockHeadersRequest(pub Vec<CryptoHash>);
 #[rtype(result = "Result<(),ReasonForBan>")]
 pub(crate) struct BlockHeadersResponse(pub Vec<BlockHeader>, pub PeerId);
 
+/// Request the light client block of the last final block of an epoch.
+#[derive(actix::Message)]
+#[rtype(result = "Option<Box<EpochSyncResponse>>")]
+pub(crate) struct EpochSyncRequest(pub EpochId);
+
+/// Epoch sync response.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Result<(),ReasonForBan>")]
+pub(crate) struct RecvEpochSyncResponse(pub Box<EpochSyncResponse>, pub PeerId);
+
+/// Request the data necessary to finish epoch sync at the first block of an epoch.
+#[derive(actix::Message)]
+#[rtype(result = "Option<Box<EpochSyncFinalizationResponse>>")]
+pub(crate) struct EpochSyncFinalizationRequest(pub EpochId);
+
+/// Epoch sync finalization response.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "Result<(),ReasonForBan>")]
+pub(crate) struct RecvEpochSyncFinalizationResponse(
+    pub Box<EpochSyncFinalizationResponse>,
+    pub PeerId,
+);
+
 /// State request header.
 #[derive(actix::Message)]
 #[rtype(result = "Option<StateResponse>")]
@@ -134,6 +159,8 @@ pub enum ProcessTxResponse {
     /// The node being queried does not track the shard needed and therefore cannot provide userful
     /// response.
     DoesNotTrackShard,
+    /// Valid transaction which the transaction pool had no room for.
+    PoolRejected(InsertTransactionResult),
 }
 
 pub struct Adapter {
@@ -359,6 +386,69 @@ impl near_network::client::Client for Adapter {
         }
     }
 
+    async fn epoch_sync_request(&self, epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        match self.view_client_addr.send(EpochSyncRequest(epoch_id).with_span_context()).await {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                None
+            }
+        }
+    }
+
+    async fn epoch_sync_response(
+        &self,
+        response: Box<EpochSyncResponse>,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        match self
+            .client_addr
+            .send(RecvEpochSyncResponse(response, peer_id).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                Ok(())
+            }
+        }
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        epoch_id: EpochId,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        match self
+            .view_client_addr
+            .send(EpochSyncFinalizationRequest(epoch_id).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                None
+            }
+        }
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        response: Box<EpochSyncFinalizationResponse>,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        match self
+            .client_addr
+            .send(RecvEpochSyncFinalizationResponse(response, peer_id).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                Ok(())
+            }
+        }
+    }
+
     async fn challenge(&self, challenge: Challenge) {
         match self.client_addr.send(RecvChallenge(challenge).with_span_context()).await {
             Ok(()) => {}
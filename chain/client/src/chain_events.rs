@@ -0,0 +1,72 @@
+//! Notifications about the changes of the chain, used by the JSON-RPC
+//! subscriptions instead of polling the chain.
+use near_chain::Chain;
+use near_client_primitives::types::ChainEvent;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::BlockHeight;
+use tokio::sync::broadcast;
+
+/// Number of events a subscriber may fall behind before it misses some.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Maximal number of `FinalBlock` events sent at once. When the final head
+/// jumps further (e.g. after sync), only the latest final blocks are sent.
+const MAX_FINAL_BLOCKS_PER_UPDATE: usize = 100;
+
+pub(crate) struct ChainEvents {
+    sender: broadcast::Sender<ChainEvent>,
+    head: Option<CryptoHash>,
+    final_height: Option<BlockHeight>,
+}
+
+impl ChainEvents {
+    pub fn new() -> Self {
+        Self { sender: broadcast::channel(CHANNEL_CAPACITY).0, head: None, final_height: None }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Sends the events for the changes of the chain since the last call.
+    pub fn update(&mut self, chain: &Chain) {
+        let (head, final_head) = match (chain.head(), chain.final_head()) {
+            (Ok(head), Ok(final_head)) => (head, final_head),
+            _ => return,
+        };
+        let last_final_height = self.final_height.replace(final_head.height);
+        let new_head = self.head.replace(head.last_block_hash) != Some(head.last_block_hash);
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        if new_head {
+            let _ = self.sender.send(ChainEvent::NewHead {
+                block_hash: head.last_block_hash,
+                height: head.height,
+            });
+        }
+        let last_final_height = match last_final_height {
+            Some(height) if height < final_head.height => height,
+            // Either nothing became final or this is the first update, in
+            // which case only the current final block is sent.
+            Some(_) => return,
+            None => final_head.height.saturating_sub(1),
+        };
+        let mut final_blocks = vec![];
+        let mut block_hash = final_head.last_block_hash;
+        while final_blocks.len() < MAX_FINAL_BLOCKS_PER_UPDATE {
+            let header = match chain.get_block_header(&block_hash) {
+                Ok(header) => header,
+                Err(_) => break,
+            };
+            if header.height() <= last_final_height {
+                break;
+            }
+            final_blocks.push((block_hash, header.height()));
+            block_hash = *header.prev_hash();
+        }
+        for (block_hash, height) in final_blocks.into_iter().rev() {
+            let _ = self.sender.send(ChainEvent::FinalBlock { block_hash, height });
+        }
+    }
+}
@@ -20,22 +20,29 @@ use near_chain::chain::{
 };
 use near_chain::test_utils::format_hash;
 use near_chain::types::LatestKnown;
+use near_chain::validate::validate_challenge;
 use near_chain::{
     BlockProcessingArtifact, BlockStatus, Chain, ChainGenesis, ChainStoreAccess,
     DoneApplyChunkCallback, Doomslug, DoomslugThresholdMode, Provenance, RuntimeAdapter,
 };
-use near_chain_configs::ClientConfig;
+use near_chain_configs::{ClientConfig, ExternalStorageLocation};
 use near_chunks::ShardsManager;
 use near_network::types::{FullPeerInfo, NetworkRequests, PeerManagerAdapter, ReasonForBan};
+use near_pool::types::InsertTransactionResult;
 use near_primitives::block::{Approval, ApprovalInner, ApprovalMessage, Block, BlockHeader, Tip};
 use near_primitives::challenge::{Challenge, ChallengeBody};
+use near_primitives::checked_feature;
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
+use near_primitives::merkle::{
+    merkle_hash_algorithm, merklize, merklize_with, MerklePath, PartialMerkleTree,
+};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{
     ChunkHash, EncodedShardChunk, PartialEncodedChunk, ReedSolomonWrapper, ShardChunk,
     ShardChunkHeader, ShardInfo,
 };
+use near_primitives::state_part::PartId;
+use near_primitives::syncing::get_num_state_parts;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, ApprovalStake, BlockHeight, EpochId, NumBlocks, ShardId};
@@ -46,6 +53,7 @@ use near_primitives::validator_signer::ValidatorSigner;
 use crate::adapter::ProcessTxResponse;
 use crate::debug::BlockProductionTracker;
 use crate::debug::PRODUCTION_TIMES_CACHE_SIZE;
+use crate::state_parts::{new_state_part_provider, StatePartProvider};
 use crate::sync::{BlockSync, EpochSync, HeaderSync, StateSync, StateSyncResult};
 use crate::{metrics, SyncStatus};
 use near_client_primitives::types::{Error, ShardSyncDownload, ShardSyncStatus};
@@ -58,6 +66,9 @@ use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{CatchupStatusView, DroppedReason};
 
 const NUM_REBROADCAST_BLOCKS: usize = 30;
+/// Maximum number of challenges kept to be included in the next produced block. Only block
+/// producers drain them, so the rest of the nodes evict the least recently seen ones.
+const MAX_PENDING_CHALLENGES: usize = 128;
 
 /// The time we wait for the response to a Epoch Sync request before retrying
 // TODO #3488 set 30_000
@@ -112,8 +123,13 @@ pub struct Client {
     pub block_sync: BlockSync,
     /// Keeps track of syncing state.
     pub state_sync: StateSync,
+    /// Storage to download state parts from instead of the peers.
+    pub state_parts_source: Option<Arc<dyn StatePartProvider>>,
+    /// Storage to dump the state parts of the tracked shards to at the start
+    /// of every epoch.
+    pub state_parts_dump: Option<Arc<dyn StatePartProvider>>,
     /// List of currently accumulated challenges.
-    pub challenges: HashMap<CryptoHash, Challenge>,
+    pub challenges: lru::LruCache<CryptoHash, Challenge>,
     /// A ReedSolomon instance to reconstruct shard.
     pub rs_for_chunk_production: ReedSolomonWrapper,
     /// Blocks that have been re-broadcast recently. They should not be broadcast again.
@@ -188,13 +204,13 @@ impl Client {
             chain.store().new_read_only_chunks_store(),
             chain.head().ok(),
         );
-        let sharded_tx_pool = ShardedTransactionPool::new(rng_seed);
+        let sharded_tx_pool =
+            ShardedTransactionPool::new(rng_seed, config.transaction_pool.clone());
         let sync_status = SyncStatus::AwaitingPeers;
         let genesis_block = chain.genesis_block();
         let epoch_sync = EpochSync::new(
             network_adapter.clone(),
             genesis_block.header().epoch_id().clone(),
-            genesis_block.header().next_epoch_id().clone(),
             runtime_adapter
                 .get_epoch_block_producers_ordered(
                     genesis_block.header().epoch_id(),
@@ -215,7 +231,18 @@ impl Client {
         );
         let block_sync =
             BlockSync::new(network_adapter.clone(), config.block_fetch_horizon, config.archive);
-        let state_sync = StateSync::new(network_adapter.clone(), config.state_sync_timeout);
+        let new_provider = |location: &ExternalStorageLocation| {
+            new_state_part_provider(location, &config.chain_id)
+                .map_err(|err| Error::Other(format!("state part storage: {}", err)))
+        };
+        let state_parts_source =
+            config.state_sync.sync_from.as_ref().map(new_provider).transpose()?;
+        let state_parts_dump = config.state_sync.dump.as_ref().map(new_provider).transpose()?;
+        let state_sync = StateSync::new(
+            network_adapter.clone(),
+            config.state_sync_timeout,
+            state_parts_source.clone(),
+        );
         let num_block_producer_seats = config.num_block_producer_seats as usize;
         let data_parts = runtime_adapter.num_data_parts();
         let parity_parts = runtime_adapter.num_total_parts() - data_parts;
@@ -254,7 +281,9 @@ impl Client {
             header_sync,
             block_sync,
             state_sync,
-            challenges: Default::default(),
+            state_parts_source,
+            state_parts_dump,
+            challenges: lru::LruCache::new(MAX_PENDING_CHALLENGES),
             rs_for_chunk_production: ReedSolomonWrapper::new(data_parts, parity_parts),
             rebroadcasted_blocks: lru::LruCache::new(NUM_REBROADCAST_BLOCKS),
             last_time_head_progress_made: Clock::instant(),
@@ -280,6 +309,59 @@ impl Client {
         Ok(())
     }
 
+    /// If `block` is the first block of an epoch, dumps the state parts of the
+    /// shards this node tracked in the previous epoch in the background, so
+    /// that other nodes can state sync to `block` from the dump.
+    fn dump_state_parts(&self, block: &Block) -> Result<(), Error> {
+        let state_parts_dump = match &self.state_parts_dump {
+            Some(state_parts_dump) => state_parts_dump,
+            None => return Ok(()),
+        };
+        let sync_hash = *block.hash();
+        let prev_block = self.chain.get_block(block.header().prev_hash())?;
+        if prev_block.header().epoch_id() == block.header().epoch_id() {
+            return Ok(());
+        }
+        let sync_prev_hash = *prev_block.hash();
+        for (shard_id, chunk) in prev_block.chunks().iter().enumerate() {
+            let shard_id = shard_id as ShardId;
+            if !self.runtime_adapter.cares_about_shard(
+                self.me.as_ref(),
+                &sync_prev_hash,
+                shard_id,
+                true,
+            ) {
+                continue;
+            }
+            // The same state as served by `Chain::get_state_response_part`.
+            let state_root = chunk.prev_state_root();
+            let state_root_node =
+                self.runtime_adapter.get_state_root_node(shard_id, &sync_prev_hash, &state_root)?;
+            let num_parts = get_num_state_parts(state_root_node.memory_usage);
+            let runtime_adapter = self.runtime_adapter.clone();
+            let state_parts_dump = state_parts_dump.clone();
+            std::thread::spawn(move || {
+                for part_id in 0..num_parts {
+                    let part_id = PartId::new(part_id, num_parts);
+                    let result = runtime_adapter
+                        .obtain_state_part(shard_id, &sync_prev_hash, &state_root, part_id)
+                        .map_err(|err| err.to_string())
+                        .and_then(|part| {
+                            state_parts_dump
+                                .put_part(&sync_hash, shard_id, part_id, &part)
+                                .map_err(|err| err.to_string())
+                        });
+                    if let Err(err) = result {
+                        error!(target: "client", %sync_hash, shard_id, part_id = part_id.idx, "Failed to dump state part: {err}");
+                        return;
+                    }
+                }
+                info!(target: "client", %sync_hash, shard_id, num_parts, "Dumped state parts");
+            });
+        }
+        Ok(())
+    }
+
     pub fn remove_transactions_for_block(&mut self, me: AccountId, block: &Block) {
         for (shard_id, chunk_header) in block.chunks().iter().enumerate() {
             let shard_id = shard_id as ShardId;
@@ -300,7 +382,7 @@ impl Client {
             }
         }
         for challenge in block.challenges().iter() {
-            self.challenges.remove(&challenge.hash);
+            self.challenges.pop(&challenge.hash);
         }
     }
 
@@ -324,7 +406,7 @@ impl Client {
             }
         }
         for challenge in block.challenges().iter() {
-            self.challenges.insert(challenge.hash, challenge.clone());
+            self.challenges.put(challenge.hash, challenge.clone());
         }
     }
 
@@ -534,8 +616,9 @@ impl Client {
         // Get block extra from previous block.
         let block_merkle_tree = self.chain.store().get_block_merkle_tree(&prev_hash)?;
         let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-        block_merkle_tree.insert(prev_hash);
-        let block_merkle_root = block_merkle_tree.root();
+        let block_merkle_hash_algorithm = merkle_hash_algorithm(protocol_version);
+        block_merkle_tree.insert_with(block_merkle_hash_algorithm, prev_hash);
+        let block_merkle_root = block_merkle_tree.root_with(block_merkle_hash_algorithm);
         // The number of leaves in Block Merkle Tree is the amount of Blocks on the Canonical Chain by construction.
         // The ordinal of the next Block will be equal to this amount plus one.
         let block_ordinal: NumBlocks = block_merkle_tree.size() + 1;
@@ -584,11 +667,31 @@ impl Client {
                 None
             };
 
-        // Get all the current challenges.
-        // TODO(2445): Enable challenges when they are working correctly.
-        // let challenges = self.challenges.drain().map(|(_, challenge)| challenge).collect();
         let this_epoch_protocol_version =
             self.runtime_adapter.get_epoch_protocol_version(&epoch_id)?;
+        // Get all the current challenges. The ones which can't be verified on top of the previous
+        // block, e.g. because they challenge a block of another fork, would make the block invalid.
+        let challenges = if checked_feature!(
+            "protocol_feature_chunk_state_challenges",
+            ChunkStateChallenges,
+            this_epoch_protocol_version
+        ) {
+            let challenges = self
+                .challenges
+                .iter()
+                .map(|(_, challenge)| challenge)
+                .filter(|challenge| {
+                    self.chain
+                        .verify_challenges(std::slice::from_ref(*challenge), &epoch_id, &prev_hash)
+                        .is_ok()
+                })
+                .cloned()
+                .collect();
+            self.challenges.clear();
+            challenges
+        } else {
+            vec![]
+        };
         let next_epoch_protocol_version =
             self.runtime_adapter.get_epoch_protocol_version(&next_epoch_id)?;
 
@@ -608,7 +711,7 @@ impl Client {
             max_gas_price,
             minted_amount,
             prev_block_extra.challenges_result.clone(),
-            vec![],
+            challenges,
             &*validator_signer,
             next_bp_hash,
             block_merkle_root,
@@ -702,9 +805,10 @@ impl Client {
         let shard_layout = self.runtime_adapter.get_shard_layout(epoch_id)?;
         let outgoing_receipts_hashes =
             Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout);
-        let (outgoing_receipts_root, _) = merklize(&outgoing_receipts_hashes);
-
         let protocol_version = self.runtime_adapter.get_epoch_protocol_version(epoch_id)?;
+        let (outgoing_receipts_root, _) =
+            merklize_with(merkle_hash_algorithm(protocol_version), &outgoing_receipts_hashes);
+
         let (encoded_chunk, merkle_paths) = ShardsManager::create_encoded_shard_chunk(
             prev_block_hash,
             *chunk_extra.state_root(),
@@ -798,7 +902,7 @@ impl Client {
         if let Some(validator_signer) = &self.validator_signer {
             for body in challenges {
                 let challenge = Challenge::produce(body, &**validator_signer);
-                self.challenges.insert(challenge.hash, challenge.clone());
+                self.challenges.put(challenge.hash, challenge.clone());
                 self.network_adapter.do_send(
                     PeerManagerMessageRequest::NetworkRequests(NetworkRequests::Challenge(
                         challenge,
@@ -1001,34 +1105,17 @@ impl Client {
 
         self.process_block_processing_artifact(block_processing_artifacts);
 
-        // Send out challenge if the block was found to be invalid.
-        if let Some(validator_signer) = self.validator_signer.as_ref() {
-            if let Err(e) = &result {
-                match e {
-                    near_chain::Error::InvalidChunkProofs(chunk_proofs) => {
-                        self.network_adapter.do_send(
-                            PeerManagerMessageRequest::NetworkRequests(NetworkRequests::Challenge(
-                                Challenge::produce(
-                                    ChallengeBody::ChunkProofs(*chunk_proofs.clone()),
-                                    &**validator_signer,
-                                ),
-                            ))
-                            .with_span_context(),
-                        );
-                    }
-                    near_chain::Error::InvalidChunkState(chunk_state) => {
-                        self.network_adapter.do_send(
-                            PeerManagerMessageRequest::NetworkRequests(NetworkRequests::Challenge(
-                                Challenge::produce(
-                                    ChallengeBody::ChunkState(*chunk_state.clone()),
-                                    &**validator_signer,
-                                ),
-                            ))
-                            .with_span_context(),
-                        );
-                    }
-                    _ => {}
+        // Send out challenge if the block was found to be invalid. The challenge is also kept to be
+        // included in the next block we produce.
+        if let Err(e) = &result {
+            match e {
+                near_chain::Error::InvalidChunkProofs(chunk_proofs) => {
+                    self.send_challenges(vec![ChallengeBody::ChunkProofs(*chunk_proofs.clone())]);
                 }
+                near_chain::Error::InvalidChunkState(challenge_body) => {
+                    self.send_challenges(vec![*challenge_body.clone()]);
+                }
+                _ => {}
             }
         }
 
@@ -1299,6 +1386,10 @@ impl Client {
             if let Err(err) = self.send_network_chain_info() {
                 error!(target:"client","Failed to update network chain info: {err}");
             }
+
+            if let Err(err) = self.dump_state_parts(&block) {
+                error!(target: "client", "Failed to dump state parts: {err}");
+            }
         }
 
         if let Some(validator_signer) = self.validator_signer.clone() {
@@ -1832,8 +1923,8 @@ impl Client {
                 // TODO #6713: Transactions don't need to be recorded if the node is not a validator
                 // for the shard.
                 // If I'm not an active validator I should forward tx to next validators.
-                self.sharded_tx_pool.insert_transaction(shard_id, tx.clone());
-                trace!(target: "client", shard_id, "Recorded a transaction.");
+                let insert_result = self.sharded_tx_pool.insert_transaction(shard_id, tx.clone());
+                trace!(target: "client", shard_id, ?insert_result, "Recorded a transaction.");
 
                 // Active validator:
                 //   possibly forward to next epoch validators
@@ -1843,6 +1934,14 @@ impl Client {
                 if active_validator {
                     trace!(target: "client", account = ?me, shard_id, is_forwarded, "Recording a transaction.");
                     metrics::TRANSACTION_RECEIVED_VALIDATOR.inc();
+                    match insert_result {
+                        InsertTransactionResult::Success | InsertTransactionResult::Duplicate => {}
+                        InsertTransactionResult::NoSpaceLeft
+                        | InsertTransactionResult::GroupLimitExceeded => {
+                            debug!(target: "client", shard_id, ?insert_result, "Transaction pool rejected a transaction.");
+                            return Ok(ProcessTxResponse::PoolRejected(insert_result));
+                        }
+                    }
 
                     if !is_forwarded {
                         self.possibly_forward_tx_to_next_epoch(tx)?;
@@ -1946,11 +2045,12 @@ impl Client {
                 }
             };
             let state_sync_timeout = self.config.state_sync_timeout;
+            let state_parts_source = self.state_parts_source.clone();
             let epoch_id = self.chain.get_block(&sync_hash)?.header().epoch_id().clone();
             let (state_sync, new_shard_sync, blocks_catch_up_state) =
                 self.catchup_state_syncs.entry(sync_hash).or_insert_with(|| {
                     (
-                        StateSync::new(network_adapter1, state_sync_timeout),
+                        StateSync::new(network_adapter1, state_sync_timeout, state_parts_source),
                         new_shard_sync,
                         BlocksCatchUpState::new(sync_hash, epoch_id),
                     )
@@ -2006,30 +2106,44 @@ impl Client {
         Ok(())
     }
 
-    /// When accepting challenge, we verify that it's valid given signature with current validators.
-    pub fn process_challenge(&mut self, _challenge: Challenge) -> Result<(), Error> {
-        // TODO(2445): Enable challenges when they are working correctly.
-        //        if self.challenges.contains_key(&challenge.hash) {
-        //            return Ok(());
-        //        }
-        //        debug!(target: "client", "Received challenge: {:?}", challenge);
-        //        let head = self.chain.head()?;
-        //        if self.runtime_adapter.verify_validator_or_fisherman_signature(
-        //            &head.epoch_id,
-        //            &head.prev_block_hash,
-        //            &challenge.account_id,
-        //            challenge.hash.as_ref(),
-        //            &challenge.signature,
-        //        )? {
-        //            // If challenge is not double sign, we should process it right away to invalidate the chain.
-        //            match challenge.body {
-        //                ChallengeBody::BlockDoubleSign(_) => {}
-        //                _ => {
-        //                    self.chain.process_challenge(&challenge);
-        //                }
-        //            }
-        //            self.challenges.insert(challenge.hash, challenge);
-        //        }
+    /// When accepting challenge, we verify its signature with current validators and its body.
+    /// Only a valid challenge is gossiped further and kept to be included in the next block we
+    /// produce. Challenges are ignored before `ChunkStateChallenges`.
+    pub fn process_challenge(&mut self, challenge: Challenge) -> Result<(), Error> {
+        if self.challenges.contains(&challenge.hash) {
+            return Ok(());
+        }
+        debug!(target: "client", ?challenge, "Received challenge");
+        let head = self.chain.head()?;
+        let protocol_version = self.runtime_adapter.get_epoch_protocol_version(&head.epoch_id)?;
+        if !checked_feature!(
+            "protocol_feature_chunk_state_challenges",
+            ChunkStateChallenges,
+            protocol_version
+        ) {
+            return Ok(());
+        }
+        // A malicious challenge only slashes its author, it must not be spread further.
+        validate_challenge(
+            &*self.runtime_adapter,
+            &head.epoch_id,
+            &head.last_block_hash,
+            &challenge,
+        )?;
+        // If challenge is not double sign, we should process it right away to invalidate the chain.
+        match challenge.body {
+            ChallengeBody::BlockDoubleSign(_) => {}
+            _ => {
+                self.chain.process_challenge(&challenge);
+            }
+        }
+        self.network_adapter.do_send(
+            PeerManagerMessageRequest::NetworkRequests(NetworkRequests::Challenge(
+                challenge.clone(),
+            ))
+            .with_span_context(),
+        );
+        self.challenges.put(challenge.hash, challenge);
         Ok(())
     }
 }
@@ -7,9 +7,11 @@
 
 use crate::adapter::{
     BlockApproval, BlockHeadersResponse, BlockResponse, ProcessTxRequest, ProcessTxResponse,
-    RecvChallenge, RecvPartialEncodedChunk, RecvPartialEncodedChunkForward,
-    RecvPartialEncodedChunkRequest, RecvPartialEncodedChunkResponse, SetNetworkInfo, StateResponse,
+    RecvChallenge, RecvEpochSyncFinalizationResponse, RecvEpochSyncResponse,
+    RecvPartialEncodedChunk, RecvPartialEncodedChunkForward, RecvPartialEncodedChunkRequest,
+    RecvPartialEncodedChunkResponse, SetNetworkInfo, StateResponse,
 };
+use crate::chain_events::ChainEvents;
 use crate::client::{Client, EPOCH_START_INFO_BLOCKS};
 use crate::info::{
     display_sync_status, get_validator_epoch_stats, InfoHelper, ValidatorInfoHelper,
@@ -18,7 +20,7 @@ use crate::metrics::PARTIAL_ENCODED_CHUNK_RESPONSE_DELAY;
 use crate::sync::{StateSync, StateSyncResult};
 use crate::{metrics, StatusResponse};
 use actix::dev::SendError;
-use actix::{Actor, Addr, Arbiter, AsyncContext, Context, Handler, Message};
+use actix::{Actor, Addr, Arbiter, AsyncContext, Context, Handler, Message, MessageResult};
 use actix_rt::ArbiterHandle;
 use borsh::BorshSerialize;
 use chrono::DateTime;
@@ -37,8 +39,9 @@ use near_chain_configs::ClientConfig;
 use near_chunks::client::ShardsManagerResponse;
 use near_chunks::logic::cares_about_shard_this_or_next_epoch;
 use near_client_primitives::types::{
-    Error, GetNetworkInfo, NetworkInfoResponse, ShardSyncDownload, ShardSyncStatus, Status,
-    StatusError, StatusSyncInfo, SyncStatus,
+    Error, GetMempoolTransaction, GetMempoolTransactionHashes, GetNetworkInfo, NetworkInfoResponse,
+    ShardSyncDownload, ShardSyncStatus, Status, StatusError, StatusSyncInfo, SubscribeChainEvents,
+    SyncStatus,
 };
 #[cfg(feature = "test_features")]
 use near_network::types::NetworkAdversarialMessage;
@@ -56,6 +59,7 @@ use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::state_part::PartId;
 use near_primitives::syncing::StatePartKey;
 use near_primitives::time::{Clock, Utc};
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{BlockHeight, ValidatorInfoIdentifier};
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::{from_timestamp, MaybeValidated};
@@ -95,6 +99,8 @@ pub struct ClientActor {
     last_validator_announce_time: Option<Instant>,
     /// Info helper.
     info_helper: InfoHelper,
+    /// Notifies the subscribers about the new heads and final blocks.
+    chain_events: ChainEvents,
 
     /// Last time handle_block_production method was called
     block_production_next_attempt: DateTime<Utc>,
@@ -200,6 +206,7 @@ impl ClientActor {
             },
             last_validator_announce_time: None,
             info_helper,
+            chain_events: ChainEvents::new(),
             block_production_next_attempt: now,
             log_summary_timer_next_attempt: now,
             block_production_started: false,
@@ -487,6 +494,60 @@ impl Handler<WithSpanContext<BlockHeadersResponse>> for ClientActor {
     }
 }
 
+impl Handler<WithSpanContext<RecvEpochSyncResponse>> for ClientActor {
+    type Result = Result<(), ReasonForBan>;
+
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<RecvEpochSyncResponse>,
+        ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        self.wrap(msg, ctx, "RecvEpochSyncResponse", |this, msg| {
+            let RecvEpochSyncResponse(response, peer_id) = msg;
+            match this.client.epoch_sync.on_response(&mut this.client.chain, peer_id, *response) {
+                Ok(()) => Ok(()),
+                Err(err) if err.is_bad_data() => {
+                    warn!(target: "client", ?err, "Banning node for sending invalid epoch sync response");
+                    Err(ReasonForBan::BadBlockHeader)
+                }
+                Err(err) => {
+                    error!(target: "client", ?err, "Failed to process epoch sync response");
+                    Ok(())
+                }
+            }
+        })
+    }
+}
+
+impl Handler<WithSpanContext<RecvEpochSyncFinalizationResponse>> for ClientActor {
+    type Result = Result<(), ReasonForBan>;
+
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<RecvEpochSyncFinalizationResponse>,
+        ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        self.wrap(msg, ctx, "RecvEpochSyncFinalizationResponse", |this, msg| {
+            let RecvEpochSyncFinalizationResponse(response, peer_id) = msg;
+            match this.client.epoch_sync.on_finalization_response(
+                &mut this.client.chain,
+                peer_id,
+                *response,
+            ) {
+                Ok(()) => Ok(()),
+                Err(err) if err.is_bad_data() => {
+                    warn!(target: "client", ?err, "Banning node for sending invalid epoch sync finalization response");
+                    Err(ReasonForBan::BadBlockHeader)
+                }
+                Err(err) => {
+                    error!(target: "client", ?err, "Failed to process epoch sync finalization response");
+                    Ok(())
+                }
+            }
+        })
+    }
+}
+
 impl Handler<WithSpanContext<BlockApproval>> for ClientActor {
     type Result = ();
 
@@ -932,6 +993,48 @@ impl Handler<WithSpanContext<GetNetworkInfo>> for ClientActor {
     }
 }
 
+impl Handler<WithSpanContext<GetMempoolTransactionHashes>> for ClientActor {
+    type Result = Vec<CryptoHash>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetMempoolTransactionHashes>,
+        _ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        let (_span, _msg) = handler_debug_span!(target: "client", msg);
+        self.client.sharded_tx_pool.transaction_hashes()
+    }
+}
+
+impl Handler<WithSpanContext<SubscribeChainEvents>> for ClientActor {
+    type Result = MessageResult<WithSpanContext<SubscribeChainEvents>>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<SubscribeChainEvents>,
+        _ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        let (_span, _msg) = handler_debug_span!(target: "client", msg);
+        MessageResult(self.chain_events.subscribe())
+    }
+}
+
+impl Handler<WithSpanContext<GetMempoolTransaction>> for ClientActor {
+    type Result = Option<SignedTransaction>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetMempoolTransaction>,
+        _ctx: &mut Context<Self>,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        self.client.sharded_tx_pool.get_transaction(&msg.transaction_hash).cloned()
+    }
+}
+
 /// `ApplyChunksDoneMessage` is a message that signals the finishing of applying chunks of a block.
 /// Upon receiving this message, ClientActors knows that it's time to finish processing the blocks that
 /// just finished applying chunks.
@@ -1419,6 +1522,7 @@ impl ClientActor {
             );
             self.check_send_announce_account(*last_final_hash);
         }
+        self.chain_events.update(&self.client.chain);
     }
 
     /// Returns the callback function that will be passed to various functions that may trigger
@@ -1529,8 +1633,17 @@ impl ClientActor {
     /// so this is only relevant on epoch boundaries.
     fn find_sync_hash(&mut self) -> Result<CryptoHash, near_chain::Error> {
         let header_head = self.client.chain.header_head()?;
+        // Headers before the block Epoch Sync finished at are unknown, so never look past it.
+        let epoch_sync_hash = Some(self.client.epoch_sync.sync_hash)
+            .filter(|hash| self.client.epoch_sync.done && hash != &CryptoHash::default());
         let mut sync_hash = header_head.prev_block_hash;
+        if Some(header_head.last_block_hash) == epoch_sync_hash {
+            sync_hash = header_head.last_block_hash;
+        }
         for _ in 0..self.client.config.state_fetch_horizon {
+            if Some(sync_hash) == epoch_sync_hash {
+                break;
+            }
             sync_hash = *self.client.chain.get_block_header(&sync_hash)?.prev_hash();
         }
         let mut epoch_start_sync_hash =
@@ -1641,6 +1754,23 @@ impl ClientActor {
             wait_period = self.client.config.sync_check_period;
         } else {
             // Run each step of syncing separately.
+            if self.client.config.epoch_sync_enabled {
+                unwrap_or_run_later!(self.client.epoch_sync.run(
+                    &mut self.client.sync_status,
+                    &self.client.chain,
+                    &self.network_info.highest_height_peers
+                ));
+                if !self.client.epoch_sync.done {
+                    near_performance_metrics::actix::run_later(
+                        ctx,
+                        wait_period,
+                        move |act, ctx| {
+                            act.sync(ctx);
+                        },
+                    );
+                    return;
+                }
+            }
             unwrap_or_run_later!(self.client.header_sync.run(
                 &mut self.client.sync_status,
                 &mut self.client.chain,
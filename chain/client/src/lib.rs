@@ -1,10 +1,11 @@
 pub use near_client_primitives::types::{
-    Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunk,
-    GetExecutionOutcome, GetExecutionOutcomeResponse, GetExecutionOutcomesForBlock, GetGasPrice,
-    GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
+    ChainEvent, Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree,
+    GetChunk, GetExecutionOutcome, GetExecutionOutcomeResponse, GetExecutionOutcomesForBlock,
+    GetGasPrice, GetMempoolTransaction, GetMempoolTransactionHashes, GetNetworkInfo,
+    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
     GetStateChangesInBlock, GetStateChangesWithCauseInBlock,
     GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered, Query,
-    QueryError, Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    QueryError, Status, StatusResponse, SubscribeChainEvents, SyncStatus, TxStatus, TxStatusError,
 };
 
 pub use near_client_primitives::debug::DebugStatus;
@@ -18,12 +19,14 @@ pub use crate::view_client::{start_view_client, ViewClientActor};
 
 pub mod adapter;
 pub mod adversarial;
+mod chain_events;
 mod client;
 mod client_actor;
 pub mod debug;
 mod info;
 mod metrics;
 mod rocksdb_metrics;
+pub mod state_parts;
 pub mod sync;
 pub mod test_utils;
 #[cfg(test)]
@@ -0,0 +1,419 @@
+//! Storage of state parts outside of the node and its peers.
+//!
+//! Nodes configured with `state_sync.dump` write the parts of the state of
+//! their shards at the start of every epoch, and nodes configured with
+//! `state_sync.sync_from` download parts from there instead of requesting them
+//! from the peers.  Parts are keyed by
+//! `<chain_id>/<sync_hash>/shard_<shard_id>/part_<part_id>_of_<num_parts>`.
+//! Downloaded parts are validated against the state root exactly like the
+//! parts received from the peers, so the storage doesn't need to be trusted.
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+
+use near_chain_configs::ExternalStorageLocation;
+use near_primitives::hash::CryptoHash;
+use near_primitives::state_part::PartId;
+use near_primitives::time::Clock;
+use near_primitives::types::ShardId;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StatePartProviderError {
+    #[error("state part {0} not found")]
+    NotFound(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+}
+
+/// Where state parts are read from and written to.
+pub trait StatePartProvider: Send + Sync {
+    fn get_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+    ) -> Result<Vec<u8>, StatePartProviderError>;
+
+    fn put_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+        data: &[u8],
+    ) -> Result<(), StatePartProviderError>;
+}
+
+/// Creates the provider for `location` which stores the parts of `chain_id`.
+pub fn new_state_part_provider(
+    location: &ExternalStorageLocation,
+    chain_id: &str,
+) -> Result<Arc<dyn StatePartProvider>, StatePartProviderError> {
+    Ok(match location {
+        ExternalStorageLocation::Filesystem { root_dir } => {
+            Arc::new(FilesystemStatePartProvider::new(root_dir.clone(), chain_id))
+        }
+        ExternalStorageLocation::S3 { endpoint, bucket, region, credentials_file } => {
+            let credentials = match credentials_file {
+                Some(path) => Some(S3Credentials::from_file(path)?),
+                None => None,
+            };
+            Arc::new(S3StatePartProvider::new(endpoint, bucket, region, credentials, chain_id))
+        }
+    })
+}
+
+fn part_key(chain_id: &str, sync_hash: &CryptoHash, shard_id: ShardId, part_id: PartId) -> String {
+    format!(
+        "{}/{}/shard_{}/part_{:06}_of_{:06}",
+        chain_id, sync_hash, shard_id, part_id.idx, part_id.total
+    )
+}
+
+/// Keeps the parts as files under a local directory.
+pub struct FilesystemStatePartProvider {
+    root_dir: PathBuf,
+    chain_id: String,
+}
+
+impl FilesystemStatePartProvider {
+    pub fn new(root_dir: PathBuf, chain_id: &str) -> Self {
+        Self { root_dir, chain_id: chain_id.to_string() }
+    }
+
+    fn path(&self, sync_hash: &CryptoHash, shard_id: ShardId, part_id: PartId) -> PathBuf {
+        self.root_dir.join(part_key(&self.chain_id, sync_hash, shard_id, part_id))
+    }
+}
+
+impl StatePartProvider for FilesystemStatePartProvider {
+    fn get_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+    ) -> Result<Vec<u8>, StatePartProviderError> {
+        let path = self.path(sync_hash, shard_id, part_id);
+        std::fs::read(&path).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                StatePartProviderError::NotFound(path.display().to_string())
+            }
+            _ => err.into(),
+        })
+    }
+
+    fn put_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+        data: &[u8],
+    ) -> Result<(), StatePartProviderError> {
+        let path = self.path(sync_hash, shard_id, part_id);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        // Readers must never see a partially written part.
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct S3Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+impl S3Credentials {
+    fn from_file(path: &Path) -> Result<Self, StatePartProviderError> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data).map_err(|err| {
+            let msg = format!("invalid credentials file {}: {}", path.display(), err);
+            std::io::Error::new(std::io::ErrorKind::InvalidData, msg).into()
+        })
+    }
+}
+
+/// Keeps the parts as objects of a bucket of an S3-compatible object store.
+/// Requests are signed with AWS Signature Version 4 if credentials are given.
+pub struct S3StatePartProvider {
+    /// Created on the first request, as the blocking client panics if it is
+    /// created in an async context such as the client actor.
+    client: OnceCell<reqwest::blocking::Client>,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    credentials: Option<S3Credentials>,
+    chain_id: String,
+}
+
+impl S3StatePartProvider {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        credentials: Option<S3Credentials>,
+        chain_id: &str,
+    ) -> Self {
+        Self {
+            client: OnceCell::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            credentials,
+            chain_id: chain_id.to_string(),
+        }
+    }
+
+    fn request(
+        &self,
+        method: reqwest::Method,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+        body: Vec<u8>,
+    ) -> Result<reqwest::blocking::Response, StatePartProviderError> {
+        let path =
+            format!("/{}/{}", self.bucket, part_key(&self.chain_id, sync_hash, shard_id, part_id));
+        let url = reqwest::Url::parse(&format!("{}{}", self.endpoint, path))
+            .map_err(|err| StatePartProviderError::Http(err.to_string()))?;
+        let client = self.client.get_or_init(reqwest::blocking::Client::new);
+        let mut request = client.request(method.clone(), url.clone());
+        if let Some(credentials) = &self.credentials {
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                None => url.host_str().unwrap_or_default().to_string(),
+            };
+            let payload_hash = hex::encode(Sha256::digest(&body));
+            let now = Clock::utc();
+            let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+            let authorization = sign_v4(
+                credentials,
+                &self.region,
+                method.as_str(),
+                url.path(),
+                &host,
+                &payload_hash,
+                &amz_date,
+            );
+            request = request
+                .header("x-amz-content-sha256", payload_hash)
+                .header("x-amz-date", amz_date)
+                .header("authorization", authorization);
+        }
+        request
+            .body(body)
+            .send()
+            .map_err(|err| StatePartProviderError::Http(err.to_string()))?
+            .error_for_status()
+            .map_err(|err| match err.status() {
+                Some(reqwest::StatusCode::NOT_FOUND) => StatePartProviderError::NotFound(path),
+                _ => StatePartProviderError::Http(err.to_string()),
+            })
+    }
+}
+
+impl StatePartProvider for S3StatePartProvider {
+    fn get_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+    ) -> Result<Vec<u8>, StatePartProviderError> {
+        let response =
+            self.request(reqwest::Method::GET, sync_hash, shard_id, part_id, Vec::new())?;
+        let data = response.bytes().map_err(|err| StatePartProviderError::Http(err.to_string()))?;
+        Ok(data.to_vec())
+    }
+
+    fn put_part(
+        &self,
+        sync_hash: &CryptoHash,
+        shard_id: ShardId,
+        part_id: PartId,
+        data: &[u8],
+    ) -> Result<(), StatePartProviderError> {
+        self.request(reqwest::Method::PUT, sync_hash, shard_id, part_id, data.to_vec())?;
+        Ok(())
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Returns the `Authorization` header of a request without a query string
+/// which signs the `host`, `x-amz-content-sha256` and `x-amz-date` headers.
+/// `path` must already be URI-encoded; part keys never need escaping.
+fn sign_v4(
+    credentials: &S3Credentials,
+    region: &str,
+    method: &str,
+    path: &str,
+    host: &str,
+    payload_hash: &str,
+    amz_date: &str,
+) -> String {
+    const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let mut key = hmac_sha256(format!("AWS4{}", credentials.secret_key).as_bytes(), date);
+    for step in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, step);
+    }
+    let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key, scope, SIGNED_HEADERS, signature
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+
+    fn check_round_trip(provider: &dyn StatePartProvider) {
+        let sync_hash = CryptoHash::hash_bytes(b"sync");
+        let part_id = PartId::new(2, 5);
+        assert!(matches!(
+            provider.get_part(&sync_hash, 0, part_id),
+            Err(StatePartProviderError::NotFound(_))
+        ));
+        provider.put_part(&sync_hash, 0, part_id, b"state part").unwrap();
+        assert_eq!(provider.get_part(&sync_hash, 0, part_id).unwrap(), b"state part");
+        // Parts of other shards and other splits of the state are different objects.
+        assert!(provider.get_part(&sync_hash, 1, part_id).is_err());
+        assert!(provider.get_part(&sync_hash, 0, PartId::new(2, 6)).is_err());
+    }
+
+    #[test]
+    fn test_filesystem_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = FilesystemStatePartProvider::new(dir.path().to_path_buf(), "test-chain");
+        check_round_trip(&provider);
+        let sync_hash = CryptoHash::hash_bytes(b"sync");
+        let path =
+            dir.path().join(format!("test-chain/{}/shard_0/part_000002_of_000005", sync_hash));
+        assert_eq!(std::fs::read(path).unwrap(), b"state part");
+    }
+
+    /// A stand-in for an S3 bucket which keeps the objects in memory and
+    /// records the `Authorization` headers of the requests.
+    fn start_s3_stand_in() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let authorizations = Arc::new(Mutex::new(vec![]));
+        let recorded = authorizations.clone();
+        std::thread::spawn(move || {
+            let mut objects = HashMap::<String, Vec<u8>>::new();
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut request_line = request_line.split_whitespace();
+                let method = request_line.next().unwrap().to_string();
+                let path = request_line.next().unwrap().to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(": ").unwrap();
+                    match name.to_lowercase().as_str() {
+                        "content-length" => content_length = value.parse().unwrap(),
+                        "authorization" => recorded.lock().unwrap().push(value.to_string()),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let (status, body) = match (method.as_str(), objects.get(&path)) {
+                    ("PUT", _) => {
+                        objects.insert(path, body);
+                        ("200 OK", vec![])
+                    }
+                    ("GET", Some(object)) => ("200 OK", object.clone()),
+                    _ => ("404 Not Found", vec![]),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        (endpoint, authorizations)
+    }
+
+    #[test]
+    fn test_s3_provider() {
+        let (endpoint, authorizations) = start_s3_stand_in();
+        let provider = S3StatePartProvider::new(&endpoint, "bucket", "us-east-1", None, "test");
+        check_round_trip(&provider);
+        assert!(authorizations.lock().unwrap().is_empty());
+
+        let credentials = S3Credentials {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "secret".to_string(),
+        };
+        let provider =
+            S3StatePartProvider::new(&endpoint, "bucket", "us-east-1", Some(credentials), "signed");
+        check_round_trip(&provider);
+        let authorizations = authorizations.lock().unwrap();
+        assert!(!authorizations.is_empty());
+        for authorization in authorizations.iter() {
+            assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        }
+    }
+
+    /// The expected signature was computed with botocore's `SigV4Auth`.
+    #[test]
+    fn test_sign_v4() {
+        let credentials = S3Credentials {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+        };
+        let authorization = sign_v4(
+            &credentials,
+            "us-east-1",
+            "PUT",
+            "/bucket/test-chain/11111111111111111111111111111111/shard_0/part_000002_of_000005",
+            "127.0.0.1:9000",
+            &hex::encode(Sha256::digest(b"state part")),
+            "20260101T000000Z",
+        );
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260101/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, \
+             Signature=6cc0715e810ddfd30fd8a15e2a5035dd37d8f57c4f92813e65990f2983fb89fa"
+        );
+    }
+}
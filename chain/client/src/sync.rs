@@ -1,12 +1,16 @@
-use near_chain::{check_known, near_chain_primitives, ChainStoreAccess, Error};
+use near_chain::{
+    check_known, light_client_block_hash, near_chain_primitives, validate_light_client_block,
+    ChainStoreAccess, Error,
+};
 use std::cmp::min;
 use std::collections::{HashMap, HashSet};
 use std::ops::Add;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration as TimeDuration;
 
 use ansi_term::Color::{Purple, Yellow};
+use borsh::BorshSerialize;
 use chrono::{DateTime, Duration};
 use futures::{future, FutureExt};
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -16,15 +20,19 @@ use tracing::{debug, error, info, warn};
 use near_chain::{Chain, RuntimeAdapter};
 use near_network::types::{FullPeerInfo, NetworkRequests, NetworkResponses, PeerManagerAdapter};
 use near_primitives::block::Tip;
-use near_primitives::hash::CryptoHash;
+use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::network::PeerId;
-use near_primitives::syncing::get_num_state_parts;
+use near_primitives::syncing::{
+    get_num_state_parts, EpochSyncFinalizationResponse, EpochSyncResponse,
+};
 use near_primitives::time::{Clock, Utc};
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{
     AccountId, BlockHeight, BlockHeightDelta, EpochId, ShardId, StateRoot,
 };
 use near_primitives::utils::to_timestamp;
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::LightClientBlockView;
 
 use near_chain::chain::{ApplyStatePartsRequest, StateSplitRequest};
 use near_client_primitives::types::{
@@ -34,6 +42,9 @@ use near_network::types::AccountOrPeerIdOrHash;
 use near_network::types::PeerManagerMessageRequest;
 use near_o11y::WithSpanContextExt;
 use near_primitives::shard_layout::ShardUId;
+use near_primitives::state_part::PartId;
+
+use crate::state_parts::StatePartProvider;
 
 /// Maximum number of block headers send over the network.
 pub const MAX_BLOCK_HEADERS: u64 = 512;
@@ -48,29 +59,33 @@ const BLOCK_REQUEST_TIMEOUT: i64 = 2;
 
 /// Maximum number of state parts to request per peer on each round when node is trying to download the state.
 pub const MAX_STATE_PART_REQUEST: u64 = 16;
+/// Maximum number of state parts downloaded from the external storage at once.
+const MAX_EXTERNAL_STATE_PART_REQUESTS: usize = 16;
 /// Number of state parts already requested stored as pending.
 /// This number should not exceed MAX_STATE_PART_REQUEST times (number of peers in the network).
 pub const MAX_PENDING_PART: u64 = MAX_STATE_PART_REQUEST * 10000;
 
 pub const NS_PER_SECOND: u128 = 1_000_000_000;
 
-/// Helper to keep track of the Epoch Sync
-// TODO #3488
-#[allow(dead_code)]
+/// Helper to keep track of the Epoch Sync.
+///
+/// Starting from genesis, Epoch Sync downloads the light client block of the last final block
+/// of every epoch and validates it against the block producers attested by the previous one.
+/// Once a peer reports that it knows no later epoch, the headers and epoch data needed to start
+/// the chain at the first block of that epoch are requested, after which Header Sync and State
+/// Sync continue from that block.
 pub struct EpochSync {
     network_adapter: Arc<dyn PeerManagerAdapter>,
     /// Datastructure to keep track of when the last request to each peer was made.
     /// Peers do not respond to Epoch Sync requests more frequently than once per a certain time
     /// interval, thus there's no point in requesting more frequently.
     peer_to_last_request_time: HashMap<PeerId, DateTime<Utc>>,
-    /// Tracks all the peers who have reported that we are already up to date
-    peers_reporting_up_to_date: HashSet<PeerId>,
-    /// The last epoch we are synced to
+    /// The epoch whose light client block we need to sync next
     current_epoch_id: EpochId,
-    /// The next epoch id we need to sync
-    next_epoch_id: EpochId,
-    /// The block producers set to validate the light client block view for the next epoch
+    /// The block producers set to validate the light client block view of the current epoch
     next_block_producers: Vec<ValidatorStake>,
+    /// Light client block of the last synced epoch, i.e. the one preceding the current epoch
+    last_light_client_block: Option<LightClientBlockView>,
     /// The last epoch id that we have requested
     requested_epoch_id: EpochId,
     /// When and to whom was the last request made
@@ -82,16 +97,18 @@ pub struct EpochSync {
     /// How frequently to send request to the same peer
     peer_timeout: Duration,
 
-    /// True, if all peers agreed that we're at the last Epoch.
+    /// True, if a peer reported that we're at the last Epoch.
     /// Only finalization is needed.
     have_all_epochs: bool,
     /// Whether the Epoch Sync was performed to completion previously.
     /// Current state machine allows for only one Epoch Sync.
     pub done: bool,
 
+    /// The first block of the epoch Epoch Sync finished at.  State Sync is done for this block.
     pub sync_hash: CryptoHash,
 
-    received_epoch: bool,
+    /// Number of epochs synced so far.
+    epoch_ord: u64,
 
     is_just_started: bool,
 }
@@ -100,7 +117,6 @@ impl EpochSync {
     pub fn new(
         network_adapter: Arc<dyn PeerManagerAdapter>,
         genesis_epoch_id: EpochId,
-        genesis_next_epoch_id: EpochId,
         first_epoch_block_producers: Vec<ValidatorStake>,
         request_timeout: TimeDuration,
         peer_timeout: TimeDuration,
@@ -108,22 +124,226 @@ impl EpochSync {
         Self {
             network_adapter,
             peer_to_last_request_time: HashMap::new(),
-            peers_reporting_up_to_date: HashSet::new(),
             current_epoch_id: genesis_epoch_id.clone(),
-            next_epoch_id: genesis_next_epoch_id,
             next_block_producers: first_epoch_block_producers,
+            last_light_client_block: None,
             requested_epoch_id: genesis_epoch_id,
             last_request_time: Clock::utc(),
             last_request_peer_id: None,
             request_timeout: Duration::from_std(request_timeout).unwrap(),
             peer_timeout: Duration::from_std(peer_timeout).unwrap(),
-            received_epoch: false,
             have_all_epochs: false,
             done: false,
             sync_hash: CryptoHash::default(),
+            epoch_ord: 0,
             is_just_started: true,
         }
     }
+
+    /// Sends the next Epoch Sync request, unless one is still in flight.
+    /// Epoch Sync is only run by nodes which have nothing but the genesis block.
+    pub fn run(
+        &mut self,
+        sync_status: &mut SyncStatus,
+        chain: &Chain,
+        highest_height_peers: &[FullPeerInfo],
+    ) -> Result<(), near_chain::Error> {
+        let _span = tracing::debug_span!(target: "sync", "run", sync = "EpochSync").entered();
+        if self.is_just_started {
+            self.is_just_started = false;
+            if chain.header_head()?.height > chain.genesis().height() {
+                debug!(target: "sync", "Epoch Sync: skipped as the node already has headers");
+                self.done = true;
+            }
+        }
+        if self.done {
+            return Ok(());
+        }
+        *sync_status = SyncStatus::EpochSync { epoch_ord: self.epoch_ord };
+
+        let now = Clock::utc();
+        if self.last_request_peer_id.is_some()
+            && now - self.last_request_time < self.request_timeout
+        {
+            return Ok(());
+        }
+        let peer_timeout = self.peer_timeout;
+        let peer_to_last_request_time = &self.peer_to_last_request_time;
+        let peer_id = match highest_height_peers
+            .iter()
+            .map(|peer| &peer.peer_info.id)
+            .filter(|peer_id| {
+                peer_to_last_request_time
+                    .get(*peer_id)
+                    .map_or(true, |last_request_time| now - *last_request_time >= peer_timeout)
+            })
+            .choose(&mut thread_rng())
+        {
+            Some(peer_id) => peer_id.clone(),
+            None => return Ok(()),
+        };
+
+        debug!(target: "sync", epoch_id = ?self.current_epoch_id, %peer_id, have_all_epochs = self.have_all_epochs, "Epoch Sync: request");
+        self.requested_epoch_id = self.current_epoch_id.clone();
+        self.last_request_time = now;
+        self.last_request_peer_id = Some(peer_id.clone());
+        self.peer_to_last_request_time.insert(peer_id.clone(), now);
+        let epoch_id = self.current_epoch_id.clone();
+        let request = if self.have_all_epochs {
+            NetworkRequests::EpochSyncFinalizationRequest { epoch_id, peer_id }
+        } else {
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id }
+        };
+        self.network_adapter
+            .do_send(PeerManagerMessageRequest::NetworkRequests(request).with_span_context());
+        Ok(())
+    }
+
+    /// Returns whether `peer_id` is the peer the pending request was sent to.
+    fn is_expected_response(&self, peer_id: &PeerId) -> bool {
+        !self.done && self.last_request_peer_id.as_ref() == Some(peer_id)
+    }
+
+    /// Processes the light client block of the requested epoch, or a report that the peer knows
+    /// no later epoch.  Returns an error if the light client block is invalid.
+    pub fn on_response(
+        &mut self,
+        chain: &mut Chain,
+        peer_id: PeerId,
+        response: EpochSyncResponse,
+    ) -> Result<(), near_chain::Error> {
+        if !self.is_expected_response(&peer_id) || self.have_all_epochs {
+            debug!(target: "sync", %peer_id, "Epoch Sync: unexpected response");
+            return Ok(());
+        }
+        match response {
+            EpochSyncResponse::UpToDate => {
+                debug!(target: "sync", epoch_id = ?self.current_epoch_id, "Epoch Sync: all epochs received");
+                if self.last_light_client_block.is_none() {
+                    // The network is still in the genesis epoch, Header Sync will do.
+                    self.done = true;
+                } else {
+                    self.have_all_epochs = true;
+                }
+            }
+            EpochSyncResponse::Advance { light_client_block_view } => {
+                validate_light_client_block(
+                    &self.requested_epoch_id,
+                    &self.next_block_producers,
+                    &light_client_block_view,
+                )?;
+                // Keep the light client block so that this node can serve it to other peers.
+                let mut chain_store_update = chain.mut_store().store_update();
+                chain_store_update.save_epoch_light_client_block(
+                    &self.requested_epoch_id.0,
+                    light_client_block_view.clone(),
+                );
+                chain_store_update.commit()?;
+
+                self.current_epoch_id = EpochId(light_client_block_view.inner_lite.next_epoch_id);
+                self.next_block_producers = light_client_block_view
+                    .next_bps
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .map(ValidatorStakeView::into_validator_stake)
+                    .collect();
+                self.last_light_client_block = Some(light_client_block_view);
+                self.epoch_ord += 1;
+            }
+        }
+        self.last_request_peer_id = None;
+        Ok(())
+    }
+
+    /// Validates the headers and epoch data of the last epoch against the light client blocks
+    /// received so far and initializes the chain with them.
+    pub fn on_finalization_response(
+        &mut self,
+        chain: &mut Chain,
+        peer_id: PeerId,
+        response: EpochSyncFinalizationResponse,
+    ) -> Result<(), near_chain::Error> {
+        if !self.is_expected_response(&peer_id) || !self.have_all_epochs {
+            debug!(target: "sync", %peer_id, "Epoch Sync: unexpected finalization response");
+            return Ok(());
+        }
+        let prev_epoch_light_client_block = match &self.last_light_client_block {
+            Some(light_client_block) => light_client_block,
+            None => return Ok(()),
+        };
+        self.validate_finalization_response(prev_epoch_light_client_block, &response)?;
+
+        let sync_hash = *response.cur_epoch_header.hash();
+        chain.finalize_epoch_sync(response)?;
+        info!(target: "sync", epoch_id = ?self.current_epoch_id, %sync_hash, epochs = self.epoch_ord, "Epoch Sync: done");
+        self.done = true;
+        self.sync_hash = sync_hash;
+        self.last_request_peer_id = None;
+        Ok(())
+    }
+
+    fn validate_finalization_response(
+        &self,
+        prev_epoch_light_client_block: &LightClientBlockView,
+        response: &EpochSyncFinalizationResponse,
+    ) -> Result<(), near_chain::Error> {
+        let cur_epoch_header = &response.cur_epoch_header;
+        if cur_epoch_header.epoch_id() != &self.current_epoch_id {
+            return Err(Error::InvalidEpochSyncData);
+        }
+
+        // The headers must link the last validated light client block to the first block of
+        // the current epoch.
+        let prev_epoch_id = EpochId(prev_epoch_light_client_block.inner_lite.epoch_id);
+        let first_hash = light_client_block_hash(prev_epoch_light_client_block);
+        match response.prev_epoch_headers.first() {
+            Some(header) if header.hash() == &first_hash => {}
+            _ => return Err(Error::InvalidEpochSyncData),
+        }
+        for (header, next_header) in response
+            .prev_epoch_headers
+            .iter()
+            .zip(response.prev_epoch_headers.iter().skip(1).chain(Some(cur_epoch_header)))
+        {
+            if header.epoch_id() != &prev_epoch_id || next_header.prev_hash() != header.hash() {
+                return Err(Error::InvalidEpochSyncData);
+            }
+        }
+
+        // Epoch data is committed to in the first block of the epoch.
+        let mut data = response.prev_epoch_first_block_info.try_to_vec()?;
+        data.extend(response.prev_epoch_prev_last_block_info.try_to_vec()?);
+        data.extend(response.prev_epoch_last_block_info.try_to_vec()?);
+        data.extend(response.prev_epoch_info.try_to_vec()?);
+        data.extend(response.cur_epoch_info.try_to_vec()?);
+        data.extend(response.next_epoch_info.try_to_vec()?);
+        if cur_epoch_header.epoch_sync_data_hash() != Some(hash(&data)) {
+            return Err(Error::InvalidEpochSyncData);
+        }
+
+        let prev_epoch_last_header = response.prev_epoch_headers.last().unwrap();
+        if response.prev_epoch_last_block_info.hash() != prev_epoch_last_header.hash()
+            || response.prev_epoch_prev_last_block_info.hash() != prev_epoch_last_header.prev_hash()
+            || response.prev_epoch_first_block_info.hash()
+                != response.prev_epoch_last_block_info.epoch_first_block()
+        {
+            return Err(Error::InvalidEpochSyncData);
+        }
+
+        // The epoch info must agree with the block producers attested by the light client block.
+        let cur_epoch_info = &response.cur_epoch_info;
+        let mut seen = HashSet::new();
+        let block_producers = cur_epoch_info
+            .block_producers_settlement()
+            .iter()
+            .map(|&validator_id| cur_epoch_info.get_validator(validator_id))
+            .filter(|validator| seen.insert(validator.account_id().clone()));
+        if !block_producers.eq(self.next_block_producers.iter().cloned()) {
+            return Err(Error::InvalidEpochSyncData);
+        }
+        Ok(())
+    }
 }
 
 /// Helper to keep track of sync headers.
@@ -661,10 +881,22 @@ pub struct StateSync {
 
     /// Maps shard_id to result of splitting state for resharding
     split_state_roots: HashMap<ShardId, Result<HashMap<ShardUId, StateRoot>, Error>>,
+
+    /// Storage to download state parts from instead of the peers.
+    external_storage: Option<Arc<dyn StatePartProvider>>,
+    /// Parts downloaded from the external storage which are yet to be
+    /// validated and saved, by sync hash and shard id.
+    external_parts: Arc<Mutex<HashMap<(CryptoHash, ShardId), Vec<(u64, Vec<u8>)>>>>,
+    /// Number of parts being downloaded from the external storage.
+    external_requests_in_flight: Arc<AtomicUsize>,
 }
 
 impl StateSync {
-    pub fn new(network_adapter: Arc<dyn PeerManagerAdapter>, timeout: TimeDuration) -> Self {
+    pub fn new(
+        network_adapter: Arc<dyn PeerManagerAdapter>,
+        timeout: TimeDuration,
+        external_storage: Option<Arc<dyn StatePartProvider>>,
+    ) -> Self {
         StateSync {
             network_adapter,
             state_sync_time: Default::default(),
@@ -674,6 +906,9 @@ impl StateSync {
             timeout: Duration::from_std(timeout).unwrap(),
             state_parts_apply_results: HashMap::new(),
             split_state_roots: HashMap::new(),
+            external_storage,
+            external_parts: Default::default(),
+            external_requests_in_flight: Default::default(),
         }
     }
 
@@ -799,6 +1034,31 @@ impl StateSync {
                     }
                 }
                 ShardSyncStatus::StateDownloadParts => {
+                    let downloaded_parts = self
+                        .external_parts
+                        .lock()
+                        .unwrap()
+                        .remove(&(sync_hash, shard_id))
+                        .unwrap_or_default();
+                    let num_parts = shard_sync_download.downloads.len() as u64;
+                    for (part_id, data) in downloaded_parts {
+                        let download = &mut shard_sync_download.downloads[part_id as usize];
+                        if download.done {
+                            continue;
+                        }
+                        // A part which fails validation is downloaded again after the timeout.
+                        match chain.set_state_part(
+                            shard_id,
+                            sync_hash,
+                            PartId::new(part_id, num_parts),
+                            &data,
+                        ) {
+                            Ok(()) => download.done = true,
+                            Err(err) => {
+                                error!(target: "sync", "State sync set_state_part error, shard = {}, part = {}, hash = {}: {:?}", shard_id, part_id, sync_hash, err);
+                            }
+                        }
+                    }
                     let mut parts_done = true;
                     for part_download in shard_sync_download.downloads.iter_mut() {
                         if !part_download.done {
@@ -1112,6 +1372,18 @@ impl StateSync {
         shard_sync_download: ShardSyncDownload,
         highest_height_peers: &[FullPeerInfo],
     ) -> Result<ShardSyncDownload, near_chain::Error> {
+        if let Some(external_storage) = &self.external_storage {
+            if shard_sync_download.status == ShardSyncStatus::StateDownloadParts {
+                let external_storage = external_storage.clone();
+                return Ok(self.request_parts_from_external_storage(
+                    external_storage,
+                    shard_id,
+                    sync_hash,
+                    shard_sync_download,
+                ));
+            }
+        }
+
         let possible_targets = self.possible_targets(
             me,
             shard_id,
@@ -1210,6 +1482,61 @@ impl StateSync {
         Ok(new_shard_sync_download)
     }
 
+    /// Starts downloading the parts that need to be requested from the external
+    /// storage.  The downloaded parts are saved by `sync_shards_status`, which
+    /// validates them against the state root like the parts from the peers.
+    fn request_parts_from_external_storage(
+        &self,
+        external_storage: Arc<dyn StatePartProvider>,
+        shard_id: ShardId,
+        sync_hash: CryptoHash,
+        mut shard_sync_download: ShardSyncDownload,
+    ) -> ShardSyncDownload {
+        let num_parts = shard_sync_download.downloads.len() as u64;
+        for (part_id, download) in shard_sync_download
+            .downloads
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, download)| download.run_me.load(Ordering::SeqCst))
+        {
+            if self.external_requests_in_flight.load(Ordering::SeqCst)
+                >= MAX_EXTERNAL_STATE_PART_REQUESTS
+            {
+                // The remaining parts are requested on the next iterations.
+                break;
+            }
+            download.run_me.store(false, Ordering::SeqCst);
+            download.state_requests_count += 1;
+            download.last_target = None;
+
+            let external_storage = external_storage.clone();
+            let external_parts = self.external_parts.clone();
+            let in_flight = self.external_requests_in_flight.clone();
+            in_flight.fetch_add(1, Ordering::SeqCst);
+            std::thread::spawn(move || {
+                let part_id = part_id as u64;
+                match external_storage.get_part(
+                    &sync_hash,
+                    shard_id,
+                    PartId::new(part_id, num_parts),
+                ) {
+                    Ok(data) => external_parts
+                        .lock()
+                        .unwrap()
+                        .entry((sync_hash, shard_id))
+                        .or_default()
+                        .push((part_id, data)),
+                    // The part is requested again after the timeout.
+                    Err(err) => {
+                        warn!(target: "sync", "State sync failed to download part {} of shard {} for {} from external storage: {}", part_id, shard_id, sync_hash, err);
+                    }
+                }
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        shard_sync_download
+    }
+
     pub fn run(
         &mut self,
         me: &Option<AccountId>,
@@ -1348,7 +1675,7 @@ mod test {
     use super::*;
     use crate::test_utils::TestEnv;
     use near_network::types::{PartialEdgeInfo, PeerInfo};
-    use near_primitives::merkle::PartialMerkleTree;
+    use near_primitives::merkle::{merkle_hash_algorithm, PartialMerkleTree};
     use near_primitives::types::EpochId;
     use near_primitives::validator_signer::InMemoryValidatorSigner;
     use near_primitives::version::PROTOCOL_VERSION;
@@ -1541,10 +1868,11 @@ mod test {
                 vec![],
                 &*signers[3],
                 *last_block.header().next_bp_hash(),
-                block_merkle_tree.root(),
+                block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
                 None,
             );
-            block_merkle_tree.insert(*block.hash());
+            block_merkle_tree
+                .insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *block.hash());
 
             all_blocks.push(block);
 
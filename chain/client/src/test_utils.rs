@@ -45,7 +45,7 @@ use near_o11y::{WithSpanContext, WithSpanContextExt};
 use near_primitives::block::{ApprovalInner, Block, GenesisId};
 use near_primitives::epoch_manager::RngSeed;
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
+use near_primitives::merkle::{merkle_hash_algorithm, merklize, MerklePath, PartialMerkleTree};
 use near_primitives::network::PeerId;
 use near_primitives::receipt::Receipt;
 use near_primitives::runtime::config::RuntimeConfig;
@@ -69,9 +69,11 @@ use near_telemetry::TelemetryActor;
 
 use crate::adapter::{
     AnnounceAccountRequest, BlockApproval, BlockHeadersRequest, BlockHeadersResponse, BlockRequest,
-    BlockResponse, ProcessTxResponse, RecvPartialEncodedChunk, RecvPartialEncodedChunkForward,
-    RecvPartialEncodedChunkRequest, RecvPartialEncodedChunkResponse, SetNetworkInfo,
-    StateRequestHeader, StateRequestPart, StateResponse,
+    BlockResponse, EpochSyncFinalizationRequest, EpochSyncRequest, ProcessTxResponse,
+    RecvEpochSyncFinalizationResponse, RecvEpochSyncResponse, RecvPartialEncodedChunk,
+    RecvPartialEncodedChunkForward, RecvPartialEncodedChunkRequest,
+    RecvPartialEncodedChunkResponse, SetNetworkInfo, StateRequestHeader, StateRequestPart,
+    StateResponse,
 };
 
 pub struct PeerManagerMock {
@@ -826,6 +828,68 @@ pub fn setup_mock_all_validators(
                                 }
                             }
                         }
+                        NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                            for (i, peer_info) in key_pairs.iter().enumerate() {
+                                let peer_id = peer_id.clone();
+                                if peer_info.id == peer_id {
+                                    let me = connectors1[my_ord].0.clone();
+                                    actix::spawn(
+                                        connectors1[i]
+                                            .1
+                                            .send(
+                                                EpochSyncRequest(epoch_id.clone())
+                                                    .with_span_context(),
+                                            )
+                                            .then(move |response| {
+                                                let response = response.unwrap();
+                                                match response {
+                                                    Some(response) => {
+                                                        me.do_send(
+                                                            RecvEpochSyncResponse(
+                                                                response, peer_id,
+                                                            )
+                                                            .with_span_context(),
+                                                        );
+                                                    }
+                                                    None => {}
+                                                }
+                                                future::ready(())
+                                            }),
+                                    );
+                                }
+                            }
+                        }
+                        NetworkRequests::EpochSyncFinalizationRequest { epoch_id, peer_id } => {
+                            for (i, peer_info) in key_pairs.iter().enumerate() {
+                                let peer_id = peer_id.clone();
+                                if peer_info.id == peer_id {
+                                    let me = connectors1[my_ord].0.clone();
+                                    actix::spawn(
+                                        connectors1[i]
+                                            .1
+                                            .send(
+                                                EpochSyncFinalizationRequest(epoch_id.clone())
+                                                    .with_span_context(),
+                                            )
+                                            .then(move |response| {
+                                                let response = response.unwrap();
+                                                match response {
+                                                    Some(response) => {
+                                                        me.do_send(
+                                                            RecvEpochSyncFinalizationResponse(
+                                                                response, peer_id,
+                                                            )
+                                                            .with_span_context(),
+                                                        );
+                                                    }
+                                                    None => {}
+                                                }
+                                                future::ready(())
+                                            }),
+                                    );
+                                }
+                            }
+                        }
                         NetworkRequests::StateRequestHeader {
                             shard_id,
                             sync_hash,
@@ -1077,9 +1141,10 @@ pub fn setup_client_with_runtime(
     chain_genesis: ChainGenesis,
     runtime_adapter: Arc<dyn RuntimeAdapter>,
     rng_seed: RngSeed,
+    validator_key_type: KeyType,
 ) -> Client {
     let validator_signer = account_id.map(|x| {
-        Arc::new(InMemoryValidatorSigner::from_seed(x.clone(), KeyType::ED25519, x.as_ref()))
+        Arc::new(InMemoryValidatorSigner::from_seed(x.clone(), validator_key_type, x.as_ref()))
             as Arc<dyn ValidatorSigner>
     });
     let mut config = ClientConfig::test(true, 10, 20, num_validator_seats, false, true);
@@ -1121,6 +1186,7 @@ pub fn setup_client(
         chain_genesis,
         runtime_adapter,
         rng_seed,
+        KeyType::ED25519,
     )
 }
 
@@ -1149,6 +1215,7 @@ pub struct TestEnvBuilder {
     // random seed to be inject in each client according to AccountId
     // if not set, a default constant TEST_SEED will be injected
     seeds: HashMap<AccountId, RngSeed>,
+    validator_key_type: KeyType,
 }
 
 /// Builder for the [`TestEnv`] structure.
@@ -1165,6 +1232,7 @@ impl TestEnvBuilder {
             runtime_adapters: None,
             network_adapters: None,
             seeds,
+            validator_key_type: KeyType::ED25519,
         }
     }
 
@@ -1217,6 +1285,17 @@ impl TestEnvBuilder {
         self
     }
 
+    /// Sets the key type of the validator signers of the clients.  The keys
+    /// are derived from the account ids so they match the ones created by
+    /// `Genesis::test_with_seeds` with the same key type.
+    ///
+    /// Only supported together with [`Self::runtime_adapters`]; [`Self::build`]
+    /// panics otherwise.
+    pub fn validator_key_type(mut self, key_type: KeyType) -> Self {
+        self.validator_key_type = key_type;
+        self
+    }
+
     /// Specifies custom network adaptors for each client.
     ///
     /// The vector must have the same number of elements as they are clients
@@ -1243,6 +1322,7 @@ impl TestEnvBuilder {
         let validators = self.validators;
         let num_validators = validators.len();
         let seeds = self.seeds;
+        let validator_key_type = self.validator_key_type;
         let network_adapters = self
             .network_adapters
             .unwrap_or_else(|| (0..num_clients).map(|_| Arc::new(Default::default())).collect());
@@ -1250,6 +1330,10 @@ impl TestEnvBuilder {
             .map(|_| Arc::new(MockClientAdapterForShardsManager::default()))
             .collect::<Vec<_>>();
         assert_eq!(clients.len(), network_adapters.len());
+        assert!(
+            self.runtime_adapters.is_some() || matches!(validator_key_type, KeyType::ED25519),
+            "custom validator key types require custom runtime adapters"
+        );
         let clients = match self.runtime_adapters {
             None => clients
                 .into_iter()
@@ -1294,6 +1378,7 @@ impl TestEnvBuilder {
                             chain_genesis.clone(),
                             runtime_adapter,
                             rng_seed,
+                            validator_key_type,
                         )
                     })
                     .collect()
@@ -1533,7 +1618,7 @@ impl TestEnv {
                 last_block.header().prev_hash(),
                 last_block.header().hash(),
                 last_block.header().epoch_id(),
-                &QueryRequest::ViewAccount { account_id },
+                &QueryRequest::ViewAccount { account_id, include_proof: false },
             )
             .unwrap();
         match response.kind {
@@ -1772,7 +1857,7 @@ pub fn create_chunk(
     }
     let block_merkle_tree = client.chain.store().get_block_merkle_tree(last_block.hash()).unwrap();
     let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-    block_merkle_tree.insert(*last_block.hash());
+    block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *last_block.hash());
     let block = Block::produce(
         PROTOCOL_VERSION,
         PROTOCOL_VERSION,
@@ -1792,7 +1877,7 @@ pub fn create_chunk(
         vec![],
         &*client.validator_signer.as_ref().unwrap().clone(),
         *last_block.header().next_bp_hash(),
-        block_merkle_tree.root(),
+        block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
         None,
     );
     (chunk, merkle_paths, receipts, block)
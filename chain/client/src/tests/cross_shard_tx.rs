@@ -73,7 +73,10 @@ fn test_keyvalue_runtime_balances() {
             let actor = connectors_[i].1.send(
                 Query::new(
                     BlockReference::latest(),
-                    QueryRequest::ViewAccount { account_id: validators[i].clone() },
+                    QueryRequest::ViewAccount {
+                        account_id: validators[i].clone(),
+                        include_proof: false,
+                    },
                 )
                 .with_span_context(),
             );
@@ -197,7 +200,10 @@ fn test_cross_shard_tx_callback(
             let actor = actor.send(
                 Query::new(
                     BlockReference::latest(),
-                    QueryRequest::ViewAccount { account_id: account_id.clone() },
+                    QueryRequest::ViewAccount {
+                        account_id: account_id.clone(),
+                        include_proof: false,
+                    },
                 )
                 .with_span_context(),
             );
@@ -295,7 +301,10 @@ fn test_cross_shard_tx_callback(
                     let actor = actor.send(
                         Query::new(
                             BlockReference::latest(),
-                            QueryRequest::ViewAccount { account_id: validators[i].clone() },
+                            QueryRequest::ViewAccount {
+                                account_id: validators[i].clone(),
+                                include_proof: false,
+                            },
                         )
                         .with_span_context(),
                     );
@@ -349,7 +358,10 @@ fn test_cross_shard_tx_callback(
             let actor = actor.send(
                 Query::new(
                     BlockReference::latest(),
-                    QueryRequest::ViewAccount { account_id: account_id.clone() },
+                    QueryRequest::ViewAccount {
+                        account_id: account_id.clone(),
+                        include_proof: false,
+                    },
                 )
                 .with_span_context(),
             );
@@ -493,7 +505,10 @@ fn test_cross_shard_tx_common(
             let actor = actor.send(
                 Query::new(
                     BlockReference::latest(),
-                    QueryRequest::ViewAccount { account_id: validators[i].clone() },
+                    QueryRequest::ViewAccount {
+                        account_id: validators[i].clone(),
+                        include_proof: false,
+                    },
                 )
                 .with_span_context(),
             );
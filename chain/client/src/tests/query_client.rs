@@ -1,7 +1,7 @@
 use actix::System;
 use futures::{future, FutureExt};
 use near_chain::test_utils::ValidatorSchedule;
-use near_primitives::merkle::PartialMerkleTree;
+use near_primitives::merkle::{merkle_hash_algorithm, PartialMerkleTree};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -42,7 +42,10 @@ fn query_client() {
         let actor = view_client.send(
             Query::new(
                 BlockReference::latest(),
-                QueryRequest::ViewAccount { account_id: "test".parse().unwrap() },
+                QueryRequest::ViewAccount {
+                    account_id: "test".parse().unwrap(),
+                    include_proof: false,
+                },
             )
             .with_span_context(),
         );
@@ -73,7 +76,8 @@ fn query_status_not_crash() {
             let (block, block_merkle_tree) = res.unwrap().unwrap();
             let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
             let header: BlockHeader = block.header.clone().into();
-            block_merkle_tree.insert(*header.hash());
+            block_merkle_tree
+                .insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *header.hash());
             let mut next_block = Block::produce(
                 PROTOCOL_VERSION,
                 PROTOCOL_VERSION,
@@ -93,7 +97,7 @@ fn query_status_not_crash() {
                 vec![],
                 &signer,
                 block.header.next_bp_hash,
-                block_merkle_tree.root(),
+                block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
                 None,
             );
             next_block.mut_header().get_mut().inner_lite.timestamp =
@@ -305,6 +309,7 @@ fn test_garbage_collection() {
                                                 )),
                                                 QueryRequest::ViewAccount {
                                                     account_id: "test1".parse().unwrap(),
+                                                    include_proof: false,
                                                 },
                                             )
                                             .with_span_context(),
@@ -328,6 +333,7 @@ fn test_garbage_collection() {
                                             BlockReference::BlockId(BlockId::Height(1)),
                                             QueryRequest::ViewAccount {
                                                 account_id: "test1".parse().unwrap(),
+                                                include_proof: false,
                                             },
                                         )
                                         .with_span_context(),
@@ -353,6 +359,7 @@ fn test_garbage_collection() {
                                             BlockReference::BlockId(BlockId::Height(1)),
                                             QueryRequest::ViewAccount {
                                                 account_id: "test1".parse().unwrap(),
+                                                include_proof: false,
                                             },
                                         )
                                         .with_span_context(),
@@ -35,12 +35,12 @@ use near_o11y::{handler_debug_span, OpenTelemetrySpanExt, WithSpanContext, WithS
 use near_performance_metrics_macros::perf;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::{merklize, PartialMerkleTree};
+use near_primitives::merkle::{merkle_hash_algorithm, merklize_with, PartialMerkleTree};
 use near_primitives::network::AnnounceAccount;
 use near_primitives::sharding::ShardChunk;
 use near_primitives::syncing::{
-    ShardStateSyncResponse, ShardStateSyncResponseHeader, ShardStateSyncResponseV1,
-    ShardStateSyncResponseV2,
+    EpochSyncFinalizationResponse, EpochSyncResponse, ShardStateSyncResponse,
+    ShardStateSyncResponseHeader, ShardStateSyncResponseV1, ShardStateSyncResponseV2,
 };
 use near_primitives::types::{
     AccountId, BlockId, BlockReference, EpochReference, Finality, MaybeBlockId, ShardId,
@@ -54,8 +54,9 @@ use near_primitives::views::{
 };
 
 use crate::adapter::{
-    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, StateRequestHeader,
-    StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
+    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, EpochSyncFinalizationRequest,
+    EpochSyncRequest, StateRequestHeader, StateRequestPart, StateResponse, TxStatusRequest,
+    TxStatusResponse,
 };
 use crate::{
     metrics, sync, GetChunk, GetExecutionOutcomeResponse, GetNextLightClientBlock, GetStateChanges,
@@ -877,9 +878,11 @@ impl Handler<WithSpanContext<GetExecutionOutcome>> for ViewClientActor {
                     outcome_proof.block_hash = h;
                     // Here we assume the number of shards is small so this reconstruction
                     // should be fast
-                    let outcome_roots = self
-                        .chain
-                        .get_block(&h)?
+                    let block = self.chain.get_block(&h)?;
+                    let protocol_version = self
+                        .runtime_adapter
+                        .get_epoch_protocol_version(block.header().epoch_id())?;
+                    let outcome_roots = block
                         .chunks()
                         .iter()
                         .map(|header| header.outcome_root())
@@ -892,7 +895,11 @@ impl Handler<WithSpanContext<GetExecutionOutcome>> for ViewClientActor {
                     }
                     Ok(GetExecutionOutcomeResponse {
                         outcome_proof: outcome_proof.into(),
-                        outcome_root_proof: merklize(&outcome_roots).1[target_shard_id as usize]
+                        outcome_root_proof: merklize_with(
+                            merkle_hash_algorithm(protocol_version),
+                            &outcome_roots,
+                        )
+                        .1[target_shard_id as usize]
                             .clone(),
                     })
                 } else {
@@ -981,8 +988,9 @@ impl Handler<WithSpanContext<GetBlockProof>> for ViewClientActor {
         let head_block_header = self.chain.get_block_header(&msg.head_block_hash)?;
         self.chain.check_blocks_final_and_canonical(&[&block_header, &head_block_header])?;
         let block_header_lite = block_header.into();
-        let proof = self.chain.get_block_proof(&msg.block_hash, &msg.head_block_hash)?;
-        Ok(GetBlockProofResponse { block_header_lite, proof })
+        let (proof, proof_hash_algorithms) =
+            self.chain.get_block_proof(&msg.block_hash, &msg.head_block_hash)?;
+        Ok(GetBlockProofResponse { block_header_lite, proof, proof_hash_algorithms })
     }
 }
 
@@ -1137,6 +1145,54 @@ impl Handler<WithSpanContext<BlockHeadersRequest>> for ViewClientActor {
     }
 }
 
+impl Handler<WithSpanContext<EpochSyncRequest>> for ViewClientActor {
+    type Result = Option<Box<EpochSyncResponse>>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochSyncRequest>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochSyncRequest"])
+            .start_timer();
+        let EpochSyncRequest(epoch_id) = msg;
+        match self.chain.get_epoch_sync_response(&epoch_id) {
+            Ok(response) => Some(Box::new(response)),
+            Err(err) => {
+                debug!(target: "sync", ?epoch_id, ?err, "Cannot build epoch sync response");
+                None
+            }
+        }
+    }
+}
+
+impl Handler<WithSpanContext<EpochSyncFinalizationRequest>> for ViewClientActor {
+    type Result = Option<Box<EpochSyncFinalizationResponse>>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochSyncFinalizationRequest>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochSyncFinalizationRequest"])
+            .start_timer();
+        let EpochSyncFinalizationRequest(epoch_id) = msg;
+        match self.chain.get_epoch_sync_finalization_response(&epoch_id) {
+            Ok(response) => Some(Box::new(response)),
+            Err(err) => {
+                debug!(target: "sync", ?epoch_id, ?err, "Cannot build epoch sync finalization response");
+                None
+            }
+        }
+    }
+}
+
 impl Handler<WithSpanContext<StateRequestHeader>> for ViewClientActor {
     type Result = Option<StateResponse>;
 
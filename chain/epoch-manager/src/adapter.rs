@@ -446,10 +446,9 @@ impl<T: HasEpochMangerHandle + Send + Sync> EpochManagerAdapter for T {
     ) -> Result<(), Error> {
         let epoch_manager = self.read();
         let validator = epoch_manager.get_block_producer_info(epoch_id, block_height)?;
-        let public_key = near_crypto::key_conversion::convert_public_key(
-            validator.public_key().unwrap_as_ed25519(),
-        )
-        .unwrap();
+        let public_key =
+            near_crypto::key_conversion::convert_staking_public_key(validator.public_key())
+                .unwrap();
 
         if !public_key.is_vrf_valid(&prev_random_value.as_ref(), vrf_value, vrf_proof) {
             return Err(Error::InvalidRandomnessBeaconOutput);
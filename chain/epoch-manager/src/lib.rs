@@ -298,16 +298,26 @@ impl EpochManager {
         next_epoch_info: EpochInfo,
     ) -> Result<StoreUpdate, EpochError> {
         let mut store_update = self.store.store_update();
+        self.save_epoch_start(
+            &mut store_update,
+            prev_epoch_id,
+            prev_epoch_first_block_info.height(),
+        )?;
+        // Blocks of the previous epoch before its last block are not known, so
+        // the aggregator starts from the last one.  The first blocks of the
+        // current epoch may still have their last final block in the previous
+        // epoch; those must not be aggregated either.
+        self.epoch_info_aggregator =
+            EpochInfoAggregator::new(prev_epoch_id.clone(), *prev_epoch_last_block_info.hash());
+        self.largest_final_height = prev_epoch_last_block_info.height();
+        store_update.set_ser(DBCol::EpochInfo, AGGREGATOR_KEY, &self.epoch_info_aggregator)?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_first_block_info))?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_prev_last_block_info))?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_last_block_info))?;
         self.save_epoch_info(&mut store_update, prev_epoch_id, Arc::new(prev_epoch_info))?;
         self.save_epoch_info(&mut store_update, epoch_id, Arc::new(epoch_info))?;
         self.save_epoch_info(&mut store_update, next_epoch_id, Arc::new(next_epoch_info))?;
-        // TODO #3488
-        // put unreachable! here to avoid warnings
-        unreachable!();
-        // Ok(store_update)
+        Ok(store_update)
     }
 
     /// When computing validators to kickout, we exempt some validators first so that
@@ -1,11 +1,15 @@
 #![doc = include_str!("../README.md")]
 
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
 use anyhow::Context;
+use rocksdb::DB;
 use tokio::sync::mpsc;
 
 use near_chain_configs::GenesisValidationMode;
 pub use near_primitives;
-use near_primitives::types::Gas;
+use near_primitives::types::{BlockHeight, Gas};
 pub use nearcore::{get_default_home, init_configs, NearConfig};
 
 pub use near_indexer_primitives::{
@@ -14,6 +18,9 @@ pub use near_indexer_primitives::{
     StreamerMessage,
 };
 
+pub use streamer::{ReceiptKind, StreamerMessageFilter};
+
+pub mod sinks;
 mod streamer;
 
 pub const INDEXER: &str = "indexer";
@@ -79,12 +86,56 @@ pub struct IndexerConfig {
     pub await_for_node_synced: AwaitForNodeSyncedEnum,
 }
 
+/// Configuration of a consumer to be provided to `Indexer::consumer(ConsumerConfig)`
+#[derive(Debug, Clone)]
+pub struct ConsumerConfig {
+    /// Name of the consumer, which identifies its cursor across restarts
+    pub name: String,
+    /// Mode of syncing for the consumer, `FromInterruption` resumes after
+    /// the last acknowledged block
+    pub sync_mode: SyncModeEnum,
+    /// Which data to stream to the consumer
+    pub filter: StreamerMessageFilter,
+}
+
+/// Stream of `StreamerMessage`s with at-least-once delivery.
+///
+/// Every received message should be acknowledged with `Consumer::ack` once
+/// it has been handled. The consumer's cursor only advances over acknowledged
+/// blocks (and blocks with nothing to stream), so after a restart in
+/// `SyncModeEnum::FromInterruption` mode the blocks which haven't been
+/// acknowledged are streamed again.
+pub struct Consumer {
+    cursor: Arc<streamer::ConsumerCursor>,
+    receiver: mpsc::Receiver<StreamerMessage>,
+}
+
+impl Consumer {
+    pub fn name(&self) -> &str {
+        self.cursor.name()
+    }
+
+    /// Receives the next message, or `None` if the streamer has stopped.
+    pub async fn recv(&mut self) -> Option<StreamerMessage> {
+        self.receiver.recv().await
+    }
+
+    /// Acknowledges the message of the block at `block_height`. Messages may
+    /// be acknowledged in any order.
+    pub fn ack(&self, block_height: BlockHeight) {
+        self.cursor.ack(block_height)
+    }
+}
+
 /// This is the core component, which handles `nearcore` and internal `streamer`.
 pub struct Indexer {
     indexer_config: IndexerConfig,
     near_config: nearcore::NearConfig,
     view_client: actix::Addr<near_client::ViewClientActor>,
     client: actix::Addr<near_client::ClientActor>,
+    /// Keeps the cursors of the streamers.
+    db: Arc<DB>,
+    consumer_names: Mutex<HashSet<String>>,
 }
 
 impl Indexer {
@@ -110,22 +161,63 @@ impl Indexer {
         let nearcore::NearNode { client, view_client, .. } =
             nearcore::start_with_config(&indexer_config.home_dir, near_config.clone())
                 .with_context(|| "start_with_config")?;
-        Ok(Self { view_client, client, near_config, indexer_config })
+        let indexer_db_path = near_store::NodeStorage::opener(
+            &indexer_config.home_dir,
+            &near_config.config.store,
+            None,
+        )
+        .path()
+        .join("indexer");
+        let db = Arc::new(DB::open_default(indexer_db_path).with_context(|| "open indexer db")?);
+        Ok(Self {
+            view_client,
+            client,
+            near_config,
+            indexer_config,
+            db,
+            consumer_names: Mutex::new(HashSet::new()),
+        })
     }
 
     /// Boots up `near_indexer::streamer`, so it monitors the new blocks with chunks, transactions, receipts, and execution outcomes inside. The returned stream handler should be drained and handled on the user side.
+    ///
+    /// The block height is persisted as soon as the message is pushed into the stream, so messages which haven't been handled before a crash are skipped after a restart. Use `Indexer::consumer` for at-least-once delivery.
     pub fn streamer(&self) -> mpsc::Receiver<StreamerMessage> {
         let (sender, receiver) = mpsc::channel(100);
         actix::spawn(streamer::start(
             self.view_client.clone(),
             self.client.clone(),
-            self.indexer_config.clone(),
-            self.near_config.config.store.clone(),
+            self.indexer_config.sync_mode.clone(),
+            self.indexer_config.await_for_node_synced.clone(),
+            StreamerMessageFilter::default(),
+            streamer::Checkpoint::LastSynced(self.db.clone()),
             sender,
         ));
         receiver
     }
 
+    /// Boots up a streamer for the named consumer, which has its own cursor and only receives the data passing its filter. See `Consumer` for the delivery guarantees.
+    pub fn consumer(&self, config: ConsumerConfig) -> Result<Consumer, anyhow::Error> {
+        anyhow::ensure!(!config.name.is_empty(), "consumer name must not be empty");
+        anyhow::ensure!(
+            self.consumer_names.lock().unwrap().insert(config.name.clone()),
+            "consumer {} is already started",
+            config.name
+        );
+        let cursor = Arc::new(streamer::ConsumerCursor::new(self.db.clone(), &config.name));
+        let (sender, receiver) = mpsc::channel(100);
+        actix::spawn(streamer::start(
+            self.view_client.clone(),
+            self.client.clone(),
+            config.sync_mode,
+            self.indexer_config.await_for_node_synced.clone(),
+            config.filter,
+            streamer::Checkpoint::Acked(cursor.clone()),
+            sender,
+        ));
+        Ok(Consumer { cursor, receiver })
+    }
+
     /// Expose neard config
     pub fn near_config(&self) -> &nearcore::NearConfig {
         &self.near_config
@@ -0,0 +1,199 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use near_indexer_primitives::StreamerMessage;
+use near_primitives::types::BlockHeight;
+
+use super::{Compression, Sink};
+
+enum Writer {
+    Plain(BufWriter<File>),
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl Writer {
+    fn create(path: &Path, compression: Compression) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            Compression::None => Self::Plain(file),
+            Compression::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(file, 0)?),
+        })
+    }
+
+    fn get_mut(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(file) => file,
+            Self::Zstd(encoder) => encoder,
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        let file = match self {
+            Self::Plain(file) => file,
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+        file.into_inner().map_err(|err| err.into_error())?.sync_all()
+    }
+}
+
+struct OpenFile {
+    /// The first height of the files partition.
+    partition: BlockHeight,
+    writer: Writer,
+}
+
+/// Writes the messages as newline-delimited JSON into files, optionally
+/// compressed with zstd.
+///
+/// The heights are partitioned into ranges of `blocks_per_file` blocks, and
+/// a file never holds blocks of two partitions. Files are named after the
+/// first block written to them, e.g. `000012345678.ndjson.zst`, so they sort
+/// by height. Blocks which were written but not acknowledged before a restart
+/// are written again into a new file, hence readers should skip heights they
+/// have already seen.
+pub struct FilesSink {
+    dir: PathBuf,
+    blocks_per_file: u64,
+    compression: Compression,
+    current: Option<OpenFile>,
+}
+
+impl FilesSink {
+    pub fn new(dir: PathBuf, blocks_per_file: u64, compression: Compression) -> io::Result<Self> {
+        if blocks_per_file == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "blocks_per_file is 0"));
+        }
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, blocks_per_file, compression, current: None })
+    }
+
+    fn file_name(&self, height: BlockHeight) -> String {
+        match self.compression {
+            Compression::None => format!("{:012}.ndjson", height),
+            Compression::Zstd => format!("{:012}.ndjson.zst", height),
+        }
+    }
+
+    fn write_line(&mut self, height: BlockHeight, line: &[u8]) -> io::Result<()> {
+        let partition = height - height % self.blocks_per_file;
+        if self.current.as_ref().map(|file| file.partition) != Some(partition) {
+            if let Some(file) = self.current.take() {
+                file.writer.finish()?;
+            }
+            let path = self.dir.join(self.file_name(height));
+            let writer = Writer::create(&path, self.compression)?;
+            self.current = Some(OpenFile { partition, writer });
+        }
+        let writer = self.current.as_mut().unwrap().writer.get_mut();
+        writer.write_all(line)?;
+        writer.write_all(b"\n")?;
+        // Compressed blocks are only written out on flush, and the message is
+        // acknowledged right after this.
+        writer.flush()
+    }
+}
+
+#[async_trait::async_trait]
+impl Sink for FilesSink {
+    async fn write(&mut self, streamer_message: &StreamerMessage) -> io::Result<()> {
+        let line = serde_json::to_vec(streamer_message)?;
+        self.write_line(streamer_message.block.header.height, &line)
+    }
+}
+
+impl Drop for FilesSink {
+    fn drop(&mut self) {
+        if let Some(file) = self.current.take() {
+            if let Err(err) = file.writer.finish() {
+                tracing::warn!(target: crate::INDEXER, "Failed to finish the last file: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::test_message;
+
+    fn read_heights(dir: &Path) -> Vec<(String, Vec<BlockHeight>)> {
+        let mut names = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let data = std::fs::read(dir.join(&name)).unwrap();
+                let data = if name.ends_with(".zst") {
+                    zstd::decode_all(&data[..]).unwrap()
+                } else {
+                    data
+                };
+                let heights = String::from_utf8(data)
+                    .unwrap()
+                    .lines()
+                    .map(|line| {
+                        let message: StreamerMessage = serde_json::from_str(line).unwrap();
+                        message.block.header.height
+                    })
+                    .collect();
+                (name, heights)
+            })
+            .collect()
+    }
+
+    async fn check_files_sink(compression: Compression, extension: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = FilesSink::new(dir.path().to_path_buf(), 10, compression).unwrap();
+        for height in [7, 9, 10, 15, 19, 23] {
+            sink.write(&test_message(height)).await.unwrap();
+        }
+        drop(sink);
+        // Blocks 15 and 19 are streamed again after a restart.
+        let mut sink = FilesSink::new(dir.path().to_path_buf(), 10, compression).unwrap();
+        for height in [15, 19, 20] {
+            sink.write(&test_message(height)).await.unwrap();
+        }
+        drop(sink);
+
+        let name = |height: u64| format!("{:012}.{}", height, extension);
+        assert_eq!(
+            read_heights(dir.path()),
+            vec![
+                (name(7), vec![7, 9]),
+                (name(10), vec![10, 15, 19]),
+                (name(15), vec![15, 19]),
+                (name(20), vec![20]),
+                (name(23), vec![23]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_files() {
+        check_files_sink(Compression::None, "ndjson").await;
+    }
+
+    #[tokio::test]
+    async fn test_zstd_files() {
+        check_files_sink(Compression::Zstd, "ndjson.zst").await;
+    }
+
+    /// Everything flushed so far can be read even if the file was never finished.
+    #[tokio::test]
+    async fn test_unfinished_zstd_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = FilesSink::new(dir.path().to_path_buf(), 10, Compression::Zstd).unwrap();
+        sink.write(&test_message(1)).await.unwrap();
+        sink.write(&test_message(2)).await.unwrap();
+        let data = std::fs::read(dir.path().join("000000000001.ndjson.zst")).unwrap();
+        let mut decoder = zstd::stream::read::Decoder::new(&data[..]).unwrap();
+        let mut lines = vec![];
+        // The end of the frame is missing, but the flushed blocks are complete.
+        let _ = std::io::Read::read_to_end(&mut decoder, &mut lines);
+        assert_eq!(lines.iter().filter(|&&byte| byte == b'\n').count(), 2);
+    }
+}
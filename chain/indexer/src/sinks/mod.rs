@@ -0,0 +1,90 @@
+//! Ready-made destinations for the streamed `StreamerMessage`s.
+//!
+//! A sink is fed by a `Consumer` with `sinks::run`, which acknowledges every
+//! block once the sink has written it, so a sink gets every block at least
+//! once even if the indexer is restarted.
+use std::io;
+use std::path::PathBuf;
+
+use near_indexer_primitives::StreamerMessage;
+
+use crate::Consumer;
+
+pub use self::files::FilesSink;
+pub use self::stream::{StreamAddress, StreamSink, StreamSubscriber};
+
+mod files;
+mod stream;
+
+/// Destination the streamed messages are written to.
+#[async_trait::async_trait]
+pub trait Sink: Send {
+    /// Writes `streamer_message`. Once this returns, the message is handed
+    /// over and may be acknowledged.
+    async fn write(&mut self, streamer_message: &StreamerMessage) -> io::Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    None,
+    Zstd,
+}
+
+fn default_blocks_per_file() -> u64 {
+    1000
+}
+
+/// Configuration of a sink, e.g. `{"files": {"dir": "blocks", "compression": "zstd"}}`
+/// or `{"stream": {"address": {"tcp": "127.0.0.1:9000"}}}`.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// Newline-delimited JSON files, see `FilesSink`.
+    Files {
+        dir: PathBuf,
+        #[serde(default = "default_blocks_per_file")]
+        blocks_per_file: u64,
+        #[serde(default)]
+        compression: Compression,
+    },
+    /// Length-prefixed frames served to the subscribers, see `StreamSink`.
+    Stream { address: StreamAddress },
+}
+
+/// Creates the sink described by `config`.
+pub async fn open(config: &SinkConfig) -> io::Result<Box<dyn Sink>> {
+    Ok(match config {
+        SinkConfig::Files { dir, blocks_per_file, compression } => {
+            Box::new(FilesSink::new(dir.clone(), *blocks_per_file, *compression)?)
+        }
+        SinkConfig::Stream { address } => Box::new(StreamSink::bind(address).await?),
+    })
+}
+
+/// Writes everything received by `consumer` into `sink` and acknowledges the
+/// written blocks. Returns once the streamer stops or the sink fails.
+pub async fn run(mut consumer: Consumer, mut sink: Box<dyn Sink>) -> io::Result<()> {
+    while let Some(streamer_message) = consumer.recv().await {
+        sink.write(&streamer_message).await?;
+        consumer.ack(streamer_message.block.header.height);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+fn test_message(height: near_primitives::types::BlockHeight) -> StreamerMessage {
+    use near_primitives::block::Block;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::time::Clock;
+    use near_primitives::version::PROTOCOL_VERSION;
+    use near_primitives::views::BlockView;
+
+    let block =
+        Block::genesis(PROTOCOL_VERSION, vec![], Clock::utc(), height, 0, 0, CryptoHash::default());
+    StreamerMessage {
+        block: BlockView::from_author_block("test".parse().unwrap(), block),
+        shards: vec![],
+    }
+}
@@ -0,0 +1,268 @@
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use futures::future::join_all;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use near_indexer_primitives::StreamerMessage;
+use near_primitives::types::BlockHeight;
+
+use super::Sink;
+use crate::INDEXER;
+
+/// Address a `StreamSink` listens on, e.g. `{"tcp": "127.0.0.1:9000"}` or
+/// `{"unix": "/run/indexer.sock"}`.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+trait Socket: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Socket for T {}
+
+/// Serves the messages to the processes connected to a TCP or Unix socket.
+///
+/// Every message is sent to every subscriber as a frame: the length of the
+/// JSON-serialized `StreamerMessage` as a 4-byte big-endian integer followed
+/// by the JSON itself. The subscriber acknowledges the message by sending
+/// back its block height as an 8-byte big-endian integer, see
+/// `StreamSubscriber`. A message is handed over once all the subscribers have
+/// acknowledged it, so the streaming goes at the pace of the slowest
+/// subscriber and pauses while there are no subscribers. If no subscriber
+/// acknowledges a message, it is sent again to the next ones to connect.
+pub struct StreamSink {
+    local_addr: Option<SocketAddr>,
+    accept_loop: JoinHandle<()>,
+    new_subscribers: mpsc::UnboundedReceiver<Box<dyn Socket>>,
+    subscribers: Vec<Box<dyn Socket>>,
+}
+
+impl StreamSink {
+    pub async fn bind(address: &StreamAddress) -> io::Result<Self> {
+        let (sender, new_subscribers) = mpsc::unbounded_channel();
+        let (local_addr, accept_loop) = match address {
+            StreamAddress::Tcp(addr) => {
+                let listener = TcpListener::bind(addr).await?;
+                let local_addr = listener.local_addr()?;
+                info!(target: INDEXER, "Serving StreamerMessages on {}", local_addr);
+                let accept_loop = tokio::spawn(async move {
+                    loop {
+                        match listener.accept().await {
+                            Ok((stream, peer_addr)) => {
+                                info!(target: INDEXER, "New subscriber {}", peer_addr);
+                                let _ = stream.set_nodelay(true);
+                                if sender.send(Box::new(stream) as Box<dyn Socket>).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(err) => {
+                                warn!(target: INDEXER, "Failed to accept a subscriber: {}", err);
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            }
+                        }
+                    }
+                });
+                (Some(local_addr), accept_loop)
+            }
+            StreamAddress::Unix(path) => {
+                // Remove the socket left behind by the previous run.
+                match std::fs::remove_file(path) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                    _ => {}
+                }
+                let listener = UnixListener::bind(path)?;
+                info!(target: INDEXER, "Serving StreamerMessages on {}", path.display());
+                let accept_loop = tokio::spawn(async move {
+                    loop {
+                        match listener.accept().await {
+                            Ok((stream, _)) => {
+                                info!(target: INDEXER, "New subscriber");
+                                if sender.send(Box::new(stream) as Box<dyn Socket>).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(err) => {
+                                warn!(target: INDEXER, "Failed to accept a subscriber: {}", err);
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            }
+                        }
+                    }
+                });
+                (None, accept_loop)
+            }
+        };
+        Ok(Self { local_addr, accept_loop, new_subscribers, subscribers: vec![] })
+    }
+
+    /// The address the sink listens on if it's a TCP socket.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+}
+
+async fn send_frame(
+    subscriber: &mut dyn Socket,
+    frame: &[u8],
+    height: BlockHeight,
+) -> io::Result<()> {
+    subscriber.write_all(frame).await?;
+    subscriber.flush().await?;
+    let acked_height = subscriber.read_u64().await?;
+    if acked_height != height {
+        let msg = format!("expected acknowledgement of block #{}, got #{}", height, acked_height);
+        return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl Sink for StreamSink {
+    async fn write(&mut self, streamer_message: &StreamerMessage) -> io::Result<()> {
+        let height = streamer_message.block.header.height;
+        let payload = serde_json::to_vec(streamer_message)?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "message is too large"))?;
+        let mut frame = Vec::with_capacity(4 + payload.len());
+        frame.extend_from_slice(&len.to_be_bytes());
+        frame.extend_from_slice(&payload);
+
+        loop {
+            while let Ok(subscriber) = self.new_subscribers.try_recv() {
+                self.subscribers.push(subscriber);
+            }
+            if self.subscribers.is_empty() {
+                match self.new_subscribers.recv().await {
+                    Some(subscriber) => self.subscribers.push(subscriber),
+                    None => {
+                        let msg = "the listener has stopped";
+                        return Err(io::Error::new(io::ErrorKind::BrokenPipe, msg));
+                    }
+                }
+            }
+            let results = join_all(
+                self.subscribers
+                    .iter_mut()
+                    .map(|subscriber| send_frame(subscriber.as_mut(), &frame, height)),
+            )
+            .await;
+            let mut results = results.into_iter();
+            self.subscribers.retain(|_| match results.next().unwrap() {
+                Ok(()) => true,
+                Err(err) => {
+                    info!(target: INDEXER, "Dropping subscriber: {}", err);
+                    false
+                }
+            });
+            if !self.subscribers.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for StreamSink {
+    fn drop(&mut self) {
+        self.accept_loop.abort();
+    }
+}
+
+/// Client side of a `StreamSink`.
+pub struct StreamSubscriber {
+    socket: Box<dyn Socket>,
+}
+
+impl StreamSubscriber {
+    pub async fn connect(address: &StreamAddress) -> io::Result<Self> {
+        let socket: Box<dyn Socket> = match address {
+            StreamAddress::Tcp(addr) => Box::new(TcpStream::connect(addr).await?),
+            StreamAddress::Unix(path) => Box::new(UnixStream::connect(path).await?),
+        };
+        Ok(Self { socket })
+    }
+
+    /// Receives the next message, or `None` if the sink has gone away.
+    pub async fn recv(&mut self) -> io::Result<Option<StreamerMessage>> {
+        let len = match self.socket.read_u32().await {
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let mut payload = vec![0; len as usize];
+        self.socket.read_exact(&mut payload).await?;
+        Ok(Some(serde_json::from_slice(&payload)?))
+    }
+
+    /// Acknowledges the last received message, which lets the sink move on.
+    pub async fn ack(&mut self, block_height: BlockHeight) -> io::Result<()> {
+        self.socket.write_u64(block_height).await?;
+        self.socket.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::test_message;
+
+    async fn recv_height(subscriber: &mut StreamSubscriber) -> BlockHeight {
+        subscriber.recv().await.unwrap().unwrap().block.header.height
+    }
+
+    #[tokio::test]
+    async fn test_tcp_stream_sink() {
+        let mut sink =
+            StreamSink::bind(&StreamAddress::Tcp("127.0.0.1:0".parse().unwrap())).await.unwrap();
+        let address = StreamAddress::Tcp(sink.local_addr().unwrap());
+        let writer = tokio::spawn(async move {
+            for height in 1..=3 {
+                sink.write(&test_message(height)).await.unwrap();
+            }
+        });
+
+        let mut subscriber = StreamSubscriber::connect(&address).await.unwrap();
+        for height in 1..=3 {
+            assert_eq!(recv_height(&mut subscriber).await, height);
+            subscriber.ack(height).await.unwrap();
+        }
+        writer.await.unwrap();
+        // The sink is gone.
+        assert!(subscriber.recv().await.unwrap().is_none());
+    }
+
+    /// A message which isn't acknowledged by the subscriber is sent to the next one.
+    #[tokio::test]
+    async fn test_unix_stream_sink_resends_unacked_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = StreamAddress::Unix(dir.path().join("indexer.sock"));
+        let mut sink = StreamSink::bind(&address).await.unwrap();
+        let (written, mut written_receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for height in 1..=2 {
+                sink.write(&test_message(height)).await.unwrap();
+                written.send(height).unwrap();
+            }
+        });
+
+        let mut subscriber = StreamSubscriber::connect(&address).await.unwrap();
+        assert_eq!(recv_height(&mut subscriber).await, 1);
+        subscriber.ack(1).await.unwrap();
+        assert_eq!(written_receiver.recv().await, Some(1));
+        assert_eq!(recv_height(&mut subscriber).await, 2);
+        drop(subscriber);
+
+        let mut subscriber = StreamSubscriber::connect(&address).await.unwrap();
+        assert_eq!(recv_height(&mut subscriber).await, 2);
+        assert!(written_receiver.try_recv().is_err());
+        subscriber.ack(2).await.unwrap();
+        assert_eq!(written_receiver.recv().await, Some(2));
+    }
+}
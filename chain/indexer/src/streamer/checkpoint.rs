@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use rocksdb::DB;
+use tracing::warn;
+
+use near_primitives::types::BlockHeight;
+
+use crate::INDEXER;
+
+use super::metrics;
+
+const LAST_SYNCED_BLOCK_HEIGHT_KEY: &[u8] = b"last_synced_block_height";
+
+fn read_height(db: &DB, key: &[u8]) -> Option<BlockHeight> {
+    db.get(key).unwrap().map(|value| String::from_utf8(value).unwrap().parse().unwrap())
+}
+
+fn write_height(db: &DB, key: &[u8], height: BlockHeight) {
+    db.put(key, height.to_string()).unwrap();
+}
+
+/// Where the streamer records how far its listener got.
+pub(crate) enum Checkpoint {
+    /// The height of the last block pushed into the channel, see `Indexer::streamer`.
+    LastSynced(Arc<DB>),
+    /// The heights acknowledged by a consumer, see `Indexer::consumer`.
+    Acked(Arc<ConsumerCursor>),
+}
+
+impl Checkpoint {
+    /// The height to start from in `SyncModeEnum::FromInterruption` mode.
+    pub(crate) fn resume_height(&self) -> Option<BlockHeight> {
+        match self {
+            // The last synced block is streamed again.
+            Self::LastSynced(db) => read_height(db, LAST_SYNCED_BLOCK_HEIGHT_KEY),
+            Self::Acked(cursor) => cursor.resume_height(),
+        }
+    }
+
+    /// Called right before the block at `height` is pushed into the channel.
+    pub(crate) fn on_delivery(&self, height: BlockHeight) {
+        match self {
+            Self::LastSynced(_) => {}
+            Self::Acked(cursor) => cursor.deliver(height),
+        }
+    }
+
+    /// Called once the streamer is done with the block at `height`, whether
+    /// it was pushed into the channel or not.
+    pub(crate) fn on_processed(&self, height: BlockHeight, delivered: bool) {
+        match self {
+            Self::LastSynced(db) => write_height(db, LAST_SYNCED_BLOCK_HEIGHT_KEY, height),
+            Self::Acked(cursor) => {
+                if !delivered {
+                    cursor.skip(height);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps track of the blocks delivered to a consumer and advances its cursor,
+/// i.e. the height up to which every block has been acknowledged or skipped.
+#[derive(Debug, Default)]
+struct AckTracker {
+    committed: Option<BlockHeight>,
+    /// Blocks which are delivered or skipped after `committed`, with whether
+    /// they are acknowledged.
+    pending: BTreeMap<BlockHeight, bool>,
+}
+
+impl AckTracker {
+    fn deliver(&mut self, height: BlockHeight) {
+        self.pending.insert(height, false);
+    }
+
+    /// Returns the new cursor if it has advanced.
+    fn skip(&mut self, height: BlockHeight) -> Option<BlockHeight> {
+        self.pending.insert(height, true);
+        self.advance()
+    }
+
+    /// Returns `Err` if the block at `height` is not awaiting an
+    /// acknowledgement, and otherwise the new cursor if it has advanced.
+    fn ack(&mut self, height: BlockHeight) -> Result<Option<BlockHeight>, ()> {
+        match self.pending.get_mut(&height) {
+            Some(acked) => *acked = true,
+            None => return Err(()),
+        }
+        Ok(self.advance())
+    }
+
+    fn advance(&mut self) -> Option<BlockHeight> {
+        let committed = self.committed;
+        while let Some((&height, &true)) = self.pending.iter().next() {
+            self.pending.remove(&height);
+            self.committed = Some(height);
+        }
+        if self.committed != committed {
+            self.committed
+        } else {
+            None
+        }
+    }
+}
+
+/// The persisted cursor of a named consumer. Only acknowledged heights are
+/// persisted, so unacknowledged blocks are delivered again after a restart.
+pub(crate) struct ConsumerCursor {
+    db: Arc<DB>,
+    name: String,
+    key: Vec<u8>,
+    tracker: Mutex<AckTracker>,
+}
+
+impl ConsumerCursor {
+    pub(crate) fn new(db: Arc<DB>, name: &str) -> Self {
+        let key = format!("consumer:{}:last_acked_block_height", name).into_bytes();
+        let committed = read_height(&db, &key);
+        let tracker = Mutex::new(AckTracker { committed, pending: BTreeMap::new() });
+        Self { db, name: name.to_string(), key, tracker }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn resume_height(&self) -> Option<BlockHeight> {
+        self.tracker.lock().unwrap().committed.map(|height| height + 1)
+    }
+
+    fn deliver(&self, height: BlockHeight) {
+        self.tracker.lock().unwrap().deliver(height);
+    }
+
+    fn skip(&self, height: BlockHeight) {
+        let mut tracker = self.tracker.lock().unwrap();
+        if let Some(committed) = tracker.skip(height) {
+            self.persist(committed);
+        }
+    }
+
+    pub(crate) fn ack(&self, height: BlockHeight) {
+        let mut tracker = self.tracker.lock().unwrap();
+        match tracker.ack(height) {
+            Ok(Some(committed)) => self.persist(committed),
+            Ok(None) => {}
+            Err(()) => warn!(
+                target: INDEXER,
+                "Consumer {} acknowledged block #{} which is not awaiting an acknowledgement",
+                self.name,
+                height
+            ),
+        }
+    }
+
+    fn persist(&self, committed: BlockHeight) {
+        write_height(&self.db, &self.key, committed);
+        metrics::CONSUMER_ACKED_BLOCK_HEIGHT.with_label_values(&[&self.name]).set(committed as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AckTracker;
+
+    #[test]
+    fn test_ack_tracker_commits_contiguous_acks() {
+        let mut tracker = AckTracker::default();
+        tracker.deliver(10);
+        tracker.deliver(11);
+        assert_eq!(tracker.skip(12), None);
+        tracker.deliver(13);
+
+        // Out of order acknowledgements don't move the cursor past a gap.
+        assert_eq!(tracker.ack(11), Ok(None));
+        assert_eq!(tracker.ack(13), Ok(None));
+        assert_eq!(tracker.committed, None);
+        assert_eq!(tracker.ack(10), Ok(Some(13)));
+        assert!(tracker.pending.is_empty());
+
+        assert_eq!(tracker.ack(13), Err(()));
+        assert_eq!(tracker.ack(14), Err(()));
+        assert_eq!(tracker.skip(14), Some(14));
+        assert_eq!(tracker.committed, Some(14));
+    }
+
+    #[test]
+    fn test_ack_tracker_keeps_unacked_blocks() {
+        let mut tracker = AckTracker { committed: Some(5), ..Default::default() };
+        tracker.deliver(6);
+        assert_eq!(tracker.skip(7), None);
+        tracker.deliver(8);
+        assert_eq!(tracker.ack(8), Ok(None));
+        // Everything after block #5 is delivered again after a restart.
+        assert_eq!(tracker.committed, Some(5));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+
+use near_indexer_primitives::{IndexerExecutionOutcomeWithOptionalReceipt, StreamerMessage};
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::{AccountId, ShardId};
+use near_primitives::views;
+
+/// Kind of a receipt. Action receipts are of the kinds of all their actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiptKind {
+    Data,
+    CreateAccount,
+    DeployContract,
+    FunctionCall,
+    Transfer,
+    Stake,
+    AddKey,
+    DeleteKey,
+    DeleteAccount,
+}
+
+impl ReceiptKind {
+    fn of_action(action: &views::ActionView) -> Self {
+        match action {
+            views::ActionView::CreateAccount => Self::CreateAccount,
+            views::ActionView::DeployContract { .. } => Self::DeployContract,
+            views::ActionView::FunctionCall { .. } => Self::FunctionCall,
+            views::ActionView::Transfer { .. } => Self::Transfer,
+            views::ActionView::Stake { .. } => Self::Stake,
+            views::ActionView::AddKey { .. } => Self::AddKey,
+            views::ActionView::DeleteKey { .. } => Self::DeleteKey,
+            views::ActionView::DeleteAccount { .. } => Self::DeleteAccount,
+        }
+    }
+}
+
+/// Narrows down the data streamed to a consumer.
+///
+/// Transactions and receipts are kept if one of their accounts (signer and
+/// receiver, or predecessor and receiver) is in `accounts` and they are of
+/// one of `receipt_kinds`; transactions are of the kinds of their actions.
+/// Execution outcomes are kept together with their receipts. State changes
+/// are kept if they are of one of `accounts` and, when `receipt_kinds` is not
+/// empty, caused by a kept transaction or receipt. An empty set doesn't
+/// restrict anything, and blocks with nothing left are not streamed at all.
+///
+/// Blocks are checked before the rest of the `StreamerMessage` is fetched, so
+/// blocks in which the accounts only changed without a matching transaction,
+/// receipt or outcome (e.g. by validator rewards) are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct StreamerMessageFilter {
+    pub accounts: HashSet<AccountId>,
+    pub receipt_kinds: HashSet<ReceiptKind>,
+}
+
+impl StreamerMessageFilter {
+    /// Whether the filter lets everything through.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.receipt_kinds.is_empty()
+    }
+
+    fn matches_accounts<'a>(&self, mut accounts: impl Iterator<Item = &'a AccountId>) -> bool {
+        self.accounts.is_empty() || accounts.any(|account_id| self.accounts.contains(account_id))
+    }
+
+    fn matches_kinds(&self, mut kinds: impl Iterator<Item = ReceiptKind>) -> bool {
+        self.receipt_kinds.is_empty() || kinds.any(|kind| self.receipt_kinds.contains(&kind))
+    }
+
+    fn matches_transaction(&self, transaction: &views::SignedTransactionView) -> bool {
+        self.matches_accounts([&transaction.signer_id, &transaction.receiver_id].into_iter())
+            && self.matches_kinds(transaction.actions.iter().map(ReceiptKind::of_action))
+    }
+
+    fn matches_receipt(&self, receipt: &views::ReceiptView) -> bool {
+        if !self.matches_accounts([&receipt.predecessor_id, &receipt.receiver_id].into_iter()) {
+            return false;
+        }
+        match &receipt.receipt {
+            views::ReceiptEnumView::Action { actions, .. } => {
+                self.matches_kinds(actions.iter().map(ReceiptKind::of_action))
+            }
+            views::ReceiptEnumView::Data { .. } => {
+                self.matches_kinds(std::iter::once(ReceiptKind::Data))
+            }
+        }
+    }
+
+    /// Whether the block with the given chunks and execution outcomes may
+    /// contain anything of interest, so that the `StreamerMessage` is worth
+    /// building.
+    pub(crate) fn matches_block(
+        &self,
+        chunks: &[views::ChunkView],
+        shards_outcomes: &HashMap<ShardId, Vec<IndexerExecutionOutcomeWithOptionalReceipt>>,
+    ) -> bool {
+        for chunk in chunks {
+            if chunk.transactions.iter().any(|transaction| self.matches_transaction(transaction))
+                || chunk.receipts.iter().any(|receipt| self.matches_receipt(receipt))
+            {
+                return true;
+            }
+        }
+        shards_outcomes.iter().any(|(shard_id, outcomes)| {
+            // The outcomes of the transactions come first and the
+            // transactions have been checked above.
+            let num_transactions = chunks
+                .iter()
+                .find(|chunk| chunk.header.shard_id == *shard_id)
+                .map_or(0, |chunk| chunk.transactions.len());
+            outcomes.iter().skip(num_transactions).any(|outcome| match &outcome.receipt {
+                Some(receipt) => self.matches_receipt(receipt),
+                // Local receipts are only found while building the message.
+                None => self.matches_accounts(std::iter::once(
+                    &outcome.execution_outcome.outcome.executor_id,
+                )),
+            })
+        })
+    }
+
+    /// Drops everything not of interest from `streamer_message` and returns
+    /// whether anything is left.
+    pub(crate) fn apply(&self, streamer_message: &mut StreamerMessage) -> bool {
+        let mut is_empty = true;
+        for shard in &mut streamer_message.shards {
+            let mut causes = HashSet::<CryptoHash>::new();
+            if let Some(chunk) = &mut shard.chunk {
+                chunk.transactions.retain(|tx| self.matches_transaction(&tx.transaction));
+                chunk.receipts.retain(|receipt| self.matches_receipt(receipt));
+                causes.extend(chunk.transactions.iter().map(|tx| tx.transaction.hash));
+                causes.extend(chunk.receipts.iter().map(|receipt| receipt.receipt_id));
+                is_empty &= chunk.transactions.is_empty() && chunk.receipts.is_empty();
+            }
+            shard
+                .receipt_execution_outcomes
+                .retain(|outcome| self.matches_receipt(&outcome.receipt));
+            causes.extend(
+                shard.receipt_execution_outcomes.iter().map(|outcome| outcome.receipt.receipt_id),
+            );
+            shard.state_changes.retain(|state_change| {
+                self.matches_accounts(std::iter::once(state_change_account(&state_change.value)))
+                    && (self.receipt_kinds.is_empty()
+                        || state_change_cause(&state_change.cause)
+                            .map_or(false, |hash| causes.contains(hash)))
+            });
+            is_empty &=
+                shard.receipt_execution_outcomes.is_empty() && shard.state_changes.is_empty();
+        }
+        !is_empty
+    }
+}
+
+fn state_change_account(value: &views::StateChangeValueView) -> &AccountId {
+    use views::StateChangeValueView::*;
+    match value {
+        AccountUpdate { account_id, .. }
+        | AccountDeletion { account_id }
+        | AccessKeyUpdate { account_id, .. }
+        | AccessKeyDeletion { account_id, .. }
+        | DataUpdate { account_id, .. }
+        | DataDeletion { account_id, .. }
+        | ContractCodeUpdate { account_id, .. }
+        | ContractCodeDeletion { account_id } => account_id,
+    }
+}
+
+/// The transaction or receipt which caused a state change, if any.
+fn state_change_cause(cause: &views::StateChangeCauseView) -> Option<&CryptoHash> {
+    use views::StateChangeCauseView::*;
+    match cause {
+        TransactionProcessing { tx_hash } => Some(tx_hash),
+        ActionReceiptProcessingStarted { receipt_hash }
+        | ActionReceiptGasReward { receipt_hash }
+        | ReceiptProcessing { receipt_hash }
+        | PostponedReceipt { receipt_hash } => Some(receipt_hash),
+        NotWritableToDisk
+        | InitialState
+        | UpdatedDelayedReceipts
+        | ValidatorAccountsUpdate
+        | Migration
+        | Resharding => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(
+        predecessor_id: &str,
+        receiver_id: &str,
+        actions: Vec<views::ActionView>,
+    ) -> views::ReceiptView {
+        views::ReceiptView {
+            predecessor_id: predecessor_id.parse().unwrap(),
+            receiver_id: receiver_id.parse().unwrap(),
+            receipt_id: CryptoHash::hash_bytes(receiver_id.as_bytes()),
+            receipt: views::ReceiptEnumView::Action {
+                signer_id: predecessor_id.parse().unwrap(),
+                signer_public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+                gas_price: 0,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions,
+            },
+        }
+    }
+
+    #[test]
+    fn test_matches_receipt() {
+        let transfer =
+            receipt("alice.near", "bob.near", vec![views::ActionView::Transfer { deposit: 1 }]);
+        let data = views::ReceiptView {
+            receipt: views::ReceiptEnumView::Data { data_id: CryptoHash::default(), data: None },
+            ..receipt("alice.near", "carol.near", vec![])
+        };
+
+        let filter = StreamerMessageFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches_receipt(&transfer) && filter.matches_receipt(&data));
+
+        let filter = StreamerMessageFilter {
+            accounts: ["bob.near".parse().unwrap()].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(filter.matches_receipt(&transfer));
+        assert!(!filter.matches_receipt(&data));
+
+        let filter = StreamerMessageFilter {
+            accounts: ["alice.near".parse().unwrap()].into_iter().collect(),
+            receipt_kinds: [ReceiptKind::Data, ReceiptKind::FunctionCall].into_iter().collect(),
+        };
+        assert!(!filter.matches_receipt(&transfer));
+        assert!(filter.matches_receipt(&data));
+    }
+}
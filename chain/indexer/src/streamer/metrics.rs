@@ -1,6 +1,6 @@
 use near_o11y::metrics::{
-    try_create_histogram, try_create_int_counter, try_create_int_gauge, Histogram, IntCounter,
-    IntGauge,
+    try_create_histogram, try_create_int_counter, try_create_int_gauge, try_create_int_gauge_vec,
+    Histogram, IntCounter, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -43,3 +43,12 @@ pub(crate) static BUILD_STREAMER_MESSAGE_TIME: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub(crate) static CONSUMER_ACKED_BLOCK_HEIGHT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "near_indexer_consumer_acked_block_height",
+        "Block height up to which the consumer has acknowledged all the blocks",
+        &["consumer"],
+    )
+    .unwrap()
+});
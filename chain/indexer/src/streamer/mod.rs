@@ -1,8 +1,8 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use actix::Addr;
 use async_recursion::async_recursion;
-use rocksdb::DB;
 use tokio::sync::mpsc;
 use tokio::time;
 use tracing::{debug, info};
@@ -13,9 +13,10 @@ use near_indexer_primitives::{
     StreamerMessage,
 };
 use near_primitives::hash::CryptoHash;
+use near_primitives::types::ShardId;
 use near_primitives::views;
 
-use crate::{AwaitForNodeSyncedEnum, IndexerConfig};
+use crate::{AwaitForNodeSyncedEnum, SyncModeEnum};
 
 use self::errors::FailedToFetchData;
 use self::fetchers::{
@@ -26,8 +27,13 @@ use self::utils::convert_transactions_sir_into_local_receipts;
 use crate::streamer::fetchers::fetch_protocol_config;
 use crate::INDEXER;
 
+pub(crate) use self::checkpoint::{Checkpoint, ConsumerCursor};
+pub use self::filter::{ReceiptKind, StreamerMessageFilter};
+
+mod checkpoint;
 mod errors;
 mod fetchers;
+mod filter;
 mod metrics;
 mod utils;
 
@@ -73,12 +79,38 @@ async fn build_streamer_message(
 ) -> Result<StreamerMessage, FailedToFetchData> {
     let _timer = metrics::BUILD_STREAMER_MESSAGE_TIME.start_timer();
     let chunks = fetch_block_chunks(&client, &block).await?;
+    let shards_outcomes = fetch_outcomes(&client, block.header.hash).await?;
+    build_streamer_message_from_parts(client, block, chunks, shards_outcomes).await
+}
 
+/// Same as `build_streamer_message`, but returns `None` without building the
+/// `StreamerMessage` if there's nothing in the block that passes `filter`.
+async fn build_filtered_streamer_message(
+    client: &Addr<near_client::ViewClientActor>,
+    block: views::BlockView,
+    filter: &StreamerMessageFilter,
+) -> Result<Option<StreamerMessage>, FailedToFetchData> {
+    let _timer = metrics::BUILD_STREAMER_MESSAGE_TIME.start_timer();
+    let chunks = fetch_block_chunks(&client, &block).await?;
+    let shards_outcomes = fetch_outcomes(&client, block.header.hash).await?;
+    if !filter.matches_block(&chunks, &shards_outcomes) {
+        return Ok(None);
+    }
+    let mut streamer_message =
+        build_streamer_message_from_parts(client, block, chunks, shards_outcomes).await?;
+    Ok(filter.apply(&mut streamer_message).then_some(streamer_message))
+}
+
+async fn build_streamer_message_from_parts(
+    client: &Addr<near_client::ViewClientActor>,
+    block: views::BlockView,
+    chunks: Vec<views::ChunkView>,
+    mut shards_outcomes: HashMap<ShardId, Vec<IndexerExecutionOutcomeWithOptionalReceipt>>,
+) -> Result<StreamerMessage, FailedToFetchData> {
     let protocol_config_view = fetch_protocol_config(&client, block.header.hash).await?;
     let num_shards = protocol_config_view.num_block_producer_seats_per_shard.len()
         as near_primitives::types::NumShards;
 
-    let mut shards_outcomes = fetch_outcomes(&client, block.header.hash).await?;
     let mut state_changes = fetch_state_changes(
         &client,
         block.header.hash,
@@ -280,28 +312,30 @@ async fn find_local_receipt_by_id_in_block(
 
 /// Function that starts Streamer's busy loop. Every half a seconds it fetches the status
 /// compares to already fetched block height and in case it differs fetches new block of given height.
+/// Blocks without anything passing `filter` are not pushed into `blocks_sink`, and the progress
+/// of the listener is recorded in `checkpoint`.
 ///
 /// We have to pass `client: Addr<near_client::ClientActor>` and `view_client: Addr<near_client::ViewClientActor>`.
 pub(crate) async fn start(
     view_client: Addr<near_client::ViewClientActor>,
     client: Addr<near_client::ClientActor>,
-    indexer_config: IndexerConfig,
-    store_config: near_store::StoreConfig,
+    sync_mode: SyncModeEnum,
+    await_for_node_synced: AwaitForNodeSyncedEnum,
+    filter: StreamerMessageFilter,
+    checkpoint: Checkpoint,
     blocks_sink: mpsc::Sender<StreamerMessage>,
 ) {
-    info!(target: INDEXER, "Starting Streamer...");
-    let indexer_db_path =
-        near_store::NodeStorage::opener(&indexer_config.home_dir, &store_config, None)
-            .path()
-            .join("indexer");
-
-    // TODO: implement proper error handling
-    let db = DB::open_default(indexer_db_path).unwrap();
+    match &checkpoint {
+        Checkpoint::LastSynced(_) => info!(target: INDEXER, "Starting Streamer..."),
+        Checkpoint::Acked(cursor) => {
+            info!(target: INDEXER, "Starting Streamer for consumer {}...", cursor.name())
+        }
+    }
     let mut last_synced_block_height: Option<near_primitives::types::BlockHeight> = None;
 
     'main: loop {
         time::sleep(INTERVAL).await;
-        match indexer_config.await_for_node_synced {
+        match await_for_node_synced {
             AwaitForNodeSyncedEnum::WaitForFullSync => {
                 let status = fetch_status(&client).await;
                 if let Ok(status) = status {
@@ -320,22 +354,18 @@ pub(crate) async fn start(
         };
 
         let latest_block_height = block.header.height;
-        let start_syncing_block_height = if let Some(last_synced_block_height) =
-            last_synced_block_height
-        {
-            last_synced_block_height + 1
-        } else {
-            match indexer_config.sync_mode {
-                crate::SyncModeEnum::FromInterruption => {
-                    match db.get(b"last_synced_block_height").unwrap() {
-                        Some(value) => String::from_utf8(value).unwrap().parse::<u64>().unwrap(),
-                        None => latest_block_height,
+        let start_syncing_block_height =
+            if let Some(last_synced_block_height) = last_synced_block_height {
+                last_synced_block_height + 1
+            } else {
+                match sync_mode {
+                    SyncModeEnum::FromInterruption => {
+                        checkpoint.resume_height().unwrap_or(latest_block_height)
                     }
+                    SyncModeEnum::LatestSynced => latest_block_height,
+                    SyncModeEnum::BlockHeight(height) => height,
                 }
-                crate::SyncModeEnum::LatestSynced => latest_block_height,
-                crate::SyncModeEnum::BlockHeight(height) => height,
-            }
-        };
+            };
 
         debug!(
             target: INDEXER,
@@ -347,12 +377,18 @@ pub(crate) async fn start(
         metrics::LATEST_BLOCK_HEIGHT.set(latest_block_height as i64);
         for block_height in start_syncing_block_height..=latest_block_height {
             metrics::CURRENT_BLOCK_HEIGHT.set(block_height as i64);
+            let mut delivered = false;
             if let Ok(block) = fetch_block_by_height(&view_client, block_height).await {
-                let response = build_streamer_message(&view_client, block).await;
+                let response = if filter.is_empty() {
+                    build_streamer_message(&view_client, block).await.map(Some)
+                } else {
+                    build_filtered_streamer_message(&view_client, block, &filter).await
+                };
 
                 match response {
-                    Ok(streamer_message) => {
+                    Ok(Some(streamer_message)) => {
                         debug!(target: INDEXER, "{:#?}", &streamer_message);
+                        checkpoint.on_delivery(block_height);
                         if blocks_sink.send(streamer_message).await.is_err() {
                             info!(
                                 target: INDEXER,
@@ -362,6 +398,13 @@ pub(crate) async fn start(
                         } else {
                             metrics::NUM_STREAMER_MESSAGES_SENT.inc();
                         }
+                        delivered = true;
+                    }
+                    Ok(None) => {
+                        debug!(
+                            target: INDEXER,
+                            "Nothing passes the filter, skipping block #{}...", block_height
+                        );
                     }
                     Err(err) => {
                         debug!(
@@ -372,7 +415,7 @@ pub(crate) async fn start(
                     }
                 }
             }
-            db.put(b"last_synced_block_height", &block_height.to_string()).unwrap();
+            checkpoint.on_processed(block_height, delivered);
             last_synced_block_height = Some(block_height);
         }
     }
@@ -39,6 +39,7 @@ pub(crate) async fn convert_transactions_sir_into_local_receipts(
                                 near_primitives::transaction::Action::try_from(action).unwrap()
                             })
                             .collect(),
+                        priority_fee: tx.transaction.priority_fee,
                     },
                     prev_block_gas_price,
                     true,
@@ -38,6 +38,9 @@ pub enum RpcErrorKind {
 pub enum RpcRequestValidationErrorKind {
     MethodNotFound { method_name: String },
     ParseError { error_message: String },
+    InvalidBatch { error_message: String },
+    RateLimitExceeded { method_name: String },
+    TooManySubscriptions { max_subscriptions: usize },
 }
 
 /// A general Server Error
@@ -143,6 +146,44 @@ impl RpcError {
             )),
         }
     }
+
+    /// Create an error for a batch which can't be processed, e.g. an empty
+    /// one or one longer than the server allows.
+    pub fn invalid_batch(e: String) -> Self {
+        RpcError {
+            code: -32_600,
+            message: "Invalid Request".to_owned(),
+            data: Some(Value::String(e.clone())),
+            error_struct: Some(RpcErrorKind::RequestValidationError(
+                RpcRequestValidationErrorKind::InvalidBatch { error_message: e },
+            )),
+        }
+    }
+
+    /// Create an error for a request rejected by the rate limits of the server.
+    pub fn rate_limit_exceeded(method: String) -> Self {
+        RpcError {
+            code: -32_005,
+            message: "Rate limit exceeded".to_owned(),
+            data: Some(Value::String(method.clone())),
+            error_struct: Some(RpcErrorKind::RequestValidationError(
+                RpcRequestValidationErrorKind::RateLimitExceeded { method_name: method },
+            )),
+        }
+    }
+
+    /// Create an error for a subscription over the limit of a WebSocket
+    /// connection.
+    pub fn too_many_subscriptions(max_subscriptions: usize) -> Self {
+        RpcError {
+            code: -32_005,
+            message: "Too many subscriptions".to_owned(),
+            data: Some(Value::from(max_subscriptions)),
+            error_struct: Some(RpcErrorKind::RequestValidationError(
+                RpcRequestValidationErrorKind::TooManySubscriptions { max_subscriptions },
+            )),
+        }
+    }
 }
 
 impl fmt::Display for RpcError {
@@ -20,6 +20,10 @@ pub struct RpcLightClientExecutionProofResponse {
     pub outcome_root_proof: near_primitives::merkle::MerklePath,
     pub block_header_lite: near_primitives::views::LightClientBlockLiteView,
     pub block_proof: near_primitives::merkle::MerklePath,
+    /// Hash function of each item of `block_proof`.  Empty in responses of
+    /// older nodes, whose block proofs are hashed with SHA-256 only.
+    #[serde(default)]
+    pub block_proof_hash_algorithms: Vec<near_primitives::hash::HashAlgorithm>,
 }
 
 #[derive(Debug, Serialize)]
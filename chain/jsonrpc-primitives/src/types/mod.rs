@@ -9,5 +9,6 @@ pub mod query;
 pub mod receipts;
 pub mod sandbox;
 pub mod status;
+pub mod subscriptions;
 pub mod transactions;
 pub mod validator;
@@ -77,6 +77,10 @@ pub struct RpcQueryResponse {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum QueryResponseKind {
+    // The variants with proofs have all the fields of the ones without, so
+    // they must come first for the untagged deserialization to pick them.
+    ViewAccountWithProof(near_primitives::views::AccountWithProofView),
+    AccessKeyWithProof(near_primitives::views::AccessKeyWithProofView),
     ViewAccount(near_primitives::views::AccountView),
     ViewCode(near_primitives::views::ContractCodeView),
     ViewState(near_primitives::views::ViewStateResult),
@@ -100,3 +104,27 @@ impl From<RpcQueryError> for crate::errors::RpcError {
         Self::new_internal_or_handler_error(error_data, error_data_value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_with_proof() {
+        let account = serde_json::json!({
+            "amount": "1",
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": 100,
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        });
+        let response: RpcQueryResponse = serde_json::from_value(account.clone()).unwrap();
+        assert!(matches!(response.kind, QueryResponseKind::ViewAccount(_)));
+
+        let mut with_proof = account;
+        with_proof["proof"] = serde_json::json!([]);
+        let response: RpcQueryResponse = serde_json::from_value(with_proof).unwrap();
+        assert!(matches!(response.kind, QueryResponseKind::ViewAccountWithProof(_)));
+    }
+}
@@ -1,7 +1,7 @@
 use near_client_primitives::debug::{
     DebugBlockStatusData, EpochInfoView, TrackedShardsView, ValidatorStatus,
 };
-use near_primitives::views::{CatchupStatusView, PeerStoreView, SyncStatusView};
+use near_primitives::views::{CatchupStatusView, PeerStoreView, SubnetsView, SyncStatusView};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +22,8 @@ pub enum DebugStatusResponse {
     // Detailed information about the validator (approvals, block & chunk production etc.)
     ValidatorStatus(ValidatorStatus),
     PeerStore(PeerStoreView),
+    // Number of connections per subnet.
+    Subnets(SubnetsView),
 }
 
 #[cfg(feature = "debug_types")]
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Method of the notifications sent to the WebSocket subscribers.
+pub const SUBSCRIPTION_METHOD: &str = "subscription";
+
+/// Params of the `subscription` notifications.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcSubscriptionNotification {
+    pub subscription: u64,
+    pub result: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcSubscribeTxStatusRequest {
+    pub tx_hash: near_primitives::hash::CryptoHash,
+    pub sender_account_id: near_primitives::types::AccountId,
+    #[serde(default)]
+    pub wait_until: near_primitives::views::TxExecutionStatus,
+}
+
+/// Result of a `subscribe_tx_status` notification, sent once the transaction
+/// reaches the awaited status.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcTxStatusNotification {
+    pub status: near_primitives::views::TxExecutionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<near_primitives::views::FinalExecutionOutcomeView>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcSubscribeStateChangesRequest {
+    #[serde(flatten)]
+    pub state_changes_request: near_primitives::views::StateChangesRequestView,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcUnsubscribeRequest {
+    pub subscription: u64,
+}
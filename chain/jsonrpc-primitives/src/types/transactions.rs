@@ -34,6 +34,8 @@ pub enum RpcTransactionError {
     RequestRouted { transaction_hash: near_primitives::hash::CryptoHash },
     #[error("Transaction {requested_transaction_hash} doesn't exist")]
     UnknownTransaction { requested_transaction_hash: near_primitives::hash::CryptoHash },
+    #[error("The node's transaction pool has no room for the transaction: {reason}")]
+    TransactionPoolRejected { reason: String },
     #[error("The node reached its limits. Try again later. More details: {debug_info}")]
     InternalError { debug_info: String },
     #[error("Timeout")]
@@ -19,6 +19,10 @@ use near_primitives::views::{
     StatusResponse,
 };
 
+mod ws;
+
+pub use ws::WsClient;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ChunkId {
@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use awc::ws;
+use futures::stream::LocalBoxStream;
+use futures::{Sink, SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use near_jsonrpc_primitives::errors::RpcError;
+use near_jsonrpc_primitives::message::{from_slice, Message, Notification};
+use near_jsonrpc_primitives::types::subscriptions::{
+    RpcSubscribeStateChangesRequest, RpcSubscribeTxStatusRequest, RpcSubscriptionNotification,
+    RpcUnsubscribeRequest, SUBSCRIPTION_METHOD,
+};
+
+use crate::{create_client, PAYLOAD_LIMIT};
+
+/// Client of the `/ws` endpoint of the JSON-RPC server, which pushes the
+/// results of the subscriptions as they happen.
+///
+/// The results of `subscribe_new_heads` and `subscribe_final_blocks` are
+/// `BlockView`s, the ones of `subscribe_tx_status` are
+/// `RpcTxStatusNotification`s and the ones of `subscribe_state_changes` are
+/// `RpcStateChangesInBlockResponse`s.
+pub struct WsClient {
+    sink: Pin<Box<dyn Sink<ws::Message, Error = ws::ProtocolError>>>,
+    frames: LocalBoxStream<'static, Result<ws::Frame, ws::ProtocolError>>,
+    /// Notifications received while waiting for a response.
+    notifications: VecDeque<RpcSubscriptionNotification>,
+}
+
+fn internal_error(err: impl std::fmt::Debug) -> RpcError {
+    RpcError::new_internal_error(None, format!("{:?}", err))
+}
+
+fn parse_notification(notification: Notification) -> Option<RpcSubscriptionNotification> {
+    if notification.method != SUBSCRIPTION_METHOD {
+        return None;
+    }
+    serde_json::from_value(notification.params?).ok()
+}
+
+impl WsClient {
+    /// Connects to the JSON-RPC server at `server_addr`, e.g. `http://127.0.0.1:3030`.
+    pub async fn connect(server_addr: &str) -> Result<Self, RpcError> {
+        let url = format!("{}/ws", server_addr.replacen("http", "ws", 1));
+        let (_response, framed) = create_client()
+            .ws(url)
+            .max_frame_size(PAYLOAD_LIMIT)
+            .connect()
+            .await
+            .map_err(internal_error)?;
+        let (sink, frames) = framed.split();
+        Ok(Self {
+            sink: Box::pin(sink),
+            frames: frames.boxed_local(),
+            notifications: VecDeque::new(),
+        })
+    }
+
+    /// Calls a method over the connection and waits for its response.
+    pub async fn call<P, R>(&mut self, method: &str, params: P) -> Result<R, RpcError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let request =
+            Message::request(method.to_string(), Some(serde_json::to_value(&params).unwrap()));
+        let id = request.id();
+        let text = serde_json::to_string(&request).unwrap();
+        self.sink.send(ws::Message::Text(text.into())).await.map_err(internal_error)?;
+        loop {
+            match self.next_message().await? {
+                // Errors which the server can't attribute to a request have no id.
+                Message::Response(response) if response.id == id || response.id.is_null() => {
+                    return response.result.and_then(|x| {
+                        serde_json::from_value(x).map_err(|err| {
+                            RpcError::parse_error(format!("Failed to parse: {:?}", err))
+                        })
+                    });
+                }
+                Message::Notification(notification) => {
+                    self.notifications.extend(parse_notification(notification));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn next_message(&mut self) -> Result<Message, RpcError> {
+        while let Some(frame) = self.frames.next().await {
+            match frame.map_err(internal_error)? {
+                ws::Frame::Text(bytes) => {
+                    return from_slice(&bytes).map_err(|err| {
+                        RpcError::parse_error(format!("Error {:?} in {:?}", err, bytes))
+                    });
+                }
+                ws::Frame::Ping(bytes) => {
+                    self.sink.send(ws::Message::Pong(bytes)).await.map_err(internal_error)?
+                }
+                ws::Frame::Close(_) => break,
+                _ => {}
+            }
+        }
+        Err(RpcError::new_internal_error(None, "Connection closed".to_string()))
+    }
+
+    /// Waits for the next notification of any of the subscriptions.
+    pub async fn next_notification(&mut self) -> Result<RpcSubscriptionNotification, RpcError> {
+        if let Some(notification) = self.notifications.pop_front() {
+            return Ok(notification);
+        }
+        loop {
+            if let Message::Notification(notification) = self.next_message().await? {
+                if let Some(notification) = parse_notification(notification) {
+                    return Ok(notification);
+                }
+            }
+        }
+    }
+
+    /// Subscribes to the blocks which become the head of the chain. Returns
+    /// the id of the subscription.
+    pub async fn subscribe_new_heads(&mut self) -> Result<u64, RpcError> {
+        self.call("subscribe_new_heads", [(); 0]).await
+    }
+
+    /// Subscribes to the blocks which become final, in order.
+    pub async fn subscribe_final_blocks(&mut self) -> Result<u64, RpcError> {
+        self.call("subscribe_final_blocks", [(); 0]).await
+    }
+
+    /// Subscribes to a single notification sent once the transaction reaches
+    /// the requested status. The server gives up after a few minutes, without
+    /// sending a notification.
+    pub async fn subscribe_tx_status(
+        &mut self,
+        request: RpcSubscribeTxStatusRequest,
+    ) -> Result<u64, RpcError> {
+        self.call("subscribe_tx_status", request).await
+    }
+
+    /// Subscribes to the changes of the requested kind in the final blocks.
+    pub async fn subscribe_state_changes(
+        &mut self,
+        request: RpcSubscribeStateChangesRequest,
+    ) -> Result<u64, RpcError> {
+        self.call("subscribe_state_changes", request).await
+    }
+
+    /// Cancels a subscription. Returns whether it was still active.
+    pub async fn unsubscribe(&mut self, subscription: u64) -> Result<bool, RpcError> {
+        self.call("unsubscribe", RpcUnsubscribeRequest { subscription }).await
+    }
+}
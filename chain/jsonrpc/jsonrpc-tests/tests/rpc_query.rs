@@ -159,21 +159,30 @@ fn test_query_account() {
         let query_response_1 = client
             .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
                 block_reference: BlockReference::latest(),
-                request: QueryRequest::ViewAccount { account_id: "test".parse().unwrap() },
+                request: QueryRequest::ViewAccount {
+                    account_id: "test".parse().unwrap(),
+                    include_proof: false,
+                },
             })
             .await
             .unwrap();
         let query_response_2 = client
             .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
                 block_reference: BlockReference::BlockId(BlockId::Height(0)),
-                request: QueryRequest::ViewAccount { account_id: "test".parse().unwrap() },
+                request: QueryRequest::ViewAccount {
+                    account_id: "test".parse().unwrap(),
+                    include_proof: false,
+                },
             })
             .await
             .unwrap();
         let query_response_3 = client
             .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
                 block_reference: BlockReference::BlockId(BlockId::Hash(block_hash)),
-                request: QueryRequest::ViewAccount { account_id: "test".parse().unwrap() },
+                request: QueryRequest::ViewAccount {
+                    account_id: "test".parse().unwrap(),
+                    include_proof: false,
+                },
             })
             .await
             .unwrap();
@@ -273,6 +282,7 @@ fn test_query_access_key() {
                     public_key: "ed25519:23vYngy8iL7q94jby3gszBnZ9JptpMf5Hgf7KVVa2yQ2"
                         .parse()
                         .unwrap(),
+                    include_proof: false,
                 },
             })
             .await
@@ -582,3 +592,28 @@ fn test_get_chunk_with_object_in_params() {
         assert_eq!(chunk.header.chunk_hash, same_chunk.header.chunk_hash);
     });
 }
+
+/// Send a batch of requests and get the responses in the order of the requests.
+#[test]
+fn test_batch() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "block", "params": {"block_id": 0}},
+            {"jsonrpc": "2.0", "id": 2, "method": "no_such_method", "params": []},
+            {"jsonrpc": "2.0", "id": 3, "method": "status", "params": []},
+        ]);
+        let mut response = client.client.post(&client.server_addr).send_json(&batch).await.unwrap();
+        let responses: Vec<serde_json::Value> = response.json().await.unwrap();
+        let ids: Vec<_> = responses.iter().map(|response| response["id"].clone()).collect();
+        assert_eq!(ids, vec![json!(1), json!(2), json!(3)]);
+        assert_eq!(responses[0]["result"]["header"]["height"], json!(0));
+        assert_eq!(responses[1]["error"]["code"], json!(-32_601));
+        assert_eq!(responses[2]["result"]["chain_id"], json!("unittest"));
+
+        // An empty batch is rejected as a whole.
+        let mut response =
+            client.client.post(&client.server_addr).send_json(&json!([])).await.unwrap();
+        let response: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(response["error"]["code"], json!(-32_600));
+    });
+}
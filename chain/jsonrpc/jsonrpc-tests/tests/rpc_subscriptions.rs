@@ -0,0 +1,95 @@
+use actix::System;
+use borsh::BorshSerialize;
+
+use near_crypto::{InMemorySigner, KeyType};
+use near_jsonrpc::client::{new_client, WsClient};
+use near_jsonrpc_primitives::errors::{RpcErrorKind, RpcRequestValidationErrorKind};
+use near_jsonrpc_primitives::types::subscriptions::{
+    RpcSubscribeTxStatusRequest, RpcTxStatusNotification,
+};
+use near_o11y::testonly::init_test_logger;
+use near_primitives::serialize::to_base64;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::BlockReference;
+use near_primitives::views::{BlockView, FinalExecutionStatus, TxExecutionStatus};
+
+use near_jsonrpc_tests::{self as test_utils, test_with_client};
+
+/// Subscribe to the new heads and receive blocks of increasing heights.
+#[test]
+fn test_subscribe_new_heads() {
+    test_with_client!(test_utils::NodeType::Validator, client, async move {
+        let mut ws = WsClient::connect(&client.server_addr).await.unwrap();
+        let subscription = ws.subscribe_new_heads().await.unwrap();
+        let mut last_height = 0;
+        for _ in 0..3 {
+            let notification = ws.next_notification().await.unwrap();
+            assert_eq!(notification.subscription, subscription);
+            let block: BlockView = serde_json::from_value(notification.result).unwrap();
+            assert!(block.header.height > last_height);
+            last_height = block.header.height;
+        }
+        assert!(ws.unsubscribe(subscription).await.unwrap());
+        assert!(!ws.unsubscribe(subscription).await.unwrap());
+    });
+}
+
+/// A connection can't have more than 100 subscriptions at a time.
+#[test]
+fn test_subscriptions_limit() {
+    test_with_client!(test_utils::NodeType::Validator, client, async move {
+        let mut ws = WsClient::connect(&client.server_addr).await.unwrap();
+        let mut subscriptions = Vec::new();
+        for _ in 0..100 {
+            subscriptions.push(ws.subscribe_final_blocks().await.unwrap());
+        }
+        let err = ws.subscribe_final_blocks().await.unwrap_err();
+        assert_eq!(
+            err.error_struct,
+            Some(RpcErrorKind::RequestValidationError(
+                RpcRequestValidationErrorKind::TooManySubscriptions { max_subscriptions: 100 }
+            ))
+        );
+        assert!(ws.unsubscribe(subscriptions[0]).await.unwrap());
+        ws.subscribe_final_blocks().await.unwrap();
+    });
+}
+
+/// Subscribe to the status of a transaction and get notified once it is executed.
+#[test]
+fn test_subscribe_tx_status() {
+    test_with_client!(test_utils::NodeType::Validator, client, async move {
+        let mut ws = WsClient::connect(&client.server_addr).await.unwrap();
+        let block_hash = client.block(BlockReference::latest()).await.unwrap().header.hash;
+        let signer = InMemorySigner::from_seed("test1".parse().unwrap(), KeyType::ED25519, "test1");
+        let tx = SignedTransaction::send_money(
+            1,
+            "test1".parse().unwrap(),
+            "test2".parse().unwrap(),
+            &signer,
+            100,
+            block_hash,
+        );
+        let subscription = ws
+            .subscribe_tx_status(RpcSubscribeTxStatusRequest {
+                tx_hash: tx.get_hash(),
+                sender_account_id: "test1".parse().unwrap(),
+                wait_until: TxExecutionStatus::Executed,
+            })
+            .await
+            .unwrap();
+        let bytes = tx.try_to_vec().unwrap();
+        client.broadcast_tx_async(to_base64(&bytes)).await.unwrap();
+
+        let notification = ws.next_notification().await.unwrap();
+        assert_eq!(notification.subscription, subscription);
+        let notification: RpcTxStatusNotification =
+            serde_json::from_value(notification.result).unwrap();
+        assert_eq!(notification.status, TxExecutionStatus::Executed);
+        let outcome = notification.outcome.unwrap();
+        assert_eq!(outcome.status, FinalExecutionStatus::SuccessValue(Vec::new()));
+        assert_eq!(outcome.transaction.hash, tx.get_hash());
+        // The subscription ends after the notification.
+        assert!(!ws.unsubscribe(subscription).await.unwrap());
+    });
+}
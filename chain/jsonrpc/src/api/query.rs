@@ -53,7 +53,7 @@ impl RpcRequest for RpcQueryRequest {
             let maybe_extra_arg = path_parts.next();
 
             let request = match query_command {
-                "account" => QueryRequest::ViewAccount { account_id },
+                "account" => QueryRequest::ViewAccount { account_id, include_proof: false },
                 "access_key" => match maybe_extra_arg {
                     None => QueryRequest::ViewAccessKeyList { account_id },
                     Some(pk) => QueryRequest::ViewAccessKey {
@@ -61,6 +61,7 @@ impl RpcRequest for RpcQueryRequest {
                         public_key: pk
                             .parse()
                             .map_err(|_| RpcParseError("Invalid public key".to_string()))?,
+                        include_proof: false,
                     },
                 },
                 "code" => QueryRequest::ViewCode { account_id },
@@ -153,6 +154,9 @@ impl RpcFrom<near_primitives::views::QueryResponseKind>
             near_primitives::views::QueryResponseKind::ViewAccount(account_view) => {
                 Self::ViewAccount(account_view)
             }
+            near_primitives::views::QueryResponseKind::ViewAccountWithProof(account_view) => {
+                Self::ViewAccountWithProof(account_view)
+            }
             near_primitives::views::QueryResponseKind::ViewCode(contract_code_view) => {
                 Self::ViewCode(contract_code_view)
             }
@@ -165,6 +169,9 @@ impl RpcFrom<near_primitives::views::QueryResponseKind>
             near_primitives::views::QueryResponseKind::AccessKey(access_key_view) => {
                 Self::AccessKey(access_key_view)
             }
+            near_primitives::views::QueryResponseKind::AccessKeyWithProof(access_key_view) => {
+                Self::AccessKeyWithProof(access_key_view)
+            }
             near_primitives::views::QueryResponseKind::AccessKeyList(access_key_list) => {
                 Self::AccessKeyList(access_key_list)
             }
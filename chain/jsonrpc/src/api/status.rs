@@ -53,6 +53,9 @@ impl RpcFrom<near_network::debug::DebugStatus>
             near_network::debug::DebugStatus::PeerStore(x) => {
                 near_jsonrpc_primitives::types::status::DebugStatusResponse::PeerStore(x)
             }
+            near_network::debug::DebugStatus::Subnets(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::Subnets(x)
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use actix::{Addr, MailboxError};
@@ -35,10 +36,14 @@ use near_primitives::views::FinalExecutionOutcomeViewEnum;
 
 mod api;
 mod metrics;
+mod rate_limit;
+mod ws;
 
 use api::RpcRequest;
 pub use api::{RpcFrom, RpcInto};
 use near_o11y::{WithSpanContext, WithSpanContextExt};
+use rate_limit::{ClientId, RateLimiter};
+pub use rate_limit::{RateLimit, RpcRateLimitsConfig};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct RpcPollingConfig {
@@ -59,11 +64,25 @@ impl Default for RpcPollingConfig {
 pub struct RpcLimitsConfig {
     /// Maximum byte size of the json payload.
     pub json_payload_max_size: usize,
+    /// Maximum number of requests in a batch.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Per-client limits of the request rate. Nothing is limited by default.
+    #[serde(default)]
+    pub rate_limits: RpcRateLimitsConfig,
+}
+
+fn default_max_batch_size() -> usize {
+    100
 }
 
 impl Default for RpcLimitsConfig {
     fn default() -> Self {
-        Self { json_payload_max_size: 10 * 1024 * 1024 }
+        Self {
+            json_payload_max_size: 10 * 1024 * 1024,
+            max_batch_size: default_max_batch_size(),
+            rate_limits: Default::default(),
+        }
     }
 }
 
@@ -145,6 +164,9 @@ impl near_jsonrpc_primitives::types::transactions::RpcTransactionError {
             ProcessTxResponse::DoesNotTrackShard | ProcessTxResponse::RequestRouted => {
                 Self::DoesNotTrackShard
             }
+            ProcessTxResponse::PoolRejected(result) => {
+                Self::TransactionPoolRejected { reason: result.to_string() }
+            }
             internal_error => Self::InternalError { debug_info: format!("{:?}", internal_error) },
         }
     }
@@ -225,18 +247,46 @@ struct JsonRpcHandler {
     genesis_config: GenesisConfig,
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
+    max_batch_size: usize,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl JsonRpcHandler {
-    pub async fn process(&self, message: Message) -> Result<Message, HttpError> {
+    pub async fn process(&self, message: Message, client: &ClientId) -> Result<Message, HttpError> {
+        match message {
+            Message::Batch(messages) => Ok(self.process_batch(messages, client).await),
+            message => Ok(self.process_single(message, client).await),
+        }
+    }
+
+    /// Processes the requests of a batch concurrently. The responses are in
+    /// the order of the requests.
+    async fn process_batch(&self, messages: Vec<Message>, client: &ClientId) -> Message {
+        if messages.is_empty() || messages.len() > self.max_batch_size {
+            metrics::RPC_REJECTED_BATCH_COUNT.inc();
+            return Message::error(RpcError::invalid_batch(format!(
+                "Batch must have from 1 to {} requests, got {}",
+                self.max_batch_size,
+                messages.len()
+            )));
+        }
+        let responses = messages.into_iter().map(|message| self.process_single(message, client));
+        Message::Batch(futures::future::join_all(responses).await)
+    }
+
+    async fn process_single(&self, message: Message, client: &ClientId) -> Message {
         let id = message.id();
         match message {
             Message::Request(request) => {
-                Ok(Message::response(id, self.process_request(request).await))
+                let response = match self.rate_limiter.check(client, &request) {
+                    Ok(()) => self.process_request(request).await,
+                    Err(err) => Err(err),
+                };
+                Message::response(id, response)
             }
-            _ => Ok(Message::error(RpcError::parse_error(
+            _ => Message::error(RpcError::parse_error(
                 "JSON RPC Request format was expected".to_owned(),
-            ))),
+            )),
         }
     }
 
@@ -790,6 +840,10 @@ impl JsonRpcHandler {
                         .peer_manager_send(near_network::debug::GetDebugStatus::PeerStore)
                         .await?
                         .rpc_into(),
+                    "/debug/api/subnets" => self
+                        .peer_manager_send(near_network::debug::GetDebugStatus::Subnets)
+                        .await?
+                        .rpc_into(),
                     _ => return Ok(None),
                 };
             return Ok(Some(near_jsonrpc_primitives::types::status::RpcDebugStatusResponse {
@@ -983,6 +1037,7 @@ impl JsonRpcHandler {
             outcome_root_proof: execution_outcome_proof.outcome_root_proof,
             block_header_lite: block_proof.block_header_lite,
             block_proof: block_proof.proof,
+            block_proof_hash_algorithms: block_proof.proof_hash_algorithms,
         })
     }
 
@@ -1278,11 +1333,13 @@ impl JsonRpcHandler {
 }
 
 fn rpc_handler(
+    req: HttpRequest,
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
     let response = async move {
-        let message = handler.process(message.0).await?;
+        let client = handler.rate_limiter.client_id(&req);
+        let message = handler.process(message.0, &client).await?;
         Ok(HttpResponse::Ok().json(&message))
     };
     response.boxed()
@@ -1452,6 +1509,9 @@ pub fn start_http(
     } = config;
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr);
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
+    // Shared by all the workers so that a client has the same limits whichever
+    // of them serves it.
+    let rate_limiter = Arc::new(RateLimiter::new(limits_config.rate_limits.clone()));
     info!(target:"network", "Starting http server at {}", addr);
     let mut servers = Vec::new();
     let server = HttpServer::new(move || {
@@ -1465,6 +1525,8 @@ pub fn start_http(
                 genesis_config: genesis_config.clone(),
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
+                max_batch_size: limits_config.max_batch_size,
+                rate_limiter: rate_limiter.clone(),
             }))
             .app_data(web::JsonConfig::default().limit(limits_config.json_payload_max_size))
             .wrap(middleware::Logger::default())
@@ -1488,6 +1550,7 @@ pub fn start_http(
             )
             .service(debug_html)
             .service(display_debug_html)
+            .service(web::resource("/ws").route(web::get().to(ws::ws_handler)))
     })
     .bind(addr)
     .unwrap()
@@ -55,3 +55,18 @@ pub static RPC_UNREACHABLE_ERROR_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+pub static RPC_RATE_LIMITED_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    near_o11y::metrics::try_create_int_counter_vec(
+        "near_rpc_rate_limited_total",
+        "Total count of RPC requests rejected by the rate limits, by class of method",
+        &["method_class"],
+    )
+    .unwrap()
+});
+pub static RPC_REJECTED_BATCH_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    near_o11y::metrics::try_create_int_counter(
+        "near_rpc_rejected_batch_total",
+        "Total count of RPC batches rejected for being empty or too long",
+    )
+    .unwrap()
+});
@@ -0,0 +1,290 @@
+//! Per-client rate limiting of the JSON RPC requests.
+//!
+//! Every client gets a token bucket per class of methods, so that a client
+//! flooding the node with cheap view requests doesn't use up its budget of
+//! `call_function` queries or transactions, and the other way around.
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use actix_web::HttpRequest;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use near_jsonrpc_primitives::errors::RpcError;
+use near_jsonrpc_primitives::message::Request;
+
+use crate::metrics;
+
+/// Number of buckets after which the least recently used ones are dropped.
+/// Dropping a bucket refills it, which is what happens to the buckets of
+/// clients which went idle anyway.
+const MAX_TRACKED_BUCKETS: usize = 100_000;
+
+/// Rate of the requests a single client may send.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Number of requests per second the bucket is refilled with.
+    pub requests_per_second: f64,
+    /// Capacity of the bucket, i.e. the number of requests a client may send
+    /// at once after being idle.
+    pub burst: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct RpcRateLimitsConfig {
+    /// Header with the API key of the client. Clients which send one of the
+    /// `api_keys` in it are limited per key rather than per IP address.
+    pub api_key_header: Option<String>,
+    /// API keys accepted in `api_key_header`. Requests with any other key are
+    /// limited per IP address, as if they didn't send one.
+    pub api_keys: Vec<String>,
+    /// Limit of the methods not covered by the other limits.
+    pub view: Option<RateLimit>,
+    /// Limit of the `query` requests with `call_function`, which run
+    /// contract code.
+    pub call_function: Option<RateLimit>,
+    /// Limit of `broadcast_tx_commit` and the other methods which submit
+    /// transactions.
+    pub broadcast_tx: Option<RateLimit>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum MethodClass {
+    View,
+    CallFunction,
+    BroadcastTx,
+}
+
+impl MethodClass {
+    fn of(request: &Request) -> Self {
+        match request.method.as_str() {
+            "broadcast_tx_async" | "broadcast_tx_commit" | "EXPERIMENTAL_broadcast_tx_sync" => {
+                Self::BroadcastTx
+            }
+            "query" if is_call_function(&request.params) => Self::CallFunction,
+            _ => Self::View,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::View => "view",
+            Self::CallFunction => "call_function",
+            Self::BroadcastTx => "broadcast_tx",
+        }
+    }
+}
+
+/// Whether the params of a `query` request, either the object or the legacy
+/// `[path, data]` form, ask to call a function.
+fn is_call_function(params: &Option<Value>) -> bool {
+    match params {
+        Some(Value::Object(params)) => {
+            params.get("request_type").and_then(Value::as_str) == Some("call_function")
+        }
+        Some(Value::Array(params)) => {
+            params.first().and_then(Value::as_str).map_or(false, |path| path.starts_with("call/"))
+        }
+        _ => false,
+    }
+}
+
+/// Client the requests are accounted to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClientId {
+    ApiKey(String),
+    Ip(IpAddr),
+    /// The peer address isn't known, e.g. when listening on a Unix socket.
+    Unknown,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, updated: now }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+
+    fn try_take(&mut self, limit: &RateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+pub(crate) struct RateLimiter {
+    config: RpcRateLimitsConfig,
+    api_keys: HashSet<String>,
+    buckets: Mutex<LruCache<(ClientId, MethodClass), TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RpcRateLimitsConfig) -> Self {
+        let api_keys = config.api_keys.iter().cloned().collect();
+        Self { config, api_keys, buckets: Mutex::new(LruCache::new(MAX_TRACKED_BUCKETS)) }
+    }
+
+    fn limit(&self, class: MethodClass) -> Option<&RateLimit> {
+        match class {
+            MethodClass::View => self.config.view.as_ref(),
+            MethodClass::CallFunction => self.config.call_function.as_ref(),
+            MethodClass::BroadcastTx => self.config.broadcast_tx.as_ref(),
+        }
+    }
+
+    pub fn client_id(&self, req: &HttpRequest) -> ClientId {
+        let api_key = self
+            .config
+            .api_key_header
+            .as_ref()
+            .and_then(|header| req.headers().get(header))
+            .and_then(|value| value.to_str().ok())
+            .filter(|api_key| self.api_keys.contains(*api_key));
+        match (api_key, req.peer_addr()) {
+            (Some(api_key), _) => ClientId::ApiKey(api_key.to_owned()),
+            (None, Some(addr)) => ClientId::Ip(addr.ip()),
+            (None, None) => ClientId::Unknown,
+        }
+    }
+
+    /// Takes a token for the request from the bucket of the client.
+    pub fn check(&self, client: &ClientId, request: &Request) -> Result<(), RpcError> {
+        self.check_at(client, request, Instant::now())
+    }
+
+    fn check_at(&self, client: &ClientId, request: &Request, now: Instant) -> Result<(), RpcError> {
+        let class = MethodClass::of(request);
+        let limit = match self.limit(class) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        let key = (client.clone(), class);
+        if !buckets.contains(&key) {
+            buckets.put(key.clone(), TokenBucket::new(limit, now));
+        }
+        let bucket = buckets.get_mut(&key).unwrap();
+        if bucket.try_take(limit, now) {
+            return Ok(());
+        }
+        metrics::RPC_RATE_LIMITED_COUNT.with_label_values(&[class.as_str()]).inc();
+        Err(RpcError::rate_limit_exceeded(request.method.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(method: &str, params: Value) -> Request {
+        serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": method,
+            "params": params,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_method_class() {
+        let call = request("query", json!({"request_type": "call_function", "finality": "final"}));
+        assert_eq!(MethodClass::of(&call), MethodClass::CallFunction);
+        let call = request("query", json!(["call/test.near/method", ""]));
+        assert_eq!(MethodClass::of(&call), MethodClass::CallFunction);
+        let view = request("query", json!({"request_type": "view_account", "finality": "final"}));
+        assert_eq!(MethodClass::of(&view), MethodClass::View);
+        let view = request("query", json!(["account/test.near", ""]));
+        assert_eq!(MethodClass::of(&view), MethodClass::View);
+        assert_eq!(MethodClass::of(&request("block", json!([1]))), MethodClass::View);
+        let tx = request("broadcast_tx_commit", json!(["AAAA"]));
+        assert_eq!(MethodClass::of(&tx), MethodClass::BroadcastTx);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(RpcRateLimitsConfig {
+            view: Some(RateLimit { requests_per_second: 2.0, burst: 3 }),
+            call_function: Some(RateLimit { requests_per_second: 1.0, burst: 1 }),
+            ..Default::default()
+        });
+        let alice = ClientId::ApiKey("alice".to_owned());
+        let bob = ClientId::Ip([127, 0, 0, 1].into());
+        let view = request("block", json!({"finality": "final"}));
+        let call = request("query", json!({"request_type": "call_function"}));
+        let tx = request("broadcast_tx_async", json!(["AAAA"]));
+
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at(&alice, &view, now).is_ok());
+        }
+        assert!(limiter.check_at(&alice, &view, now).is_err());
+        // Other clients and other classes of methods have their own buckets.
+        assert!(limiter.check_at(&bob, &view, now).is_ok());
+        assert!(limiter.check_at(&alice, &call, now).is_ok());
+        assert!(limiter.check_at(&alice, &call, now).is_err());
+        // Methods without a limit aren't limited.
+        for _ in 0..10 {
+            assert!(limiter.check_at(&alice, &tx, now).is_ok());
+        }
+
+        // The bucket refills with time, but not above the burst.
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.check_at(&alice, &view, later).is_ok());
+        assert!(limiter.check_at(&alice, &view, later).is_err());
+        let much_later = later + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.check_at(&alice, &view, much_later).is_ok());
+        }
+        assert!(limiter.check_at(&alice, &view, much_later).is_err());
+    }
+
+    #[test]
+    fn test_client_id() {
+        let limiter = RateLimiter::new(RpcRateLimitsConfig {
+            api_key_header: Some("x-api-key".to_owned()),
+            api_keys: vec!["alice".to_owned()],
+            ..Default::default()
+        });
+        let addr = "127.0.0.1:12345".parse().unwrap();
+        let ip = ClientId::Ip([127, 0, 0, 1].into());
+
+        let req = TestRequest::default()
+            .insert_header(("x-api-key", "alice"))
+            .peer_addr(addr)
+            .to_http_request();
+        assert_eq!(limiter.client_id(&req), ClientId::ApiKey("alice".to_owned()));
+        // Unknown keys don't get a bucket of their own.
+        let req = TestRequest::default()
+            .insert_header(("x-api-key", "mallory"))
+            .peer_addr(addr)
+            .to_http_request();
+        assert_eq!(limiter.client_id(&req), ip);
+        let req = TestRequest::default().peer_addr(addr).to_http_request();
+        assert_eq!(limiter.client_id(&req), ip);
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(limiter.client_id(&req), ClientId::Unknown);
+    }
+}
@@ -0,0 +1,384 @@
+//! WebSocket endpoint serving the JSON-RPC methods along with subscriptions to
+//! the changes of the chain, so that clients don't need to poll for them.
+//!
+//! A `subscribe_*` request is answered with the id of the subscription, after
+//! which its results are pushed as `subscription` notifications until
+//! `unsubscribe` is called or the connection is closed. The subscriptions are
+//! driven by the `ChainEvent`s of the `ClientActor`.
+//!
+//! A connection has at most `MAX_SUBSCRIPTIONS` subscriptions at a time, and a
+//! `subscribe_tx_status` subscription ends after `TX_STATUS_TIMEOUT` even if
+//! the transaction hasn't reached the awaited status.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use actix::{
+    Actor, ActorContext, ActorFutureExt, ActorStreamExt, AsyncContext, SpawnHandle, StreamHandler,
+    WrapFuture, WrapStream,
+};
+use actix_web::{web, Error as HttpError, HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use near_client::{ChainEvent, GetBlock, GetStateChanges, SubscribeChainEvents, TxStatus};
+use near_jsonrpc_primitives::errors::RpcError;
+use near_jsonrpc_primitives::message::{Message, Request};
+use near_jsonrpc_primitives::types::changes::RpcStateChangesInBlockResponse;
+use near_jsonrpc_primitives::types::subscriptions::{
+    RpcSubscribeStateChangesRequest, RpcSubscribeTxStatusRequest, RpcSubscriptionNotification,
+    RpcTxStatusNotification, RpcUnsubscribeRequest, SUBSCRIPTION_METHOD,
+};
+use near_o11y::WithSpanContextExt;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::{BlockId, BlockReference, Finality};
+use near_primitives::views::{
+    BlockView, FinalExecutionOutcomeView, FinalExecutionStatus, StateChangesRequestView,
+    TxExecutionStatus,
+};
+
+use crate::api::parse_params;
+use crate::rate_limit::ClientId;
+use crate::JsonRpcHandler;
+
+/// How often the connection is checked with a ping.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a client may stay silent before the connection is closed.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum number of active subscriptions of a connection. Every subscription
+/// queries the view client on chain events, so they aren't free.
+const MAX_SUBSCRIPTIONS: usize = 100;
+/// How long a `subscribe_tx_status` subscription waits for the transaction.
+const TX_STATUS_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) async fn ws_handler(
+    req: HttpRequest,
+    stream: web::Payload,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    let session = WsSession {
+        client: handler.rate_limiter.client_id(&req),
+        handler,
+        subscriptions: HashMap::new(),
+        next_subscription: 0,
+        last_heartbeat: Instant::now(),
+    };
+    ws::start(session, &req, stream)
+}
+
+struct WsSession {
+    handler: web::Data<JsonRpcHandler>,
+    client: ClientId,
+    subscriptions: HashMap<u64, SpawnHandle>,
+    next_subscription: u64,
+    last_heartbeat: Instant,
+}
+
+impl Actor for WsSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
+            if act.last_heartbeat.elapsed() > CLIENT_TIMEOUT {
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(err) => {
+                tracing::debug!(target: "jsonrpc", ?err, "WebSocket protocol error");
+                ctx.stop();
+                return;
+            }
+        };
+        self.last_heartbeat = Instant::now();
+        match msg {
+            ws::Message::Text(text) => self.handle_text(&text, ctx),
+            ws::Message::Binary(_) => send(
+                ctx,
+                Message::error(RpcError::parse_error("Binary messages are not supported".into())),
+            ),
+            ws::Message::Ping(bytes) => ctx.pong(&bytes),
+            ws::Message::Close(reason) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            ws::Message::Pong(_) | ws::Message::Continuation(_) | ws::Message::Nop => {}
+        }
+    }
+}
+
+impl WsSession {
+    fn handle_text(&mut self, text: &str, ctx: &mut ws::WebsocketContext<Self>) {
+        let request = match serde_json::from_str(text) {
+            Ok(Message::Request(request)) => request,
+            Ok(batch @ Message::Batch(_)) => return self.process_batch(batch, ctx),
+            Ok(_) => {
+                let err = RpcError::parse_error("JSON RPC Request format was expected".into());
+                return send(ctx, Message::error(err));
+            }
+            Err(err) => return send(ctx, Message::error(RpcError::parse_error(err.to_string()))),
+        };
+        if let Err(err) = self.handler.rate_limiter.check(&self.client, &request) {
+            return send(ctx, Message::response(request.id, Err(err)));
+        }
+        let handler = self.handler.clone();
+        let subscription = match request.method.as_ref() {
+            "subscribe_new_heads" => Ok(blocks(handler, false)),
+            "subscribe_final_blocks" => Ok(blocks(handler, true)),
+            "subscribe_state_changes" => parse_params(request.params.clone()).map(
+                |params: RpcSubscribeStateChangesRequest| {
+                    state_changes(handler, params.state_changes_request)
+                },
+            ),
+            "subscribe_tx_status" => parse_params(request.params.clone())
+                .map(|params: RpcSubscribeTxStatusRequest| tx_status(handler, params)),
+            "unsubscribe" => {
+                let result = parse_params(request.params).map(|params: RpcUnsubscribeRequest| {
+                    let handle = self.subscriptions.remove(&params.subscription);
+                    Value::Bool(handle.map_or(false, |handle| ctx.cancel_future(handle)))
+                });
+                return send(ctx, Message::response(request.id, result.map_err(RpcError::from)));
+            }
+            _ => return self.process_request(request, ctx),
+        };
+        match subscription {
+            Ok(subscription) => self.subscribe(request.id, subscription, ctx),
+            Err(err) => send(ctx, Message::response(request.id, Err(err.into()))),
+        }
+    }
+
+    /// Processes a request the same way the HTTP endpoint does.
+    fn process_request(&mut self, request: Request, ctx: &mut ws::WebsocketContext<Self>) {
+        let handler = self.handler.clone();
+        let id = request.id.clone();
+        let response = async move { handler.process_request(request).await };
+        ctx.spawn(
+            response
+                .into_actor(self)
+                .map(move |result, _act, ctx| send(ctx, Message::response(id, result))),
+        );
+    }
+
+    /// Processes a batch the same way the HTTP endpoint does. Subscriptions
+    /// aren't supported in batches.
+    fn process_batch(&mut self, batch: Message, ctx: &mut ws::WebsocketContext<Self>) {
+        let handler = self.handler.clone();
+        let client = self.client.clone();
+        let response = async move { handler.process(batch, &client).await };
+        ctx.spawn(response.into_actor(self).map(|result, _act, ctx| {
+            if let Ok(response) = result {
+                send(ctx, response);
+            }
+        }));
+    }
+
+    fn subscribe(
+        &mut self,
+        id: Value,
+        subscription: BoxStream<'static, Value>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if self.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+            let err = RpcError::too_many_subscriptions(MAX_SUBSCRIPTIONS);
+            return send(ctx, Message::response(id, Err(err)));
+        }
+        let subscription_id = self.next_subscription;
+        self.next_subscription += 1;
+        send(ctx, Message::response(id, Ok(Value::from(subscription_id))));
+        let handle = ctx.spawn(
+            subscription
+                .into_actor(self)
+                .map(move |result, _act, ctx| {
+                    let notification =
+                        RpcSubscriptionNotification { subscription: subscription_id, result };
+                    let params = serde_json::to_value(notification).ok();
+                    send(ctx, Message::notification(SUBSCRIPTION_METHOD.to_owned(), params));
+                })
+                .finish()
+                .map(move |(), act: &mut Self, _ctx| {
+                    act.subscriptions.remove(&subscription_id);
+                }),
+        );
+        self.subscriptions.insert(subscription_id, handle);
+    }
+}
+
+fn send(ctx: &mut ws::WebsocketContext<WsSession>, message: Message) {
+    match serde_json::to_string(&message) {
+        Ok(text) => ctx.text(text),
+        Err(err) => tracing::warn!(target: "jsonrpc", ?err, "Failed to serialize a message"),
+    }
+}
+
+/// Events of the client. A subscriber which falls behind misses some of the
+/// events rather than holding up the client.
+fn chain_events(handler: web::Data<JsonRpcHandler>) -> BoxStream<'static, ChainEvent> {
+    let receiver =
+        async move { handler.client_addr.send(SubscribeChainEvents {}.with_span_context()).await };
+    stream::once(receiver)
+        .filter_map(|receiver| future::ready(receiver.ok()))
+        .flat_map(|receiver| {
+            stream::unfold(receiver, |mut receiver| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) => return Some((event, receiver)),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            })
+        })
+        .boxed()
+}
+
+async fn block(handler: &JsonRpcHandler, block_reference: BlockReference) -> Option<BlockView> {
+    handler.view_client_addr.send(GetBlock(block_reference).with_span_context()).await.ok()?.ok()
+}
+
+async fn block_by_hash(handler: &JsonRpcHandler, block_hash: CryptoHash) -> Option<BlockView> {
+    block(handler, BlockReference::BlockId(BlockId::Hash(block_hash))).await
+}
+
+/// Blocks which become the head of the chain, or which become final.
+fn blocks(handler: web::Data<JsonRpcHandler>, only_final: bool) -> BoxStream<'static, Value> {
+    chain_events(handler.clone())
+        .filter_map(move |event| {
+            let handler = handler.clone();
+            async move {
+                let block_hash = match event {
+                    ChainEvent::NewHead { block_hash, .. } if !only_final => block_hash,
+                    ChainEvent::FinalBlock { block_hash, .. } if only_final => block_hash,
+                    _ => return None,
+                };
+                serde_json::to_value(block_by_hash(&handler, block_hash).await?).ok()
+            }
+        })
+        .boxed()
+}
+
+/// Changes of the given kind in every final block which has any. Only final
+/// blocks are considered so that no block is skipped and no change is reverted.
+fn state_changes(
+    handler: web::Data<JsonRpcHandler>,
+    state_changes_request: StateChangesRequestView,
+) -> BoxStream<'static, Value> {
+    chain_events(handler.clone())
+        .filter_map(move |event| {
+            let handler = handler.clone();
+            let state_changes_request = state_changes_request.clone();
+            async move {
+                let block_hash = match event {
+                    ChainEvent::FinalBlock { block_hash, .. } => block_hash,
+                    ChainEvent::NewHead { .. } => return None,
+                };
+                let request = GetStateChanges { block_hash, state_changes_request };
+                let changes = handler
+                    .view_client_addr
+                    .send(request.with_span_context())
+                    .await
+                    .ok()?
+                    .ok()
+                    .filter(|changes| !changes.is_empty())?;
+                serde_json::to_value(RpcStateChangesInBlockResponse { block_hash, changes }).ok()
+            }
+        })
+        .boxed()
+}
+
+/// A single notification sent once the transaction reaches the awaited
+/// status. The status is checked on subscription and on every chain event,
+/// until `TX_STATUS_TIMEOUT` after which the subscription ends without one.
+fn tx_status(
+    handler: web::Data<JsonRpcHandler>,
+    request: RpcSubscribeTxStatusRequest,
+) -> BoxStream<'static, Value> {
+    stream::once(future::ready(()))
+        .chain(chain_events(handler.clone()).map(drop))
+        .filter_map(move |()| {
+            let handler = handler.clone();
+            let request = request.clone();
+            async move {
+                let notification = check_tx_status(&handler, &request).await?;
+                serde_json::to_value(notification).ok()
+            }
+        })
+        .take(1)
+        .take_until(tokio::time::sleep(TX_STATUS_TIMEOUT))
+        .boxed()
+}
+
+/// Whether a transaction at `status` has reached `wait_until`.
+fn reached(status: TxExecutionStatus, wait_until: TxExecutionStatus) -> bool {
+    match wait_until {
+        TxExecutionStatus::Included => true,
+        TxExecutionStatus::Executed => status != TxExecutionStatus::Included,
+        TxExecutionStatus::Final => status == TxExecutionStatus::Final,
+    }
+}
+
+/// Returns the notification if the transaction has reached the awaited status.
+///
+/// A node which doesn't track the shard of the sender learns about the
+/// outcome of the transaction from other nodes, so it may report the
+/// transaction as included before it is.
+async fn check_tx_status(
+    handler: &JsonRpcHandler,
+    request: &RpcSubscribeTxStatusRequest,
+) -> Option<RpcTxStatusNotification> {
+    let msg = TxStatus {
+        tx_hash: request.tx_hash,
+        signer_account_id: request.sender_account_id.clone(),
+        fetch_receipt: false,
+    };
+    let outcome = match handler.view_client_addr.send(msg.with_span_context()).await.ok()? {
+        Ok(Some(outcome)) => outcome.into_outcome(),
+        // The transaction is included but some of its receipts aren't executed.
+        Ok(None) => {
+            let status = TxExecutionStatus::Included;
+            return reached(status, request.wait_until)
+                .then_some(RpcTxStatusNotification { status, outcome: None });
+        }
+        // The transaction isn't included yet.
+        Err(_) => return None,
+    };
+    let status = match outcome.status {
+        FinalExecutionStatus::NotStarted | FinalExecutionStatus::Started => {
+            TxExecutionStatus::Included
+        }
+        FinalExecutionStatus::Failure(_) | FinalExecutionStatus::SuccessValue(_) => {
+            if request.wait_until == TxExecutionStatus::Final && is_final(handler, &outcome).await {
+                TxExecutionStatus::Final
+            } else {
+                TxExecutionStatus::Executed
+            }
+        }
+    };
+    reached(status, request.wait_until)
+        .then_some(RpcTxStatusNotification { status, outcome: Some(outcome) })
+}
+
+/// Whether all the blocks with the outcomes of the transaction are final.
+async fn is_final(handler: &JsonRpcHandler, outcome: &FinalExecutionOutcomeView) -> bool {
+    let final_height = match block(handler, BlockReference::Finality(Finality::Final)).await {
+        Some(block) => block.header.height,
+        None => return false,
+    };
+    for outcome in std::iter::once(&outcome.transaction_outcome).chain(&outcome.receipts_outcome) {
+        match block_by_hash(handler, outcome.block_hash).await {
+            Some(block) if block.header.height <= final_height => {}
+            _ => return false,
+        }
+    }
+    true
+}
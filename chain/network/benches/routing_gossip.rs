@@ -0,0 +1,74 @@
+//! Compares the cost of gossiping the routing table between networks of nodes with node keys
+//! of different types: the size of a full routing table sync and the time it takes to encode
+//! it and to verify the edges.
+#[macro_use]
+extern crate criterion;
+
+use criterion::{black_box, Criterion, Throughput};
+use near_crypto::{KeyType, SecretKey};
+use near_network::types::{Edge, Encoding, PartialEdgeInfo, PeerMessage, RoutingTableUpdate};
+use near_primitives::network::PeerId;
+
+const KEY_TYPES: [KeyType; 3] = [KeyType::ED25519, KeyType::MLDSA, KeyType::ED25519MLDSA];
+
+/// Number of nodes in the network.
+const NODES: usize = 20;
+/// Every node is connected to the next PEERS nodes (modulo NODES).
+const PEERS: usize = 5;
+
+fn make_edges(key_type: KeyType) -> Vec<Edge> {
+    let keys: Vec<_> =
+        (0..NODES).map(|i| SecretKey::from_seed(key_type, &format!("node{i}"))).collect();
+    let peers: Vec<_> = keys.iter().map(|key| PeerId::new(key.public_key())).collect();
+    let mut edges = vec![];
+    for i in 0..NODES {
+        for j in 1..=PEERS {
+            let k = (i + j) % NODES;
+            let nonce = 1;
+            let info = PartialEdgeInfo::new(&peers[k], &peers[i], nonce, &keys[k]);
+            edges.push(Edge::build_with_secret_key(
+                peers[i].clone(),
+                peers[k].clone(),
+                nonce,
+                &keys[i],
+                info.signature,
+            ));
+        }
+    }
+    edges
+}
+
+fn full_routing_table_sync(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_routing_table_sync");
+    for key_type in KEY_TYPES {
+        let edges = make_edges(key_type);
+        let msg = PeerMessage::SyncRoutingTable(RoutingTableUpdate { edges, accounts: vec![] });
+        let bytes = msg.serialize(Encoding::Proto).len();
+        // Throughput is reported as encoding speed, so print the size of the message as well.
+        eprintln!(
+            "full_routing_table_sync/{key_type}: {} edges, {bytes}B, {}B per edge",
+            NODES * PEERS,
+            bytes / (NODES * PEERS)
+        );
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_function(key_type.to_string(), |bench| {
+            bench.iter(|| black_box(msg.serialize(Encoding::Proto)))
+        });
+    }
+    group.finish();
+}
+
+fn verify_edges(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_edges");
+    for key_type in KEY_TYPES {
+        let edges = make_edges(key_type);
+        group.throughput(Throughput::Elements(edges.len() as u64));
+        group.bench_function(key_type.to_string(), |bench| {
+            bench.iter(|| assert!(edges.iter().all(|edge| black_box(edge).verify())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, full_routing_table_sync, verify_edges);
+criterion_main!(benches);
@@ -8,6 +8,7 @@ use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::PartialEncodedChunk;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -72,6 +73,25 @@ pub trait Client: Send + Sync + 'static {
         peer_id: PeerId,
     ) -> Result<(), ReasonForBan>;
 
+    async fn epoch_sync_request(&self, epoch_id: EpochId) -> Option<Box<EpochSyncResponse>>;
+
+    async fn epoch_sync_response(
+        &self,
+        response: Box<EpochSyncResponse>,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan>;
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        epoch_id: EpochId,
+    ) -> Option<Box<EpochSyncFinalizationResponse>>;
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        response: Box<EpochSyncFinalizationResponse>,
+        peer_id: PeerId,
+    ) -> Result<(), ReasonForBan>;
+
     async fn challenge(&self, challenge: Challenge);
 
     async fn network_info(&self, info: NetworkInfo);
@@ -155,6 +175,33 @@ impl Client for Noop {
         Ok(())
     }
 
+    async fn epoch_sync_request(&self, _epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        None
+    }
+
+    async fn epoch_sync_response(
+        &self,
+        _response: Box<EpochSyncResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        _epoch_id: EpochId,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        None
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
     async fn challenge(&self, _challenge: Challenge) {}
 
     async fn network_info(&self, _info: NetworkInfo) {}
@@ -1,10 +1,12 @@
 use crate::blacklist;
 use crate::concurrency::demux;
+use crate::network_protocol;
 use crate::network_protocol::PeerAddr;
 use crate::network_protocol::PeerInfo;
 use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_store;
 use crate::sink::Sink;
+use crate::subnet;
 use crate::time;
 use crate::types::ROUTED_MESSAGE_TTL;
 use anyhow::Context;
@@ -124,6 +126,11 @@ pub struct NetworkConfig {
     //   * not broadcasting deleted edges
     //   * ignoring received deleted edges as well
     pub skip_tombstones: Option<time::Duration>,
+    /// Whether to reject peers which don't support the encrypted transport,
+    /// see `peer::transport`.
+    pub require_encrypted_transport: bool,
+    /// Limits on the number of connections with peers from the same subnet.
+    pub subnet_limits: subnet::Config,
 
     /// TEST-ONLY
     /// TODO(gprusak): make it pub(crate), once all integration tests
@@ -223,6 +230,8 @@ impl NetworkConfig {
             } else {
                 None
             },
+            require_encrypted_transport: cfg.require_encrypted_transport,
+            subnet_limits: cfg.subnet_limits,
             event_sink: Sink::null(),
         };
         Ok(this)
@@ -282,6 +291,8 @@ impl NetworkConfig {
             accounts_data_broadcast_rate_limit: demux::RateLimit { qps: 100., burst: 1000000 },
             features: Features { enable_tier1: true },
             skip_tombstones: None,
+            require_encrypted_transport: false,
+            subnet_limits: subnet::Config::unlimited(),
             event_sink: Sink::null(),
         }
     }
@@ -316,6 +327,17 @@ impl NetworkConfig {
                 self.peer_recent_time_window, UPDATE_INTERVAL_LAST_TIME_RECEIVED_MESSAGE
             );
         }
+        for (name, limits) in
+            [("inbound", &self.subnet_limits.inbound), ("outbound", &self.subnet_limits.outbound)]
+        {
+            if limits.per_narrow_subnet == 0 || limits.per_wide_subnet == 0 {
+                anyhow::bail!("subnet_limits.{name} must be positive, got {limits:?}.");
+            }
+        }
+        let node_key_type = self.node_key.key_type();
+        if !network_protocol::is_node_key_type_supported(node_key_type) {
+            anyhow::bail!("node key of type {node_key_type} is not supported.");
+        }
         self.accounts_data_broadcast_rate_limit
             .validate()
             .context("accounts_Data_broadcast_rate_limit")?;
@@ -355,10 +377,14 @@ mod test {
     use crate::config;
     use crate::network_protocol;
     use crate::network_protocol::testonly as data;
-    use crate::network_protocol::AccountData;
+    use crate::network_protocol::{AccountData, PeerAddr};
     use crate::testonly::make_rng;
     use crate::time;
+    use near_crypto::{KeyType, SecretKey};
+    use near_primitives::network::PeerId;
     use near_primitives::validator_signer::ValidatorSigner;
+    use rand::Rng as _;
+    use std::net::SocketAddr;
 
     #[test]
     fn test_network_config() {
@@ -380,6 +406,16 @@ mod test {
         let mut nc = config::NetworkConfig::from_seed("123", 213);
         nc.peer_recent_time_window = UPDATE_INTERVAL_LAST_TIME_RECEIVED_MESSAGE;
         assert!(nc.verify().is_err());
+
+        for key_type in [KeyType::MLDSA, KeyType::ED25519MLDSA] {
+            let mut nc = config::NetworkConfig::from_seed("123", 213);
+            nc.node_key = SecretKey::from_seed(key_type, "123");
+            assert!(nc.verify().is_ok());
+        }
+
+        let mut nc = config::NetworkConfig::from_seed("123", 213);
+        nc.node_key = SecretKey::from_seed(KeyType::SLHDSA, "123");
+        assert!(nc.verify().is_err());
     }
 
     // Check that MAX_PEER_ADDRS limit is consistent with the
@@ -393,9 +429,16 @@ mod test {
         let ad = AccountData {
             peers: (0..config::MAX_PEER_ADDRS)
                 .map(|_| {
-                    // Using IPv6 gives maximal size of the resulting config.
+                    // Using IPv6 and hybrid node keys gives maximal size of the resulting config.
                     let ip = data::make_ipv6(&mut rng);
-                    data::make_peer_addr(&mut rng, ip)
+                    let port = rng.gen();
+                    PeerAddr {
+                        addr: SocketAddr::new(ip, port),
+                        peer_id: PeerId::new(
+                            data::make_secret_key_of_type(&mut rng, KeyType::ED25519MLDSA)
+                                .public_key(),
+                        ),
+                    }
                 })
                 .collect(),
             account_id: signer.validator_id().clone(),
@@ -166,6 +166,20 @@ pub struct Config {
     // TODO: unskip, once the functionality is implemented.
     #[serde(skip)] // TODO: add a default list.
     pub trusted_stun_servers: Vec<String>,
+    /// If true, connections are established only with peers supporting the encrypted
+    /// transport, both for inbound and outbound connections. Otherwise the transport
+    /// is encrypted whenever the peer supports it. Peers using the Borsh encoding
+    /// don't support it.
+    #[serde(default)]
+    pub require_encrypted_transport: bool,
+    /// Limits on the number of connections with peers from the same subnet,
+    /// which make it harder to surround the node with malicious peers. Example:
+    ///   {"inbound": {"per_narrow_subnet": 5, "per_wide_subnet": 10},
+    ///    "outbound": {"per_narrow_subnet": 3, "per_wide_subnet": 6}}
+    /// where the narrow subnets are IPv4 /24 (IPv6 /48) and the wide ones are
+    /// IPv4 /16 (IPv6 /32). Loopback and private addresses are not limited.
+    #[serde(default)]
+    pub subnet_limits: crate::subnet::Config,
     // Experimental part of the JSON config. Regular users/validators should not have to set any values there.
     // Field names in here can change/disappear at any moment without warning.
     #[serde(default)]
@@ -223,6 +237,8 @@ impl Default for Config {
             peer_expiration_duration: default_peer_expiration_duration(),
             public_addrs: vec![],
             trusted_stun_servers: vec![],
+            require_encrypted_transport: false,
+            subnet_limits: Default::default(),
             experimental: Default::default(),
         }
     }
@@ -1,14 +1,16 @@
 use ::actix::Message;
-use near_primitives::views::PeerStoreView;
+use near_primitives::views::{PeerStoreView, SubnetsView};
 
 // Different debug requests that can be sent by HTML pages, via GET.
 pub enum GetDebugStatus {
     PeerStore,
+    Subnets,
 }
 
 #[derive(actix::MessageResponse, Debug)]
 pub enum DebugStatus {
     PeerStore(PeerStoreView),
+    Subnets(SubnetsView),
 }
 
 impl Message for GetDebugStatus {
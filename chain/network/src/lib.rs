@@ -17,6 +17,7 @@ pub mod config;
 pub mod config_json;
 pub mod debug;
 pub mod routing;
+pub mod subnet;
 pub mod tcp;
 pub mod test_utils;
 pub mod time;
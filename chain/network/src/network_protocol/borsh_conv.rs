@@ -14,6 +14,8 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_listen_port: x.sender_listen_port,
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
+            // Borsh encoding doesn't support the encrypted transport.
+            key_exchange: None,
         }
     }
 }
@@ -158,8 +160,12 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
             mem::PeerMessage::RequestUpdateNonce(e) => net::PeerMessage::RequestUpdateNonce(e),
             mem::PeerMessage::ResponseUpdateNonce(e) => net::PeerMessage::ResponseUpdateNonce(e),
 
-            // This message is not supported, we translate it to an empty RoutingTableUpdate.
-            mem::PeerMessage::SyncAccountsData(_) => {
+            // These messages are not supported, we translate them to an empty RoutingTableUpdate.
+            mem::PeerMessage::SyncAccountsData(_)
+            | mem::PeerMessage::EpochSyncRequest(_)
+            | mem::PeerMessage::EpochSyncResponse(_)
+            | mem::PeerMessage::EpochSyncFinalizationRequest(_)
+            | mem::PeerMessage::EpochSyncFinalizationResponse(_) => {
                 net::PeerMessage::SyncRoutingTable(net::RoutingTableUpdate::default())
             }
 
@@ -77,6 +77,20 @@ impl Edge {
         self.0.removal_info.as_ref()
     }
 
+    /// Length of the Borsh encoded edge. It is dominated by the peer ids and signatures,
+    /// so it varies a lot between the key types of the peers: an edge between ed25519
+    /// peers takes ~200B, while an edge between ML-DSA peers takes ~10kB.
+    pub fn encoded_len(&self) -> usize {
+        let (peer0, peer1) = self.key();
+        peer0.public_key().len()
+            + peer1.public_key().len()
+            + std::mem::size_of::<u64>()
+            + self.signature0().len()
+            + self.signature1().len()
+            + 1
+            + self.removal_info().map_or(0, |(_, signature)| 1 + signature.len())
+    }
+
     pub fn make_fake_edge(peer0: PeerId, peer1: PeerId, nonce: u64) -> Self {
         Self(Arc::new(EdgeInner {
             key: (peer0, peer1),
@@ -160,6 +174,11 @@ impl Edge {
         if self.key().0 > self.key().1 {
             return false;
         }
+        if !super::is_node_key_type_supported(self.key().0.public_key().key_type())
+            || !super::is_node_key_type_supported(self.key().1.public_key().key_type())
+        {
+            return false;
+        }
 
         match self.edge_type() {
             EdgeState::Active => {
@@ -21,6 +21,7 @@ pub use _proto::network as proto;
 
 use crate::time;
 use borsh::{BorshDeserialize as _, BorshSerialize as _};
+use near_crypto::KeyType;
 use near_crypto::PublicKey;
 use near_crypto::Signature;
 use near_primitives::block::{Approval, Block, BlockHeader, GenesisId};
@@ -31,11 +32,15 @@ use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::{
     ChunkHash, PartialEncodedChunk, PartialEncodedChunkPart, ReceiptProof, ShardChunkHeader,
 };
-use near_primitives::syncing::{ShardStateSyncResponse, ShardStateSyncResponseV1};
+use near_primitives::syncing::{
+    EpochSyncFinalizationResponse, EpochSyncResponse, ShardStateSyncResponse,
+    ShardStateSyncResponseV1,
+};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId};
 use near_primitives::types::{BlockHeight, ShardId};
 use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::version::ProtocolVersion;
 use near_primitives::views::FinalExecutionOutcomeView;
 use protobuf::Message as _;
 use std::collections::HashSet;
@@ -95,7 +100,75 @@ pub struct AccountData {
 // Limit on the size of the serialized AccountData message.
 // It is important to have such a constraint on the serialized proto,
 // because it may contain many unknown fields (which are dropped during parsing).
-pub const MAX_ACCOUNT_DATA_SIZE_BYTES: usize = 10000; // 10kB
+// It fits config::MAX_PEER_ADDRS proxies with hybrid ed25519 + ML-DSA node keys (~2kB each).
+pub const MAX_ACCOUNT_DATA_SIZE_BYTES: usize = 25000; // 25kB
+
+/// Limit on the size of the serialized AccountData enforced by peers older than
+/// POST_QUANTUM_KEYS_PROTOCOL_VERSION. Such peers ban whoever sends them larger data.
+pub(crate) const LEGACY_MAX_ACCOUNT_DATA_SIZE_BYTES: usize = 10000; // 10kB
+
+/// Protocol version starting from which peers decode ML-DSA and hybrid ed25519 + ML-DSA keys
+/// and signatures, and accept AccountData of up to MAX_ACCOUNT_DATA_SIZE_BYTES. Edges,
+/// account announcements and AccountData which need either are not sent to peers which
+/// negotiated an older version, so that they are only broadcast once the network has upgraded.
+/// Without the post-quantum validator keys feature no peer qualifies.
+#[cfg(feature = "protocol_feature_post_quantum_validator_keys")]
+pub(crate) const POST_QUANTUM_KEYS_PROTOCOL_VERSION: ProtocolVersion =
+    near_primitives::version::ProtocolFeature::PostQuantumValidatorKeys.protocol_version();
+#[cfg(not(feature = "protocol_feature_post_quantum_validator_keys"))]
+pub(crate) const POST_QUANTUM_KEYS_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::MAX;
+
+/// SyncRoutingTable and SyncAccountsData messages larger than this are split by the sender
+/// into multiple messages. With post-quantum node keys a single edge takes ~10kB, so a full
+/// routing table sync would otherwise take hundreds of megabytes.
+pub(crate) const SYNC_MESSAGE_SPLIT_SIZE_BYTES: usize = 1 << 20; // 1MiB
+
+/// Receiving a SyncRoutingTable or SyncAccountsData message larger than this is considered
+/// abusive. Nodes which don't split the syncs can only have ed25519 edges, ~300k of which
+/// fit within the limit.
+pub(crate) const MAX_SYNC_MESSAGE_SIZE_BYTES: usize = 64 << 20; // 64MiB
+
+/// Whether keys of the given type may be used as node keys (i.e. as `PeerId`).
+/// SLH-DSA is not supported: producing a single signature takes hundreds of milliseconds,
+/// while a node signs every edge and every routed message it originates.
+pub fn is_node_key_type_supported(key_type: KeyType) -> bool {
+    match key_type {
+        KeyType::ED25519 | KeyType::SECP256K1 | KeyType::MLDSA | KeyType::ED25519MLDSA => true,
+        KeyType::SLHDSA => false,
+    }
+}
+
+/// Whether peers which negotiated `protocol_version` can decode keys and signatures of the
+/// given type.
+fn is_key_type_supported_by(key_type: KeyType, protocol_version: ProtocolVersion) -> bool {
+    match key_type {
+        KeyType::ED25519 | KeyType::SECP256K1 => true,
+        KeyType::MLDSA | KeyType::ED25519MLDSA | KeyType::SLHDSA => {
+            protocol_version >= POST_QUANTUM_KEYS_PROTOCOL_VERSION
+        }
+    }
+}
+
+/// Splits `items` into consecutive chunks with total length (as returned by `len`) of at most
+/// `max_len`. An item longer than `max_len` gets a chunk of its own.
+fn split_by_len<T>(items: Vec<T>, max_len: usize, len: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+    let mut chunks = vec![];
+    let mut chunk = vec![];
+    let mut chunk_len = 0;
+    for item in items {
+        let item_len = len(&item);
+        if !chunk.is_empty() && chunk_len + item_len > max_len {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_len = 0;
+        }
+        chunk_len += item_len;
+        chunk.push(item);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
 
 impl AccountData {
     /// Serializes AccountData to proto and signs it using `signer`.
@@ -171,6 +244,11 @@ impl SignedAccountData {
     pub fn payload(&self) -> &AccountKeySignedPayload {
         &self.payload
     }
+
+    /// Approximate length of the encoded SignedAccountData.
+    pub fn encoded_len(&self) -> usize {
+        self.payload.len() + self.payload.signature.len()
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Default)]
@@ -191,6 +269,55 @@ impl RoutingTableUpdate {
     pub(crate) fn new(edges: Vec<Edge>, accounts: Vec<AnnounceAccount>) -> Self {
         Self { edges, accounts }
     }
+
+    /// Approximate length of the encoded RoutingTableUpdate.
+    pub fn encoded_len(&self) -> usize {
+        self.edges.iter().map(Edge::encoded_len).sum::<usize>()
+            + self.accounts.iter().map(announce_account_encoded_len).sum::<usize>()
+    }
+
+    /// Splits the update into updates of `encoded_len()` at most `max_len` each
+    /// (unless a single edge or account is larger than that).
+    pub(crate) fn split(self, max_len: usize) -> Vec<Self> {
+        if self.encoded_len() <= max_len {
+            return vec![self];
+        }
+        let edges = split_by_len(self.edges, max_len, Edge::encoded_len);
+        let accounts = split_by_len(self.accounts, max_len, announce_account_encoded_len);
+        edges
+            .into_iter()
+            .map(Self::from_edges)
+            .chain(accounts.into_iter().map(Self::from_accounts))
+            .collect()
+    }
+
+    /// Returns the update without the edges and account announcements which a peer of
+    /// `protocol_version` couldn't decode, or `None` if the peer decodes the whole update.
+    pub(crate) fn for_protocol_version(&self, protocol_version: ProtocolVersion) -> Option<Self> {
+        let edge_supported = |e: &Edge| {
+            let (peer0, peer1) = e.key();
+            is_key_type_supported_by(peer0.public_key().key_type(), protocol_version)
+                && is_key_type_supported_by(peer1.public_key().key_type(), protocol_version)
+        };
+        let account_supported = |a: &AnnounceAccount| {
+            is_key_type_supported_by(a.peer_id.public_key().key_type(), protocol_version)
+                && is_key_type_supported_by(a.signature.key_type(), protocol_version)
+        };
+        if self.edges.iter().all(edge_supported) && self.accounts.iter().all(account_supported) {
+            return None;
+        }
+        Some(Self {
+            edges: self.edges.iter().filter(|e| edge_supported(e)).cloned().collect(),
+            accounts: self.accounts.iter().filter(|a| account_supported(a)).cloned().collect(),
+        })
+    }
+}
+
+fn announce_account_encoded_len(a: &AnnounceAccount) -> usize {
+    4 + a.account_id.len()
+        + a.peer_id.public_key().len()
+        + std::mem::size_of::<EpochId>()
+        + a.signature.len()
 }
 /// Structure representing handshake between peers.
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -209,6 +336,25 @@ pub struct Handshake {
     pub(crate) sender_chain_info: PeerChainInfoV2,
     /// Represents new `edge`. Contains only `none` and `Signature` from the sender.
     pub(crate) partial_edge_info: PartialEdgeInfo,
+    /// Sender's half of the encrypted transport key exchange, see `peer::transport`.
+    /// `None` if the sender doesn't support (or the encoding can't carry) encryption.
+    pub(crate) key_exchange: Option<TransportKeyExchange>,
+}
+
+/// See TransportKeyExchange in network_protocol/network.proto.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TransportKeyExchange {
+    pub(crate) x25519_public_key: Vec<u8>,
+    pub(crate) kem: TransportKem,
+    pub(crate) signature: Signature,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TransportKem {
+    /// ML-KEM encapsulation key offered by the initiator of the connection.
+    EncapsulationKey(Vec<u8>),
+    /// ML-KEM ciphertext with which the receiver accepts the offer.
+    Ciphertext(Vec<u8>),
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr)]
@@ -226,6 +372,52 @@ pub struct SyncAccountsData {
     pub incremental: bool,
 }
 
+impl SyncAccountsData {
+    /// Approximate length of the encoded SyncAccountsData.
+    pub fn encoded_len(&self) -> usize {
+        self.accounts_data.iter().map(|d| d.encoded_len()).sum()
+    }
+
+    /// Splits the message into messages of `encoded_len()` at most `max_len` each
+    /// (unless a single AccountData is larger than that).
+    /// Only the first message requests a full sync, so that the peer responds just once.
+    pub(crate) fn split(self, max_len: usize) -> Vec<Self> {
+        if self.encoded_len() <= max_len {
+            return vec![self];
+        }
+        split_by_len(self.accounts_data, max_len, |d| d.encoded_len())
+            .into_iter()
+            .enumerate()
+            .map(|(i, accounts_data)| Self {
+                accounts_data,
+                requesting_full_sync: self.requesting_full_sync && i == 0,
+                incremental: self.incremental,
+            })
+            .collect()
+    }
+
+    /// Returns the message without the AccountData which a peer of `protocol_version` would
+    /// consider too large or couldn't decode, or `None` if the peer accepts the whole message.
+    pub(crate) fn for_protocol_version(&self, protocol_version: ProtocolVersion) -> Option<Self> {
+        let unsupported = |d: &SignedAccountData| {
+            (protocol_version < POST_QUANTUM_KEYS_PROTOCOL_VERSION
+                && d.payload().len() > LEGACY_MAX_ACCOUNT_DATA_SIZE_BYTES)
+                || !is_key_type_supported_by(d.payload().signature().key_type(), protocol_version)
+                || d.peers.iter().any(|p| {
+                    !is_key_type_supported_by(p.peer_id.public_key().key_type(), protocol_version)
+                })
+        };
+        if !self.accounts_data.iter().any(|d| unsupported(d)) {
+            return None;
+        }
+        Some(Self {
+            accounts_data: self.accounts_data.iter().filter(|d| !unsupported(d)).cloned().collect(),
+            requesting_full_sync: self.requesting_full_sync,
+            incremental: self.incremental,
+        })
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr, strum::EnumVariantNames)]
 #[allow(clippy::large_enum_variant)]
 pub enum PeerMessage {
@@ -255,6 +447,11 @@ pub enum PeerMessage {
     /// Gracefully disconnect from other peer.
     Disconnect,
     Challenge(Challenge),
+
+    EpochSyncRequest(EpochId),
+    EpochSyncResponse(Box<EpochSyncResponse>),
+    EpochSyncFinalizationRequest(EpochId),
+    EpochSyncFinalizationResponse(Box<EpochSyncFinalizationResponse>),
 }
 
 impl fmt::Display for PeerMessage {
@@ -282,7 +479,8 @@ pub enum ParsePeerMessageError {
 }
 
 impl PeerMessage {
-    pub(crate) fn serialize(&self, enc: Encoding) -> Vec<u8> {
+    /// Encodes the message as it is sent over the wire (excluding the framing).
+    pub fn serialize(&self, enc: Encoding) -> Vec<u8> {
         match enc {
             Encoding::Borsh => borsh_::PeerMessage::from(self).try_to_vec().unwrap(),
             Encoding::Proto => proto::PeerMessage::from(self).write_to_bytes().unwrap(),
@@ -305,6 +503,47 @@ impl PeerMessage {
         })
     }
 
+    /// Approximate length of the encoded SyncRoutingTable or SyncAccountsData message,
+    /// `None` for other messages.
+    pub(crate) fn sync_encoded_len(&self) -> Option<usize> {
+        match self {
+            PeerMessage::SyncRoutingTable(rtu) => Some(rtu.encoded_len()),
+            PeerMessage::SyncAccountsData(msg) => Some(msg.encoded_len()),
+            _ => None,
+        }
+    }
+
+    /// Splits a SyncRoutingTable or SyncAccountsData message larger than `max_len` into
+    /// multiple messages. Returns `None` if the message doesn't need to be split.
+    pub(crate) fn split(&self, max_len: usize) -> Option<Vec<PeerMessage>> {
+        if self.sync_encoded_len()? <= max_len {
+            return None;
+        }
+        Some(match self {
+            PeerMessage::SyncRoutingTable(rtu) => {
+                rtu.clone().split(max_len).into_iter().map(PeerMessage::SyncRoutingTable).collect()
+            }
+            PeerMessage::SyncAccountsData(msg) => {
+                msg.clone().split(max_len).into_iter().map(PeerMessage::SyncAccountsData).collect()
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    /// Returns a SyncRoutingTable or SyncAccountsData message without the parts which a peer of
+    /// `protocol_version` doesn't accept, or `None` if the peer accepts the whole message.
+    pub(crate) fn for_protocol_version(&self, protocol_version: ProtocolVersion) -> Option<Self> {
+        match self {
+            PeerMessage::SyncRoutingTable(rtu) => {
+                rtu.for_protocol_version(protocol_version).map(PeerMessage::SyncRoutingTable)
+            }
+            PeerMessage::SyncAccountsData(msg) => {
+                msg.for_protocol_version(protocol_version).map(PeerMessage::SyncAccountsData)
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn msg_variant(&self) -> &'static str {
         match self {
             PeerMessage::Routed(routed_msg) => routed_msg.body_variant(),
@@ -2,8 +2,10 @@
 use super::*;
 
 use crate::network_protocol::proto;
+use crate::network_protocol::proto::transport_key_exchange::Kem as ProtoKem;
 use crate::network_protocol::{Handshake, HandshakeFailureReason};
 use crate::network_protocol::{PeerChainInfoV2, PeerInfo};
+use crate::network_protocol::{TransportKem, TransportKeyExchange};
 use near_primitives::block::GenesisId;
 use protobuf::MessageField as MF;
 
@@ -63,6 +65,44 @@ impl TryFrom<&proto::PeerChainInfo> for PeerChainInfoV2 {
 
 //////////////////////////////////////////
 
+#[derive(thiserror::Error, Debug)]
+pub enum ParseTransportKeyExchangeError {
+    #[error("kem missing")]
+    KemMissing,
+    #[error("signature {0}")]
+    Signature(ParseRequiredError<ParseSignatureError>),
+}
+
+impl From<&TransportKeyExchange> for proto::TransportKeyExchange {
+    fn from(x: &TransportKeyExchange) -> Self {
+        Self {
+            x25519_public_key: x.x25519_public_key.clone(),
+            kem: Some(match &x.kem {
+                TransportKem::EncapsulationKey(ek) => ProtoKem::MlkemEncapsulationKey(ek.clone()),
+                TransportKem::Ciphertext(ct) => ProtoKem::MlkemCiphertext(ct.clone()),
+            }),
+            signature: MF::some((&x.signature).into()),
+            ..Self::default()
+        }
+    }
+}
+
+impl TryFrom<&proto::TransportKeyExchange> for TransportKeyExchange {
+    type Error = ParseTransportKeyExchangeError;
+    fn try_from(p: &proto::TransportKeyExchange) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x25519_public_key: p.x25519_public_key.clone(),
+            kem: match p.kem.as_ref().ok_or(Self::Error::KemMissing)? {
+                ProtoKem::MlkemEncapsulationKey(ek) => TransportKem::EncapsulationKey(ek.clone()),
+                ProtoKem::MlkemCiphertext(ct) => TransportKem::Ciphertext(ct.clone()),
+            },
+            signature: try_from_required(&p.signature).map_err(Self::Error::Signature)?,
+        })
+    }
+}
+
+//////////////////////////////////////////
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParseHandshakeError {
     #[error("sender_peer_id {0}")]
@@ -75,6 +115,8 @@ pub enum ParseHandshakeError {
     SenderChainInfo(ParseRequiredError<ParsePeerChainInfoV2Error>),
     #[error("partial_edge_info {0}")]
     PartialEdgeInfo(ParseRequiredError<ParsePartialEdgeInfoError>),
+    #[error("key_exchange {0}")]
+    KeyExchange(ParseTransportKeyExchangeError),
 }
 
 impl From<&Handshake> for proto::Handshake {
@@ -87,6 +129,7 @@ impl From<&Handshake> for proto::Handshake {
             sender_listen_port: x.sender_listen_port.unwrap_or(0).into(),
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
+            key_exchange: x.key_exchange.as_ref().map(Into::into).into(),
             ..Self::default()
         }
     }
@@ -115,6 +158,12 @@ impl TryFrom<&proto::Handshake> for Handshake {
                 .map_err(Self::Error::SenderChainInfo)?,
             partial_edge_info: try_from_required(&p.partial_edge_info)
                 .map_err(Self::Error::PartialEdgeInfo)?,
+            key_exchange: p
+                .key_exchange
+                .as_ref()
+                .map(TryInto::try_into)
+                .transpose()
+                .map_err(Self::Error::KeyExchange)?,
         })
     }
 }
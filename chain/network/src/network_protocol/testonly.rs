@@ -76,7 +76,11 @@ pub fn make_account_id<R: Rng>(rng: &mut R) -> AccountId {
 }
 
 pub fn make_secret_key<R: Rng>(rng: &mut R) -> SecretKey {
-    SecretKey::from_seed(KeyType::ED25519, &rng.gen::<u64>().to_string())
+    make_secret_key_of_type(rng, KeyType::ED25519)
+}
+
+pub fn make_secret_key_of_type<R: Rng>(rng: &mut R, key_type: KeyType) -> SecretKey {
+    SecretKey::from_seed(key_type, &rng.gen::<u64>().to_string())
 }
 
 pub fn make_peer_id<R: Rng>(rng: &mut R) -> PeerId {
@@ -87,6 +91,10 @@ pub fn make_signer<R: Rng>(rng: &mut R) -> InMemorySigner {
     InMemorySigner::from_secret_key(make_account_id(rng), make_secret_key(rng))
 }
 
+pub fn make_signer_of_type<R: Rng>(rng: &mut R, key_type: KeyType) -> InMemorySigner {
+    InMemorySigner::from_secret_key(make_account_id(rng), make_secret_key_of_type(rng, key_type))
+}
+
 pub fn make_validator_signer<R: Rng>(rng: &mut R) -> InMemoryValidatorSigner {
     let account_id = make_account_id(rng);
     let seed = rng.gen::<u64>().to_string();
@@ -348,6 +356,7 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_listen_port: Some(rng.gen()),
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
+        key_exchange: None,
     }
 }
 
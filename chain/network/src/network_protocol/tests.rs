@@ -6,6 +6,7 @@ use crate::time;
 use crate::types::{HandshakeFailureReason, PeerMessage};
 use crate::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg};
 use anyhow::{bail, Context as _};
+use borsh::BorshSerialize as _;
 
 #[test]
 fn bad_account_data_size() {
@@ -29,16 +30,185 @@ fn bad_account_data_size() {
 }
 
 #[test]
-fn serialize_deserialize_protobuf_only() {
-    let mut rng = make_rng(39521947542);
+fn edge_encoded_len() {
+    let mut rng = make_rng(8732457234);
+    for key_type in [KeyType::ED25519, KeyType::MLDSA, KeyType::ED25519MLDSA] {
+        let a = data::make_signer_of_type(&mut rng, key_type);
+        let b = data::make_signer_of_type(&mut rng, key_type);
+        for edge in [data::make_edge(&a, &b), data::make_edge_tombstone(&a, &b)] {
+            assert!(edge.verify(), "{key_type}");
+            assert_eq!(edge.try_to_vec().unwrap().len(), edge.encoded_len(), "{key_type}");
+        }
+    }
+    let aa = data::make_announce_account(&mut rng);
+    assert_eq!(aa.try_to_vec().unwrap().len(), announce_account_encoded_len(&aa));
+}
+
+#[test]
+fn split_routing_table_update() {
+    let mut rng = make_rng(4358734534);
+    let signers: Vec<_> =
+        (0..5).map(|_| data::make_signer_of_type(&mut rng, KeyType::MLDSA)).collect();
+    let rtu = RoutingTableUpdate {
+        edges: (1..signers.len()).map(|i| data::make_edge(&signers[0], &signers[i])).collect(),
+        accounts: (0..50).map(|_| data::make_announce_account(&mut rng)).collect(),
+    };
+    let max_len = 25000;
+    assert!(rtu.encoded_len() > max_len);
+    assert_eq!(vec![rtu.clone()], rtu.clone().split(rtu.encoded_len()));
+
+    let parts = rtu.clone().split(max_len);
+    assert!(parts.len() > 1);
+    for part in &parts {
+        assert!(part.encoded_len() <= max_len);
+    }
+    let edges: Vec<_> = parts.iter().flat_map(|p| p.edges.clone()).collect();
+    let accounts: Vec<_> = parts.iter().flat_map(|p| p.accounts.clone()).collect();
+    assert_eq!(rtu.edges, edges);
+    assert_eq!(rtu.accounts, accounts);
+
+    // An edge larger than the limit is sent on its own.
+    let parts = RoutingTableUpdate::from_edges(rtu.edges.clone()).split(1);
+    assert_eq!(rtu.edges.len(), parts.len());
+}
+
+#[test]
+fn split_sync_accounts_data() {
+    let mut rng = make_rng(2398472342);
     let clock = time::FakeClock::default();
-    let msgs = [PeerMessage::SyncAccountsData(SyncAccountsData {
-        accounts_data: (0..4)
+    let msg = SyncAccountsData {
+        accounts_data: (0..10)
             .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
             .collect(),
+        incremental: false,
+        requesting_full_sync: true,
+    };
+    let max_len = msg.encoded_len() / 3;
+    let parts = PeerMessage::SyncAccountsData(msg.clone()).split(max_len).unwrap();
+    assert!(parts.len() >= 3);
+    let mut accounts_data = vec![];
+    for (i, part) in parts.into_iter().enumerate() {
+        let part = match part {
+            PeerMessage::SyncAccountsData(part) => part,
+            part => panic!("unexpected message {part}"),
+        };
+        assert!(part.encoded_len() <= max_len);
+        // Full sync is requested just once.
+        assert_eq!(i == 0, part.requesting_full_sync);
+        assert!(!part.incremental);
+        accounts_data.extend(part.accounts_data);
+    }
+    assert_eq!(msg.accounts_data, accounts_data);
+    assert_eq!(None, PeerMessage::SyncAccountsData(msg.clone()).split(msg.encoded_len()));
+}
+
+#[test]
+fn sync_accounts_data_for_protocol_version() {
+    let mut rng = make_rng(8923479234);
+    let clock = time::FakeClock::default();
+    let small = Arc::new(data::make_signed_account_data(&mut rng, &clock.clock()));
+    let mut large = data::make_signed_account_data(&mut rng, &clock.clock());
+    large.payload_mut().resize(LEGACY_MAX_ACCOUNT_DATA_SIZE_BYTES + 1, 0);
+    let large = Arc::new(large);
+    let msg = SyncAccountsData {
+        accounts_data: vec![small.clone(), large],
         incremental: true,
         requesting_full_sync: true,
-    })];
+    };
+
+    // Peers running an older protocol version don't get the large data.
+    let legacy = msg.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION - 1).unwrap();
+    assert_eq!(vec![small.clone()], legacy.accounts_data);
+    assert!(legacy.incremental);
+    assert!(legacy.requesting_full_sync);
+    assert_eq!(None, legacy.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION - 1));
+
+    assert_eq!(None, msg.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION));
+
+    // Nor the data with post-quantum proxies.
+    let signer = data::make_validator_signer(&mut rng);
+    let mut pq = data::make_account_data(
+        &mut rng,
+        clock.now_utc(),
+        data::make_epoch_id(&mut rng),
+        signer.validator_id().clone(),
+    );
+    pq.peers[0].peer_id =
+        PeerId::new(data::make_secret_key_of_type(&mut rng, KeyType::MLDSA).public_key());
+    let pq = Arc::new(pq.sign(&signer).unwrap());
+    assert!(pq.payload().len() <= LEGACY_MAX_ACCOUNT_DATA_SIZE_BYTES);
+    let msg = SyncAccountsData {
+        accounts_data: vec![small.clone(), pq],
+        incremental: true,
+        requesting_full_sync: false,
+    };
+    let legacy = msg.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION - 1).unwrap();
+    assert_eq!(vec![small], legacy.accounts_data);
+    assert_eq!(None, msg.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION));
+}
+
+#[test]
+fn routing_table_update_for_protocol_version() {
+    let mut rng = make_rng(3498572394);
+    let ed25519: Vec<_> =
+        (0..3).map(|_| data::make_signer_of_type(&mut rng, KeyType::ED25519)).collect();
+    let mldsa = data::make_signer_of_type(&mut rng, KeyType::MLDSA);
+    let hybrid = data::make_signer_of_type(&mut rng, KeyType::ED25519MLDSA);
+    let legacy_edges =
+        vec![data::make_edge(&ed25519[0], &ed25519[1]), data::make_edge(&ed25519[1], &ed25519[2])];
+    let legacy_account = data::make_announce_account(&mut rng);
+    let mut pq_account = data::make_announce_account(&mut rng);
+    pq_account.peer_id = PeerId::new(hybrid.public_key.clone());
+    let rtu = RoutingTableUpdate {
+        edges: vec![
+            legacy_edges[0].clone(),
+            data::make_edge(&ed25519[0], &mldsa),
+            legacy_edges[1].clone(),
+            data::make_edge(&mldsa, &hybrid),
+        ],
+        accounts: vec![pq_account, legacy_account.clone()],
+    };
+
+    // Peers running an older protocol version only get the edges and accounts they can decode.
+    let legacy_version = POST_QUANTUM_KEYS_PROTOCOL_VERSION - 1;
+    let msg = PeerMessage::SyncRoutingTable(rtu.clone());
+    let want = RoutingTableUpdate::new(legacy_edges, vec![legacy_account]);
+    assert_eq!(
+        Some(PeerMessage::SyncRoutingTable(want.clone())),
+        msg.for_protocol_version(legacy_version)
+    );
+    assert_eq!(None, want.for_protocol_version(legacy_version));
+
+    assert_eq!(None, rtu.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION));
+    assert_eq!(None, msg.for_protocol_version(POST_QUANTUM_KEYS_PROTOCOL_VERSION));
+    assert_eq!(None, PeerMessage::PeersRequest.for_protocol_version(legacy_version));
+}
+
+#[test]
+fn serialize_deserialize_protobuf_only() {
+    let mut rng = make_rng(39521947542);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let signer = data::make_signer(&mut rng);
+    let mut handshake = data::make_handshake(&mut rng, &chain);
+    handshake.key_exchange = Some(TransportKeyExchange {
+        x25519_public_key: vec![3; 32],
+        kem: TransportKem::Ciphertext(vec![7; 1088]),
+        signature: signer.secret_key.sign(b"transcript"),
+    });
+    let msgs = [
+        PeerMessage::SyncAccountsData(SyncAccountsData {
+            accounts_data: (0..4)
+                .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
+                .collect(),
+            incremental: true,
+            requesting_full_sync: true,
+        }),
+        PeerMessage::Handshake(handshake),
+        PeerMessage::EpochSyncRequest(data::make_epoch_id(&mut rng)),
+        PeerMessage::EpochSyncResponse(Box::new(EpochSyncResponse::UpToDate)),
+        PeerMessage::EpochSyncFinalizationRequest(data::make_epoch_id(&mut rng)),
+    ];
     for m in msgs {
         let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto))
             .with_context(|| m.to_string())
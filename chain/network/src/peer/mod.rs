@@ -1,7 +1,8 @@
 pub(crate) mod peer_actor;
-mod stream;
+pub(crate) mod stream;
 mod tracker;
 mod transfer_stats;
+pub(crate) mod transport;
 
 #[cfg(test)]
 pub(crate) mod testonly;
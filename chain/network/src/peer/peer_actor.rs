@@ -2,11 +2,13 @@ use crate::accounts_data;
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
 use crate::network_protocol::{
-    Edge, EdgeState, Encoding, ParsePeerMessageError, PartialEdgeInfo, PeerChainInfoV2, PeerInfo,
-    RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, SyncAccountsData,
+    self, Edge, EdgeState, Encoding, ParsePeerMessageError, PartialEdgeInfo, PeerChainInfoV2,
+    PeerInfo, RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, SyncAccountsData,
+    TransportKeyExchange,
 };
 use crate::peer::stream;
 use crate::peer::tracker::Tracker;
+use crate::peer::transport;
 use crate::peer_manager::connection;
 use crate::peer_manager::network_state::NetworkState;
 use crate::peer_manager::peer_manager_actor::Event;
@@ -75,6 +77,8 @@ pub struct HandshakeStartedEvent {
 pub struct HandshakeCompletedEvent {
     pub(crate) stream_id: tcp::StreamId,
     pub(crate) edge: Edge,
+    /// Whether the connection uses the encrypted transport.
+    pub(crate) encrypted: bool,
 }
 
 #[derive(thiserror::Error, Clone, PartialEq, Eq, Debug)]
@@ -96,6 +100,8 @@ pub(crate) enum ClosingReason {
     PeerManager,
     #[error("Received DisconnectMessage from peer")]
     DisconnectMessage,
+    #[error("encrypted transport is required, but the peer didn't negotiate it")]
+    TransportNotEncrypted,
 }
 
 pub(crate) struct PeerActor {
@@ -155,6 +161,21 @@ struct HandshakeSpec {
     partial_edge_info: PartialEdgeInfo,
 }
 
+/// Offer of the encrypted transport to be sent in the outbound Handshake described by `spec`.
+fn transport_offer(
+    network_state: &NetworkState,
+    initiator: &transport::Initiator,
+    spec: &HandshakeSpec,
+) -> TransportKeyExchange {
+    let config = &network_state.config;
+    let session = transport::Session {
+        initiator: &config.node_id(),
+        responder: &spec.peer_id,
+        nonce: spec.partial_edge_info.nonce,
+    };
+    initiator.offer(&config.node_key, session)
+}
+
 impl PeerActor {
     pub(crate) fn spawn(
         clock: time::Clock,
@@ -198,6 +219,7 @@ impl PeerActor {
                     protocol_version: PROTOCOL_VERSION,
                     peer_id: peer_id.clone(),
                 },
+                initiator: transport::Initiator::new(),
             },
         };
 
@@ -281,6 +303,17 @@ impl PeerActor {
         if let (PeerStatus::Ready(conn), PeerMessage::PeersRequest) = (&self.peer_status, msg) {
             conn.last_time_peer_requested.store(Some(self.clock.now()));
         }
+        if let PeerStatus::Ready(conn) = &self.peer_status {
+            if let Some(msg) = msg.for_protocol_version(conn.protocol_version) {
+                return self.send_message(&msg);
+            }
+        }
+        if let Some(msgs) = msg.split(network_protocol::SYNC_MESSAGE_SPLIT_SIZE_BYTES) {
+            for msg in &msgs {
+                self.send_message(msg);
+            }
+            return;
+        }
         if let Some(enc) = self.encoding() {
             return self.send_message_with_encoding(msg, enc);
         }
@@ -315,7 +348,7 @@ impl PeerActor {
             .inc_by(bytes_len as u64);
     }
 
-    fn send_handshake(&self, spec: HandshakeSpec) {
+    fn send_handshake(&self, spec: HandshakeSpec, key_exchange: Option<TransportKeyExchange>) {
         let chain_info = self.network_state.chain_info.load();
         let handshake = Handshake {
             protocol_version: spec.protocol_version,
@@ -330,6 +363,7 @@ impl PeerActor {
                 archival: self.network_state.config.archive,
             },
             partial_edge_info: spec.partial_edge_info,
+            key_exchange,
         };
         let msg = PeerMessage::Handshake(handshake);
         self.send_message_or_log(&msg);
@@ -428,6 +462,12 @@ impl PeerActor {
                     ));
                     return;
                 }
+                let key_type = handshake.sender_peer_id.public_key().key_type();
+                if !network_protocol::is_node_key_type_supported(key_type) {
+                    debug!(target: "network", peer_id=?handshake.sender_peer_id, "unsupported node key type {key_type}, disconnecting");
+                    self.stop(ctx, ClosingReason::HandshakeFailed);
+                    return;
+                }
                 // Verify if nonce is sane.
                 if let Err(err) = verify_nonce(&self.clock, handshake.partial_edge_info.nonce) {
                     debug!(target: "network", nonce=?handshake.partial_edge_info.nonce, my_node_id = ?self.my_node_id(), peer_id=?handshake.sender_peer_id, "bad nonce, disconnecting: {err}");
@@ -460,8 +500,59 @@ impl PeerActor {
             return;
         }
 
-        // Merge partial edges.
+        // Negotiate the encrypted transport.
         let nonce = handshake.partial_edge_info.nonce;
+        let transport = match (cs, &handshake.key_exchange) {
+            (_, None) => Ok(None),
+            (ConnectingStatus::Outbound { initiator, .. }, Some(accept)) => {
+                let session = transport::Session {
+                    initiator: self.my_node_id(),
+                    responder: &handshake.sender_peer_id,
+                    nonce,
+                };
+                initiator.finish(session, accept).map(|keys| Some((None, keys)))
+            }
+            (ConnectingStatus::Inbound(..), Some(offer)) => {
+                let session = transport::Session {
+                    initiator: &handshake.sender_peer_id,
+                    responder: self.my_node_id(),
+                    nonce,
+                };
+                transport::accept(&self.network_state.config.node_key, session, offer)
+                    .map(|(accept, keys)| Some((Some(accept), keys)))
+            }
+        };
+        let (key_exchange, session_keys) = match transport {
+            Ok(Some((key_exchange, keys))) => (key_exchange, Some(keys)),
+            Ok(None) => (None, None),
+            Err(transport::Error::InvalidSignature) => {
+                warn!(target: "network", "key exchange with invalid signature, disconnecting");
+                self.stop(ctx, ClosingReason::Ban(ReasonForBan::InvalidSignature));
+                return;
+            }
+            Err(err) => {
+                warn!(target: "network", "Key exchange failed: {err}. Disconnecting peer {}", handshake.sender_peer_id);
+                self.stop(ctx, ClosingReason::HandshakeFailed);
+                return;
+            }
+        };
+        let encrypted = session_keys.is_some();
+        if !encrypted && self.network_state.config.require_encrypted_transport {
+            info!(target: "network", "Peer {} doesn't support the encrypted transport, disconnecting", handshake.sender_peer_id);
+            self.stop(ctx, ClosingReason::TransportNotEncrypted);
+            return;
+        }
+        // Inbound peer encrypts everything it sends after its Handshake,
+        // so outbound peer has to enable encryption before it processes any other frame.
+        let session_keys = match (self.peer_type, session_keys) {
+            (PeerType::Outbound, Some(keys)) => {
+                self.framed.enable_encryption(keys);
+                None
+            }
+            (_, keys) => keys,
+        };
+
+        // Merge partial edges.
         let partial_edge_info = match cs {
             ConnectingStatus::Outbound { handshake_spec, .. } => {
                 handshake_spec.partial_edge_info.clone()
@@ -496,6 +587,8 @@ impl PeerActor {
         let conn = Arc::new(connection::Connection {
             addr: ctx.address(),
             peer_info: peer_info.clone(),
+            peer_addr: self.peer_addr,
+            protocol_version: handshake.protocol_version,
             initial_chain_info: handshake.sender_chain_info.clone(),
             chain_height: AtomicU64::new(handshake.sender_chain_info.height),
             edge,
@@ -504,6 +597,7 @@ impl PeerActor {
             _peer_connections_metric: metrics::PEER_CONNECTIONS.new_point(&metrics::Connection {
                 type_: self.peer_type,
                 encoding: self.encoding(),
+                encrypted,
             }),
             last_time_peer_requested: AtomicCell::new(None),
             last_time_received_message: AtomicCell::new(now),
@@ -572,7 +666,11 @@ impl PeerActor {
                                 peer_id: handshake.sender_peer_id.clone(),
                                 protocol_version: handshake.protocol_version,
                                 partial_edge_info: partial_edge_info,
-                            });
+                            }, key_exchange);
+                            // Handshake is the last plaintext frame that we send.
+                            if let Some(keys) = session_keys {
+                                act.framed.enable_encryption(keys);
+                            }
                         } else {
                             // Outbound peer triggers the inital full accounts data sync.
                             // TODO(gprusak): implement triggering the periodic full sync.
@@ -604,6 +702,7 @@ impl PeerActor {
                         act.network_state.config.event_sink.push(Event::HandshakeCompleted(HandshakeCompletedEvent{
                             stream_id: act.stream_id,
                             edge: conn.edge.clone(),
+                            encrypted,
                         }));
                     },
                     Ok(RegisterPeerResponse::Reject(err)) => {
@@ -639,7 +738,11 @@ impl PeerActor {
     fn handle_msg_connecting(&mut self, ctx: &mut actix::Context<Self>, msg: PeerMessage) {
         match (&mut self.peer_status, msg) {
             (
-                PeerStatus::Connecting(ConnectingStatus::Outbound { handshake_spec, .. }),
+                PeerStatus::Connecting(ConnectingStatus::Outbound {
+                    handshake_spec,
+                    initiator,
+                    ..
+                }),
                 PeerMessage::HandshakeFailure(peer_info, reason),
             ) => {
                 match reason {
@@ -662,8 +765,9 @@ impl PeerActor {
                         }
                         handshake_spec.protocol_version = common_version;
                         let spec = handshake_spec.clone();
+                        let offer = transport_offer(&self.network_state, initiator, &spec);
                         ctx.wait(actix::fut::ready(()).then(move |_, act: &mut Self, _| {
-                            act.send_handshake(spec);
+                            act.send_handshake(spec, Some(offer));
                             actix::fut::ready(())
                         }));
                     }
@@ -679,7 +783,11 @@ impl PeerActor {
             // TODO(gprusak): LastEdge should rather be a variant of HandshakeFailure.
             // Clean this up (you don't have to modify the proto, just the translation layer).
             (
-                PeerStatus::Connecting(ConnectingStatus::Outbound { handshake_spec, .. }),
+                PeerStatus::Connecting(ConnectingStatus::Outbound {
+                    handshake_spec,
+                    initiator,
+                    ..
+                }),
                 PeerMessage::LastEdge(edge),
             ) => {
                 // Check that the edge provided:
@@ -707,8 +815,9 @@ impl PeerActor {
                 handshake_spec.partial_edge_info =
                     self.network_state.propose_edge(&handshake_spec.peer_id, Some(edge.next()));
                 let spec = handshake_spec.clone();
+                let offer = transport_offer(&self.network_state, initiator, &spec);
                 ctx.wait(actix::fut::ready(()).then(move |_, act: &mut Self, _| {
-                    act.send_handshake(spec);
+                    act.send_handshake(spec, Some(offer));
                     actix::fut::ready(())
                 }));
             }
@@ -851,6 +960,20 @@ impl PeerActor {
                     network_state.client.challenge(challenge).await;
                     None
                 }
+                PeerMessage::EpochSyncRequest(epoch_id) => {
+                    network_state.client.epoch_sync_request(epoch_id).await.map(PeerMessage::EpochSyncResponse)
+                }
+                PeerMessage::EpochSyncResponse(response) => {
+                    network_state.client.epoch_sync_response(response, peer_id).await?;
+                    None
+                }
+                PeerMessage::EpochSyncFinalizationRequest(epoch_id) => {
+                    network_state.client.epoch_sync_finalization_request(epoch_id).await.map(PeerMessage::EpochSyncFinalizationResponse)
+                }
+                PeerMessage::EpochSyncFinalizationResponse(response) => {
+                    network_state.client.epoch_sync_finalization_response(response, peer_id).await?;
+                    None
+                }
                 msg => {
                     tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
                     None
@@ -874,6 +997,14 @@ impl PeerActor {
         conn: &connection::Connection,
         peer_msg: PeerMessage,
     ) {
+        // Syncs are split by the sender, see network_protocol::SYNC_MESSAGE_SPLIT_SIZE_BYTES.
+        if let Some(len) = peer_msg.sync_encoded_len() {
+            if len > network_protocol::MAX_SYNC_MESSAGE_SIZE_BYTES {
+                debug!(target: "network", "{} message of {len}B from {}", peer_msg, self.peer_info);
+                self.stop(ctx, ClosingReason::Ban(ReasonForBan::Abusive));
+                return;
+            }
+        }
         match peer_msg.clone() {
             PeerMessage::Disconnect => {
                 debug!(target: "network", "Disconnect signal. Me: {:?} Peer: {:?}", self.my_node_info.id, self.other_peer_id());
@@ -1129,10 +1260,14 @@ impl Actor for PeerActor {
         );
 
         // If outbound peer, initiate handshake.
-        if let PeerStatus::Connecting(ConnectingStatus::Outbound { handshake_spec, .. }) =
-            &self.peer_status
+        if let PeerStatus::Connecting(ConnectingStatus::Outbound {
+            handshake_spec,
+            initiator,
+            ..
+        }) = &self.peer_status
         {
-            self.send_handshake(handshake_spec.clone());
+            let offer = transport_offer(&self.network_state, initiator, handshake_spec);
+            self.send_handshake(handshake_spec.clone(), Some(offer));
         }
         self.network_state
             .config
@@ -1181,6 +1316,12 @@ impl actix::Handler<stream::Error> for PeerActor {
                 self.stop(ctx, ClosingReason::Ban(ReasonForBan::Abusive));
                 true
             }
+            // The frames might have been tampered with on the way,
+            // so we don't punish the peer for them.
+            stream::Error::Recv(stream::RecvError::Decryption(_))
+            | stream::Error::Recv(stream::RecvError::UnexpectedEncryptedFrame) => true,
+            // Running out of nonces is not going to happen in practice.
+            stream::Error::Send(stream::SendError::Encryption(_)) => false,
             // It is expected in a sense that the peer might be just slow.
             stream::Error::Send(stream::SendError::QueueOverflow { .. }) => true,
             stream::Error::Recv(stream::RecvError::IO(err))
@@ -1332,7 +1473,12 @@ type InboundHandshakePermit = tokio::sync::OwnedSemaphorePermit;
 #[derive(Debug)]
 enum ConnectingStatus {
     Inbound(InboundHandshakePermit),
-    Outbound { _permit: connection::OutboundHandshakePermit, handshake_spec: HandshakeSpec },
+    Outbound {
+        _permit: connection::OutboundHandshakePermit,
+        handshake_spec: HandshakeSpec,
+        /// Ephemeral secrets of the encrypted transport offered in the Handshake.
+        initiator: transport::Initiator,
+    },
 }
 
 /// State machine of the PeerActor.
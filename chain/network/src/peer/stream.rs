@@ -1,9 +1,11 @@
+use crate::peer::transport;
 use crate::peer_manager::connection;
 use crate::stats::metrics;
 use crate::tcp;
 use actix::fut::future::wrap_future;
 use actix::AsyncContext as _;
 use bytesize::{GIB, MIB};
+use parking_lot::Mutex;
 use std::io;
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
@@ -16,6 +18,9 @@ use tokio::io::AsyncWriteExt as _;
 const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
 /// Maximum capacity of write buffer in bytes.
 const MAX_WRITE_BUFFER_CAPACITY_BYTES: usize = GIB as usize;
+/// Bit of the length prefix marking frames sealed by the encrypted transport.
+/// Lengths never reach it, since they are bounded by `NETWORK_MESSAGE_MAX_SIZE_BYTES`.
+pub(crate) const ENCRYPTED_FRAME_FLAG: u32 = 1 << 31;
 
 type ReadHalf = tokio::io::ReadHalf<tokio::net::TcpStream>;
 type WriteHalf = tokio::io::WriteHalf<tokio::net::TcpStream>;
@@ -26,6 +31,8 @@ pub(crate) enum SendError {
     IO(#[source] io::Error),
     #[error("queue is full, got {got_bytes}B, max capacity is {want_max_bytes}")]
     QueueOverflow { got_bytes: usize, want_max_bytes: usize },
+    #[error("encryption: {0}")]
+    Encryption(#[source] transport::CipherError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -34,12 +41,22 @@ pub(crate) enum RecvError {
     IO(#[source] io::Error),
     #[error("message too large: got {got_bytes}B, want <={want_max_bytes}B")]
     MessageTooLarge { got_bytes: usize, want_max_bytes: usize },
+    #[error("decryption: {0}")]
+    Decryption(#[source] transport::CipherError),
+    #[error("received an encrypted frame before encryption was enabled")]
+    UnexpectedEncryptedFrame,
 }
 
 #[derive(actix::Message, PartialEq, Eq, Clone, Debug)]
 #[rtype(result = "()")]
 pub(crate) struct Frame(pub Vec<u8>);
 
+enum SendItem {
+    Frame(Frame),
+    /// Frames following this item are sealed with the cipher.
+    EnableEncryption(transport::Cipher),
+}
+
 /// Stream critical error.
 /// Actor is responsible for calling ctx.stop() after receiving stream::Error.
 /// Actor might receive more than 1 stream::Error, but should call ctx.stop() just after the
@@ -57,7 +74,9 @@ pub(crate) enum Error {
 }
 
 pub(crate) struct FramedStream<Actor: actix::Actor> {
-    queue_send: tokio::sync::mpsc::UnboundedSender<Frame>,
+    queue_send: tokio::sync::mpsc::UnboundedSender<SendItem>,
+    /// Cipher for the received frames, picked up by the recv loop.
+    recv_cipher: Arc<Mutex<Option<transport::Cipher>>>,
     stats: Arc<connection::Stats>,
     send_buf_size_metric: Arc<metrics::IntGaugeGuard>,
     addr: actix::Addr<Actor>,
@@ -86,22 +105,42 @@ where
             let m = send_buf_size_metric.clone();
             async move {
                 if let Err(err) = Self::run_send_loop(tcp_send, queue_recv, stats, m).await {
-                    addr.do_send(Error::Send(SendError::IO(err)));
+                    addr.do_send(Error::Send(err));
                 }
             }
         }));
+        let recv_cipher = Arc::new(Mutex::new(None));
         ctx.spawn(wrap_future({
             let addr = ctx.address();
             let stats = stats.clone();
+            let recv_cipher = recv_cipher.clone();
             async move {
-                if let Err(err) =
-                    Self::run_recv_loop(stream.peer_addr, tcp_recv, addr.clone(), stats).await
+                if let Err(err) = Self::run_recv_loop(
+                    stream.peer_addr,
+                    tcp_recv,
+                    addr.clone(),
+                    stats,
+                    recv_cipher,
+                )
+                .await
                 {
                     addr.do_send(Error::Recv(err));
                 }
             }
         }));
-        Self { queue_send, stats, send_buf_size_metric, addr: ctx.address() }
+        Self { queue_send, recv_cipher, stats, send_buf_size_metric, addr: ctx.address() }
+    }
+
+    /// Encrypts all the frames sent after this call and requires all the frames received
+    /// after this call to be encrypted. To avoid races, it should be called by Actor
+    /// synchronously with processing the last plaintext frame that it expects.
+    /// Plaintext frames received afterwards are dropped, since the peer may have sent
+    /// them before it enabled encryption on its side (i.e. a Handshake sent twice
+    /// when the encoding was not known yet).
+    pub fn enable_encryption(&self, keys: transport::SessionKeys) {
+        let (send, recv) = keys.into_ciphers();
+        *self.recv_cipher.lock() = Some(recv);
+        let _ = self.queue_send.send(SendItem::EnableEncryption(send));
     }
 
     /// Pushes `msg` to the send queue.
@@ -125,7 +164,7 @@ where
                 want_max_bytes: MAX_WRITE_BUFFER_CAPACITY_BYTES,
             }));
         }
-        let _ = self.queue_send.send(frame);
+        let _ = self.queue_send.send(SendItem::Frame(frame));
     }
 
     /// Event loop receiving and processing messages.
@@ -141,6 +180,7 @@ where
         read: ReadHalf,
         addr: actix::Addr<Actor>,
         stats: Arc<connection::Stats>,
+        recv_cipher: Arc<Mutex<Option<transport::Cipher>>>,
     ) -> Result<(), RecvError> {
         const READ_BUFFER_CAPACITY: usize = 8 * 1024;
         let mut read = tokio::io::BufReader::with_capacity(READ_BUFFER_CAPACITY, read);
@@ -151,13 +191,17 @@ where
             &metrics::PEER_DATA_READ_BUFFER_SIZE,
             vec![peer_addr.to_string()],
         );
+        let mut cipher = None;
         loop {
-            let n = read.read_u32_le().await.map_err(RecvError::IO)? as usize;
-            if n > NETWORK_MESSAGE_MAX_SIZE_BYTES {
-                return Err(RecvError::MessageTooLarge {
-                    got_bytes: n,
-                    want_max_bytes: NETWORK_MESSAGE_MAX_SIZE_BYTES,
-                });
+            let n = read.read_u32_le().await.map_err(RecvError::IO)?;
+            let encrypted = n & ENCRYPTED_FRAME_FLAG != 0;
+            let n = (n & !ENCRYPTED_FRAME_FLAG) as usize;
+            let max_size = match encrypted {
+                true => NETWORK_MESSAGE_MAX_SIZE_BYTES + transport::TAG_LEN,
+                false => NETWORK_MESSAGE_MAX_SIZE_BYTES,
+            };
+            if n > max_size {
+                return Err(RecvError::MessageTooLarge { got_bytes: n, want_max_bytes: max_size });
             }
             msg_size_metric.observe(n as f64);
             buf_size_metric.set(n as i64);
@@ -168,6 +212,18 @@ where
             buf_size_metric.set(0);
             stats.received_messages.fetch_add(1, Ordering::Relaxed);
             stats.received_bytes.fetch_add(n as u64, Ordering::Relaxed);
+            if cipher.is_none() {
+                cipher = recv_cipher.lock().take();
+            }
+            let buf = match (&mut cipher, encrypted) {
+                (None, false) => buf,
+                (Some(cipher), true) => cipher.open(&buf).map_err(RecvError::Decryption)?,
+                (None, true) => return Err(RecvError::UnexpectedEncryptedFrame),
+                (Some(_), false) => {
+                    tracing::debug!(target: "network", "dropping plaintext frame");
+                    continue;
+                }
+            };
             if let Err(_) = addr.send(Frame(buf)).await {
                 // We got mailbox error, which means that Actor has stopped,
                 // so we should just close the stream.
@@ -177,28 +233,42 @@ where
     }
     async fn run_send_loop(
         tcp_send: WriteHalf,
-        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<Frame>,
+        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<SendItem>,
         stats: Arc<connection::Stats>,
         buf_size_metric: Arc<metrics::IntGaugeGuard>,
-    ) -> io::Result<()> {
+    ) -> Result<(), SendError> {
         const WRITE_BUFFER_CAPACITY: usize = 8 * 1024;
         let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, tcp_send);
-        while let Some(Frame(mut msg)) = queue_recv.recv().await {
+        let mut cipher: Option<transport::Cipher> = None;
+        while let Some(mut item) = queue_recv.recv().await {
             // Try writing a batch of messages and flush once at the end.
             loop {
-                // TODO(gprusak): sending a too large message should probably be treated as a bug,
-                // since dropping messages may lead to hard-to-debug high-level issues.
-                if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
-                    metrics::MessageDropped::InputTooLong.inc_unknown_msg();
-                } else {
-                    writer.write_u32_le(msg.len() as u32).await?;
-                    writer.write_all(&msg[..]).await?;
+                match item {
+                    SendItem::EnableEncryption(it) => cipher = Some(it),
+                    SendItem::Frame(Frame(msg)) => {
+                        // TODO(gprusak): sending a too large message should probably be treated
+                        // as a bug, since dropping messages may lead to hard-to-debug high-level
+                        // issues.
+                        if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+                            metrics::MessageDropped::InputTooLong.inc_unknown_msg();
+                        } else if let Some(cipher) = &mut cipher {
+                            let sealed = cipher.seal(&msg[..]).map_err(SendError::Encryption)?;
+                            writer
+                                .write_u32_le(sealed.len() as u32 | ENCRYPTED_FRAME_FLAG)
+                                .await
+                                .map_err(SendError::IO)?;
+                            writer.write_all(&sealed[..]).await.map_err(SendError::IO)?;
+                        } else {
+                            writer.write_u32_le(msg.len() as u32).await.map_err(SendError::IO)?;
+                            writer.write_all(&msg[..]).await.map_err(SendError::IO)?;
+                        }
+                        stats.messages_to_send.fetch_sub(1, Ordering::Release);
+                        stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
+                        buf_size_metric.sub(msg.len() as i64);
+                    }
                 }
-                stats.messages_to_send.fetch_sub(1, Ordering::Release);
-                stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
-                buf_size_metric.sub(msg.len() as i64);
-                msg = match queue_recv.try_recv() {
-                    Ok(Frame(it)) => it,
+                item = match queue_recv.try_recv() {
+                    Ok(it) => it,
                     Err(_) => break,
                 };
             }
@@ -208,7 +278,7 @@ where
             // and added to the queue at a rate similar to flush latency. To fix that
             // we would need to put writer.flush() and queue_recv.recv() into a tokio::select
             // and make sure that both are cancellation-safe.
-            writer.flush().await?;
+            writer.flush().await.map_err(SendError::IO)?;
         }
         Ok(())
     }
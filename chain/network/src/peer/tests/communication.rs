@@ -1,7 +1,9 @@
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::Encoding;
 use crate::network_protocol::{Handshake, HandshakeFailureReason, PeerMessage, RoutedMessageBody};
+use crate::peer::peer_actor::ClosingReason;
 use crate::peer::testonly::{Event, PeerConfig, PeerHandle};
+use crate::peer::transport;
 use crate::peer_manager::peer_manager_actor::Event as PME;
 use crate::tcp;
 use crate::testonly::make_rng;
@@ -17,25 +19,28 @@ use std::sync::Arc;
 async fn test_peer_communication(
     outbound_encoding: Option<Encoding>,
     inbound_encoding: Option<Encoding>,
+    require_encrypted_transport: bool,
 ) -> anyhow::Result<()> {
     let mut rng = make_rng(89028037453);
     let mut clock = time::FakeClock::default();
 
     let chain = Arc::new(data::Chain::make(&mut clock, &mut rng, 12));
-    let inbound_cfg = PeerConfig {
+    let mut inbound_cfg = PeerConfig {
         chain: chain.clone(),
         network: chain.make_config(&mut rng),
         peers: (0..5).map(|_| data::make_peer_info(&mut rng)).collect(),
         force_encoding: inbound_encoding,
         nonce: None,
     };
-    let outbound_cfg = PeerConfig {
+    inbound_cfg.network.require_encrypted_transport = require_encrypted_transport;
+    let mut outbound_cfg = PeerConfig {
         chain: chain.clone(),
         network: chain.make_config(&mut rng),
         peers: (0..5).map(|_| data::make_peer_info(&mut rng)).collect(),
         force_encoding: outbound_encoding,
         nonce: None,
     };
+    outbound_cfg.network.require_encrypted_transport = require_encrypted_transport;
     let (outbound_stream, inbound_stream) = tcp::Stream::loopback(inbound_cfg.id()).await;
     let mut inbound = PeerHandle::start_endpoint(clock.clock(), inbound_cfg, inbound_stream).await;
     let mut outbound =
@@ -166,7 +171,23 @@ async fn peer_communication() -> anyhow::Result<()> {
                     continue;
                 }
             }
-            test_peer_communication(outbound.clone(), inbound.clone())
+            test_peer_communication(outbound.clone(), inbound.clone(), false)
+                .await
+                .with_context(|| format!("(outbound={outbound:?},inbound={inbound:?})"))?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+// Verifies that peers requiring the encrypted transport are able to communicate.
+// Borsh encoding doesn't support the encrypted transport.
+async fn peer_communication_encrypted() -> anyhow::Result<()> {
+    init_test_logger();
+    let encodings = [None, Some(Encoding::Proto)];
+    for outbound in &encodings {
+        for inbound in &encodings {
+            test_peer_communication(outbound.clone(), inbound.clone(), true)
                 .await
                 .with_context(|| format!("(outbound={outbound:?},inbound={inbound:?})"))?;
         }
@@ -207,6 +228,7 @@ async fn test_handshake(outbound_encoding: Option<Encoding>, inbound_encoding: O
         sender_listen_port: Some(outbound_port),
         sender_chain_info: outbound_cfg.chain.get_peer_chain_info(),
         partial_edge_info: outbound_cfg.partial_edge_info(&inbound.cfg.id(), 1),
+        key_exchange: None,
     };
     // We will also introduce chain_id mismatch, but ProtocolVersionMismatch is expected to take priority.
     handshake.sender_chain_info.genesis_id.chain_id = "unknown_chain".to_string();
@@ -263,3 +285,104 @@ async fn handshake() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+/// Starts an inbound PeerActor requiring the encrypted transport and connects to it
+/// with a raw stream. Returns a valid handshake for the raw stream to send, without
+/// the transport offer.
+async fn start_encrypted_inbound(
+    seed: u64,
+) -> (PeerHandle, PeerConfig, Stream, Handshake, Arc<data::Chain>) {
+    let mut rng = make_rng(seed);
+    let mut clock = time::FakeClock::default();
+
+    let chain = Arc::new(data::Chain::make(&mut clock, &mut rng, 12));
+    let mut inbound_cfg = PeerConfig {
+        network: chain.make_config(&mut rng),
+        chain: chain.clone(),
+        peers: (0..5).map(|_| data::make_peer_info(&mut rng)).collect(),
+        force_encoding: Some(Encoding::Proto),
+        nonce: None,
+    };
+    inbound_cfg.network.require_encrypted_transport = true;
+    let outbound_cfg = PeerConfig {
+        network: chain.make_config(&mut rng),
+        chain: chain.clone(),
+        peers: (0..5).map(|_| data::make_peer_info(&mut rng)).collect(),
+        force_encoding: Some(Encoding::Proto),
+        nonce: None,
+    };
+    let (outbound_stream, inbound_stream) = tcp::Stream::loopback(inbound_cfg.id()).await;
+    let inbound = PeerHandle::start_endpoint(clock.clock(), inbound_cfg, inbound_stream).await;
+    let outbound_port = outbound_stream.local_addr.port();
+    let outbound = Stream::new(Some(Encoding::Proto), outbound_stream);
+    let handshake = Handshake {
+        protocol_version: PROTOCOL_VERSION,
+        oldest_supported_version: PEER_MIN_ALLOWED_PROTOCOL_VERSION,
+        sender_peer_id: outbound_cfg.id(),
+        target_peer_id: inbound.cfg.id(),
+        sender_listen_port: Some(outbound_port),
+        sender_chain_info: chain.get_peer_chain_info(),
+        partial_edge_info: outbound_cfg.partial_edge_info(&inbound.cfg.id(), 1),
+        key_exchange: None,
+    };
+    (inbound, outbound_cfg, outbound, handshake, chain)
+}
+
+#[tokio::test]
+// Verifies that a peer requiring the encrypted transport rejects peers not offering it.
+async fn encrypted_transport_required() {
+    init_test_logger();
+    let (mut inbound, _, mut outbound, handshake, _) = start_encrypted_inbound(42342423).await;
+    outbound.write(&PeerMessage::Handshake(handshake)).await;
+    assert_eq!(ClosingReason::TransportNotEncrypted, inbound.fail_handshake().await);
+}
+
+#[tokio::test]
+// Verifies that the encrypted transport is negotiated in the Handshake and
+// that frames sealed with keys from a different session are rejected.
+async fn encrypted_transport() {
+    init_test_logger();
+    for valid_keys in [true, false] {
+        let (mut inbound, outbound_cfg, mut outbound, mut handshake, chain) =
+            start_encrypted_inbound(79032342).await;
+        let initiator = transport::Initiator::new();
+        let (initiator_id, responder_id) =
+            (handshake.sender_peer_id.clone(), handshake.target_peer_id.clone());
+        let session = transport::Session {
+            initiator: &initiator_id,
+            responder: &responder_id,
+            nonce: handshake.partial_edge_info.nonce,
+        };
+        let offer = initiator.offer(&outbound_cfg.network.node_key, session);
+        handshake.key_exchange = Some(offer.clone());
+        outbound.write(&PeerMessage::Handshake(handshake.clone())).await;
+        let accept = match outbound.read().await {
+            PeerMessage::Handshake(h) => h.key_exchange.unwrap(),
+            msg => panic!("expected Handshake, got {msg}"),
+        };
+        let keys = initiator.finish(session, &accept).unwrap();
+        if valid_keys {
+            outbound.enable_encryption(keys);
+        } else {
+            // Keys derived by a responder in an unrelated key exchange.
+            let (_, keys) =
+                transport::accept(&outbound_cfg.network.node_key, session, &offer).unwrap();
+            outbound.enable_encryption(keys);
+        }
+
+        let mut events = inbound.events.from_now();
+        let want = PeerMessage::BlockRequest(chain.blocks[5].hash().clone());
+        outbound.write(&want).await;
+        if valid_keys {
+            inbound.complete_handshake().await;
+            events
+                .recv_until(|ev| match ev {
+                    Event::Network(PME::MessageProcessed(got)) if got == want => Some(()),
+                    _ => None,
+                })
+                .await;
+        } else {
+            assert_eq!(ClosingReason::StreamError, inbound.fail_handshake().await);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use crate::actix::ActixSystem;
 use crate::network_protocol::testonly as data;
 use crate::peer::stream;
+use crate::peer::transport;
 use crate::tcp;
-use crate::testonly::make_rng;
+use crate::testonly::{make_rng, Rng};
 use actix::Actor as _;
 use actix::ActorContext as _;
+use near_primitives::network::PeerId;
 use rand::Rng as _;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -29,6 +31,17 @@ impl actix::Handler<SendFrame> for Actor {
     }
 }
 
+#[derive(actix::Message)]
+#[rtype("()")]
+struct EnableEncryption(transport::SessionKeys);
+
+impl actix::Handler<EnableEncryption> for Actor {
+    type Result = ();
+    fn handle(&mut self, EnableEncryption(keys): EnableEncryption, _ctx: &mut Self::Context) {
+        self.stream.enable_encryption(keys);
+    }
+}
+
 impl actix::Handler<stream::Frame> for Actor {
     type Result = ();
     fn handle(&mut self, frame: stream::Frame, _ctx: &mut Self::Context) {
@@ -64,13 +77,7 @@ impl Actor {
     }
 }
 
-#[tokio::test]
-async fn send_recv() {
-    let mut rng = make_rng(98324532);
-    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng)).await;
-    let a1 = Actor::spawn(s1).await;
-    let mut a2 = Actor::spawn(s2).await;
-
+async fn send_recv_frames(rng: &mut Rng, a1: &Handler, a2: &mut Handler) {
     for _ in 0..5 {
         let n = rng.gen_range(1..10);
         let msgs: Vec<_> = (0..n)
@@ -90,3 +97,35 @@ async fn send_recv() {
         }
     }
 }
+
+#[tokio::test]
+async fn send_recv() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng)).await;
+    let a1 = Actor::spawn(s1).await;
+    let mut a2 = Actor::spawn(s2).await;
+    send_recv_frames(&mut rng, &a1, &mut a2).await;
+}
+
+#[tokio::test]
+async fn send_recv_encrypted() {
+    let mut rng = make_rng(3242342);
+    let key1 = data::make_secret_key(&mut rng);
+    let key2 = data::make_secret_key(&mut rng);
+    let (id1, id2) = (PeerId::new(key1.public_key()), PeerId::new(key2.public_key()));
+    let session = transport::Session { initiator: &id1, responder: &id2, nonce: 1 };
+    let initiator = transport::Initiator::new();
+    let (accept, keys2) =
+        transport::accept(&key2, session, &initiator.offer(&key1, session)).unwrap();
+    let keys1 = initiator.finish(session, &accept).unwrap();
+
+    let (s1, s2) = tcp::Stream::loopback(id2.clone()).await;
+    let mut a1 = Actor::spawn(s1).await;
+    let mut a2 = Actor::spawn(s2).await;
+    // Plaintext frames before enabling encryption.
+    send_recv_frames(&mut rng, &a1, &mut a2).await;
+    a1.system.addr.send(EnableEncryption(keys1)).await.unwrap();
+    a2.system.addr.send(EnableEncryption(keys2)).await.unwrap();
+    send_recv_frames(&mut rng, &a1, &mut a2).await;
+    send_recv_frames(&mut rng, &a2, &mut a1).await;
+}
@@ -0,0 +1,401 @@
+//! Encrypted transport for peer connections.
+//!
+//! Session keys are derived from a hybrid key exchange: an ephemeral X25519
+//! Diffie-Hellman combined with an ML-KEM-768 encapsulation. The traffic stays
+//! confidential as long as either of the two is unbroken, in particular against
+//! an adversary recording it now to decrypt it with a quantum computer later.
+//! Both sides sign the transcript of the exchange with their `PeerId` keys, which
+//! binds the session to the peers authenticated by the `Handshake`.
+//!
+//! The outbound peer (initiator) puts an offer into its `Handshake`, the inbound
+//! peer answers with an accept in its `Handshake`. Every frame sent after the
+//! handshakes is sealed with ChaCha20-Poly1305 under a per-direction key.
+use crate::network_protocol::{TransportKem, TransportKeyExchange};
+use chacha20poly1305::aead::{Aead as _, NewAead as _};
+use chacha20poly1305::ChaCha20Poly1305;
+use fips203::ml_kem_768;
+use fips203::traits::{Decaps as _, Encaps as _, KeyGen as _, SerDes as _};
+use near_crypto::SecretKey;
+use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
+use rand::RngCore as _;
+use sha2::Sha256;
+
+/// Domain separator of the transcripts signed by the peers.
+const TRANSCRIPT_DOMAIN: &str = "near-p2p-transport-v1";
+/// HKDF info of the key encrypting frames sent by the initiator.
+const INITIATOR_KEY_INFO: &[u8] = b"near-p2p-transport-v1 initiator";
+/// HKDF info of the key encrypting frames sent by the responder.
+const RESPONDER_KEY_INFO: &[u8] = b"near-p2p-transport-v1 responder";
+
+const X25519_KEY_LEN: usize = 32;
+/// Size of the authentication tag appended to every encrypted frame.
+pub(crate) const TAG_LEN: usize = 16;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Error {
+    #[error("invalid transcript signature")]
+    InvalidSignature,
+    #[error("got {got} message, want {want}")]
+    UnexpectedKem { got: &'static str, want: &'static str },
+    #[error("x25519 public key: got {got}B, want {want}B")]
+    X25519KeyLength { got: usize, want: usize },
+    #[error("x25519 shared secret is not contributory")]
+    X25519LowOrder,
+    #[error("ML-KEM {what}: got {got}B, want {want}B")]
+    MlKemLength { what: &'static str, got: usize, want: usize },
+    #[error("ML-KEM: {0}")]
+    MlKem(&'static str),
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CipherError {
+    #[error("frame counter exhausted")]
+    CounterExhausted,
+    #[error("frame authentication failed")]
+    Decrypt,
+}
+
+/// Identities of the connection endpoints, in the order in which the
+/// connection was established.
+#[derive(Clone, Copy)]
+pub(crate) struct Session<'a> {
+    pub initiator: &'a PeerId,
+    pub responder: &'a PeerId,
+    /// Nonce of the edge proposed in the handshake.
+    pub nonce: u64,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct Transcript<'a> {
+    domain: &'a str,
+    initiator: &'a PeerId,
+    responder: &'a PeerId,
+    nonce: u64,
+    offer_x25519_public_key: &'a [u8],
+    offer_mlkem_encapsulation_key: &'a [u8],
+    /// Unset in the transcript signed by the initiator.
+    accept: Option<(&'a [u8], &'a [u8])>,
+}
+
+impl<'a> Session<'a> {
+    fn transcript(
+        &self,
+        offer: (&'a [u8], &'a [u8]),
+        accept: Option<(&'a [u8], &'a [u8])>,
+    ) -> CryptoHash {
+        CryptoHash::hash_borsh(&Transcript {
+            domain: TRANSCRIPT_DOMAIN,
+            initiator: self.initiator,
+            responder: self.responder,
+            nonce: self.nonce,
+            offer_x25519_public_key: offer.0,
+            offer_mlkem_encapsulation_key: offer.1,
+            accept,
+        })
+    }
+}
+
+/// Ephemeral secrets of the initiator, kept until the responder accepts the offer.
+pub(crate) struct Initiator {
+    x25519_secret: x25519_dalek::StaticSecret,
+    x25519_public_key: x25519_dalek::PublicKey,
+    mlkem_encapsulation_key: [u8; ml_kem_768::EK_LEN],
+    mlkem_decapsulation_key: ml_kem_768::DecapsKey,
+}
+
+impl std::fmt::Debug for Initiator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Initiator").finish_non_exhaustive()
+    }
+}
+
+impl Initiator {
+    pub fn new() -> Self {
+        let mut seed = [0; X25519_KEY_LEN];
+        rand::thread_rng().fill_bytes(&mut seed);
+        let x25519_secret = x25519_dalek::StaticSecret::from(seed);
+        let (ek, dk) = ml_kem_768::KG::try_keygen().expect("ML-KEM key generation failed");
+        Self {
+            x25519_public_key: x25519_dalek::PublicKey::from(&x25519_secret),
+            x25519_secret,
+            mlkem_encapsulation_key: ek.into_bytes(),
+            mlkem_decapsulation_key: dk,
+        }
+    }
+
+    fn offer_keys(&self) -> (&[u8], &[u8]) {
+        (self.x25519_public_key.as_bytes(), &self.mlkem_encapsulation_key)
+    }
+
+    /// Constructs the offer to be sent in the initiator's `Handshake`.
+    pub fn offer(&self, node_key: &SecretKey, session: Session<'_>) -> TransportKeyExchange {
+        let transcript = session.transcript(self.offer_keys(), None);
+        TransportKeyExchange {
+            x25519_public_key: self.x25519_public_key.as_bytes().to_vec(),
+            kem: TransportKem::EncapsulationKey(self.mlkem_encapsulation_key.to_vec()),
+            signature: node_key.sign(transcript.as_ref()),
+        }
+    }
+
+    /// Verifies the responder's accept and derives the session keys.
+    pub fn finish(
+        &self,
+        session: Session<'_>,
+        accept: &TransportKeyExchange,
+    ) -> Result<SessionKeys, Error> {
+        let ct = match &accept.kem {
+            TransportKem::Ciphertext(ct) => ct,
+            TransportKem::EncapsulationKey(_) => {
+                return Err(Error::UnexpectedKem { got: "offer", want: "accept" })
+            }
+        };
+        let transcript =
+            session.transcript(self.offer_keys(), Some((&accept.x25519_public_key, ct.as_slice())));
+        if !accept.signature.verify(transcript.as_ref(), session.responder.public_key()) {
+            return Err(Error::InvalidSignature);
+        }
+        let x25519_ss = x25519(&self.x25519_secret, &accept.x25519_public_key)?;
+        let ct = ml_kem_768::CipherText::try_from_bytes(to_array(ct, "ciphertext")?)
+            .map_err(Error::MlKem)?;
+        let mlkem_ss = self.mlkem_decapsulation_key.try_decaps(&ct).map_err(Error::MlKem)?;
+        let (send, recv) = derive_keys(&transcript, &x25519_ss, &mlkem_ss.into_bytes());
+        Ok(SessionKeys { send, recv })
+    }
+}
+
+/// Verifies the initiator's offer, answers it and derives the session keys.
+/// Returns the accept to be sent in the responder's `Handshake`.
+pub(crate) fn accept(
+    node_key: &SecretKey,
+    session: Session<'_>,
+    offer: &TransportKeyExchange,
+) -> Result<(TransportKeyExchange, SessionKeys), Error> {
+    let ek = match &offer.kem {
+        TransportKem::EncapsulationKey(ek) => ek,
+        TransportKem::Ciphertext(_) => {
+            return Err(Error::UnexpectedKem { got: "accept", want: "offer" })
+        }
+    };
+    let offer_keys = (offer.x25519_public_key.as_slice(), ek.as_slice());
+    if !offer
+        .signature
+        .verify(session.transcript(offer_keys, None).as_ref(), session.initiator.public_key())
+    {
+        return Err(Error::InvalidSignature);
+    }
+
+    let mut seed = [0; X25519_KEY_LEN];
+    rand::thread_rng().fill_bytes(&mut seed);
+    let x25519_secret = x25519_dalek::StaticSecret::from(seed);
+    let x25519_public_key = x25519_dalek::PublicKey::from(&x25519_secret);
+    let x25519_ss = x25519(&x25519_secret, &offer.x25519_public_key)?;
+    let ek = ml_kem_768::EncapsKey::try_from_bytes(to_array(ek, "encapsulation key")?)
+        .map_err(Error::MlKem)?;
+    let (mlkem_ss, ct) = ek.try_encaps().map_err(Error::MlKem)?;
+    let ct = ct.into_bytes();
+
+    let transcript = session.transcript(offer_keys, Some((x25519_public_key.as_bytes(), &ct[..])));
+    let (recv, send) = derive_keys(&transcript, &x25519_ss, &mlkem_ss.into_bytes());
+    let accept = TransportKeyExchange {
+        x25519_public_key: x25519_public_key.as_bytes().to_vec(),
+        kem: TransportKem::Ciphertext(ct.to_vec()),
+        signature: node_key.sign(transcript.as_ref()),
+    };
+    Ok((accept, SessionKeys { send, recv }))
+}
+
+fn to_array<const N: usize>(bytes: &[u8], what: &'static str) -> Result<[u8; N], Error> {
+    bytes.try_into().map_err(|_| Error::MlKemLength { what, got: bytes.len(), want: N })
+}
+
+fn x25519(secret: &x25519_dalek::StaticSecret, public_key: &[u8]) -> Result<[u8; 32], Error> {
+    let public_key: [u8; X25519_KEY_LEN] = public_key
+        .try_into()
+        .map_err(|_| Error::X25519KeyLength { got: public_key.len(), want: X25519_KEY_LEN })?;
+    let ss = secret.diffie_hellman(&x25519_dalek::PublicKey::from(public_key)).to_bytes();
+    // A low order public key results in an all-zero shared secret,
+    // which would let the peer control the X25519 half of the key material.
+    if ss == [0; 32] {
+        return Err(Error::X25519LowOrder);
+    }
+    Ok(ss)
+}
+
+/// Returns the keys of the (initiator, responder) directions.
+fn derive_keys(
+    transcript: &CryptoHash,
+    x25519_ss: &[u8; 32],
+    mlkem_ss: &[u8; 32],
+) -> ([u8; 32], [u8; 32]) {
+    let ikm = [&x25519_ss[..], &mlkem_ss[..]].concat();
+    let hkdf = hkdf::Hkdf::<Sha256>::new(Some(transcript.as_ref()), &ikm);
+    let mut initiator = [0; 32];
+    let mut responder = [0; 32];
+    hkdf.expand(INITIATOR_KEY_INFO, &mut initiator).unwrap();
+    hkdf.expand(RESPONDER_KEY_INFO, &mut responder).unwrap();
+    (initiator, responder)
+}
+
+/// Keys protecting the frames of a single connection, from the perspective of one peer.
+pub(crate) struct SessionKeys {
+    send: [u8; 32],
+    recv: [u8; 32],
+}
+
+impl std::fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionKeys").finish_non_exhaustive()
+    }
+}
+
+impl SessionKeys {
+    /// Returns the (send, recv) ciphers.
+    pub fn into_ciphers(self) -> (Cipher, Cipher) {
+        (Cipher::new(&self.send), Cipher::new(&self.recv))
+    }
+}
+
+/// AEAD for a single direction of a connection. Frames are numbered implicitly:
+/// the n-th frame is sealed with nonce n, so a reordered, replayed or dropped
+/// frame fails to decrypt.
+pub(crate) struct Cipher {
+    aead: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl Cipher {
+    fn new(key: &[u8; 32]) -> Self {
+        Self { aead: ChaCha20Poly1305::new(key.into()), counter: 0 }
+    }
+
+    fn next_nonce(&mut self) -> Result<chacha20poly1305::Nonce, CipherError> {
+        let mut nonce = chacha20poly1305::Nonce::default();
+        nonce[..8].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter = self.counter.checked_add(1).ok_or(CipherError::CounterExhausted)?;
+        Ok(nonce)
+    }
+
+    pub fn seal(&mut self, frame: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let nonce = self.next_nonce()?;
+        // Encryption fails only if the frame is larger than the AEAD limit (256GiB).
+        Ok(self.aead.encrypt(&nonce, frame).expect("frame too large"))
+    }
+
+    pub fn open(&mut self, frame: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let nonce = self.next_nonce()?;
+        self.aead.decrypt(&nonce, frame).map_err(|_| CipherError::Decrypt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+
+    struct Peers {
+        initiator_key: SecretKey,
+        initiator: PeerId,
+        responder_key: SecretKey,
+        responder: PeerId,
+    }
+
+    impl Peers {
+        fn new(key_type: KeyType, seed: &str) -> Self {
+            let a = SecretKey::from_seed(key_type, &format!("{seed}/initiator"));
+            let b = SecretKey::from_seed(key_type, &format!("{seed}/responder"));
+            Self {
+                initiator: PeerId::new(a.public_key()),
+                initiator_key: a,
+                responder: PeerId::new(b.public_key()),
+                responder_key: b,
+            }
+        }
+
+        fn session(&self, nonce: u64) -> Session<'_> {
+            Session { initiator: &self.initiator, responder: &self.responder, nonce }
+        }
+    }
+
+    fn assert_connected(initiator: SessionKeys, responder: SessionKeys) {
+        let (mut i_send, mut i_recv) = initiator.into_ciphers();
+        let (mut r_send, mut r_recv) = responder.into_ciphers();
+        for msg in [&b"hello"[..], b"", &[7; 1000]] {
+            assert_eq!(msg, r_recv.open(&i_send.seal(msg).unwrap()).unwrap());
+            assert_eq!(msg, i_recv.open(&r_send.seal(msg).unwrap()).unwrap());
+        }
+    }
+
+    #[test]
+    fn key_exchange() {
+        for key_type in [KeyType::ED25519, KeyType::MLDSA, KeyType::ED25519MLDSA] {
+            let peers = Peers::new(key_type, "key_exchange");
+            let initiator = Initiator::new();
+            let offer = initiator.offer(&peers.initiator_key, peers.session(3));
+            let (accept, responder_keys) =
+                accept(&peers.responder_key, peers.session(3), &offer).unwrap();
+            let initiator_keys = initiator.finish(peers.session(3), &accept).unwrap();
+            assert_connected(initiator_keys, responder_keys);
+        }
+    }
+
+    #[test]
+    fn transcript_binding() {
+        let peers = Peers::new(KeyType::ED25519, "peers");
+        let other = Peers::new(KeyType::ED25519, "other");
+        let initiator = Initiator::new();
+        let offer = initiator.offer(&peers.initiator_key, peers.session(3));
+
+        // Offer is bound to the nonce and to the initiator's key.
+        assert_eq!(
+            Error::InvalidSignature,
+            accept(&peers.responder_key, peers.session(5), &offer).unwrap_err()
+        );
+        let forged = initiator.offer(&other.initiator_key, peers.session(3));
+        assert_eq!(
+            Error::InvalidSignature,
+            accept(&peers.responder_key, peers.session(3), &forged).unwrap_err()
+        );
+        let mut tampered = offer.clone();
+        tampered.x25519_public_key = Initiator::new().x25519_public_key.as_bytes().to_vec();
+        assert_eq!(
+            Error::InvalidSignature,
+            accept(&peers.responder_key, peers.session(3), &tampered).unwrap_err()
+        );
+
+        // Accept is bound to the offer and to the responder's key.
+        let (accept_msg, _) = accept(&peers.responder_key, peers.session(3), &offer).unwrap();
+        assert_eq!(
+            Error::InvalidSignature,
+            Initiator::new().finish(peers.session(3), &accept_msg).unwrap_err()
+        );
+        let forged = TransportKeyExchange {
+            signature: other.responder_key.sign(b"forged"),
+            ..accept_msg.clone()
+        };
+        assert_eq!(
+            Error::InvalidSignature,
+            initiator.finish(peers.session(3), &forged).unwrap_err()
+        );
+        assert_eq!(
+            Error::UnexpectedKem { got: "offer", want: "accept" },
+            initiator.finish(peers.session(3), &offer).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn cipher_rejects_reordering() {
+        let peers = Peers::new(KeyType::ED25519, "reordering");
+        let initiator = Initiator::new();
+        let offer = initiator.offer(&peers.initiator_key, peers.session(1));
+        let (accept, responder_keys) =
+            accept(&peers.responder_key, peers.session(1), &offer).unwrap();
+        let (mut send, _) = initiator.finish(peers.session(1), &accept).unwrap().into_ciphers();
+        let (_, mut recv) = responder_keys.into_ciphers();
+        let first = send.seal(b"first").unwrap();
+        let second = send.seal(b"second").unwrap();
+        assert_eq!(CipherError::Decrypt, recv.open(&second).unwrap_err());
+        // The failed frame still consumed a nonce.
+        assert_eq!(CipherError::Decrypt, recv.open(&first).unwrap_err());
+    }
+}
@@ -13,9 +13,11 @@ use crate::time;
 use crate::types::{FullPeerInfo, PeerType, ReasonForBan};
 use near_o11y::WithSpanContextExt;
 use near_primitives::network::PeerId;
+use near_primitives::version::ProtocolVersion;
 use std::collections::{hash_map::Entry, HashMap};
 use std::fmt;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
@@ -46,6 +48,11 @@ pub(crate) struct Connection {
     pub addr: actix::Addr<PeerActor>,
 
     pub peer_info: PeerInfo,
+    /// Address of the TCP connection. Unlike `peer_info.addr`, which is only set if the peer
+    /// advertises a listen port, it is always known.
+    pub peer_addr: SocketAddr,
+    /// Protocol version negotiated in the handshake.
+    pub protocol_version: ProtocolVersion,
     pub edge: Edge,
     pub initial_chain_info: PeerChainInfoV2,
     pub chain_height: AtomicU64,
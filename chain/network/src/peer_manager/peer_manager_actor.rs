@@ -17,6 +17,7 @@ use crate::private_actix::{PeerToManagerMsg, PeerToManagerMsgResp, PeersResponse
 use crate::routing;
 use crate::stats::metrics;
 use crate::store;
+use crate::subnet;
 use crate::tcp;
 use crate::time;
 use crate::types::{
@@ -36,12 +37,14 @@ use near_performance_metrics_macros::perf;
 use near_primitives::block::GenesisId;
 use near_primitives::network::PeerId;
 use near_primitives::types::AccountId;
-use near_primitives::views::{KnownPeerStateView, PeerStoreView};
+use near_primitives::views::{
+    KnownPeerStateView, PeerStoreView, SubnetConnectionsView, SubnetsView,
+};
 use rand::seq::IteratorRandom;
 use rand::thread_rng;
 use rand::Rng;
 use std::cmp::min;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -546,21 +549,39 @@ impl PeerManagerActor {
             .any(|wn| wn.account_id.is_none() || wn.account_id == peer_info.account_id)
     }
 
-    // predicate checking whether we should allow an inbound connection from peer_info.
-    fn is_inbound_allowed(&self, peer_info: &PeerInfo) -> bool {
+    // predicate checking whether we should allow an inbound connection.
+    fn is_inbound_allowed(&self, conn: &connection::Connection) -> Result<(), RegisterPeerError> {
+        // Whitelisted nodes are allowed to connect, even if the inbound connections limit has
+        // been reached.
+        if self.is_peer_whitelisted(&conn.peer_info) {
+            return Ok(());
+        }
         // Check if we have spare inbound connections capacity.
         let tier2 = self.state.tier2.load();
-        if tier2.ready.len() + tier2.outbound_handshakes.len() < self.max_num_peers as usize
-            && !self.config.inbound_disabled
+        if tier2.ready.len() + tier2.outbound_handshakes.len() >= self.max_num_peers as usize
+            || self.config.inbound_disabled
         {
-            return true;
+            return Err(RegisterPeerError::ConnectionLimitExceeded);
         }
-        // Whitelisted nodes are allowed to connect, even if the inbound connections limit has
-        // been reached.
-        if self.is_peer_whitelisted(peer_info) {
-            return true;
+        // Check if the subnet of the peer has spare inbound connections capacity.
+        let counts = self.subnet_counts(PeerType::Inbound);
+        let limits = &self.config.subnet_limits;
+        if !limits.allows(&limits.inbound, &counts, &conn.peer_addr.ip()) {
+            return Err(RegisterPeerError::SubnetLimitExceeded);
         }
-        false
+        Ok(())
+    }
+
+    /// Counts the ready connections of the given type per subnet.
+    fn subnet_counts(&self, peer_type: PeerType) -> subnet::Counts {
+        self.state
+            .tier2
+            .load()
+            .ready
+            .values()
+            .filter(|p| p.peer_type == peer_type)
+            .map(|p| p.peer_addr.ip())
+            .collect()
     }
 
     /// Returns peers close to the highest height
@@ -785,6 +806,12 @@ impl PeerManagerActor {
             // With some odds - try picking one of the 'NotConnected' peers -- these are the ones that we were able to connect to in the past.
             let prefer_previously_connected_peer =
                 thread_rng().gen_bool(PREFER_PREVIOUSLY_CONNECTED_PEER);
+            let outbound_counts = self.subnet_counts(PeerType::Outbound);
+            // Prefer the peers from the subnets we are least connected to, so that
+            // the peers of a single subnet can't take over all our connections.
+            let all_counts: subnet::Counts =
+                tier2.ready.values().map(|p| p.peer_addr.ip()).collect();
+            let limits = &self.config.subnet_limits;
             if let Some(peer_info) = self.state.peer_store.unconnected_peer(
                 |peer_state| {
                     // Ignore connecting to ourself
@@ -792,7 +819,12 @@ impl PeerManagerActor {
                     || self.config.node_addr == peer_state.peer_info.addr
                     // Or to peers we are currently trying to connect to
                     || tier2.outbound_handshakes.contains(&peer_state.peer_info.id)
+                    // Or to peers from the subnets we have enough outbound connections to
+                    || peer_state.peer_info.addr.map_or(false, |addr| {
+                        !limits.allows(&limits.outbound, &outbound_counts, &addr.ip())
+                    })
                 },
+                |addr| all_counts.load(&addr.ip()),
                 prefer_previously_connected_peer,
             ) {
                 // Start monitor_peers_attempts from start after we discover the first healthy peer
@@ -975,6 +1007,27 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                if self
+                    .state
+                    .tier2
+                    .send_message(peer_id, Arc::new(PeerMessage::EpochSyncRequest(epoch_id)))
+                {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
+            NetworkRequests::EpochSyncFinalizationRequest { epoch_id, peer_id } => {
+                if self.state.tier2.send_message(
+                    peer_id,
+                    Arc::new(PeerMessage::EpochSyncFinalizationRequest(epoch_id)),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::StateRequestHeader { shard_id, sync_hash, target } => {
                 if self.send_message_to_account_or_peer_or_hash(
                     &target,
@@ -1173,15 +1226,15 @@ impl PeerManagerActor {
             return RegisterPeerResponse::Reject(RegisterPeerError::Banned);
         }
         if msg.connection.peer_type == PeerType::Inbound {
-            if !self.is_inbound_allowed(&peer_info) {
+            if let Err(err) = self.is_inbound_allowed(&msg.connection) {
                 // TODO(1896): Gracefully drop inbound connection for other peer.
                 let tier2 = self.state.tier2.load();
                 debug!(target: "network",
                     tier2 = tier2.ready.len(), outgoing_peers = tier2.outbound_handshakes.len(),
-                    max_num_peers = self.max_num_peers,
-                    "Dropping handshake (network at max capacity)."
+                    max_num_peers = self.max_num_peers, addr = ?peer_info.addr, ?err,
+                    "Dropping inbound handshake."
                 );
-                return RegisterPeerResponse::Reject(RegisterPeerError::ConnectionLimitExceeded);
+                return RegisterPeerResponse::Reject(err);
             }
         }
         if let Err(err) = self.register_peer(msg.connection.clone()) {
@@ -1500,6 +1553,22 @@ impl Handler<GetDebugStatus> for PeerManagerActor {
                 });
                 DebugStatus::PeerStore(PeerStoreView { peer_states: peer_states_view })
             }
+            GetDebugStatus::Subnets => {
+                let inbound = self.subnet_counts(PeerType::Inbound);
+                let outbound = self.subnet_counts(PeerType::Outbound);
+                let subnets: BTreeSet<_> =
+                    inbound.iter().chain(outbound.iter()).map(|(subnet, _)| *subnet).collect();
+                DebugStatus::Subnets(SubnetsView {
+                    subnets: subnets
+                        .into_iter()
+                        .map(|subnet| SubnetConnectionsView {
+                            subnet: subnet.to_string(),
+                            inbound: inbound.get(subnet),
+                            outbound: outbound.get(subnet),
+                        })
+                        .collect(),
+                })
+            }
         }
     }
 }
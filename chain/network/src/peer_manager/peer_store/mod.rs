@@ -180,6 +180,28 @@ impl Inner {
             .collect()
     }
 
+    /// Find a random peer based on filter among the peers with the lowest
+    /// `load` of their address. Peers without an address are skipped.
+    fn find_least_loaded_peer<F>(
+        &self,
+        filter: F,
+        load: &impl Fn(&SocketAddr) -> u32,
+    ) -> Option<PeerInfo>
+    where
+        F: FnMut(&&KnownPeerState) -> bool,
+    {
+        let candidates: Vec<_> = (self.peer_states.values())
+            .filter(filter)
+            .filter_map(|kps| Some((load(&kps.peer_info.addr?), kps)))
+            .collect();
+        let min_load = candidates.iter().map(|(load, _)| *load).min()?;
+        candidates
+            .into_iter()
+            .filter(|(load, _)| *load == min_load)
+            .choose(&mut thread_rng())
+            .map(|(_, kps)| kps.peer_info.clone())
+    }
+
     /// Create new pair between peer_info.id and peer_addr removing
     /// old pairs if necessary.
     fn update_peer_info(
@@ -459,14 +481,18 @@ impl PeerStore {
 
     /// Return unconnected or peers with unknown status that we can try to connect to.
     /// Peers with unknown addresses are filtered out.
+    /// Among the matching peers, the ones with the lowest `subnet_load` of
+    /// their address are chosen, so that the connections are spread over
+    /// many subnets.
     pub(crate) fn unconnected_peer(
         &self,
         ignore_fn: impl Fn(&KnownPeerState) -> bool,
+        subnet_load: impl Fn(&SocketAddr) -> u32,
         prefer_previously_connected_peer: bool,
     ) -> Option<PeerInfo> {
         let inner = self.0.lock();
         if prefer_previously_connected_peer {
-            let preferred_peer = inner.find_peers(
+            let preferred_peer = inner.find_least_loaded_peer(
                 |p| {
                     (p.status == KnownPeerStatus::NotConnected)
                         && !ignore_fn(p)
@@ -474,17 +500,15 @@ impl PeerStore {
                         // if we're connecting only to the boot nodes - filter out the nodes that are not bootnodes.
                         && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
                 },
-                1,
-            )
-            .get(0)
-            .cloned();
+                &subnet_load,
+            );
             // If we found a preferred peer - return it.
             if preferred_peer.is_some() {
                 return preferred_peer;
             };
             // otherwise, pick a peer from the wider pool below.
         }
-        inner.find_peers(
+        inner.find_least_loaded_peer(
             |p| {
                 (p.status == KnownPeerStatus::NotConnected || p.status == KnownPeerStatus::Unknown)
                     && !ignore_fn(p)
@@ -492,10 +516,8 @@ impl PeerStore {
                     // If we're connecting only to the boot nodes - filter out the nodes that are not boot nodes.
                     && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
             },
-            1,
+            &subnet_load,
         )
-        .get(0)
-        .cloned()
     }
 
     /// Return healthy known peers up to given amount.
@@ -85,11 +85,47 @@ fn test_unconnected_peer() {
             store,
         )
         .unwrap();
-        assert!(peer_store.unconnected_peer(|_| false, false).is_some());
-        assert!(peer_store.unconnected_peer(|_| true, false).is_none());
+        assert!(peer_store.unconnected_peer(|_| false, |_| 0, false).is_some());
+        assert!(peer_store.unconnected_peer(|_| true, |_| 0, false).is_none());
     }
 }
 
+#[test]
+fn test_unconnected_peer_prefers_least_loaded_subnet() {
+    let clock = time::FakeClock::default();
+    let addr = |a, b| SocketAddrV4::new(Ipv4Addr::new(a, b, 0, 1), 24567).into();
+    let crowded: Vec<_> = (0..10)
+        .map(|i| get_peer_info(get_peer_id(format!("crowded{i}")), Some(addr(1, i))))
+        .collect();
+    let diverse = get_peer_info(get_peer_id("diverse".to_string()), Some(addr(2, 0)));
+    let boot_nodes: Vec<_> = crowded.iter().cloned().chain([diverse.clone()]).collect();
+    let store = store::Store::from(near_store::db::TestDB::new());
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        store,
+    )
+    .unwrap();
+
+    // We are already connected to a peer in 1.0.0.0/8, so the only peer from
+    // another subnet is picked, even though most of the candidates are in 1.0.0.0/8.
+    let load = |addr: &SocketAddr| match addr.ip() {
+        std::net::IpAddr::V4(ip) if ip.octets()[0] == 1 => 1,
+        _ => 0,
+    };
+    for prefer_previously_connected_peer in [false, true] {
+        for _ in 0..10 {
+            assert_eq!(
+                peer_store.unconnected_peer(|_| false, load, prefer_previously_connected_peer),
+                Some(diverse.clone())
+            );
+        }
+    }
+    // Once it's ignored, the crowded subnet is used.
+    let peer = peer_store.unconnected_peer(|p| p.peer_info.id == diverse.id, load, false).unwrap();
+    assert!(crowded.contains(&peer));
+}
+
 #[test]
 fn test_unknown_vs_not_connected() {
     use KnownPeerStatus::{Connected, NotConnected, Unknown};
@@ -162,7 +198,7 @@ fn test_unknown_vs_not_connected() {
         // if we prefer 'previously connected' peers - we should keep picking 'b'.
         assert_eq!(
             (0..10)
-                .map(|_| peer_store.unconnected_peer(|_| false, true).unwrap().id)
+                .map(|_| peer_store.unconnected_peer(|_| false, |_| 0, true).unwrap().id)
                 .collect::<HashSet<PeerId>>(),
             [peer_info_b.id.clone()].into_iter().collect()
         );
@@ -170,7 +206,7 @@ fn test_unknown_vs_not_connected() {
         // if we don't care, we should pick either 'b' or 'boot'.
         assert_eq!(
             (0..100)
-                .map(|_| peer_store.unconnected_peer(|_| false, false).unwrap().id)
+                .map(|_| peer_store.unconnected_peer(|_| false, |_| 0, false).unwrap().id)
                 .collect::<HashSet<PeerId>>(),
             [peer_info_b.id.clone(), peer_info_boot_node.id.clone()].into_iter().collect()
         );
@@ -209,7 +245,7 @@ fn test_unknown_vs_not_connected() {
         // After restart - we should try to connect to 'a' (if we prefer previously connected nodes).
         assert_eq!(
             (0..10)
-                .map(|_| peer_store.unconnected_peer(|_| false, true).unwrap().id)
+                .map(|_| peer_store.unconnected_peer(|_| false, |_| 0, true).unwrap().id)
                 .collect::<HashSet<PeerId>>(),
             [peer_info_a.id.clone()].into_iter().collect()
         );
@@ -236,7 +272,10 @@ fn test_unconnected_peer_only_boot_nodes() {
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store.clone()).unwrap();
         peer_store.peer_connected(&clock.clock(), &peer_info_a).unwrap();
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_in_store.clone()));
+        assert_eq!(
+            peer_store.unconnected_peer(|_| false, |_| 0, false),
+            Some(peer_in_store.clone())
+        );
     }
 
     // 1 boot node (peer_info_a) that we're already connected to.
@@ -252,7 +291,7 @@ fn test_unconnected_peer_only_boot_nodes() {
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store.clone()).unwrap();
         peer_store.peer_connected(&clock.clock(), &peer_info_a).unwrap();
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), None);
+        assert_eq!(peer_store.unconnected_peer(|_| false, |_| 0, false), None);
     }
 
     // 1 boot node (peer_info_a) is in the store.
@@ -266,7 +305,7 @@ fn test_unconnected_peer_only_boot_nodes() {
         )
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone()).unwrap();
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_info_a.clone()));
+        assert_eq!(peer_store.unconnected_peer(|_| false, |_| 0, false), Some(peer_info_a.clone()));
     }
 }
 
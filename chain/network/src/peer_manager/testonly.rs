@@ -21,6 +21,7 @@ use near_o11y::{WithSpanContext, WithSpanContextExt};
 use near_primitives::network::PeerId;
 use near_primitives::types::{AccountId, EpochId};
 use std::collections::HashSet;
+use std::net::Ipv4Addr;
 use std::sync::Arc;
 
 #[derive(actix::Message, Debug)]
@@ -173,12 +174,22 @@ impl ActorHandler {
         &self,
         chain: Arc<data::Chain>,
         network_cfg: config::NetworkConfig,
+    ) -> RawConnection {
+        self.start_inbound_from(Ipv4Addr::LOCALHOST, chain, network_cfg).await
+    }
+
+    /// Like `start_inbound`, but the connection comes from the given loopback `ip`.
+    pub async fn start_inbound_from(
+        &self,
+        ip: Ipv4Addr,
+        chain: Arc<data::Chain>,
+        network_cfg: config::NetworkConfig,
     ) -> RawConnection {
         // To avoid race condition:
         // 1. reserve a TCP port
         // 2. snapshot event stream
         // 3. establish connection.
-        let socket = tcp::Socket::bind_v4();
+        let socket = tcp::Socket::bind_v4_ip(ip);
         let events = self.events.from_now();
         let stream = socket.connect(&self.peer_info()).await;
         let stream_id = stream.id();
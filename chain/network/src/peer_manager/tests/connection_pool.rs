@@ -8,12 +8,14 @@ use crate::peer_manager::network_state::LIMIT_PENDING_PEERS;
 use crate::peer_manager::peer_manager_actor::Event as PME;
 use crate::peer_manager::testonly::Event;
 use crate::private_actix::RegisterPeerError;
+use crate::subnet;
 use crate::tcp;
 use crate::testonly::make_rng;
 use crate::testonly::stream::Stream;
 use crate::time;
 use near_o11y::testonly::init_test_logger;
 use near_primitives::version::PROTOCOL_VERSION;
+use std::net::Ipv4Addr;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -99,6 +101,7 @@ async fn loop_connection() {
                 1,
                 &pm.cfg.node_key,
             ),
+            key_exchange: None,
         }))
         .await;
     let reason = events
@@ -119,3 +122,65 @@ async fn loop_connection() {
         reason
     );
 }
+
+#[tokio::test]
+async fn subnet_limits() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut cfg = chain.make_config(rng);
+    cfg.subnet_limits = subnet::Config {
+        inbound: subnet::Limits { per_narrow_subnet: 2, per_wide_subnet: 3 },
+        // All the test connections come from the loopback addresses.
+        limit_local_addrs: true,
+        ..subnet::Config::unlimited()
+    };
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        cfg,
+        chain.clone(),
+    )
+    .await;
+
+    // An attacker fills the limit of its /24 subnet.
+    let mut peers = vec![];
+    for i in 1..=2 {
+        let conn = pm
+            .start_inbound_from(Ipv4Addr::new(127, 0, 0, i), chain.clone(), chain.make_config(rng))
+            .await;
+        peers.push(conn.handshake(&clock.clock()).await);
+    }
+    // Further connections from the same /24 subnet are rejected.
+    for i in 3..10 {
+        let conn = pm
+            .start_inbound_from(Ipv4Addr::new(127, 0, 0, i), chain.clone(), chain.make_config(rng))
+            .await;
+        assert_eq!(
+            ClosingReason::RejectedByPeerManager(RegisterPeerError::SubnetLimitExceeded),
+            conn.manager_fail_handshake(&clock.clock()).await
+        );
+    }
+    // The attacker moves to another /24 subnet of the same /16 subnet, which fills it up.
+    let conn = pm
+        .start_inbound_from(Ipv4Addr::new(127, 0, 1, 1), chain.clone(), chain.make_config(rng))
+        .await;
+    peers.push(conn.handshake(&clock.clock()).await);
+    let conn = pm
+        .start_inbound_from(Ipv4Addr::new(127, 0, 2, 1), chain.clone(), chain.make_config(rng))
+        .await;
+    assert_eq!(
+        ClosingReason::RejectedByPeerManager(RegisterPeerError::SubnetLimitExceeded),
+        conn.manager_fail_handshake(&clock.clock()).await
+    );
+    // Honest peers from other subnets can still connect.
+    for i in 1..=3 {
+        let conn = pm
+            .start_inbound_from(Ipv4Addr::new(127, i, 0, 1), chain.clone(), chain.make_config(rng))
+            .await;
+        peers.push(conn.handshake(&clock.clock()).await);
+    }
+}
@@ -56,6 +56,7 @@ pub(crate) enum RegisterPeerError {
     Banned,
     PoolError(connection::PoolError),
     ConnectionLimitExceeded,
+    SubnetLimitExceeded,
 }
 
 #[derive(actix::MessageResponse, Debug)]
@@ -58,13 +58,18 @@ impl Drop for GaugePoint {
 pub struct Connection {
     pub type_: PeerType,
     pub encoding: Option<Encoding>,
+    pub encrypted: bool,
 }
 
 impl Labels for Connection {
-    type Array = [&'static str; 2];
-    const NAMES: Self::Array = ["peer_type", "encoding"];
+    type Array = [&'static str; 3];
+    const NAMES: Self::Array = ["peer_type", "encoding", "encrypted"];
     fn values(&self) -> Self::Array {
-        [self.type_.into(), self.encoding.map(|e| e.into()).unwrap_or("unknown")]
+        [
+            self.type_.into(),
+            self.encoding.map(|e| e.into()).unwrap_or("unknown"),
+            if self.encrypted { "true" } else { "false" },
+        ]
     }
 }
 
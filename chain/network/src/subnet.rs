@@ -0,0 +1,213 @@
+//! Grouping of peer addresses by subnet.
+//!
+//! An attacker usually controls many addresses in a few subnets rather than
+//! addresses spread over the whole internet, so limiting the number of
+//! connections per subnet makes it much more expensive to occupy all the
+//! connection slots of a node (eclipse it).
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Size of a subnet.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Prefix {
+    /// IPv4 /24 or IPv6 /48, which is usually a single site.
+    Narrow,
+    /// IPv4 /16 or IPv6 /32, which is usually a single provider.
+    Wide,
+}
+
+impl Prefix {
+    fn len(self, ip: &IpAddr) -> u8 {
+        match (self, ip) {
+            (Self::Narrow, IpAddr::V4(_)) => 24,
+            (Self::Wide, IpAddr::V4(_)) => 16,
+            (Self::Narrow, IpAddr::V6(_)) => 48,
+            (Self::Wide, IpAddr::V6(_)) => 32,
+        }
+    }
+}
+
+/// A subnet containing a peer address, e.g. `1.2.3.0/24`.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub struct Subnet {
+    ip: IpAddr,
+    prefix_len: u8,
+}
+
+impl Subnet {
+    pub fn new(ip: IpAddr, prefix: Prefix) -> Self {
+        // IPv4 addresses mapped to IPv6 are the same hosts as the IPv4 ones.
+        let ip = match ip {
+            IpAddr::V6(ip6) => ip6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        let prefix_len = prefix.len(&ip);
+        let ip = match ip {
+            IpAddr::V4(ip) => {
+                let mask = u32::MAX << (32 - prefix_len);
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+            }
+            IpAddr::V6(ip) => {
+                let mask = u128::MAX << (128 - prefix_len);
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+        };
+        Self { ip, prefix_len }
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.ip, self.prefix_len)
+    }
+}
+
+/// Whether the address is reachable only from the same host or private network.
+fn is_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_local(&IpAddr::V4(ip)),
+            // fc00::/7 are unique local addresses, fe80::/10 are link local ones.
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (ip.segments()[0] & 0xfe00) == 0xfc00
+                    || (ip.segments()[0] & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// Maximal number of connections with peers of a single subnet.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// Per IPv4 /24 or IPv6 /48 subnet.
+    pub per_narrow_subnet: u32,
+    /// Per IPv4 /16 or IPv6 /32 subnet.
+    pub per_wide_subnet: u32,
+}
+
+impl Limits {
+    pub const UNLIMITED: Self = Self { per_narrow_subnet: u32::MAX, per_wide_subnet: u32::MAX };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    /// Limits of the connections which peers establish with us.
+    pub inbound: Limits,
+    /// Limits of the connections we establish with peers.
+    pub outbound: Limits,
+    /// Whether the limits apply to loopback and private addresses. All the
+    /// nodes of a local network usually run on the same host, so by default
+    /// they don't.
+    pub limit_local_addrs: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            inbound: Limits { per_narrow_subnet: 5, per_wide_subnet: 10 },
+            outbound: Limits { per_narrow_subnet: 3, per_wide_subnet: 6 },
+            limit_local_addrs: false,
+        }
+    }
+}
+
+impl Config {
+    /// Config which doesn't limit anything.
+    pub fn unlimited() -> Self {
+        Self { inbound: Limits::UNLIMITED, outbound: Limits::UNLIMITED, limit_local_addrs: false }
+    }
+
+    /// Whether another connection with a peer at `ip` fits into `limits`
+    /// given the `counts` of the existing connections.
+    pub fn allows(&self, limits: &Limits, counts: &Counts, ip: &IpAddr) -> bool {
+        if !self.limit_local_addrs && is_local(ip) {
+            return true;
+        }
+        counts.get(Subnet::new(*ip, Prefix::Narrow)) < limits.per_narrow_subnet
+            && counts.get(Subnet::new(*ip, Prefix::Wide)) < limits.per_wide_subnet
+    }
+}
+
+/// Number of connections per subnet.
+#[derive(Debug, Default, Clone)]
+pub struct Counts(HashMap<Subnet, u32>);
+
+impl Counts {
+    pub fn add(&mut self, ip: &IpAddr) {
+        for prefix in [Prefix::Narrow, Prefix::Wide] {
+            *self.0.entry(Subnet::new(*ip, prefix)).or_default() += 1;
+        }
+    }
+
+    pub fn get(&self, subnet: Subnet) -> u32 {
+        self.0.get(&subnet).copied().unwrap_or(0)
+    }
+
+    /// Number of connections in the wide subnet of `ip`, used to prefer
+    /// peers from the least represented subnets.
+    pub fn load(&self, ip: &IpAddr) -> u32 {
+        self.get(Subnet::new(*ip, Prefix::Wide))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Subnet, &u32)> {
+        self.0.iter()
+    }
+}
+
+impl FromIterator<IpAddr> for Counts {
+    fn from_iter<I: IntoIterator<Item = IpAddr>>(iter: I) -> Self {
+        let mut counts = Self::default();
+        for ip in iter {
+            counts.add(&ip);
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_subnet() {
+        assert_eq!(Subnet::new(ip("1.2.3.4"), Prefix::Narrow).to_string(), "1.2.3.0/24");
+        assert_eq!(Subnet::new(ip("1.2.3.4"), Prefix::Wide).to_string(), "1.2.0.0/16");
+        assert_eq!(Subnet::new(ip("::ffff:1.2.3.4"), Prefix::Narrow).to_string(), "1.2.3.0/24");
+        assert_eq!(
+            Subnet::new(ip("2001:db8:1:2::1"), Prefix::Narrow).to_string(),
+            "2001:db8:1::/48"
+        );
+        assert_eq!(Subnet::new(ip("2001:db8:1:2::1"), Prefix::Wide).to_string(), "2001:db8::/32");
+    }
+
+    #[test]
+    fn test_limits() {
+        let cfg = Config::default();
+        let limits = Limits { per_narrow_subnet: 2, per_wide_subnet: 3 };
+        let counts: Counts = [ip("1.2.3.4"), ip("1.2.3.5"), ip("1.2.4.1")].into_iter().collect();
+        // The /24 subnet is full.
+        assert!(!cfg.allows(&limits, &counts, &ip("1.2.3.6")));
+        // The /24 subnet is not full, but the /16 one is.
+        let counts: Counts = [ip("1.2.3.4"), ip("1.2.4.1"), ip("1.2.5.1")].into_iter().collect();
+        assert!(!cfg.allows(&limits, &counts, &ip("1.2.6.1")));
+        assert!(cfg.allows(&limits, &counts, &ip("1.3.0.1")));
+
+        // Local addresses aren't limited unless asked to.
+        let counts: Counts = [ip("127.0.0.1"), ip("127.0.0.1")].into_iter().collect();
+        assert!(cfg.allows(&limits, &counts, &ip("127.0.0.1")));
+        let cfg = Config { limit_local_addrs: true, ..cfg };
+        assert!(!cfg.allows(&limits, &counts, &ip("127.0.0.1")));
+    }
+}
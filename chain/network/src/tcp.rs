@@ -37,8 +37,14 @@ pub(crate) struct Socket(tokio::net::TcpSocket);
 #[cfg(test)]
 impl Socket {
     pub fn bind_v4() -> Self {
+        Self::bind_v4_ip(std::net::Ipv4Addr::LOCALHOST)
+    }
+
+    /// Binds to the given local IP, so that connections from different
+    /// subnets can be simulated with addresses from 127.0.0.0/8.
+    pub fn bind_v4_ip(ip: std::net::Ipv4Addr) -> Self {
         let socket = tokio::net::TcpSocket::new_v4().unwrap();
-        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        socket.bind(std::net::SocketAddr::new(ip.into(), 0)).unwrap();
         Self(socket)
     }
 
@@ -10,6 +10,7 @@ use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::{ChunkHash, PartialEncodedChunk, PartialEncodedChunkPart};
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -119,6 +120,33 @@ impl client::Client for Fake {
         Ok(())
     }
 
+    async fn epoch_sync_request(&self, _epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        unimplemented!();
+    }
+
+    async fn epoch_sync_response(
+        &self,
+        _response: Box<EpochSyncResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        unimplemented!();
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        _epoch_id: EpochId,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        unimplemented!();
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        unimplemented!();
+    }
+
     async fn challenge(&self, challenge: Challenge) {
         self.event_sink.push(Event::Challenge(challenge));
     }
@@ -4,17 +4,34 @@ use bytes::BytesMut;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::network_protocol::{Encoding, PeerMessage};
+use crate::peer::stream::ENCRYPTED_FRAME_FLAG;
+use crate::peer::transport;
 use crate::tcp;
 
 pub struct Stream {
     stream: tcp::Stream,
     force_encoding: Option<Encoding>,
     protocol_buffers_supported: bool,
+    send_cipher: Option<transport::Cipher>,
+    recv_cipher: Option<transport::Cipher>,
 }
 
 impl Stream {
     pub fn new(force_encoding: Option<Encoding>, stream: tcp::Stream) -> Self {
-        Self { stream, force_encoding, protocol_buffers_supported: false }
+        Self {
+            stream,
+            force_encoding,
+            protocol_buffers_supported: false,
+            send_cipher: None,
+            recv_cipher: None,
+        }
+    }
+
+    /// Encrypts the frames written and decrypts the frames read from now on.
+    pub fn enable_encryption(&mut self, keys: transport::SessionKeys) {
+        let (send, recv) = keys.into_ciphers();
+        self.send_cipher = Some(send);
+        self.recv_cipher = Some(recv);
     }
 
     fn encoding(&self) -> Option<Encoding> {
@@ -29,16 +46,30 @@ impl Stream {
 
     pub async fn read(&mut self) -> PeerMessage {
         'read: loop {
-            let n = self.stream.stream.read_u32_le().await.unwrap() as usize;
+            let n = self.stream.stream.read_u32_le().await.unwrap();
+            let encrypted = n & ENCRYPTED_FRAME_FLAG != 0;
+            let n = (n & !ENCRYPTED_FRAME_FLAG) as usize;
             let mut buf = BytesMut::new();
             buf.resize(n, 0);
             self.stream.stream.read_exact(&mut buf[..]).await.unwrap();
+            let buf = match (&mut self.recv_cipher, encrypted) {
+                (None, false) => buf,
+                (Some(cipher), true) => BytesMut::from(&cipher.open(&buf[..]).unwrap()[..]),
+                (None, true) => panic!("encrypted frame received before enabling encryption"),
+                (Some(_), false) => {
+                    tracing::warn!(
+                        target: "test",
+                        "plaintext frame received after enabling encryption, ignoring"
+                    );
+                    continue 'read;
+                }
+            };
             for enc in [Encoding::Proto, Encoding::Borsh] {
                 if let Ok(msg) = PeerMessage::deserialize(enc, &buf[..]) {
                     // If deserialize() succeeded but we expected different encoding, ignore the
                     // message.
                     if self.encoding().unwrap_or(enc) != enc {
-                        println!("unexpected encoding, ignoring message");
+                        tracing::debug!(target: "test", "unexpected encoding, ignoring message");
                         continue 'read;
                     }
                     if enc == Encoding::Proto {
@@ -61,8 +92,21 @@ impl Stream {
     }
 
     async fn write_encoded(&mut self, msg: &[u8]) {
-        self.stream.stream.write_u32_le(msg.len() as u32).await.unwrap();
-        self.stream.stream.write_all(msg).await.unwrap();
+        match &mut self.send_cipher {
+            None => {
+                self.stream.stream.write_u32_le(msg.len() as u32).await.unwrap();
+                self.stream.stream.write_all(msg).await.unwrap();
+            }
+            Some(cipher) => {
+                let msg = cipher.seal(msg).unwrap();
+                self.stream
+                    .stream
+                    .write_u32_le(msg.len() as u32 | ENCRYPTED_FRAME_FLAG)
+                    .await
+                    .unwrap();
+                self.stream.stream.write_all(&msg).await.unwrap();
+            }
+        }
         self.stream.stream.flush().await.unwrap();
     }
 }
@@ -231,6 +231,10 @@ pub enum NetworkRequests {
     BlockRequest { hash: CryptoHash, peer_id: PeerId },
     /// Request given block headers.
     BlockHeadersRequest { hashes: Vec<CryptoHash>, peer_id: PeerId },
+    /// Request the light client block of the last final block of an epoch for Epoch Sync.
+    EpochSyncRequest { epoch_id: EpochId, peer_id: PeerId },
+    /// Request the data necessary to finish Epoch Sync at the first block of an epoch.
+    EpochSyncFinalizationRequest { epoch_id: EpochId, peer_id: PeerId },
     /// Request state header for given shard at given state root.
     StateRequestHeader { shard_id: ShardId, sync_hash: CryptoHash, target: AccountOrPeerIdOrHash },
     /// Request state part for given shard at given state root.
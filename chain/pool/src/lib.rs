@@ -1,7 +1,11 @@
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
 
-use crate::types::{PoolIterator, PoolKey, TransactionGroup};
+use crate::types::{InsertTransactionResult, PoolIterator, PoolKey, TransactionGroup};
 use borsh::BorshSerialize;
+use near_chain_configs::{
+    TransactionPoolConfig, TransactionPoolEvictionPolicy, TransactionPoolOrdering,
+};
 use near_crypto::PublicKey;
 use near_primitives::epoch_manager::RngSeed;
 use near_primitives::hash::{hash, CryptoHash};
@@ -12,14 +16,30 @@ use std::ops::Bound;
 mod metrics;
 pub mod types;
 
+/// Bookkeeping of a transaction in the pool.
+struct PooledTransaction {
+    key: PoolKey,
+    size: u64,
+    /// Position of the transaction in `TransactionPool::insertion_order`.
+    insertion_index: u64,
+}
+
 /// Transaction pool: keeps track of transactions that were not yet accepted into the block chain.
 pub struct TransactionPool {
     /// Transactions are grouped by a pair of (account ID, signer public key).
     /// NOTE: It's more efficient on average to keep transactions unsorted and with potentially
     /// conflicting nonce than to create a BTreeMap for every transaction.
     transactions: BTreeMap<PoolKey, Vec<SignedTransaction>>,
-    /// Set of all hashes to quickly check if the given transaction is in the pool.
-    unique_transactions: HashSet<CryptoHash>,
+    /// All hashes to quickly check if the given transaction is in the pool.
+    unique_transactions: HashMap<CryptoHash, PooledTransaction>,
+    /// Hashes of the transactions in the pool in the order they were inserted.
+    insertion_order: BTreeMap<u64, CryptoHash>,
+    /// Insertion index of the next transaction.
+    next_insertion_index: u64,
+    /// Total size in bytes of the transactions in the pool.
+    total_size: u64,
+    /// Capacity and eviction policy of the pool.
+    config: TransactionPoolConfig,
     /// A uniquely generated key seed to randomize PoolKey order.
     key_seed: RngSeed,
     /// The key after which the pool iterator starts. Doesn't have to be present in the pool.
@@ -27,11 +47,15 @@ pub struct TransactionPool {
 }
 
 impl TransactionPool {
-    pub fn new(key_seed: RngSeed) -> Self {
+    pub fn new(key_seed: RngSeed, config: TransactionPoolConfig) -> Self {
         Self {
             key_seed,
             transactions: BTreeMap::new(),
-            unique_transactions: HashSet::new(),
+            unique_transactions: HashMap::new(),
+            insertion_order: BTreeMap::new(),
+            next_insertion_index: 0,
+            total_size: 0,
+            config,
             last_used_key: CryptoHash::default(),
         }
     }
@@ -39,6 +63,8 @@ impl TransactionPool {
     pub fn init_metrics() {
         // A `get()` call initializes a metric even if its value is zero.
         metrics::TRANSACTION_POOL_TOTAL.get();
+        metrics::TRANSACTION_POOL_SIZE.get();
+        metrics::TRANSACTION_POOL_EVICTED.get();
     }
 
     fn key(&self, account_id: &AccountId, public_key: &PublicKey) -> PoolKey {
@@ -49,24 +75,188 @@ impl TransactionPool {
     }
 
     /// Insert a signed transaction into the pool that passed validation.
-    pub fn insert_transaction(&mut self, signed_transaction: SignedTransaction) -> bool {
-        if !self.unique_transactions.insert(signed_transaction.get_hash()) {
+    /// If the pool is full, transactions are evicted according to the eviction policy to make
+    /// room for it.
+    pub fn insert_transaction(
+        &mut self,
+        signed_transaction: SignedTransaction,
+    ) -> InsertTransactionResult {
+        let tx_hash = signed_transaction.get_hash();
+        if self.unique_transactions.contains_key(&tx_hash) {
             // The hash of this transaction was already seen, skip it.
-            return false;
+            return InsertTransactionResult::Duplicate;
         }
-        metrics::TRANSACTION_POOL_TOTAL.inc();
 
         let signer_id = &signed_transaction.transaction.signer_id;
         let signer_public_key = &signed_transaction.transaction.public_key;
-        self.transactions
-            .entry(self.key(signer_id, signer_public_key))
-            .or_insert_with(Vec::new)
-            .push(signed_transaction);
-        true
+        let key = self.key(signer_id, signer_public_key);
+        let victims = if self.exceeds_group_limits(&key, &signed_transaction) {
+            Err(InsertTransactionResult::GroupLimitExceeded)
+        } else {
+            self.choose_victims(&key, &signed_transaction)
+                .ok_or(InsertTransactionResult::NoSpaceLeft)
+        };
+        match victims {
+            Ok(victims) => {
+                for victim in victims {
+                    self.evict_transaction(&victim);
+                }
+            }
+            Err(result) => {
+                metrics::TRANSACTION_POOL_REJECTED.with_label_values(&[result.into()]).inc();
+                return result;
+            }
+        }
+
+        let size = signed_transaction.get_size();
+        let insertion_index = self.next_insertion_index;
+        self.next_insertion_index += 1;
+        self.unique_transactions.insert(tx_hash, PooledTransaction { key, size, insertion_index });
+        self.insertion_order.insert(insertion_index, tx_hash);
+        self.total_size += size;
+        metrics::TRANSACTION_POOL_TOTAL.inc();
+        metrics::TRANSACTION_POOL_SIZE.add(size as i64);
+
+        self.transactions.entry(key).or_insert_with(Vec::new).push(signed_transaction);
+        InsertTransactionResult::Success
+    }
+
+    /// Whether adding the transaction to its group would exceed the group limits.
+    fn exceeds_group_limits(&self, key: &PoolKey, tx: &SignedTransaction) -> bool {
+        let group = self.transactions.get(key).map_or(&[][..], Vec::as_slice);
+        let count = group.len() as u64 + 1;
+        let size = group.iter().map(SignedTransaction::get_size).sum::<u64>() + tx.get_size();
+        self.config.group_count_limit.map_or(false, |limit| count > limit)
+            || self.config.group_size_limit.map_or(false, |limit| size > limit)
+    }
+
+    /// Whether the given number of transactions of given total size fit into the pool.
+    fn fits(&self, count: u64, size: u64) -> bool {
+        self.config.count_limit.map_or(true, |limit| count <= limit)
+            && self.config.size_limit.map_or(true, |limit| size <= limit)
+    }
+
+    /// Returns hashes of the transactions which have to be evicted for the new transaction to
+    /// fit into the pool, or `None` if the eviction policy doesn't allow to make room for it.
+    fn choose_victims(&self, key: &PoolKey, tx: &SignedTransaction) -> Option<Vec<CryptoHash>> {
+        let mut count = self.len() as u64 + 1;
+        let mut size = self.total_size + tx.get_size();
+        let mut victims = vec![];
+        if self.fits(count, size) {
+            return Some(victims);
+        }
+        match self.config.eviction_policy {
+            TransactionPoolEvictionPolicy::Reject => None,
+            TransactionPoolEvictionPolicy::Oldest => {
+                for victim in self.insertion_order.values() {
+                    victims.push(*victim);
+                    count -= 1;
+                    size -= self.unique_transactions[victim].size;
+                    if self.fits(count, size) {
+                        return Some(victims);
+                    }
+                }
+                None
+            }
+            TransactionPoolEvictionPolicy::LowestValue => {
+                // Groups by their number of transactions, including the new one.
+                let mut groups: BinaryHeap<(usize, PoolKey)> = self
+                    .transactions
+                    .iter()
+                    .map(|(group_key, group)| {
+                        (group.len() + usize::from(group_key == key), *group_key)
+                    })
+                    .collect();
+                if !self.transactions.contains_key(key) {
+                    groups.push((1, *key));
+                }
+                let mut eviction_orders = HashMap::new();
+                while let Some((len, group_key)) = groups.pop() {
+                    let eviction_order = eviction_orders
+                        .entry(group_key)
+                        .or_insert_with(|| self.eviction_order(&group_key, key, tx));
+                    let (victim, victim_size) = eviction_order.pop().expect("group is not empty");
+                    if victim == tx.get_hash() {
+                        return None;
+                    }
+                    victims.push(victim);
+                    count -= 1;
+                    size -= victim_size;
+                    if self.fits(count, size) {
+                        return Some(victims);
+                    }
+                    if len > 1 {
+                        groups.push((len - 1, group_key));
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns hashes and sizes of the transactions of a group, including the new transaction if
+    /// it belongs to the group, so that the next one to evict is last: the one with the highest
+    /// nonce and, among equal nonces, the newest one.
+    fn eviction_order(
+        &self,
+        group_key: &PoolKey,
+        key: &PoolKey,
+        tx: &SignedTransaction,
+    ) -> Vec<(CryptoHash, u64)> {
+        let mut order: Vec<_> = self
+            .transactions
+            .get(group_key)
+            .into_iter()
+            .flatten()
+            .map(|group_tx| {
+                let pooled = &self.unique_transactions[&group_tx.get_hash()];
+                (
+                    group_tx.transaction.nonce,
+                    pooled.insertion_index,
+                    group_tx.get_hash(),
+                    pooled.size,
+                )
+            })
+            .collect();
+        if group_key == key {
+            order.push((
+                tx.transaction.nonce,
+                self.next_insertion_index,
+                tx.get_hash(),
+                tx.get_size(),
+            ));
+        }
+        order.sort_unstable();
+        order.into_iter().map(|(_, _, tx_hash, size)| (tx_hash, size)).collect()
+    }
+
+    /// Removes a transaction from its group to make room for another one.
+    fn evict_transaction(&mut self, tx_hash: &CryptoHash) {
+        let key = self.unique_transactions[tx_hash].key;
+        let mut remove_entry = false;
+        if let Some(v) = self.transactions.get_mut(&key) {
+            v.retain(|tx| tx.get_hash() != *tx_hash);
+            remove_entry = v.is_empty();
+        }
+        if remove_entry {
+            self.transactions.remove(&key);
+        }
+        self.forget_transaction(tx_hash);
+        metrics::TRANSACTION_POOL_EVICTED.inc();
+    }
+
+    /// Drops the bookkeeping of a transaction which is no longer in the pool.
+    fn forget_transaction(&mut self, tx_hash: &CryptoHash) {
+        if let Some(pooled) = self.unique_transactions.remove(tx_hash) {
+            self.insertion_order.remove(&pooled.insertion_index);
+            self.total_size -= pooled.size;
+            metrics::TRANSACTION_POOL_TOTAL.dec();
+            metrics::TRANSACTION_POOL_SIZE.sub(pooled.size as i64);
+        }
     }
 
     /// Returns a pool iterator wrapper that implements an iterator like trait to iterate over
-    /// transaction groups in the proper order defined by the protocol.
+    /// transaction groups in the order given by the configured `TransactionPoolOrdering`.
     /// When the iterator is dropped, all remaining groups are inserted back into the pool.
     pub fn pool_iterator(&mut self) -> PoolIteratorWrapper<'_> {
         PoolIteratorWrapper::new(self)
@@ -77,7 +267,7 @@ impl TransactionPool {
     pub fn remove_transactions(&mut self, transactions: &[SignedTransaction]) {
         let mut grouped_transactions = HashMap::new();
         for tx in transactions {
-            if self.unique_transactions.contains(&tx.get_hash()) {
+            if self.unique_transactions.contains_key(&tx.get_hash()) {
                 let signer_id = &tx.transaction.signer_id;
                 let signer_public_key = &tx.transaction.public_key;
                 grouped_transactions
@@ -96,9 +286,7 @@ impl TransactionPool {
                 self.transactions.remove(&key);
             }
             for hash in &hashes {
-                if self.unique_transactions.remove(&hash) {
-                    metrics::TRANSACTION_POOL_TOTAL.dec();
-                }
+                self.forget_transaction(hash);
             }
         }
     }
@@ -113,6 +301,22 @@ impl TransactionPool {
     pub fn len(&self) -> usize {
         self.unique_transactions.len()
     }
+
+    /// Total size in bytes of the transactions in the pool.
+    pub fn size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// Hashes of the transactions in the pool in the order they were inserted.
+    pub fn transaction_hashes(&self) -> impl Iterator<Item = &CryptoHash> + '_ {
+        self.insertion_order.values()
+    }
+
+    /// Returns the transaction with the given hash if it is in the pool.
+    pub fn get_transaction(&self, tx_hash: &CryptoHash) -> Option<&SignedTransaction> {
+        let key = self.unique_transactions.get(tx_hash)?.key;
+        self.transactions.get(&key)?.iter().find(|tx| tx.get_hash() == *tx_hash)
+    }
 }
 
 /// PoolIterator is a structure to pull transactions from the pool.
@@ -124,11 +328,115 @@ pub struct PoolIteratorWrapper<'a> {
 
     /// Queue of transaction groups. Each group there is sorted by nonce.
     sorted_groups: VecDeque<TransactionGroup>,
+
+    /// Transaction groups sorted by nonce and queued by the priority fee of their next
+    /// transaction. Only used with `TransactionPoolOrdering::PriorityFee`.
+    prioritized_groups: BinaryHeap<PrioritizedGroup>,
+    /// The group returned by the last `next()` call while it isn't queued. Only used with
+    /// `TransactionPoolOrdering::PriorityFee`.
+    current_group: Option<TransactionGroup>,
+    /// Queue position of the next group pushed to `prioritized_groups`.
+    next_queue_position: u64,
+}
+
+/// A transaction group waiting to be returned by a priority fee ordered pool iterator.
+struct PrioritizedGroup {
+    /// The priority fee of the next transaction of the group.
+    priority_fee: u64,
+    /// Groups with the same priority fee are returned in the order they were queued.
+    queue_position: u64,
+    group: TransactionGroup,
+}
+
+impl PrioritizedGroup {
+    fn priority(&self) -> (u64, Reverse<u64>) {
+        (self.priority_fee, Reverse(self.queue_position))
+    }
+}
+
+impl PartialEq for PrioritizedGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl Eq for PrioritizedGroup {}
+
+impl PartialOrd for PrioritizedGroup {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PrioritizedGroup {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority().cmp(&other.priority())
+    }
 }
 
 impl<'a> PoolIteratorWrapper<'a> {
     pub fn new(pool: &'a mut TransactionPool) -> Self {
-        Self { pool, sorted_groups: Default::default() }
+        Self {
+            pool,
+            sorted_groups: Default::default(),
+            prioritized_groups: Default::default(),
+            current_group: None,
+            next_queue_position: 0,
+        }
+    }
+
+    /// Removes the group of the given key from the pool and sorts it by nonce.
+    fn take_group(&mut self, key: PoolKey) -> TransactionGroup {
+        let mut transactions = self.pool.transactions.remove(&key).expect("just checked existence");
+        transactions.sort_by_key(|st| Reverse(st.transaction.nonce));
+        TransactionGroup { key, transactions, removed_transaction_hashes: vec![] }
+    }
+
+    /// Queues a group by the priority fee of its next transaction behind the groups with the
+    /// same fee. Empty groups are discarded and their pulled transactions are forgotten.
+    fn queue_group(&mut self, group: TransactionGroup) {
+        match group.transactions.last() {
+            Some(tx) => {
+                let priority_fee = tx.transaction.priority_fee;
+                let queue_position = self.next_queue_position;
+                self.next_queue_position += 1;
+                self.prioritized_groups.push(PrioritizedGroup {
+                    priority_fee,
+                    queue_position,
+                    group,
+                });
+            }
+            None => {
+                for hash in group.removed_transaction_hashes {
+                    self.pool.forget_transaction(&hash);
+                }
+            }
+        }
+    }
+
+    /// Returns the group whose next transaction has the highest priority fee. The groups are
+    /// initially queued in the same order as in `TransactionPoolOrdering::Random`.
+    fn next_by_priority_fee(&mut self) -> Option<&mut TransactionGroup> {
+        if let Some(group) = self.current_group.take() {
+            self.queue_group(group);
+        }
+        if !self.pool.transactions.is_empty() {
+            let last_used_key = self.pool.last_used_key;
+            let keys: Vec<PoolKey> = self
+                .pool
+                .transactions
+                .range((Bound::Excluded(last_used_key), Bound::Unbounded))
+                .chain(self.pool.transactions.range(..=last_used_key))
+                .map(|(key, _)| *key)
+                .collect();
+            for key in keys {
+                let group = self.take_group(key);
+                self.queue_group(group);
+            }
+        }
+        let group = self.prioritized_groups.pop()?.group;
+        self.pool.last_used_key = group.key;
+        Some(self.current_group.insert(group))
     }
 }
 
@@ -149,9 +457,15 @@ impl<'a> PoolIteratorWrapper<'a> {
 ///
 /// When the iterator is dropped, `unique_transactions` in the pool is updated for every group.
 /// And all non-empty group from the sorted groups queue are inserted back into the pool.
+///
+/// With `TransactionPoolOrdering::PriorityFee` the iterator instead takes all groups from the
+/// pool on the first call and returns the group whose next transaction has the highest priority
+/// fee, see `next_by_priority_fee`.
 impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
-        if !self.pool.transactions.is_empty() {
+        if self.pool.config.ordering == TransactionPoolOrdering::PriorityFee {
+            self.next_by_priority_fee()
+        } else if !self.pool.transactions.is_empty() {
             let key = *self
                 .pool
                 .transactions
@@ -166,22 +480,14 @@ impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
                         .expect("we've just checked that the map is not empty")
                 });
             self.pool.last_used_key = key;
-            let mut transactions =
-                self.pool.transactions.remove(&key).expect("just checked existence");
-            transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce));
-            self.sorted_groups.push_back(TransactionGroup {
-                key,
-                transactions,
-                removed_transaction_hashes: vec![],
-            });
+            let group = self.take_group(key);
+            self.sorted_groups.push_back(group);
             Some(self.sorted_groups.back_mut().expect("just pushed"))
         } else {
             while let Some(sorted_group) = self.sorted_groups.pop_front() {
                 if sorted_group.transactions.is_empty() {
                     for hash in sorted_group.removed_transaction_hashes {
-                        if self.pool.unique_transactions.remove(&hash) {
-                            metrics::TRANSACTION_POOL_TOTAL.dec();
-                        }
+                        self.pool.forget_transaction(&hash);
                     }
                 } else {
                     self.sorted_groups.push_back(sorted_group);
@@ -198,11 +504,16 @@ impl<'a> PoolIterator for PoolIteratorWrapper<'a> {
 /// removed from the pool's unique_transactions.
 impl<'a> Drop for PoolIteratorWrapper<'a> {
     fn drop(&mut self) {
-        for group in self.sorted_groups.drain(..) {
+        let prioritized_groups = self.prioritized_groups.drain().map(|group| group.group);
+        let groups: Vec<_> = self
+            .sorted_groups
+            .drain(..)
+            .chain(prioritized_groups)
+            .chain(self.current_group.take())
+            .collect();
+        for group in groups {
             for hash in group.removed_transaction_hashes {
-                if self.pool.unique_transactions.remove(&hash) {
-                    metrics::TRANSACTION_POOL_TOTAL.dec();
-                }
+                self.pool.forget_transaction(&hash);
             }
             if !group.transactions.is_empty() {
                 self.pool.transactions.insert(group.key, group.transactions);
@@ -222,6 +533,7 @@ mod tests {
     use near_crypto::{InMemorySigner, KeyType};
 
     use near_primitives::hash::CryptoHash;
+    use near_primitives::transaction::Transaction;
     use near_primitives::types::Balance;
 
     const TEST_SEED: RngSeed = [3; 32];
@@ -253,7 +565,7 @@ mod tests {
         mut transactions: Vec<SignedTransaction>,
         expected_weight: u32,
     ) -> (Vec<u64>, TransactionPool) {
-        let mut pool = TransactionPool::new(TEST_SEED);
+        let mut pool = TransactionPool::new(TEST_SEED, TransactionPoolConfig::default());
         let mut rng = thread_rng();
         transactions.shuffle(&mut rng);
         for tx in transactions {
@@ -364,7 +676,7 @@ mod tests {
             })
             .collect::<Vec<_>>();
 
-        let mut pool = TransactionPool::new(TEST_SEED);
+        let mut pool = TransactionPool::new(TEST_SEED, TransactionPoolConfig::default());
         let mut rng = thread_rng();
         transactions.shuffle(&mut rng);
         for tx in transactions.clone() {
@@ -387,6 +699,26 @@ mod tests {
         assert_eq!(pool_txs, expected_txs);
     }
 
+    #[test]
+    fn test_pool_transaction_lookup() {
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 3);
+        transactions.extend(generate_transactions("bob.near", "bob.near", 1, 2));
+        let mut pool = TransactionPool::new(TEST_SEED, TransactionPoolConfig::default());
+        for tx in transactions.iter().rev() {
+            pool.insert_transaction(tx.clone());
+        }
+
+        let hashes: Vec<_> = transactions.iter().rev().map(SignedTransaction::get_hash).collect();
+        assert_eq!(pool.transaction_hashes().copied().collect::<Vec<_>>(), hashes);
+        for tx in &transactions {
+            assert_eq!(pool.get_transaction(&tx.get_hash()), Some(tx));
+        }
+
+        pool.remove_transactions(&transactions[..1]);
+        assert_eq!(pool.get_transaction(&transactions[0].get_hash()), None);
+        assert_eq!(pool.transaction_hashes().count(), 4);
+    }
+
     /// Add transactions of nonce from 1..=3 and transactions with nonce 21..=31. Pull 10.
     /// Then try to get another 10.
     #[test]
@@ -465,4 +797,277 @@ mod tests {
         new_nonces.sort();
         assert_ne!(nonces, new_nonces);
     }
+
+    fn pool_with_config(config: TransactionPoolConfig) -> TransactionPool {
+        TransactionPool::new(TEST_SEED, config)
+    }
+
+    /// Pulls all transactions from the pool and returns their signers and nonces in order.
+    fn pull_all(pool: &mut TransactionPool) -> Vec<(String, u64)> {
+        let len = pool.len() as u32;
+        prepare_transactions(pool, len)
+            .into_iter()
+            .map(|tx| (tx.transaction.signer_id.to_string(), tx.transaction.nonce))
+            .collect()
+    }
+
+    fn pool_contents(pool: &mut TransactionPool) -> Vec<(String, u64)> {
+        let mut res = pull_all(pool);
+        res.sort();
+        res
+    }
+
+    /// Transactions that don't fit into the pool are rejected by default.
+    #[test]
+    fn test_pool_limits() {
+        let transactions = generate_transactions("alice.near", "alice.near", 1, 10);
+        let tx_size = transactions[0].get_size();
+
+        let config = TransactionPoolConfig { size_limit: Some(5 * tx_size), ..Default::default() };
+        let mut pool = pool_with_config(config);
+        for tx in &transactions[..5] {
+            assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+        }
+        assert_eq!(
+            pool.insert_transaction(transactions[5].clone()),
+            InsertTransactionResult::NoSpaceLeft
+        );
+        assert_eq!(
+            pool.insert_transaction(transactions[0].clone()),
+            InsertTransactionResult::Duplicate
+        );
+        assert_eq!((pool.len(), pool.size()), (5, 5 * tx_size));
+
+        // Pulling transactions from the pool makes room for new ones.
+        assert_eq!(prepare_transactions(&mut pool, 2).len(), 2);
+        assert_eq!((pool.len(), pool.size()), (3, 3 * tx_size));
+        assert_eq!(
+            pool.insert_transaction(transactions[5].clone()),
+            InsertTransactionResult::Success
+        );
+
+        let config = TransactionPoolConfig { count_limit: Some(3), ..Default::default() };
+        let mut pool = pool_with_config(config);
+        for tx in &transactions[..3] {
+            assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+        }
+        assert_eq!(
+            pool.insert_transaction(transactions[3].clone()),
+            InsertTransactionResult::NoSpaceLeft
+        );
+        pool.remove_transactions(&transactions[..1]);
+        assert_eq!((pool.len(), pool.size()), (2, 2 * tx_size));
+        assert_eq!(
+            pool.insert_transaction(transactions[3].clone()),
+            InsertTransactionResult::Success
+        );
+    }
+
+    /// Limits of an (account, public key) group don't affect other groups.
+    #[test]
+    fn test_pool_group_limits() {
+        let alice = generate_transactions("alice.near", "alice.near", 1, 3);
+        let alice_other_key = generate_transactions("alice.near", "bob.near", 1, 1);
+        let bob = generate_transactions("bob.near", "bob.near", 1, 1);
+        let tx_size = alice[0].get_size();
+
+        for config in [
+            TransactionPoolConfig { group_count_limit: Some(2), ..Default::default() },
+            TransactionPoolConfig { group_size_limit: Some(2 * tx_size), ..Default::default() },
+        ] {
+            let mut pool = pool_with_config(config);
+            for tx in &alice[..2] {
+                assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+            }
+            assert_eq!(
+                pool.insert_transaction(alice[2].clone()),
+                InsertTransactionResult::GroupLimitExceeded
+            );
+            for tx in alice_other_key.iter().chain(&bob) {
+                assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+            }
+            assert_eq!(pool.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_pool_evict_oldest() {
+        let config = TransactionPoolConfig {
+            count_limit: Some(4),
+            eviction_policy: TransactionPoolEvictionPolicy::Oldest,
+            ..Default::default()
+        };
+        let mut pool = pool_with_config(config);
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 3);
+        transactions.extend(generate_transactions("bob.near", "bob.near", 1, 2));
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let carol = generate_transactions("carol.near", "carol.near", 1, 1);
+        assert_eq!(pool.insert_transaction(carol[0].clone()), InsertTransactionResult::Success);
+        assert_eq!(
+            pool_contents(&mut pool),
+            [("alice.near", 3), ("bob.near", 1), ("bob.near", 2), ("carol.near", 1)]
+                .map(|(signer, nonce)| (signer.to_string(), nonce))
+        );
+    }
+
+    #[test]
+    fn test_pool_evict_lowest_value() {
+        let config = TransactionPoolConfig {
+            count_limit: Some(5),
+            eviction_policy: TransactionPoolEvictionPolicy::LowestValue,
+            ..Default::default()
+        };
+        let mut pool = pool_with_config(config);
+        let alice = generate_transactions("alice.near", "alice.near", 1, 5);
+        let bob = generate_transactions("bob.near", "bob.near", 1, 2);
+        let carol = generate_transactions("carol.near", "carol.near", 1, 1);
+        for tx in alice[..4].iter().chain(&bob[..1]) {
+            assert_eq!(pool.insert_transaction(tx.clone()), InsertTransactionResult::Success);
+        }
+
+        // The highest nonce transaction of the largest group makes room.
+        assert_eq!(pool.insert_transaction(carol[0].clone()), InsertTransactionResult::Success);
+        // A new transaction which would be evicted first is rejected.
+        assert_eq!(pool.insert_transaction(alice[4].clone()), InsertTransactionResult::NoSpaceLeft);
+        assert_eq!(pool.insert_transaction(bob[1].clone()), InsertTransactionResult::Success);
+        assert_eq!(
+            pool_contents(&mut pool),
+            [
+                ("alice.near", 1),
+                ("alice.near", 2),
+                ("bob.near", 1),
+                ("bob.near", 2),
+                ("carol.near", 1)
+            ]
+            .map(|(signer, nonce)| (signer.to_string(), nonce))
+        );
+    }
+
+    /// Transactions of `signer_id` with nonces starting from 1 and the given priority fees.
+    fn generate_transactions_with_priority_fees(
+        signer_id: &str,
+        priority_fees: &[u64],
+    ) -> Vec<SignedTransaction> {
+        let signer_id: AccountId = signer_id.parse().unwrap();
+        let signer =
+            InMemorySigner::from_seed(signer_id.clone(), KeyType::ED25519, signer_id.as_ref());
+        priority_fees
+            .iter()
+            .zip(1..)
+            .map(|(&priority_fee, nonce)| {
+                Transaction {
+                    signer_id: signer_id.clone(),
+                    public_key: signer.public_key.clone(),
+                    nonce,
+                    receiver_id: "bob.near".parse().unwrap(),
+                    block_hash: CryptoHash::default(),
+                    actions: vec![],
+                    priority_fee,
+                }
+                .sign(&signer)
+            })
+            .collect()
+    }
+
+    fn priority_fee_pool() -> TransactionPool {
+        pool_with_config(TransactionPoolConfig {
+            ordering: TransactionPoolOrdering::PriorityFee,
+            ..Default::default()
+        })
+    }
+
+    /// The random round robin ordering stays the default and ignores priority fees.
+    #[test]
+    fn test_pool_ordering_default_is_random() {
+        assert_eq!(TransactionPoolConfig::default().ordering, TransactionPoolOrdering::Random);
+
+        let signers = ["alice.near", "bob.near", "carol.near", "dave.near"];
+        let [without_fees, with_fees] = [false, true].map(|with_fees| {
+            let mut pool = pool_with_config(TransactionPoolConfig::default());
+            for (i, signer) in signers.iter().enumerate() {
+                let priority_fee = if with_fees { 100 * i as u64 } else { 0 };
+                for tx in generate_transactions_with_priority_fees(signer, &[priority_fee; 3]) {
+                    pool.insert_transaction(tx);
+                }
+            }
+            pull_all(&mut pool)
+        });
+        assert_eq!(without_fees, with_fees);
+        // Every signer gets a transaction in before any gets a second one, regardless of fees.
+        let mut first_round: Vec<_> = with_fees[..4].iter().map(|(signer, _)| signer).collect();
+        first_round.sort();
+        assert_eq!(first_round, signers);
+    }
+
+    /// Groups are ordered by the priority fee of their next transaction, so a high fee can't
+    /// get a transaction ahead of a lower nonce of the same group.
+    #[test]
+    fn test_pool_ordering_priority_fee() {
+        let mut pool = priority_fee_pool();
+        let mut transactions =
+            generate_transactions_with_priority_fees("alice.near", &[1, 100, 100]);
+        transactions.extend(generate_transactions_with_priority_fees("bob.near", &[50, 50, 10]));
+        transactions.extend(generate_transactions_with_priority_fees("carol.near", &[20]));
+        transactions.shuffle(&mut thread_rng());
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        assert_eq!(
+            pull_all(&mut pool),
+            [
+                ("bob.near", 1),
+                ("bob.near", 2),
+                ("carol.near", 1),
+                ("bob.near", 3),
+                ("alice.near", 1),
+                ("alice.near", 2),
+                ("alice.near", 3),
+            ]
+            .map(|(signer, nonce)| (signer.to_string(), nonce))
+        );
+    }
+
+    /// Groups with the same priority fee take turns: none of them gets a second transaction in
+    /// while another one waits, and only higher fees get ahead of them.
+    #[test]
+    fn test_pool_ordering_priority_fee_fairness() {
+        let mut pool = priority_fee_pool();
+        let mut signers: Vec<_> = (0..10).map(|i| format!("user_{}", i)).collect();
+        for signer in &signers {
+            for tx in generate_transactions_with_priority_fees(signer, &[7; 5]) {
+                pool.insert_transaction(tx);
+            }
+        }
+        for tx in generate_transactions_with_priority_fees("whale.near", &[1000, 1000]) {
+            pool.insert_transaction(tx);
+        }
+
+        // Pull the transactions over two chunks, the groups are returned to the pool in between.
+        let mut pulled: Vec<_> = prepare_transactions(&mut pool, 17)
+            .into_iter()
+            .map(|tx| (tx.transaction.signer_id.to_string(), tx.transaction.nonce))
+            .collect();
+        assert_eq!(pool.len(), 35);
+        pulled.extend(pull_all(&mut pool));
+        assert_eq!(pool.len(), 0);
+
+        assert_eq!(&pulled[..2], [("whale.near".to_string(), 1), ("whale.near".to_string(), 2)]);
+        signers.sort();
+        let mut rounds = 0;
+        for (round, chunk) in pulled[2..].chunks(signers.len()).enumerate() {
+            let mut round_signers: Vec<_> = chunk
+                .iter()
+                .map(|(signer, nonce)| {
+                    assert_eq!(*nonce, round as u64 + 1);
+                    signer.clone()
+                })
+                .collect();
+            round_signers.sort();
+            assert_eq!(round_signers, signers);
+            rounds += 1;
+        }
+        assert_eq!(rounds, 5);
+    }
 }
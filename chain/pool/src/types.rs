@@ -3,12 +3,28 @@ use near_primitives::transaction::SignedTransaction;
 
 /// Trait acts like an iterator. It iterates over transactions groups by returning mutable
 /// references to them. Each transaction group implements a draining iterator to pull transactions.
-/// The order of the transaction groups is round robin scheduling.
+/// The order of the transaction groups is round robin scheduling, or by the priority fee of their
+/// next transaction with `TransactionPoolOrdering::PriorityFee`.
 /// When this iterator is dropped the remaining transactions are returned back to the pool.
 pub trait PoolIterator {
     fn next(&mut self) -> Option<&mut TransactionGroup>;
 }
 
+/// Result of inserting a transaction into the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum InsertTransactionResult {
+    /// The transaction was inserted, possibly after evicting others.
+    Success,
+    /// The transaction is already in the pool.
+    Duplicate,
+    /// The pool is full and the eviction policy didn't make room for the
+    /// transaction.
+    NoSpaceLeft,
+    /// The (account, public key) pair of the transaction reached its limit.
+    GroupLimitExceeded,
+}
+
 /// A hash of (an AccountId, a PublicKey and a seed).
 /// Used to randomize the order of the keys.
 pub(crate) type PoolKey = CryptoHash;
@@ -7,6 +7,8 @@ use validated_operations::ValidatedOperation;
 mod transactions;
 mod validated_operations;
 
+pub(crate) use transactions::convert_pooled_transaction;
+
 /// NEAR Protocol defines initial state in genesis records and treats the first
 /// block differently (e.g. [it cannot contain any
 /// transactions](https://stackoverflow.com/a/63347167/1178806).
@@ -952,6 +954,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_convert_pooled_transaction() {
+        let signer = near_crypto::InMemorySigner::from_seed(
+            "sender.near".parse().unwrap(),
+            near_crypto::KeyType::ED25519,
+            "sender.near",
+        );
+        let signed_transaction = near_primitives::transaction::SignedTransaction::send_money(
+            1,
+            "sender.near".parse().unwrap(),
+            "receiver.near".parse().unwrap(),
+            &signer,
+            123,
+            near_primitives::hash::CryptoHash::default(),
+        );
+        let tx_hash = signed_transaction.get_hash();
+
+        let transaction = convert_pooled_transaction(signed_transaction.clone());
+        assert_eq!(
+            transaction.transaction_identifier,
+            crate::models::TransactionIdentifier::transaction(&tx_hash)
+        );
+        assert_eq!(transaction.transaction_identifier.transaction_hash(), Some(tx_hash));
+        assert!(transaction.related_transactions.is_empty());
+        let near_actions = NearActions::try_from(transaction.operations).unwrap();
+        assert_eq!(near_actions.sender_account_id, signed_transaction.transaction.signer_id);
+        assert_eq!(near_actions.receiver_account_id, signed_transaction.transaction.receiver_id);
+        assert_eq!(near_actions.actions, signed_transaction.transaction.actions);
+    }
+
     #[test]
     fn test_near_actions_invalid_transfer_no_amount() {
         let operations = vec![crate::models::Operation {
@@ -192,6 +192,33 @@ async fn get_predecessor_id_from_receipt_hash(
     Some(receipt_view?.predecessor_id)
 }
 
+/// Converts a transaction from the transaction pool into a Rosetta transaction.
+///
+/// The operations are those its actions translate to (see `NearActions`).  Gas
+/// fees and refunds are only known once the transaction is executed, so they
+/// are not included.
+pub(crate) fn convert_pooled_transaction(
+    signed_transaction: near_primitives::transaction::SignedTransaction,
+) -> crate::models::Transaction {
+    let transaction_identifier =
+        crate::models::TransactionIdentifier::transaction(&signed_transaction.get_hash());
+    let near_primitives::transaction::Transaction { signer_id, receiver_id, actions, .. } =
+        signed_transaction.transaction;
+    let near_actions = super::NearActions {
+        sender_account_id: signer_id,
+        receiver_account_id: receiver_id,
+        actions,
+    };
+    crate::models::Transaction {
+        transaction_identifier,
+        operations: near_actions.into(),
+        related_transactions: Vec::new(),
+        metadata: crate::models::TransactionMetadata {
+            type_: crate::models::TransactionType::Transaction,
+        },
+    }
+}
+
 type RosettaTransactionsMap = std::collections::HashMap<String, crate::models::Transaction>;
 
 pub(crate) struct RosettaTransactions<'a> {
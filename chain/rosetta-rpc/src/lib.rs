@@ -392,20 +392,33 @@ async fn account_balance(
 }
 
 #[api_v2_operation]
-/// Get All Mempool Transactions (not implemented)
+/// Get All Mempool Transactions
 ///
 /// Get all Transaction Identifiers in the mempool
 ///
-/// NOTE: The mempool is short-lived, so it is currently not implemented.
+/// NOTE: Only the transaction pools of the shards this node tracks are
+/// visible, and transactions stay there only until they are included into a
+/// chunk.
 async fn mempool(
-    _client_addr: web::Data<Addr<ClientActor>>,
-    _body: Json<models::NetworkRequest>,
+    client_addr: web::Data<Addr<ClientActor>>,
+    body: Json<models::NetworkRequest>,
 ) -> Result<Json<models::MempoolResponse>, models::Error> {
-    Ok(Json(models::MempoolResponse { transaction_identifiers: vec![] }))
+    let Json(models::NetworkRequest { network_identifier }) = body;
+
+    check_network_identifier(&client_addr, network_identifier).await?;
+
+    let transaction_hashes =
+        client_addr.send(near_client::GetMempoolTransactionHashes {}.with_span_context()).await?;
+    Ok(Json(models::MempoolResponse {
+        transaction_identifiers: transaction_hashes
+            .iter()
+            .map(models::TransactionIdentifier::transaction)
+            .collect(),
+    }))
 }
 
 #[api_v2_operation]
-/// Get a Mempool Transaction (not implemented)
+/// Get a Mempool Transaction
 ///
 /// Get a transaction in the mempool by its Transaction Identifier. This is a
 /// separate request than fetching a block transaction (/block/transaction)
@@ -416,13 +429,31 @@ async fn mempool(
 /// endpoint, it is ok that returned transactions are only estimates of what may
 /// actually be included in a block.
 ///
-/// NOTE: The mempool is short-lived, so this method does not make a lot of
-/// sense to be implemented.
+/// NOTE: The returned operations are those of the transaction actions, gas
+/// fees are only known once the transaction is executed.
 async fn mempool_transaction(
-    _client_addr: web::Data<Addr<ClientActor>>,
-    _body: Json<models::MempoolTransactionRequest>,
+    client_addr: web::Data<Addr<ClientActor>>,
+    body: Json<models::MempoolTransactionRequest>,
 ) -> Result<Json<models::MempoolTransactionResponse>, models::Error> {
-    Err(errors::ErrorKind::InternalError("Not implemented yet".to_string()).into())
+    let Json(models::MempoolTransactionRequest { network_identifier, transaction_identifier }) =
+        body;
+
+    check_network_identifier(&client_addr, network_identifier).await?;
+
+    let transaction_hash = transaction_identifier.transaction_hash().ok_or_else(|| {
+        errors::ErrorKind::InvalidInput(format!(
+            "Invalid transaction identifier {}, expecting tx:<hash>",
+            transaction_identifier.hash
+        ))
+    })?;
+    let signed_transaction = client_addr
+        .send(near_client::GetMempoolTransaction { transaction_hash }.with_span_context())
+        .await?
+        .ok_or_else(|| errors::ErrorKind::NotFound("Transaction not found in mempool".into()))?;
+
+    Ok(Json(models::MempoolTransactionResponse {
+        transaction: crate::adapters::convert_pooled_transaction(signed_transaction),
+    }))
 }
 
 #[api_v2_operation]
@@ -449,9 +480,10 @@ async fn construction_derive(
     let address = if let near_crypto::KeyType::ED25519 = public_key.key_type() {
         hex::encode(public_key.key_data())
     } else {
-        return Err(errors::ErrorKind::InvalidInput(
-            "Only Ed25519 keys are allowed for implicit accounts".to_string(),
-        )
+        return Err(errors::ErrorKind::InvalidInput(format!(
+            "Only Ed25519 keys are allowed for implicit accounts, {} keys require a named account",
+            public_key.key_type()
+        ))
         .into());
     };
 
@@ -599,6 +631,7 @@ async fn construction_payloads(
         nonce: signer_public_access_key_nonce,
         receiver_id: receiver_account_id,
         actions,
+        priority_fee: 0,
     };
 
     let (transaction_hash, _) = unsigned_transaction.get_hash_and_size().clone();
@@ -631,7 +664,7 @@ async fn construction_combine(
 
     check_network_identifier(&client_addr, network_identifier).await?;
 
-    let signature = signatures
+    let signature: near_crypto::Signature = signatures
         .iter()
         .next()
         .ok_or_else(|| {
@@ -642,10 +675,20 @@ async fn construction_combine(
             errors::ErrorKind::InvalidInput(err.to_string())
         })?;
 
-    let signed_transaction = near_primitives::transaction::SignedTransaction::new(
-        signature,
-        unsigned_transaction.into_inner(),
-    );
+    // Catch signatures made with the wrong key type or over the wrong payload
+    // here rather than when the transaction gets submitted.
+    let unsigned_transaction = unsigned_transaction.into_inner();
+    let (transaction_hash, _) = unsigned_transaction.get_hash_and_size();
+    if !signature.verify(transaction_hash.as_ref(), &unsigned_transaction.public_key) {
+        return Err(errors::ErrorKind::InvalidInput(format!(
+            "{} signature does not match the transaction public key",
+            signature.key_type()
+        ))
+        .into());
+    }
+
+    let signed_transaction =
+        near_primitives::transaction::SignedTransaction::new(signature, unsigned_transaction);
 
     Ok(Json(models::ConstructionCombineResponse { signed_transaction: signed_transaction.into() }))
 }
@@ -760,6 +803,13 @@ async fn construction_submit(
         near_client::ProcessTxResponse::InvalidTx(error) => {
             Err(errors::ErrorKind::InvalidInput(error.to_string()).into())
         }
+        near_client::ProcessTxResponse::PoolRejected(result) => {
+            Err(errors::ErrorKind::InternalError(format!(
+                "The node's transaction pool has no room for the transaction: {}",
+                result
+            ))
+            .into())
+        }
         _ => Err(errors::ErrorKind::InternalInvariantError(format!(
             "Transaction submition return unexpected result: {:?}",
             transaction_submittion
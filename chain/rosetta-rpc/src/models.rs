@@ -1079,6 +1079,12 @@ impl TransactionIdentifier {
         Self::from_prefix_and_hash("receipt", receipt_hash)
     }
 
+    /// Returns the hash of the NEAR transaction this identifier refers to, if
+    /// it refers to one.
+    pub(crate) fn transaction_hash(&self) -> Option<near_primitives::hash::CryptoHash> {
+        self.hash.strip_prefix("tx:")?.parse().ok()
+    }
+
     /// Returns an identifier for block events constructed as <prefix>:<hash>.
     ///
     /// Note: If constructing identifiers for transactions or receipts, use
@@ -1152,6 +1158,15 @@ impl TryFrom<&PublicKey> for near_crypto::PublicKey {
             CurveType::Secp256k1 => {
                 near_crypto::PublicKey::SECP256K1((hex_bytes.as_ref() as &[u8]).try_into()?)
             }
+            CurveType::MlDsa65 => {
+                near_crypto::PublicKey::MLDSA((hex_bytes.as_ref() as &[u8]).try_into()?)
+            }
+            CurveType::SlhDsaSha2128s => {
+                near_crypto::PublicKey::SLHDSA((hex_bytes.as_ref() as &[u8]).try_into()?)
+            }
+            CurveType::Edwards25519MlDsa65 => {
+                near_crypto::PublicKey::ED25519MLDSA((hex_bytes.as_ref() as &[u8]).try_into()?)
+            }
         })
     }
 }
@@ -1164,6 +1179,16 @@ pub(crate) enum CurveType {
     Edwards25519,
     /// SEC compressed - 33 bytes (<https://secg.org/sec1-v2.pdf#subsubsection.2.3.3>)
     Secp256k1,
+    /// `pkEncode(ρ, t1)` - 1952 bytes (<https://doi.org/10.6028/NIST.FIPS.204>)
+    #[serde(rename = "ml_dsa_65")]
+    MlDsa65,
+    /// `PK.seed || PK.root` - 32 bytes (<https://doi.org/10.6028/NIST.FIPS.205>)
+    #[serde(rename = "slh_dsa_sha2_128s")]
+    SlhDsaSha2128s,
+    /// `edwards25519 (32 bytes) || ml_dsa_65 (1952 bytes)` - 1984 bytes; both
+    /// halves sign every payload
+    #[serde(rename = "edwards25519_ml_dsa_65")]
+    Edwards25519MlDsa65,
 }
 
 impl From<near_crypto::KeyType> for CurveType {
@@ -1171,6 +1196,9 @@ impl From<near_crypto::KeyType> for CurveType {
         match key_type {
             near_crypto::KeyType::ED25519 => Self::Edwards25519,
             near_crypto::KeyType::SECP256K1 => Self::Secp256k1,
+            near_crypto::KeyType::MLDSA => Self::MlDsa65,
+            near_crypto::KeyType::SLHDSA => Self::SlhDsaSha2128s,
+            near_crypto::KeyType::ED25519MLDSA => Self::Edwards25519MlDsa65,
         }
     }
 }
@@ -1215,6 +1243,15 @@ impl TryFrom<&Signature> for near_crypto::Signature {
 pub(crate) enum SignatureType {
     /// `R (32-byte) || s (32-bytes)` - `64 bytes`
     Ed25519,
+    /// `sigEncode(c~, z, h)` - `3309 bytes`
+    #[serde(rename = "ml_dsa_65")]
+    MlDsa65,
+    /// `R || SIG_FORS || SIG_HT` - `7856 bytes`
+    #[serde(rename = "slh_dsa_sha2_128s")]
+    SlhDsaSha2128s,
+    /// `ed25519 (64 bytes) || ml_dsa_65 (3309 bytes)` - `3373 bytes`
+    #[serde(rename = "ed25519_ml_dsa_65")]
+    Ed25519MlDsa65,
     /* Rosetta Spec also provides:
      *
      * /// `r (32-bytes) || s (32-bytes)` - `64 bytes`
@@ -1236,6 +1273,9 @@ impl From<near_crypto::KeyType> for SignatureType {
             near_crypto::KeyType::SECP256K1 => {
                 unimplemented!("SECP256K1 keys are not implemented in Rosetta yet")
             }
+            near_crypto::KeyType::MLDSA => Self::MlDsa65,
+            near_crypto::KeyType::SLHDSA => Self::SlhDsaSha2128s,
+            near_crypto::KeyType::ED25519MLDSA => Self::Ed25519MlDsa65,
         }
     }
 }
@@ -1244,6 +1284,9 @@ impl From<SignatureType> for near_crypto::KeyType {
     fn from(signature_type: SignatureType) -> Self {
         match signature_type {
             SignatureType::Ed25519 => Self::ED25519,
+            SignatureType::MlDsa65 => Self::MLDSA,
+            SignatureType::SlhDsaSha2128s => Self::SLHDSA,
+            SignatureType::Ed25519MlDsa65 => Self::ED25519MLDSA,
         }
     }
 }
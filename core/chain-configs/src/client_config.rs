@@ -1,6 +1,7 @@
 //! Chain Client Configuration
 use std::cmp::max;
 use std::cmp::min;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -70,6 +71,115 @@ impl GCConfig {
     }
 }
 
+/// What a transaction pool does with a new transaction that doesn't fit into it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionPoolEvictionPolicy {
+    /// Reject the new transaction.
+    #[default]
+    Reject,
+    /// Evict the transactions that have been waiting in the pool the longest.
+    Oldest,
+    /// Evict the transactions that would be included last: the highest nonce
+    /// ones of the largest (account, public key) groups.  A new transaction
+    /// which would itself be evicted first is rejected, so a signer flooding
+    /// the pool only displaces its own transactions.
+    LowestValue,
+}
+
+/// The order in which a transaction pool offers (account, public key) groups
+/// of transactions to the chunk producer.  Transactions of a group are always
+/// offered in nonce order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionPoolOrdering {
+    /// Round robin over the groups, starting at a random one.
+    #[default]
+    Random,
+    /// Offer the group whose next transaction has the highest priority fee
+    /// first.  Groups with the same priority fee are offered round robin, so
+    /// a group doesn't get a second transaction in while another group with
+    /// the same fee waits.
+    PriorityFee,
+}
+
+/// Capacity of the transaction pool of each shard.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionPoolConfig {
+    /// Maximum total size in bytes of the transactions in the pool.  `None`
+    /// means no limit.
+    #[serde(default = "default_transaction_pool_size_limit")]
+    pub size_limit: Option<u64>,
+    /// Maximum number of transactions in the pool.  `None` means no limit.
+    #[serde(default)]
+    pub count_limit: Option<u64>,
+    /// Maximum total size in bytes of the transactions of a single
+    /// (account, public key) pair.  `None` means no limit.
+    #[serde(default)]
+    pub group_size_limit: Option<u64>,
+    /// Maximum number of transactions of a single (account, public key) pair.
+    /// `None` means no limit.
+    #[serde(default)]
+    pub group_count_limit: Option<u64>,
+    /// What to do with a new transaction when the pool is full.  Transactions
+    /// over a group limit are always rejected.
+    #[serde(default)]
+    pub eviction_policy: TransactionPoolEvictionPolicy,
+    /// The order in which transactions are included into chunks.
+    #[serde(default)]
+    pub ordering: TransactionPoolOrdering,
+}
+
+impl Default for TransactionPoolConfig {
+    fn default() -> Self {
+        Self {
+            size_limit: Some(100_000_000),
+            count_limit: None,
+            group_size_limit: None,
+            group_count_limit: None,
+            eviction_policy: TransactionPoolEvictionPolicy::default(),
+            ordering: TransactionPoolOrdering::default(),
+        }
+    }
+}
+
+fn default_transaction_pool_size_limit() -> Option<u64> {
+    TransactionPoolConfig::default().size_limit
+}
+
+/// Storage of state parts outside of the node and its peers.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalStorageLocation {
+    /// A local directory, e.g. a mount of a network file system.
+    Filesystem { root_dir: PathBuf },
+    /// A bucket of an S3-compatible object store, addressed path-style as
+    /// `<endpoint>/<bucket>/<key>`.
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        /// JSON file with `access_key` and `secret_key` to sign the requests
+        /// with.  Requests are sent unsigned if not set, which is enough to
+        /// read from a public bucket.
+        #[serde(default)]
+        credentials_file: Option<PathBuf>,
+    },
+}
+
+/// Sources and destinations of state parts other than the peers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct StateSyncConfig {
+    /// Dump the state parts of the tracked shards here at the start of every
+    /// epoch.
+    #[serde(default)]
+    pub dump: Option<ExternalStorageLocation>,
+    /// Download state parts from here instead of requesting them from the
+    /// peers.  Shard state headers are still requested from the peers.
+    #[serde(default)]
+    pub sync_from: Option<ExternalStorageLocation>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
     /// Version of the binary.
@@ -156,6 +266,10 @@ pub struct ClientConfig {
     pub max_gas_burnt_view: Option<Gas>,
     /// Re-export storage layer statistics as prometheus metrics.
     pub enable_statistics_export: bool,
+    /// Capacity and eviction policy of the transaction pools.
+    pub transaction_pool: TransactionPoolConfig,
+    /// External storage to dump state parts to and to sync state from.
+    pub state_sync: StateSyncConfig,
 }
 
 impl ClientConfig {
@@ -215,6 +329,8 @@ impl ClientConfig {
             trie_viewer_state_size_limit: None,
             max_gas_burnt_view: None,
             enable_statistics_export: true,
+            transaction_pool: TransactionPoolConfig::default(),
+            state_sync: StateSyncConfig::default(),
         }
     }
 }
@@ -1,9 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::genesis_config::{Genesis, GenesisConfig};
-use near_crypto::key_conversion::is_valid_staking_key;
 use near_primitives::state_record::StateRecord;
 use near_primitives::types::AccountId;
+use near_primitives::utils::is_valid_staking_key;
 use num_rational::Rational32;
 
 /// Validate genesis config and records. Panics if genesis is ill-formed.
@@ -69,7 +69,10 @@ impl<'a> GenesisValidator<'a> {
             .into_iter()
             .map(|account_info| {
                 assert!(
-                    is_valid_staking_key(&account_info.public_key),
+                    is_valid_staking_key(
+                        &account_info.public_key,
+                        self.genesis_config.protocol_version
+                    ),
                     "validator staking key is not valid"
                 );
                 (account_info.account_id, account_info.amount)
@@ -3,8 +3,9 @@ mod genesis_config;
 pub mod genesis_validate;
 
 pub use client_config::{
-    ClientConfig, GCConfig, LogSummaryStyle, DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
-    MIN_GC_NUM_EPOCHS_TO_KEEP, TEST_STATE_SYNC_TIMEOUT,
+    ClientConfig, ExternalStorageLocation, GCConfig, LogSummaryStyle, StateSyncConfig,
+    TransactionPoolConfig, TransactionPoolEvictionPolicy, TransactionPoolOrdering,
+    DEFAULT_GC_NUM_EPOCHS_TO_KEEP, MIN_GC_NUM_EPOCHS_TO_KEEP, TEST_STATE_SYNC_TIMEOUT,
 };
 pub use genesis_config::{
     get_initial_supply, stream_records_from_file, Genesis, GenesisChangeConfig, GenesisConfig,
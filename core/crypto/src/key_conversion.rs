@@ -9,7 +9,27 @@ pub fn is_valid_staking_key(public_key: &PublicKey) -> bool {
     // The valid staking key is ED25519, and can be converted to ristretto.
     match public_key {
         PublicKey::ED25519(key) => convert_public_key(key).is_some(),
-        PublicKey::SECP256K1(_) => false,
+        PublicKey::SECP256K1(_)
+        | PublicKey::MLDSA(_)
+        | PublicKey::SLHDSA(_)
+        | PublicKey::ED25519MLDSA(_) => false,
+    }
+}
+
+/// Checks whether `public_key` is usable as a staking key once post-quantum
+/// validator keys are enabled.  In addition to plain ed25519 keys this accepts
+/// hybrid ed25519 + ML-DSA keys, whose ed25519 half is used for the VRF.
+pub fn is_valid_post_quantum_staking_key(public_key: &PublicKey) -> bool {
+    convert_staking_public_key(public_key).is_some()
+}
+
+/// Converts a validator staking key into the VRF public key used to check the
+/// randomness beacon output of the blocks it produces.
+pub fn convert_staking_public_key(public_key: &PublicKey) -> Option<vrf::PublicKey> {
+    match public_key {
+        PublicKey::ED25519(key) => convert_public_key(key),
+        PublicKey::ED25519MLDSA(key) => convert_public_key(&key.ed25519()),
+        PublicKey::SECP256K1(_) | PublicKey::MLDSA(_) | PublicKey::SLHDSA(_) => None,
     }
 }
 
@@ -54,4 +74,26 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_post_quantum_staking_keys() {
+        let ed25519 = signature::SecretKey::from_random(signature::KeyType::ED25519);
+        let hybrid = signature::SecretKey::from_random(signature::KeyType::ED25519MLDSA);
+        let mldsa = signature::SecretKey::from_random(signature::KeyType::MLDSA);
+
+        assert!(is_valid_staking_key(&ed25519.public_key()));
+        assert!(!is_valid_staking_key(&hybrid.public_key()));
+        assert!(is_valid_post_quantum_staking_key(&ed25519.public_key()));
+        assert!(is_valid_post_quantum_staking_key(&hybrid.public_key()));
+        assert!(!is_valid_post_quantum_staking_key(&mldsa.public_key()));
+
+        let sk = match hybrid {
+            signature::SecretKey::ED25519MLDSA(k) => k,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            convert_secret_key(&sk.ed25519).public_key().clone(),
+            convert_staking_public_key(&sk.public_key().into()).unwrap()
+        );
+    }
 }
@@ -111,4 +111,28 @@ mod test {
         let inner_msg = err.into_inner().unwrap().to_string();
         assert!(inner_msg.contains("duplicate field"));
     }
+
+    #[test]
+    fn test_hybrid_key_file() {
+        use crate::KeyType;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("validator_key.json");
+
+        let account_id = ACCOUNT_ID.parse().unwrap();
+        let secret_key = SecretKey::from_random(KeyType::ED25519MLDSA);
+        let public_key = secret_key.public_key();
+        let key = KeyFile { account_id, public_key, secret_key };
+        key.write_to_file(&path).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("\"public_key\": \"ed25519-mldsa:"));
+        assert!(contents.contains("\"secret_key\": \"ed25519-mldsa:"));
+
+        let loaded = KeyFile::from_file(&path).unwrap();
+        assert_eq!(key.account_id, loaded.account_id);
+        assert_eq!(key.public_key, loaded.public_key);
+        assert_eq!(key.secret_key, loaded.secret_key);
+        assert_eq!(loaded.secret_key.public_key(), loaded.public_key);
+    }
 }
@@ -1,8 +1,13 @@
 pub use errors::{ParseKeyError, ParseKeyTypeError, ParseSignatureError};
 pub use key_file::KeyFile;
 pub use signature::{
-    ED25519PublicKey, ED25519SecretKey, KeyType, PublicKey, Secp256K1PublicKey, Secp256K1Signature,
-    SecretKey, Signature,
+    ED25519PublicKey, ED25519SecretKey, Ed25519MlDsaPublicKey, Ed25519MlDsaSecretKey,
+    Ed25519MlDsaSignature, KeyType, MlDsaPublicKey, MlDsaSecretKey, MlDsaSignature, PublicKey,
+    Secp256K1PublicKey, Secp256K1Signature, SecretKey, Signature, SlhDsaPublicKey, SlhDsaSecretKey,
+    SlhDsaSignature, ED25519_MLDSA_PUBLIC_KEY_LENGTH, ED25519_MLDSA_SECRET_KEY_LENGTH,
+    ED25519_MLDSA_SIGNATURE_LENGTH, MLDSA_PUBLIC_KEY_LENGTH, MLDSA_SEED_LENGTH,
+    MLDSA_SIGNATURE_LENGTH, SLHDSA_PUBLIC_KEY_LENGTH, SLHDSA_SECRET_KEY_LENGTH, SLHDSA_SEED_LENGTH,
+    SLHDSA_SIGNATURE_LENGTH,
 };
 pub use signer::{EmptySigner, InMemorySigner, Signer};
 
@@ -10,6 +10,7 @@ use ed25519_dalek::ed25519::signature::{Signer, Verifier};
 use once_cell::sync::Lazy;
 use primitive_types::U256;
 use rand::rngs::OsRng;
+use rand::RngCore;
 use secp256k1::Message;
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +21,9 @@ pub static SECP256K1: Lazy<secp256k1::Secp256k1<secp256k1::All>> =
 pub enum KeyType {
     ED25519 = 0,
     SECP256K1 = 1,
+    MLDSA = 2,
+    SLHDSA = 3,
+    ED25519MLDSA = 4,
 }
 
 impl Display for KeyType {
@@ -27,6 +31,9 @@ impl Display for KeyType {
         f.write_str(match self {
             KeyType::ED25519 => "ed25519",
             KeyType::SECP256K1 => "secp256k1",
+            KeyType::MLDSA => "mldsa",
+            KeyType::SLHDSA => "slhdsa",
+            KeyType::ED25519MLDSA => "ed25519-mldsa",
         })
     }
 }
@@ -39,6 +46,9 @@ impl FromStr for KeyType {
         match lowercase_key_type.as_str() {
             "ed25519" => Ok(KeyType::ED25519),
             "secp256k1" => Ok(KeyType::SECP256K1),
+            "mldsa" => Ok(KeyType::MLDSA),
+            "slhdsa" => Ok(KeyType::SLHDSA),
+            "ed25519-mldsa" => Ok(KeyType::ED25519MLDSA),
             _ => Err(Self::Err::UnknownKeyType { unknown_key_type: lowercase_key_type }),
         }
     }
@@ -51,6 +61,9 @@ impl TryFrom<u8> for KeyType {
         match value {
             0 => Ok(KeyType::ED25519),
             1 => Ok(KeyType::SECP256K1),
+            2 => Ok(KeyType::MLDSA),
+            3 => Ok(KeyType::SLHDSA),
+            4 => Ok(KeyType::ED25519MLDSA),
             unknown_key_type => {
                 Err(Self::Error::UnknownKeyType { unknown_key_type: unknown_key_type.to_string() })
             }
@@ -184,6 +197,145 @@ impl Ord for ED25519PublicKey {
     }
 }
 
+/// Length of an ML-DSA-65 (FIPS 204) public key.
+pub const MLDSA_PUBLIC_KEY_LENGTH: usize = fips204::ml_dsa_65::PK_LEN;
+/// Length of an expanded ML-DSA-65 private key.
+const MLDSA_PRIVATE_KEY_LENGTH: usize = fips204::ml_dsa_65::SK_LEN;
+/// Length of an ML-DSA-65 signature.
+pub const MLDSA_SIGNATURE_LENGTH: usize = fips204::ml_dsa_65::SIG_LEN;
+/// Length of the seed `ξ` from which ML-DSA key pairs are derived.
+pub const MLDSA_SEED_LENGTH: usize = 32;
+
+/// ML-DSA keys and signatures are too large to be kept inline in `PublicKey`
+/// and `Signature` enums so they live on the heap.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MlDsaPublicKey(pub Box<[u8; MLDSA_PUBLIC_KEY_LENGTH]>);
+
+impl From<[u8; MLDSA_PUBLIC_KEY_LENGTH]> for MlDsaPublicKey {
+    fn from(data: [u8; MLDSA_PUBLIC_KEY_LENGTH]) -> Self {
+        Self(Box::new(data))
+    }
+}
+
+impl TryFrom<&[u8]> for MlDsaPublicKey {
+    type Error = crate::errors::ParseKeyError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Box::new(data.try_into().map_err(|_| {
+            crate::errors::ParseKeyError::InvalidLength {
+                expected_length: MLDSA_PUBLIC_KEY_LENGTH,
+                received_length: data.len(),
+            }
+        })?)))
+    }
+}
+
+impl AsRef<[u8]> for MlDsaPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl std::fmt::Debug for MlDsaPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0[..]), f)
+    }
+}
+
+/// Length of an SLH-DSA-SHA2-128s (FIPS 205) public key.
+pub const SLHDSA_PUBLIC_KEY_LENGTH: usize = fips205::slh_dsa_sha2_128s::PK_LEN;
+/// Length of an SLH-DSA-SHA2-128s private key.  The private key embeds the
+/// public key in its last `SLHDSA_PUBLIC_KEY_LENGTH` bytes.
+pub const SLHDSA_SECRET_KEY_LENGTH: usize = fips205::slh_dsa_sha2_128s::SK_LEN;
+/// Length of an SLH-DSA-SHA2-128s signature.
+pub const SLHDSA_SIGNATURE_LENGTH: usize = fips205::slh_dsa_sha2_128s::SIG_LEN;
+/// Security parameter `n` of SLH-DSA-SHA2-128s, i.e. the length of each of the
+/// three seeds key generation is derived from.
+pub const SLHDSA_SEED_LENGTH: usize = 16;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SlhDsaPublicKey(pub [u8; SLHDSA_PUBLIC_KEY_LENGTH]);
+
+impl From<[u8; SLHDSA_PUBLIC_KEY_LENGTH]> for SlhDsaPublicKey {
+    fn from(data: [u8; SLHDSA_PUBLIC_KEY_LENGTH]) -> Self {
+        Self(data)
+    }
+}
+
+impl TryFrom<&[u8]> for SlhDsaPublicKey {
+    type Error = crate::errors::ParseKeyError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(data.try_into().map_err(|_| crate::errors::ParseKeyError::InvalidLength {
+            expected_length: SLHDSA_PUBLIC_KEY_LENGTH,
+            received_length: data.len(),
+        })?))
+    }
+}
+
+impl AsRef<[u8]> for SlhDsaPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for SlhDsaPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0), f)
+    }
+}
+
+/// Length of the composite ed25519 + ML-DSA public key.
+pub const ED25519_MLDSA_PUBLIC_KEY_LENGTH: usize =
+    ed25519_dalek::PUBLIC_KEY_LENGTH + MLDSA_PUBLIC_KEY_LENGTH;
+
+/// Composite public key of the hybrid ed25519 + ML-DSA scheme: the ed25519
+/// key immediately followed by the ML-DSA key.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ed25519MlDsaPublicKey(pub Box<[u8; ED25519_MLDSA_PUBLIC_KEY_LENGTH]>);
+
+impl Ed25519MlDsaPublicKey {
+    pub fn new(ed25519: &ED25519PublicKey, mldsa: &MlDsaPublicKey) -> Self {
+        let mut data = Box::new([0u8; ED25519_MLDSA_PUBLIC_KEY_LENGTH]);
+        data[..ed25519_dalek::PUBLIC_KEY_LENGTH].copy_from_slice(&ed25519.0);
+        data[ed25519_dalek::PUBLIC_KEY_LENGTH..].copy_from_slice(&mldsa.0[..]);
+        Self(data)
+    }
+
+    pub fn ed25519(&self) -> ED25519PublicKey {
+        ED25519PublicKey(self.0[..ed25519_dalek::PUBLIC_KEY_LENGTH].try_into().unwrap())
+    }
+
+    pub fn mldsa(&self) -> MlDsaPublicKey {
+        MlDsaPublicKey(Box::new(self.0[ed25519_dalek::PUBLIC_KEY_LENGTH..].try_into().unwrap()))
+    }
+}
+
+impl TryFrom<&[u8]> for Ed25519MlDsaPublicKey {
+    type Error = crate::errors::ParseKeyError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Box::new(data.try_into().map_err(|_| {
+            crate::errors::ParseKeyError::InvalidLength {
+                expected_length: ED25519_MLDSA_PUBLIC_KEY_LENGTH,
+                received_length: data.len(),
+            }
+        })?)))
+    }
+}
+
+impl AsRef<[u8]> for Ed25519MlDsaPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl std::fmt::Debug for Ed25519MlDsaPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0[..]), f)
+    }
+}
+
 /// Public key container supporting different curves.
 #[derive(Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub enum PublicKey {
@@ -191,13 +343,25 @@ pub enum PublicKey {
     ED25519(ED25519PublicKey),
     /// 512 bit elliptic curve based public-key used in Bitcoin's public-key cryptography.
     SECP256K1(Secp256K1PublicKey),
+    /// Lattice based ML-DSA-65 (Dilithium) public-key.
+    MLDSA(MlDsaPublicKey),
+    /// Stateless hash based SLH-DSA-SHA2-128s (SPHINCS+) public-key.
+    SLHDSA(SlhDsaPublicKey),
+    /// Hybrid key which is only valid if both its ed25519 and ML-DSA parts
+    /// are.
+    ED25519MLDSA(Ed25519MlDsaPublicKey),
 }
 
 impl PublicKey {
+    /// Length of the Borsh serialised key.
     pub fn len(&self) -> usize {
         match self {
             Self::ED25519(_) => ed25519_dalek::PUBLIC_KEY_LENGTH + 1,
             Self::SECP256K1(_) => 65,
+            Self::MLDSA(_) => MLDSA_PUBLIC_KEY_LENGTH + 1,
+            // Key type, `u32` length prefix and the key itself.
+            Self::SLHDSA(key) => 1 + 4 + key.0.len(),
+            Self::ED25519MLDSA(_) => ED25519_MLDSA_PUBLIC_KEY_LENGTH + 1,
         }
     }
 
@@ -207,6 +371,13 @@ impl PublicKey {
                 PublicKey::ED25519(ED25519PublicKey([0u8; ed25519_dalek::PUBLIC_KEY_LENGTH]))
             }
             KeyType::SECP256K1 => PublicKey::SECP256K1(Secp256K1PublicKey([0u8; 64])),
+            KeyType::MLDSA => {
+                PublicKey::MLDSA(MlDsaPublicKey::from([0u8; MLDSA_PUBLIC_KEY_LENGTH]))
+            }
+            KeyType::SLHDSA => PublicKey::SLHDSA(SlhDsaPublicKey([0u8; SLHDSA_PUBLIC_KEY_LENGTH])),
+            KeyType::ED25519MLDSA => PublicKey::ED25519MLDSA(Ed25519MlDsaPublicKey(Box::new(
+                [0u8; ED25519_MLDSA_PUBLIC_KEY_LENGTH],
+            ))),
         }
     }
 
@@ -214,6 +385,9 @@ impl PublicKey {
         match self {
             Self::ED25519(_) => KeyType::ED25519,
             Self::SECP256K1(_) => KeyType::SECP256K1,
+            Self::MLDSA(_) => KeyType::MLDSA,
+            Self::SLHDSA(_) => KeyType::SLHDSA,
+            Self::ED25519MLDSA(_) => KeyType::ED25519MLDSA,
         }
     }
 
@@ -221,13 +395,16 @@ impl PublicKey {
         match self {
             Self::ED25519(key) => key.as_ref(),
             Self::SECP256K1(key) => key.as_ref(),
+            Self::MLDSA(key) => key.as_ref(),
+            Self::SLHDSA(key) => key.as_ref(),
+            Self::ED25519MLDSA(key) => key.as_ref(),
         }
     }
 
     pub fn unwrap_as_ed25519(&self) -> &ED25519PublicKey {
         match self {
             Self::ED25519(key) => key,
-            Self::SECP256K1(_) => panic!(),
+            _ => panic!(),
         }
     }
 }
@@ -246,6 +423,18 @@ impl Hash for PublicKey {
                 state.write_u8(1u8);
                 state.write(&public_key.0);
             }
+            PublicKey::MLDSA(public_key) => {
+                state.write_u8(2u8);
+                state.write(&public_key.0[..]);
+            }
+            PublicKey::SLHDSA(public_key) => {
+                state.write_u8(3u8);
+                state.write(&public_key.0);
+            }
+            PublicKey::ED25519MLDSA(public_key) => {
+                state.write_u8(4u8);
+                state.write(&public_key.0[..]);
+            }
         }
     }
 }
@@ -255,6 +444,9 @@ impl Display for PublicKey {
         let (key_type, key_data) = match self {
             PublicKey::ED25519(public_key) => (KeyType::ED25519, &public_key.0[..]),
             PublicKey::SECP256K1(public_key) => (KeyType::SECP256K1, &public_key.0[..]),
+            PublicKey::MLDSA(public_key) => (KeyType::MLDSA, &public_key.0[..]),
+            PublicKey::SLHDSA(public_key) => (KeyType::SLHDSA, &public_key.0[..]),
+            PublicKey::ED25519MLDSA(public_key) => (KeyType::ED25519MLDSA, &public_key.0[..]),
         };
         write!(fmt, "{}:{}", key_type, Bs58(key_data))
     }
@@ -277,11 +469,59 @@ impl BorshSerialize for PublicKey {
                 BorshSerialize::serialize(&1u8, writer)?;
                 writer.write_all(&public_key.0)?;
             }
+            PublicKey::MLDSA(public_key) => {
+                BorshSerialize::serialize(&2u8, writer)?;
+                writer.write_all(&public_key.0[..])?;
+            }
+            PublicKey::SLHDSA(public_key) => {
+                BorshSerialize::serialize(&3u8, writer)?;
+                serialize_sized_bytes(&public_key.0, writer)?;
+            }
+            PublicKey::ED25519MLDSA(public_key) => {
+                BorshSerialize::serialize(&4u8, writer)?;
+                writer.write_all(&public_key.0[..])?;
+            }
         }
         Ok(())
     }
 }
 
+/// Reads a fixed-size array which is too large to use Borsh’s built-in array
+/// implementations.
+fn deserialize_boxed_array<const N: usize>(buf: &mut &[u8]) -> Result<Box<[u8; N]>, Error> {
+    if buf.len() < N {
+        return Err(Error::new(ErrorKind::InvalidInput, "Unexpected length of input"));
+    }
+    let (data, rest) = buf.split_at(N);
+    *buf = rest;
+    Ok(Box::new(data.try_into().unwrap()))
+}
+
+/// Writes a `u32` length prefixed byte string.
+///
+/// Hash based keys and signatures are encoded together with their length so
+/// that readers can tell how large the payload is (and skip or reject it)
+/// without knowing the parameter set it was produced with.
+fn serialize_sized_bytes<W: Write>(data: &[u8], writer: &mut W) -> Result<(), Error> {
+    BorshSerialize::serialize(&(data.len() as u32), writer)?;
+    writer.write_all(data)
+}
+
+/// Reads a `u32` length prefixed byte string written by
+/// `serialize_sized_bytes` which must be exactly `N` bytes long.  The prefix
+/// is validated before anything is allocated so a malformed input cannot make
+/// us reserve arbitrary amounts of memory.
+fn deserialize_sized_array<const N: usize>(buf: &mut &[u8]) -> Result<Box<[u8; N]>, Error> {
+    let len = <u32 as BorshDeserialize>::deserialize(buf)? as usize;
+    if len != N {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid payload length: expected {} bytes, but {} was given", N, len),
+        ));
+    }
+    deserialize_boxed_array(buf)
+}
+
 impl BorshDeserialize for PublicKey {
     fn deserialize(buf: &mut &[u8]) -> Result<Self, Error> {
         let key_type = KeyType::try_from(<u8 as BorshDeserialize>::deserialize(buf)?)
@@ -293,6 +533,13 @@ impl BorshDeserialize for PublicKey {
             KeyType::SECP256K1 => {
                 Ok(PublicKey::SECP256K1(Secp256K1PublicKey(BorshDeserialize::deserialize(buf)?)))
             }
+            KeyType::MLDSA => Ok(PublicKey::MLDSA(MlDsaPublicKey(deserialize_boxed_array(buf)?))),
+            KeyType::SLHDSA => {
+                Ok(PublicKey::SLHDSA(SlhDsaPublicKey(*deserialize_sized_array(buf)?)))
+            }
+            KeyType::ED25519MLDSA => {
+                Ok(PublicKey::ED25519MLDSA(Ed25519MlDsaPublicKey(deserialize_boxed_array(buf)?)))
+            }
         }
     }
 }
@@ -352,6 +599,31 @@ impl FromStr for PublicKey {
                 }
                 Ok(PublicKey::SECP256K1(Secp256K1PublicKey(array)))
             }
+            KeyType::MLDSA => {
+                let data = bs58::decode(key_data)
+                    .into_vec()
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                Ok(PublicKey::MLDSA(MlDsaPublicKey::try_from(&data[..])?))
+            }
+            KeyType::SLHDSA => {
+                let mut array = [0; SLHDSA_PUBLIC_KEY_LENGTH];
+                let length = bs58::decode(key_data)
+                    .into(&mut array[..])
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                if length != SLHDSA_PUBLIC_KEY_LENGTH {
+                    return Err(Self::Err::InvalidLength {
+                        expected_length: SLHDSA_PUBLIC_KEY_LENGTH,
+                        received_length: length,
+                    });
+                }
+                Ok(PublicKey::SLHDSA(SlhDsaPublicKey(array)))
+            }
+            KeyType::ED25519MLDSA => {
+                let data = bs58::decode(key_data)
+                    .into_vec()
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                Ok(PublicKey::ED25519MLDSA(Ed25519MlDsaPublicKey::try_from(&data[..])?))
+            }
         }
     }
 }
@@ -368,6 +640,24 @@ impl From<Secp256K1PublicKey> for PublicKey {
     }
 }
 
+impl From<MlDsaPublicKey> for PublicKey {
+    fn from(mldsa: MlDsaPublicKey) -> Self {
+        Self::MLDSA(mldsa)
+    }
+}
+
+impl From<SlhDsaPublicKey> for PublicKey {
+    fn from(slhdsa: SlhDsaPublicKey) -> Self {
+        Self::SLHDSA(slhdsa)
+    }
+}
+
+impl From<Ed25519MlDsaPublicKey> for PublicKey {
+    fn from(hybrid: Ed25519MlDsaPublicKey) -> Self {
+        Self::ED25519MLDSA(hybrid)
+    }
+}
+
 #[derive(Clone)]
 // This is actually a keypair, because ed25519_dalek api only has keypair.sign
 // From ed25519_dalek doc: The first SECRET_KEY_LENGTH of bytes is the SecretKey
@@ -388,11 +678,181 @@ impl std::fmt::Debug for ED25519SecretKey {
 
 impl Eq for ED25519SecretKey {}
 
+/// ML-DSA secret key.
+///
+/// The key is identified by the 32-byte seed `ξ` it was generated from (see
+/// FIPS 204, Algorithm 6) and only the seed is ever serialised.  The expanded
+/// private key and the public key are derived once, on construction, so that
+/// signing does not need to repeat the key generation.
+#[derive(Clone)]
+pub struct MlDsaSecretKey {
+    seed: [u8; MLDSA_SEED_LENGTH],
+    private_key: Box<[u8; MLDSA_PRIVATE_KEY_LENGTH]>,
+    public_key: MlDsaPublicKey,
+}
+
+impl MlDsaSecretKey {
+    pub fn from_seed_bytes(seed: [u8; MLDSA_SEED_LENGTH]) -> Self {
+        use fips204::traits::{KeyGen, SerDes};
+        let (public_key, private_key) = fips204::ml_dsa_65::KG::keygen_from_seed(&seed);
+        Self {
+            seed,
+            private_key: Box::new(private_key.into_bytes()),
+            public_key: MlDsaPublicKey::from(public_key.into_bytes()),
+        }
+    }
+
+    pub fn seed(&self) -> &[u8; MLDSA_SEED_LENGTH] {
+        &self.seed
+    }
+
+    pub fn public_key(&self) -> &MlDsaPublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, data: &[u8], context: &[u8]) -> [u8; MLDSA_SIGNATURE_LENGTH] {
+        use fips204::traits::{SerDes, Signer};
+        let private_key = fips204::ml_dsa_65::PrivateKey::try_from_bytes(*self.private_key)
+            .expect("private key is derived from seed");
+        // Signing only fails if the context string is longer than 255 bytes.
+        private_key.try_sign(data, context).unwrap()
+    }
+}
+
+impl PartialEq for MlDsaSecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.seed == other.seed
+    }
+}
+
+impl Eq for MlDsaSecretKey {}
+
+impl std::fmt::Debug for MlDsaSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.seed), f)
+    }
+}
+
+/// SLH-DSA private key: `SK.seed || SK.prf || PK.seed || PK.root` as laid out
+/// by FIPS 205.  The last two fields form the public key.
+#[derive(Clone)]
+pub struct SlhDsaSecretKey(pub [u8; SLHDSA_SECRET_KEY_LENGTH]);
+
+impl SlhDsaSecretKey {
+    /// Derives the key pair from the three FIPS 205 key generation seeds
+    /// (Algorithm 18, `slh_keygen_internal`).
+    pub fn from_seeds(
+        sk_seed: &[u8; SLHDSA_SEED_LENGTH],
+        sk_prf: &[u8; SLHDSA_SEED_LENGTH],
+        pk_seed: &[u8; SLHDSA_SEED_LENGTH],
+    ) -> Self {
+        use fips205::traits::SerDes;
+        let (_, private_key) =
+            fips205::slh_dsa_sha2_128s::KG::keygen_with_seeds(sk_seed, sk_prf, pk_seed);
+        Self(private_key.into_bytes())
+    }
+
+    /// Parses a serialised private key, checking that `PK.root` is the one
+    /// derived from the seeds.  A key with a wrong root would produce
+    /// signatures which don't verify against its own public key.
+    pub fn try_from_bytes(data: [u8; SLHDSA_SECRET_KEY_LENGTH]) -> Option<Self> {
+        let (sk_seed, sk_prf, pk_seed, _) = arrayref::array_refs!(
+            &data,
+            SLHDSA_SEED_LENGTH,
+            SLHDSA_SEED_LENGTH,
+            SLHDSA_SEED_LENGTH,
+            SLHDSA_SEED_LENGTH
+        );
+        let secret_key = Self::from_seeds(sk_seed, sk_prf, pk_seed);
+        (secret_key.0 == data).then_some(secret_key)
+    }
+
+    pub fn public_key(&self) -> SlhDsaPublicKey {
+        SlhDsaPublicKey(
+            self.0[SLHDSA_SECRET_KEY_LENGTH - SLHDSA_PUBLIC_KEY_LENGTH..].try_into().unwrap(),
+        )
+    }
+
+    fn sign(&self, data: &[u8]) -> SlhDsaSignature {
+        use fips205::traits::{SerDes, Signer};
+        let private_key = fips205::slh_dsa_sha2_128s::PrivateKey::try_from_bytes(self.0)
+            .expect("private key is always well formed");
+        // Signing only fails if the context string is longer than 255 bytes.
+        SlhDsaSignature(Box::new(private_key.try_sign(data, &[], true).unwrap()))
+    }
+}
+
+impl PartialEq for SlhDsaSecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for SlhDsaSecretKey {}
+
+impl std::fmt::Debug for SlhDsaSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0), f)
+    }
+}
+
+/// ML-DSA context string used by the hybrid scheme.  It makes the ML-DSA half
+/// of a hybrid signature invalid as a standalone ML-DSA signature so the two
+/// halves can't be separated and passed off as a weaker signature.
+const ED25519_MLDSA_CONTEXT: &[u8] = b"near-ed25519-mldsa";
+
+/// Secret key of the hybrid ed25519 + ML-DSA scheme.  It’s serialised as the
+/// ed25519 key pair followed by the ML-DSA seed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ed25519MlDsaSecretKey {
+    pub ed25519: ED25519SecretKey,
+    pub mldsa: MlDsaSecretKey,
+}
+
+/// Length of the serialised hybrid ed25519 + ML-DSA secret key.
+pub const ED25519_MLDSA_SECRET_KEY_LENGTH: usize =
+    ed25519_dalek::KEYPAIR_LENGTH + MLDSA_SEED_LENGTH;
+
+impl Ed25519MlDsaSecretKey {
+    pub fn public_key(&self) -> Ed25519MlDsaPublicKey {
+        let ed25519 = ED25519PublicKey(
+            self.ed25519.0[ed25519_dalek::SECRET_KEY_LENGTH..].try_into().unwrap(),
+        );
+        Ed25519MlDsaPublicKey::new(&ed25519, self.mldsa.public_key())
+    }
+
+    pub fn to_bytes(&self) -> [u8; ED25519_MLDSA_SECRET_KEY_LENGTH] {
+        let mut data = [0u8; ED25519_MLDSA_SECRET_KEY_LENGTH];
+        data[..ed25519_dalek::KEYPAIR_LENGTH].copy_from_slice(&self.ed25519.0);
+        data[ed25519_dalek::KEYPAIR_LENGTH..].copy_from_slice(self.mldsa.seed());
+        data
+    }
+
+    fn sign(&self, data: &[u8]) -> Ed25519MlDsaSignature {
+        let keypair = ed25519_dalek::Keypair::from_bytes(&self.ed25519.0).unwrap();
+        let mut signature = Box::new([0u8; ED25519_MLDSA_SIGNATURE_LENGTH]);
+        signature[..ed25519_dalek::SIGNATURE_LENGTH]
+            .copy_from_slice(&keypair.sign(data).to_bytes());
+        signature[ed25519_dalek::SIGNATURE_LENGTH..]
+            .copy_from_slice(&self.mldsa.sign(data, ED25519_MLDSA_CONTEXT));
+        Ed25519MlDsaSignature(signature)
+    }
+}
+
+impl std::fmt::Debug for Ed25519MlDsaSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{:?}+{:?}", self.ed25519, self.mldsa)
+    }
+}
+
 /// Secret key container supporting different curves.
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum SecretKey {
     ED25519(ED25519SecretKey),
     SECP256K1(secp256k1::SecretKey),
+    MLDSA(MlDsaSecretKey),
+    SLHDSA(SlhDsaSecretKey),
+    ED25519MLDSA(Ed25519MlDsaSecretKey),
 }
 
 impl SecretKey {
@@ -400,6 +860,9 @@ impl SecretKey {
         match self {
             SecretKey::ED25519(_) => KeyType::ED25519,
             SecretKey::SECP256K1(_) => KeyType::SECP256K1,
+            SecretKey::MLDSA(_) => KeyType::MLDSA,
+            SecretKey::SLHDSA(_) => KeyType::SLHDSA,
+            SecretKey::ED25519MLDSA(_) => KeyType::ED25519MLDSA,
         }
     }
 
@@ -412,6 +875,27 @@ impl SecretKey {
             KeyType::SECP256K1 => {
                 SecretKey::SECP256K1(secp256k1::SecretKey::new(&mut secp256k1::rand::rngs::OsRng))
             }
+            KeyType::MLDSA => {
+                let mut seed = [0u8; MLDSA_SEED_LENGTH];
+                OsRng.fill_bytes(&mut seed);
+                SecretKey::MLDSA(MlDsaSecretKey::from_seed_bytes(seed))
+            }
+            KeyType::SLHDSA => {
+                let mut seeds = [[0u8; SLHDSA_SEED_LENGTH]; 3];
+                for seed in seeds.iter_mut() {
+                    OsRng.fill_bytes(seed);
+                }
+                SecretKey::SLHDSA(SlhDsaSecretKey::from_seeds(&seeds[0], &seeds[1], &seeds[2]))
+            }
+            KeyType::ED25519MLDSA => {
+                let keypair = ed25519_dalek::Keypair::generate(&mut OsRng);
+                let mut seed = [0u8; MLDSA_SEED_LENGTH];
+                OsRng.fill_bytes(&mut seed);
+                SecretKey::ED25519MLDSA(Ed25519MlDsaSecretKey {
+                    ed25519: ED25519SecretKey(keypair.to_bytes()),
+                    mldsa: MlDsaSecretKey::from_seed_bytes(seed),
+                })
+            }
         }
     }
 
@@ -433,6 +917,14 @@ impl SecretKey {
                 buf[64] = rec_id.to_i32() as u8;
                 Signature::SECP256K1(Secp256K1Signature(buf))
             }
+
+            SecretKey::MLDSA(secret_key) => {
+                Signature::MLDSA(MlDsaSignature(Box::new(secret_key.sign(data, &[]))))
+            }
+
+            SecretKey::SLHDSA(secret_key) => Signature::SLHDSA(secret_key.sign(data)),
+
+            SecretKey::ED25519MLDSA(secret_key) => Signature::ED25519MLDSA(secret_key.sign(data)),
         }
     }
 
@@ -448,22 +940,32 @@ impl SecretKey {
                 public_key.0.copy_from_slice(&serialized[1..65]);
                 PublicKey::SECP256K1(public_key)
             }
+            SecretKey::MLDSA(secret_key) => PublicKey::MLDSA(secret_key.public_key().clone()),
+            SecretKey::SLHDSA(secret_key) => PublicKey::SLHDSA(secret_key.public_key()),
+            SecretKey::ED25519MLDSA(secret_key) => PublicKey::ED25519MLDSA(secret_key.public_key()),
         }
     }
 
     pub fn unwrap_as_ed25519(&self) -> &ED25519SecretKey {
         match self {
             SecretKey::ED25519(key) => key,
-            SecretKey::SECP256K1(_) => panic!(),
+            _ => panic!(),
         }
     }
 }
 
 impl std::fmt::Display for SecretKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let hybrid_data;
         let (key_type, key_data) = match self {
             SecretKey::ED25519(secret_key) => (KeyType::ED25519, &secret_key.0[..]),
             SecretKey::SECP256K1(secret_key) => (KeyType::SECP256K1, &secret_key[..]),
+            SecretKey::MLDSA(secret_key) => (KeyType::MLDSA, &secret_key.seed[..]),
+            SecretKey::SLHDSA(secret_key) => (KeyType::SLHDSA, &secret_key.0[..]),
+            SecretKey::ED25519MLDSA(secret_key) => {
+                hybrid_data = secret_key.to_bytes();
+                (KeyType::ED25519MLDSA, &hybrid_data[..])
+            }
         };
         write!(f, "{}:{}", key_type, Bs58(key_data))
     }
@@ -504,6 +1006,54 @@ impl FromStr for SecretKey {
                         .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?,
                 ))
             }
+            KeyType::MLDSA => {
+                let mut array = [0; MLDSA_SEED_LENGTH];
+                let length = bs58::decode(key_data)
+                    .into(&mut array[..])
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                if length != MLDSA_SEED_LENGTH {
+                    return Err(Self::Err::InvalidLength {
+                        expected_length: MLDSA_SEED_LENGTH,
+                        received_length: length,
+                    });
+                }
+                Ok(Self::MLDSA(MlDsaSecretKey::from_seed_bytes(array)))
+            }
+            KeyType::SLHDSA => {
+                let mut array = [0; SLHDSA_SECRET_KEY_LENGTH];
+                let length = bs58::decode(key_data)
+                    .into(&mut array[..])
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                if length != SLHDSA_SECRET_KEY_LENGTH {
+                    return Err(Self::Err::InvalidLength {
+                        expected_length: SLHDSA_SECRET_KEY_LENGTH,
+                        received_length: length,
+                    });
+                }
+                let secret_key = SlhDsaSecretKey::try_from_bytes(array).ok_or_else(|| {
+                    Self::Err::InvalidData {
+                        error_message: "PK.root doesn't match the seeds".to_string(),
+                    }
+                })?;
+                Ok(Self::SLHDSA(secret_key))
+            }
+            KeyType::ED25519MLDSA => {
+                let mut array = [0; ED25519_MLDSA_SECRET_KEY_LENGTH];
+                let length = bs58::decode(key_data)
+                    .into(&mut array[..])
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                if length != ED25519_MLDSA_SECRET_KEY_LENGTH {
+                    return Err(Self::Err::InvalidLength {
+                        expected_length: ED25519_MLDSA_SECRET_KEY_LENGTH,
+                        received_length: length,
+                    });
+                }
+                let (ed25519, mldsa) = array.split_at(ed25519_dalek::KEYPAIR_LENGTH);
+                Ok(Self::ED25519MLDSA(Ed25519MlDsaSecretKey {
+                    ed25519: ED25519SecretKey(ed25519.try_into().unwrap()),
+                    mldsa: MlDsaSecretKey::from_seed_bytes(mldsa.try_into().unwrap()),
+                }))
+            }
         }
     }
 }
@@ -637,11 +1187,122 @@ impl From<Secp256K1Signature> for [u8; 65] {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MlDsaSignature(pub Box<[u8; MLDSA_SIGNATURE_LENGTH]>);
+
+impl TryFrom<&[u8]> for MlDsaSignature {
+    type Error = crate::errors::ParseSignatureError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Box::new(data.try_into().map_err(|_| Self::Error::InvalidLength {
+            expected_length: MLDSA_SIGNATURE_LENGTH,
+            received_length: data.len(),
+        })?)))
+    }
+}
+
+impl AsRef<[u8]> for MlDsaSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl Debug for MlDsaSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0[..]), f)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SlhDsaSignature(pub Box<[u8; SLHDSA_SIGNATURE_LENGTH]>);
+
+impl TryFrom<&[u8]> for SlhDsaSignature {
+    type Error = crate::errors::ParseSignatureError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Box::new(data.try_into().map_err(|_| Self::Error::InvalidLength {
+            expected_length: SLHDSA_SIGNATURE_LENGTH,
+            received_length: data.len(),
+        })?)))
+    }
+}
+
+impl AsRef<[u8]> for SlhDsaSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl Debug for SlhDsaSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0[..]), f)
+    }
+}
+
+/// Length of the hybrid ed25519 + ML-DSA signature.
+pub const ED25519_MLDSA_SIGNATURE_LENGTH: usize =
+    ed25519_dalek::SIGNATURE_LENGTH + MLDSA_SIGNATURE_LENGTH;
+
+/// Hybrid signature: an ed25519 signature immediately followed by an ML-DSA
+/// signature of the same message.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Ed25519MlDsaSignature(pub Box<[u8; ED25519_MLDSA_SIGNATURE_LENGTH]>);
+
+impl TryFrom<&[u8]> for Ed25519MlDsaSignature {
+    type Error = crate::errors::ParseSignatureError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Box::new(data.try_into().map_err(|_| Self::Error::InvalidLength {
+            expected_length: ED25519_MLDSA_SIGNATURE_LENGTH,
+            received_length: data.len(),
+        })?)))
+    }
+}
+
+impl AsRef<[u8]> for Ed25519MlDsaSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl Debug for Ed25519MlDsaSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        Display::fmt(&Bs58(&self.0[..]), f)
+    }
+}
+
+fn verify_ed25519(signature: &[u8], data: &[u8], public_key: &[u8]) -> bool {
+    let signature = match ed25519_dalek::Signature::from_bytes(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    match ed25519_dalek::PublicKey::from_bytes(public_key) {
+        Err(_) => false,
+        Ok(public_key) => public_key.verify(data, &signature).is_ok(),
+    }
+}
+
+fn verify_mldsa(
+    signature: &[u8; MLDSA_SIGNATURE_LENGTH],
+    data: &[u8],
+    public_key: &[u8; MLDSA_PUBLIC_KEY_LENGTH],
+    context: &[u8],
+) -> bool {
+    use fips204::traits::{SerDes, Verifier};
+    match fips204::ml_dsa_65::PublicKey::try_from_bytes(*public_key) {
+        Err(_) => false,
+        Ok(public_key) => public_key.verify(data, signature, context),
+    }
+}
+
 /// Signature container supporting different curves.
 #[derive(Clone, PartialEq, Eq)]
 pub enum Signature {
     ED25519(ed25519_dalek::Signature),
     SECP256K1(Secp256K1Signature),
+    MLDSA(MlDsaSignature),
+    SLHDSA(SlhDsaSignature),
+    ED25519MLDSA(Ed25519MlDsaSignature),
 }
 
 impl Hash for Signature {
@@ -649,11 +1310,26 @@ impl Hash for Signature {
         match self {
             Signature::ED25519(sig) => sig.to_bytes().hash(state),
             Signature::SECP256K1(sig) => sig.hash(state),
+            Signature::MLDSA(sig) => sig.hash(state),
+            Signature::SLHDSA(sig) => sig.hash(state),
+            Signature::ED25519MLDSA(sig) => sig.hash(state),
         };
     }
 }
 
 impl Signature {
+    /// Length of the Borsh serialised signature.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::ED25519(_) => ed25519_dalek::SIGNATURE_LENGTH + 1,
+            Self::SECP256K1(_) => SECP256K1_SIGNATURE_LENGTH + 1,
+            Self::MLDSA(_) => MLDSA_SIGNATURE_LENGTH + 1,
+            // Key type, `u32` length prefix and the signature itself.
+            Self::SLHDSA(signature) => 1 + 4 + signature.0.len(),
+            Self::ED25519MLDSA(_) => ED25519_MLDSA_SIGNATURE_LENGTH + 1,
+        }
+    }
+
     /// Construct Signature from key type and raw signature blob
     pub fn from_parts(
         signature_type: KeyType,
@@ -674,6 +1350,11 @@ impl Signature {
                     },
                 )?))
             }
+            KeyType::MLDSA => Ok(Signature::MLDSA(MlDsaSignature::try_from(signature_data)?)),
+            KeyType::SLHDSA => Ok(Signature::SLHDSA(SlhDsaSignature::try_from(signature_data)?)),
+            KeyType::ED25519MLDSA => {
+                Ok(Signature::ED25519MLDSA(Ed25519MlDsaSignature::try_from(signature_data)?))
+            }
         }
     }
 
@@ -708,6 +1389,29 @@ impl Signature {
                     )
                     .is_ok()
             }
+            (Signature::MLDSA(signature), PublicKey::MLDSA(public_key)) => {
+                verify_mldsa(&signature.0, data, &public_key.0, &[])
+            }
+            (Signature::SLHDSA(signature), PublicKey::SLHDSA(public_key)) => {
+                use fips205::traits::{SerDes, Verifier};
+                match fips205::slh_dsa_sha2_128s::PublicKey::try_from_bytes(public_key.0) {
+                    Err(_) => false,
+                    Ok(public_key) => public_key.verify(data, &signature.0, &[]),
+                }
+            }
+            (Signature::ED25519MLDSA(signature), PublicKey::ED25519MLDSA(public_key)) => {
+                let (ed25519_signature, mldsa_signature) =
+                    signature.0.split_at(ed25519_dalek::SIGNATURE_LENGTH);
+                let (ed25519_key, mldsa_key) =
+                    public_key.0.split_at(ed25519_dalek::PUBLIC_KEY_LENGTH);
+                verify_ed25519(ed25519_signature, data, ed25519_key)
+                    && verify_mldsa(
+                        mldsa_signature.try_into().unwrap(),
+                        data,
+                        mldsa_key.try_into().unwrap(),
+                        ED25519_MLDSA_CONTEXT,
+                    )
+            }
             _ => false,
         }
     }
@@ -716,6 +1420,9 @@ impl Signature {
         match self {
             Signature::ED25519(_) => KeyType::ED25519,
             Signature::SECP256K1(_) => KeyType::SECP256K1,
+            Signature::MLDSA(_) => KeyType::MLDSA,
+            Signature::SLHDSA(_) => KeyType::SLHDSA,
+            Signature::ED25519MLDSA(_) => KeyType::ED25519MLDSA,
         }
     }
 }
@@ -737,6 +1444,18 @@ impl BorshSerialize for Signature {
                 BorshSerialize::serialize(&1u8, writer)?;
                 writer.write_all(&signature.0)?;
             }
+            Signature::MLDSA(signature) => {
+                BorshSerialize::serialize(&2u8, writer)?;
+                writer.write_all(&signature.0[..])?;
+            }
+            Signature::SLHDSA(signature) => {
+                BorshSerialize::serialize(&3u8, writer)?;
+                serialize_sized_bytes(&signature.0[..], writer)?;
+            }
+            Signature::ED25519MLDSA(signature) => {
+                BorshSerialize::serialize(&4u8, writer)?;
+                writer.write_all(&signature.0[..])?;
+            }
         }
         Ok(())
     }
@@ -759,6 +1478,13 @@ impl BorshDeserialize for Signature {
                 let array: [u8; 65] = BorshDeserialize::deserialize(buf)?;
                 Ok(Signature::SECP256K1(Secp256K1Signature(array)))
             }
+            KeyType::MLDSA => Ok(Signature::MLDSA(MlDsaSignature(deserialize_boxed_array(buf)?))),
+            KeyType::SLHDSA => {
+                Ok(Signature::SLHDSA(SlhDsaSignature(deserialize_sized_array(buf)?)))
+            }
+            KeyType::ED25519MLDSA => {
+                Ok(Signature::ED25519MLDSA(Ed25519MlDsaSignature(deserialize_boxed_array(buf)?)))
+            }
         }
     }
 }
@@ -768,6 +1494,9 @@ impl Display for Signature {
         let (key_type, key_data) = match self {
             Signature::ED25519(signature) => (KeyType::ED25519, signature.as_ref()),
             Signature::SECP256K1(signature) => (KeyType::SECP256K1, &signature.0[..]),
+            Signature::MLDSA(signature) => (KeyType::MLDSA, &signature.0[..]),
+            Signature::SLHDSA(signature) => (KeyType::SLHDSA, &signature.0[..]),
+            Signature::ED25519MLDSA(signature) => (KeyType::ED25519MLDSA, &signature.0[..]),
         };
         write!(f, "{}:{}", key_type, Bs58(key_data))
     }
@@ -826,6 +1555,24 @@ impl FromStr for Signature {
                 }
                 Ok(Signature::SECP256K1(Secp256K1Signature(array)))
             }
+            KeyType::MLDSA => {
+                let data = bs58::decode(sig_data)
+                    .into_vec()
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                Ok(Signature::MLDSA(MlDsaSignature::try_from(&data[..])?))
+            }
+            KeyType::SLHDSA => {
+                let data = bs58::decode(sig_data)
+                    .into_vec()
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                Ok(Signature::SLHDSA(SlhDsaSignature::try_from(&data[..])?))
+            }
+            KeyType::ED25519MLDSA => {
+                let data = bs58::decode(sig_data)
+                    .into_vec()
+                    .map_err(|err| Self::Err::InvalidData { error_message: err.to_string() })?;
+                Ok(Signature::ED25519MLDSA(Ed25519MlDsaSignature::try_from(&data[..])?))
+            }
         }
     }
 }
@@ -850,10 +1597,14 @@ struct Bs58<'a>(&'a [u8]);
 
 impl<'a> core::fmt::Display for Bs58<'a> {
     fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        debug_assert!(self.0.len() <= 65);
-        // The largest buffer we’re ever encoding is 65-byte long.  Base58
-        // increases size of the value by less than 40%.  96-byte buffer is
-        // therefore enough to fit the largest value we’re ever encoding.
+        // Post-quantum keys and signatures are measured in kilobytes and are
+        // encoded on the heap.
+        if self.0.len() > 65 {
+            return fmt.write_str(&bs58::encode(self.0).into_string());
+        }
+        // The largest classical buffer we’re encoding is 65-byte long.
+        // Base58 increases size of the value by less than 40%.  96-byte buffer
+        // is therefore enough to fit it.
         let mut buf = [0u8; 96];
         let len = bs58::encode(self.0).into(&mut buf[..]).unwrap();
         let output = &buf[..len];
@@ -869,7 +1620,13 @@ mod tests {
 
     #[test]
     fn test_sign_verify() {
-        for key_type in vec![KeyType::ED25519, KeyType::SECP256K1] {
+        for key_type in vec![
+            KeyType::ED25519,
+            KeyType::SECP256K1,
+            KeyType::MLDSA,
+            KeyType::SLHDSA,
+            KeyType::ED25519MLDSA,
+        ] {
             let secret_key = SecretKey::from_random(key_type);
             let public_key = secret_key.public_key();
             use sha2::Digest;
@@ -936,26 +1693,384 @@ mod tests {
     fn test_borsh_serialization() {
         use sha2::Digest;
         let data = sha2::Sha256::digest(b"123").to_vec();
-        for key_type in vec![KeyType::ED25519, KeyType::SECP256K1] {
+        for key_type in vec![
+            KeyType::ED25519,
+            KeyType::SECP256K1,
+            KeyType::MLDSA,
+            KeyType::SLHDSA,
+            KeyType::ED25519MLDSA,
+        ] {
             let sk = SecretKey::from_seed(key_type, "test");
             let pk = sk.public_key();
             let bytes = pk.try_to_vec().unwrap();
             assert_eq!(PublicKey::try_from_slice(&bytes).unwrap(), pk);
+            assert_eq!(bytes.len(), pk.len());
 
             let signature = sk.sign(&data);
             let bytes = signature.try_to_vec().unwrap();
             assert_eq!(Signature::try_from_slice(&bytes).unwrap(), signature);
+            assert_eq!(bytes.len(), signature.len());
 
             assert!(PublicKey::try_from_slice(&[0]).is_err());
             assert!(Signature::try_from_slice(&[0]).is_err());
         }
     }
 
+    #[test]
+    fn test_json_serialize_mldsa() {
+        let sk = SecretKey::from_seed(KeyType::MLDSA, "test");
+        let pk = sk.public_key();
+        // Only the seed is serialised: `"test"` padded with spaces to 32 bytes.
+        let expected =
+            format!("\"mldsa:{}\"", bs58::encode(format!("{:32}", "test")).into_string());
+        assert_eq!(serde_json::to_string(&sk).unwrap(), expected);
+        assert_eq!(sk, serde_json::from_str(&expected).unwrap());
+
+        let json = serde_json::to_string(&pk).unwrap();
+        assert!(json.starts_with("\"mldsa:"));
+        assert_eq!(pk, serde_json::from_str(&json).unwrap());
+        let pk2: PublicKey = pk.to_string().parse().unwrap();
+        assert_eq!(pk, pk2);
+
+        let signature = sk.sign(b"123");
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(signature, serde_json::from_str(&json).unwrap());
+        let signature2: Signature = signature.to_string().parse().unwrap();
+        assert_eq!(signature, signature2);
+        assert!(signature2.verify(b"123", &pk2));
+    }
+
+    #[test]
+    fn test_mldsa_from_seed() {
+        // Key generation is a deterministic function of the seed, so the same
+        // seed must always produce the same key pair.
+        let sk = SecretKey::from_seed(KeyType::MLDSA, "test");
+        assert_eq!(sk.public_key(), PublicKey::from_seed(KeyType::MLDSA, "test"));
+        assert_eq!(sk.public_key(), SecretKey::from_seed(KeyType::MLDSA, "test").public_key());
+        assert_ne!(sk.public_key(), SecretKey::from_seed(KeyType::MLDSA, "test2").public_key());
+        assert_eq!(sk.public_key().len(), MLDSA_PUBLIC_KEY_LENGTH + 1);
+
+        let signature = sk.sign(b"123");
+        assert_eq!(signature.key_type() as u8, 2);
+        assert_eq!(signature.try_to_vec().unwrap().len(), MLDSA_SIGNATURE_LENGTH + 1);
+        assert!(signature.verify(b"123", &sk.public_key()));
+        assert!(!signature.verify(b"1234", &sk.public_key()));
+        let other = SecretKey::from_seed(KeyType::MLDSA, "test2");
+        assert!(!signature.verify(b"123", &other.public_key()));
+        assert!(!signature.verify(b"123", &PublicKey::from_seed(KeyType::ED25519, "test")));
+
+        let mut tampered = signature.try_to_vec().unwrap();
+        tampered[100] ^= 1;
+        let tampered = Signature::try_from_slice(&tampered).unwrap();
+        assert!(!tampered.verify(b"123", &sk.public_key()));
+
+        let data = signature.try_to_vec().unwrap();
+        assert_eq!(Signature::from_parts(KeyType::MLDSA, &data[1..]).unwrap(), signature,);
+        assert!(Signature::from_parts(KeyType::MLDSA, &data[2..]).is_err());
+        assert!(Signature::try_from_slice(&data[..data.len() - 1]).is_err());
+    }
+
+    /// RNG returning only zeros, which turns the hedged ML-DSA signing into
+    /// the deterministic variant of FIPS 204 (`rnd = {0}^32`).
+    struct ZeroRng;
+
+    impl rand_chacha::rand_core::RngCore for ZeroRng {
+        fn next_u32(&mut self) -> u32 {
+            0
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(0)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_chacha::rand_core::Error> {
+            dest.fill(0);
+            Ok(())
+        }
+    }
+
+    impl rand_chacha::rand_core::CryptoRng for ZeroRng {}
+
+    #[test]
+    fn test_mldsa_known_answers() {
+        use fips204::traits::{SerDes, Signer};
+        use hex_literal::hex;
+        use sha2::Digest;
+
+        // ML-DSA-65 (FIPS 204) key generation from the seed `ξ = 00 01 .. 1f`
+        // and deterministic signing, with and without a context string.  The
+        // expected values were produced independently with OpenSSL 3.5:
+        //
+        //   openssl genpkey -algorithm ML-DSA-65 -pkeyopt hexseed:000102..1f
+        //   openssl pkeyutl -sign -rawin -pkeyopt deterministic:1 \
+        //       [-pkeyopt context-string:near-ed25519-mldsa]
+        //
+        // Keys and signatures are compared by their SHA-256 digests.
+        let seed = hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let message = b"NEAR ML-DSA-65 test vector";
+        let sk = MlDsaSecretKey::from_seed_bytes(seed);
+        assert_eq!(
+            sk.public_key().0[..32],
+            hex!("48683d91978e31eb3dddb8b0473482d2b88a5f625949fd8f58a561e696bd4c27")
+        );
+        assert_eq!(
+            sha2::Sha256::digest(&sk.public_key().0[..])[..],
+            hex!("d666806e11cee19a7c989f7445f90dd419cf4d2d51db8c0fdb4c0f0a542238c9")
+        );
+        assert_eq!(
+            sha2::Sha256::digest(&sk.private_key[..])[..],
+            hex!("9f1e24f47795fe50040384e3d6183988047170fa2d866406b70fe0a3f8216063")
+        );
+
+        let private_key = fips204::ml_dsa_65::PrivateKey::try_from_bytes(*sk.private_key).unwrap();
+        for (context, expected) in [
+            (&b""[..], hex!("bec85c13362931b8d6f0338ffbdf24842150b909de316bcfb5682f0cb5302231")),
+            (
+                ED25519_MLDSA_CONTEXT,
+                hex!("1221fb4a91627e0a84891e8e139d7313b6b4d7445d158f361eec1de979782fc2"),
+            ),
+        ] {
+            let signature = private_key.try_sign_with_rng(&mut ZeroRng, message, context).unwrap();
+            assert_eq!(sha2::Sha256::digest(&signature[..])[..], expected);
+            assert!(verify_mldsa(&signature, message, &sk.public_key().0, context));
+            assert!(!verify_mldsa(
+                &signature,
+                b"NEAR ML-DSA-65 test vectors",
+                &sk.public_key().0,
+                context
+            ));
+            assert!(!verify_mldsa(&signature, message, &sk.public_key().0, b"other"));
+        }
+
+        // Signing is hedged, so `SecretKey::sign` gives a different signature
+        // for the same message, which still verifies.
+        let signature = SecretKey::MLDSA(sk.clone()).sign(message);
+        assert!(signature.verify(message, &PublicKey::MLDSA(sk.public_key().clone())));
+    }
+
+    #[test]
+    fn test_json_serialize_slhdsa() {
+        let sk = SecretKey::from_seed(KeyType::SLHDSA, "test");
+        let pk = sk.public_key();
+        let json = serde_json::to_string(&pk).unwrap();
+        assert!(json.starts_with("\"slhdsa:"));
+        assert_eq!(pk, serde_json::from_str(&json).unwrap());
+        let pk2: PublicKey = pk.to_string().parse().unwrap();
+        assert_eq!(pk, pk2);
+
+        let json = serde_json::to_string(&sk).unwrap();
+        assert_eq!(sk, serde_json::from_str(&json).unwrap());
+
+        let signature = sk.sign(b"123");
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(signature, serde_json::from_str(&json).unwrap());
+        let signature2: Signature = signature.to_string().parse().unwrap();
+        assert_eq!(signature, signature2);
+        assert!(signature2.verify(b"123", &pk2));
+    }
+
+    #[test]
+    fn test_slhdsa_parameters() {
+        // Sizes of SLH-DSA-SHA2-128s as listed in FIPS 205, Table 2.
+        assert_eq!(SLHDSA_SEED_LENGTH, 16);
+        assert_eq!(SLHDSA_PUBLIC_KEY_LENGTH, 32);
+        assert_eq!(SLHDSA_SECRET_KEY_LENGTH, 64);
+        assert_eq!(SLHDSA_SIGNATURE_LENGTH, 7856);
+
+        // FIPS 205 private key layout is `SK.seed || SK.prf || PK.seed ||
+        // PK.root` and the public key is `PK.seed || PK.root`.
+        let seeds = [[1u8; 16], [2u8; 16], [3u8; 16]];
+        let sk = SlhDsaSecretKey::from_seeds(&seeds[0], &seeds[1], &seeds[2]);
+        assert_eq!(sk.0[..16], seeds[0]);
+        assert_eq!(sk.0[16..32], seeds[1]);
+        assert_eq!(sk.0[32..48], seeds[2]);
+        assert_eq!(sk.public_key().0[..16], seeds[2]);
+        assert_eq!(sk.public_key().0[..], sk.0[32..]);
+        // PK.root only depends on PK.seed and SK.seed.
+        let other = SlhDsaSecretKey::from_seeds(&seeds[0], &[4u8; 16], &seeds[2]);
+        assert_eq!(sk.public_key(), other.public_key());
+        let other = SlhDsaSecretKey::from_seeds(&[4u8; 16], &seeds[1], &seeds[2]);
+        assert_ne!(sk.public_key(), other.public_key());
+
+        let sk = SecretKey::SLHDSA(sk);
+        let signature = sk.sign(b"123");
+        assert!(signature.verify(b"123", &sk.public_key()));
+        assert!(!signature.verify(b"1234", &sk.public_key()));
+        assert!(!signature.verify(b"123", &SecretKey::from_random(KeyType::SLHDSA).public_key()));
+    }
+
+    #[test]
+    fn test_slhdsa_known_answers() {
+        use fips205::traits::{SerDes, Signer};
+        use hex_literal::hex;
+        use sha2::Digest;
+
+        // SLH-DSA-SHA2-128s (FIPS 205) key generation from `SK.seed || SK.prf
+        // || PK.seed = 00 01 .. 2f` and deterministic signing.  The expected
+        // values were produced independently with OpenSSL 3.5:
+        //
+        //   openssl genpkey -algorithm SLH-DSA-SHA2-128s -pkeyopt hexseed:000102..2f
+        //   openssl pkeyutl -sign -rawin -pkeyopt deterministic:1
+        //
+        // The signature is compared by its SHA-256 digest.
+        let message = b"NEAR SLH-DSA-SHA2-128s test vector";
+        let sk = SlhDsaSecretKey::from_seeds(
+            &hex!("000102030405060708090a0b0c0d0e0f"),
+            &hex!("101112131415161718191a1b1c1d1e1f"),
+            &hex!("202122232425262728292a2b2c2d2e2f"),
+        );
+        assert_eq!(
+            sk.public_key().0,
+            hex!("202122232425262728292a2b2c2d2e2f990ce6298792b128846a8e4a3a68954c")
+        );
+
+        let private_key = fips205::slh_dsa_sha2_128s::PrivateKey::try_from_bytes(sk.0).unwrap();
+        let signature = private_key.try_sign(message, &[], false).unwrap();
+        assert_eq!(
+            sha2::Sha256::digest(&signature[..])[..],
+            hex!("87c33fda1ac5e92741b7ad5590cfeaff33131fd4d54a4ac9537fb0dbe886c74e")
+        );
+        let public_key = PublicKey::SLHDSA(sk.public_key());
+        let signature = Signature::SLHDSA(SlhDsaSignature(Box::new(signature)));
+        assert!(signature.verify(message, &public_key));
+        assert!(!signature.verify(b"NEAR SLH-DSA-SHA2-128s test vectors", &public_key));
+
+        // Signing is hedged, so `SecretKey::sign` gives other signatures for
+        // the same message, which still verify.
+        assert!(SecretKey::SLHDSA(sk).sign(message).verify(message, &public_key));
+    }
+
+    #[test]
+    fn test_slhdsa_secret_key_root() {
+        let sk = SecretKey::from_seed(KeyType::SLHDSA, "test");
+        assert_eq!(sk, sk.to_string().parse().unwrap());
+
+        // A key whose PK.root isn't derived from its seeds is rejected.
+        let mut data = match &sk {
+            SecretKey::SLHDSA(sk) => sk.0,
+            _ => unreachable!(),
+        };
+        data[SLHDSA_SECRET_KEY_LENGTH - 1] ^= 1;
+        let tampered = format!("slhdsa:{}", bs58::encode(data).into_string());
+        assert!(matches!(
+            tampered.parse::<SecretKey>(),
+            Err(crate::ParseKeyError::InvalidData { .. })
+        ));
+        assert!(serde_json::from_str::<SecretKey>(&format!("\"{tampered}\"")).is_err());
+        assert!(SlhDsaSecretKey::try_from_bytes(data).is_none());
+    }
+
+    #[test]
+    fn test_slhdsa_borsh_sizes() {
+        let sk = SecretKey::from_seed(KeyType::SLHDSA, "test");
+        let pk = sk.public_key();
+        let bytes = pk.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), pk.len());
+        assert_eq!(bytes[..5], [3, 32, 0, 0, 0]);
+
+        let signature = sk.sign(b"123");
+        let bytes = signature.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 1 + 4 + SLHDSA_SIGNATURE_LENGTH);
+        assert_eq!(Signature::try_from_slice(&bytes).unwrap(), signature);
+
+        // Truncated payload.
+        assert!(Signature::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+        // Length prefix not matching the parameter set is rejected before
+        // reading the payload.
+        let mut wrong_length = bytes.clone();
+        wrong_length[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Signature::try_from_slice(&wrong_length).is_err());
+        let mut wrong_length = pk.try_to_vec().unwrap();
+        wrong_length[1] = 31;
+        assert!(PublicKey::try_from_slice(&wrong_length).is_err());
+    }
+
+    #[test]
+    fn test_json_serialize_ed25519_mldsa() {
+        let sk = SecretKey::from_seed(KeyType::ED25519MLDSA, "test");
+        let pk = sk.public_key();
+        let json = serde_json::to_string(&pk).unwrap();
+        assert!(json.starts_with("\"ed25519-mldsa:"));
+        assert_eq!(pk, serde_json::from_str(&json).unwrap());
+        let pk2: PublicKey = pk.to_string().parse().unwrap();
+        assert_eq!(pk, pk2);
+
+        let json = serde_json::to_string(&sk).unwrap();
+        assert_eq!(sk, serde_json::from_str(&json).unwrap());
+        // Key type prefix is case insensitive, like for other key types.
+        let sk2: SecretKey =
+            sk.to_string().replace("ed25519-mldsa:", "ED25519-MLDSA:").parse().unwrap();
+        assert_eq!(sk, sk2);
+
+        let signature = sk.sign(b"123");
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(signature, serde_json::from_str(&json).unwrap());
+        let signature2: Signature = signature.to_string().parse().unwrap();
+        assert_eq!(signature, signature2);
+        assert!(signature2.verify(b"123", &pk2));
+    }
+
+    #[test]
+    fn test_ed25519_mldsa_requires_both_signatures() {
+        let sk = SecretKey::from_seed(KeyType::ED25519MLDSA, "test");
+        let pk = sk.public_key();
+        let other = SecretKey::from_seed(KeyType::ED25519MLDSA, "other");
+        let data = b"123";
+        let signature = match sk.sign(data) {
+            Signature::ED25519MLDSA(signature) => signature,
+            _ => unreachable!(),
+        };
+        let other_signature = match other.sign(data) {
+            Signature::ED25519MLDSA(signature) => signature,
+            _ => unreachable!(),
+        };
+        assert!(Signature::ED25519MLDSA(signature.clone()).verify(data, &pk));
+
+        // Replace either half with the matching half of another key’s signature.
+        for range in [
+            0..ed25519_dalek::SIGNATURE_LENGTH,
+            ed25519_dalek::SIGNATURE_LENGTH..ED25519_MLDSA_SIGNATURE_LENGTH,
+        ] {
+            let mut mixed = signature.clone();
+            mixed.0[range.clone()].copy_from_slice(&other_signature.0[range]);
+            assert!(!Signature::ED25519MLDSA(mixed).verify(data, &pk));
+        }
+
+        // The halves are not valid standalone signatures for the halves of
+        // the public key, except for ed25519 which has no domain separation.
+        let (ed25519_key, mldsa_key) = match &pk {
+            PublicKey::ED25519MLDSA(key) => (key.ed25519(), key.mldsa()),
+            _ => unreachable!(),
+        };
+        let mldsa_signature =
+            Signature::from_parts(KeyType::MLDSA, &signature.0[ed25519_dalek::SIGNATURE_LENGTH..])
+                .unwrap();
+        assert!(!mldsa_signature.verify(data, &PublicKey::MLDSA(mldsa_key)));
+        let ed25519_signature = Signature::from_parts(
+            KeyType::ED25519,
+            &signature.0[..ed25519_dalek::SIGNATURE_LENGTH],
+        )
+        .unwrap();
+        assert!(ed25519_signature.verify(data, &PublicKey::ED25519(ed25519_key)));
+
+        // Hybrid signatures don’t verify against non-hybrid keys.
+        assert!(!Signature::ED25519MLDSA(signature)
+            .verify(data, &SecretKey::from_seed(KeyType::ED25519, "test").public_key()));
+    }
+
     #[test]
     fn test_invalid_data() {
         let invalid = "\"secp256k1:2xVqteU8PWhadHTv99TGh3bSf\"";
         assert!(serde_json::from_str::<PublicKey>(invalid).is_err());
         assert!(serde_json::from_str::<SecretKey>(invalid).is_err());
         assert!(serde_json::from_str::<Signature>(invalid).is_err());
+        let invalid = "\"mldsa:2xVqteU8PWhadHTv99TGh3bSf\"";
+        assert!(serde_json::from_str::<PublicKey>(invalid).is_err());
+        assert!(serde_json::from_str::<SecretKey>(invalid).is_err());
+        assert!(serde_json::from_str::<Signature>(invalid).is_err());
     }
 }
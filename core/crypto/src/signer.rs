@@ -76,7 +76,11 @@ impl Signer for InMemorySigner {
     }
 
     fn compute_vrf_with_proof(&self, data: &[u8]) -> (crate::vrf::Value, crate::vrf::Proof) {
-        let secret_key = convert_secret_key(self.secret_key.unwrap_as_ed25519());
+        let secret_key = match &self.secret_key {
+            // Hybrid keys derive their VRF key from the classical half.
+            SecretKey::ED25519MLDSA(secret_key) => convert_secret_key(&secret_key.ed25519),
+            secret_key => convert_secret_key(secret_key.unwrap_as_ed25519()),
+        };
         secret_key.compute_vrf_with_proof(&data)
     }
 
@@ -114,3 +118,22 @@ impl From<Arc<InMemorySigner>> for KeyFile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hybrid_signer() {
+        let signer =
+            InMemorySigner::from_seed("test".parse().unwrap(), KeyType::ED25519MLDSA, "test");
+        let signature = signer.sign(b"123");
+        assert!(signer.verify(b"123", &signature));
+
+        // VRF is computed with the ed25519 half of the key.
+        let ed25519_signer =
+            InMemorySigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test");
+        let (value, proof) = signer.compute_vrf_with_proof(b"123");
+        assert_eq!((value, proof), ed25519_signer.compute_vrf_with_proof(b"123"));
+    }
+}
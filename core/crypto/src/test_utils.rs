@@ -1,6 +1,9 @@
 use secp256k1::rand::SeedableRng;
 
-use crate::signature::{ED25519PublicKey, ED25519SecretKey, KeyType, PublicKey, SecretKey};
+use crate::signature::{
+    ED25519PublicKey, ED25519SecretKey, Ed25519MlDsaSecretKey, KeyType, MlDsaSecretKey, PublicKey,
+    SecretKey, SlhDsaSecretKey, MLDSA_SEED_LENGTH, SLHDSA_SEED_LENGTH,
+};
 use crate::{InMemorySigner, Signature};
 use near_account_id::AccountId;
 
@@ -23,6 +26,35 @@ fn secp256k1_secret_key_from_seed(seed: &str) -> secp256k1::SecretKey {
     secp256k1::SecretKey::new(&mut rng)
 }
 
+fn mldsa_secret_key_from_seed(seed: &str) -> MlDsaSecretKey {
+    let seed_bytes = seed.as_bytes();
+    let len = std::cmp::min(MLDSA_SEED_LENGTH, seed_bytes.len());
+    let mut seed: [u8; MLDSA_SEED_LENGTH] = [b' '; MLDSA_SEED_LENGTH];
+    seed[..len].copy_from_slice(&seed_bytes[..len]);
+    MlDsaSecretKey::from_seed_bytes(seed)
+}
+
+fn ed25519_mldsa_secret_key_from_seed(seed: &str) -> Ed25519MlDsaSecretKey {
+    Ed25519MlDsaSecretKey {
+        ed25519: ED25519SecretKey(ed25519_key_pair_from_seed(seed).to_bytes()),
+        mldsa: mldsa_secret_key_from_seed(seed),
+    }
+}
+
+fn slhdsa_secret_key_from_seed(seed: &str) -> SlhDsaSecretKey {
+    let seed_bytes = seed.as_bytes();
+    let len = std::cmp::min(3 * SLHDSA_SEED_LENGTH, seed_bytes.len());
+    let mut seed: [u8; 3 * SLHDSA_SEED_LENGTH] = [b' '; 3 * SLHDSA_SEED_LENGTH];
+    seed[..len].copy_from_slice(&seed_bytes[..len]);
+    let (sk_seed, rest) = seed.split_at(SLHDSA_SEED_LENGTH);
+    let (sk_prf, pk_seed) = rest.split_at(SLHDSA_SEED_LENGTH);
+    SlhDsaSecretKey::from_seeds(
+        sk_seed.try_into().unwrap(),
+        sk_prf.try_into().unwrap(),
+        pk_seed.try_into().unwrap(),
+    )
+}
+
 impl PublicKey {
     pub fn from_seed(key_type: KeyType, seed: &str) -> Self {
         match key_type {
@@ -30,6 +62,13 @@ impl PublicKey {
                 let keypair = ed25519_key_pair_from_seed(seed);
                 PublicKey::ED25519(ED25519PublicKey(keypair.public.to_bytes()))
             }
+            KeyType::MLDSA => {
+                PublicKey::MLDSA(mldsa_secret_key_from_seed(seed).public_key().clone())
+            }
+            KeyType::SLHDSA => PublicKey::SLHDSA(slhdsa_secret_key_from_seed(seed).public_key()),
+            KeyType::ED25519MLDSA => {
+                PublicKey::ED25519MLDSA(ed25519_mldsa_secret_key_from_seed(seed).public_key())
+            }
             _ => unimplemented!(),
         }
     }
@@ -42,6 +81,11 @@ impl SecretKey {
                 let keypair = ed25519_key_pair_from_seed(seed);
                 SecretKey::ED25519(ED25519SecretKey(keypair.to_bytes()))
             }
+            KeyType::MLDSA => SecretKey::MLDSA(mldsa_secret_key_from_seed(seed)),
+            KeyType::SLHDSA => SecretKey::SLHDSA(slhdsa_secret_key_from_seed(seed)),
+            KeyType::ED25519MLDSA => {
+                SecretKey::ED25519MLDSA(ed25519_mldsa_secret_key_from_seed(seed))
+            }
             _ => SecretKey::SECP256K1(secp256k1_secret_key_from_seed(seed)),
         }
     }
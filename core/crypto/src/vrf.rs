@@ -1,3 +1,9 @@
+//! Ristretto VRF used for the randomness beacon.  Its keys are derived from ed25519 keys and
+//! its security relies on the hardness of discrete logarithms.
+//!
+//! There is no post-quantum replacement: a VRF has to produce a unique output for each key
+//! and input, while constructions built on ML-DSA (or any other hedged or randomized
+//! signature scheme) let the prover choose among many valid outputs.
 use crate::util::*;
 use bs58;
 use curve25519_dalek::constants::{
@@ -316,6 +316,26 @@ pub struct ExtCostsConfig {
     #[cfg(feature = "protocol_feature_ed25519_verify")]
     pub ed25519_verify_byte: Gas,
 
+    /// Cost of verifying an ML-DSA signature base
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub mldsa_verify_base: Gas,
+    /// Cost of verifying an ML-DSA signature per message byte
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub mldsa_verify_byte: Gas,
+    /// Cost of verifying an SLH-DSA signature base
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub slhdsa_verify_base: Gas,
+    /// Cost of verifying an SLH-DSA signature per message byte
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub slhdsa_verify_byte: Gas,
+
+    /// Cost of an ML-KEM encapsulation
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    pub ml_kem_encapsulate_base: Gas,
+    /// Cost of an ML-KEM decapsulation and comparison of the shared secret
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    pub ml_kem_decapsulate_verify_base: Gas,
+
     /// Cost of calling ecrecover
     pub ecrecover_base: Gas,
 
@@ -463,6 +483,18 @@ impl ExtCostsConfig {
             ed25519_verify_base: SAFETY_MULTIPLIER * 1513656750,
             #[cfg(feature = "protocol_feature_ed25519_verify")]
             ed25519_verify_byte: SAFETY_MULTIPLIER * 7157035,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_base: SAFETY_MULTIPLIER * 50000000000,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_byte: SAFETY_MULTIPLIER * 141326201,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_base: SAFETY_MULTIPLIER * 300000000000,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_byte: SAFETY_MULTIPLIER * 141326201,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_encapsulate_base: SAFETY_MULTIPLIER * 30000000000,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_decapsulate_verify_base: SAFETY_MULTIPLIER * 40000000000,
             // Cost per byte is 3542227. There are 64 bytes in a block.
             ripemd160_block: SAFETY_MULTIPLIER * 226702528,
             ecrecover_base: SAFETY_MULTIPLIER * 1121789875000,
@@ -535,6 +567,18 @@ impl ExtCostsConfig {
             ed25519_verify_base: 0,
             #[cfg(feature = "protocol_feature_ed25519_verify")]
             ed25519_verify_byte: 0,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_base: 0,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_byte: 0,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_base: 0,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_byte: 0,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_encapsulate_base: 0,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_decapsulate_verify_base: 0,
             ecrecover_base: 0,
             log_base: 0,
             log_byte: 0,
@@ -608,6 +652,18 @@ pub enum ExtCosts {
     ed25519_verify_base,
     #[cfg(feature = "protocol_feature_ed25519_verify")]
     ed25519_verify_byte,
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    mldsa_verify_base,
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    mldsa_verify_byte,
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    slhdsa_verify_base,
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    slhdsa_verify_byte,
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    ml_kem_encapsulate_base,
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    ml_kem_decapsulate_verify_base,
     ecrecover_base,
     log_base,
     log_byte,
@@ -693,6 +749,18 @@ impl ExtCosts {
             ed25519_verify_base => config.ed25519_verify_base,
             #[cfg(feature = "protocol_feature_ed25519_verify")]
             ed25519_verify_byte => config.ed25519_verify_byte,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_base => config.mldsa_verify_base,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            mldsa_verify_byte => config.mldsa_verify_byte,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_base => config.slhdsa_verify_base,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            slhdsa_verify_byte => config.slhdsa_verify_byte,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_encapsulate_base => config.ml_kem_encapsulate_base,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ml_kem_decapsulate_verify_base => config.ml_kem_decapsulate_verify_base,
             ecrecover_base => config.ecrecover_base,
             log_base => config.log_base,
             log_byte => config.log_byte,
@@ -241,6 +241,71 @@ pub fn hash(data: &[u8]) -> CryptoHash {
     CryptoHash::hash_bytes(data)
 }
 
+/// Hash function used to build a [`CryptoHash`].
+///
+/// [`CryptoHash::hash_bytes`] and friends always use SHA-256.  Structures
+/// which may be hashed differently depending on the protocol version (such as
+/// Merkle trees) take the algorithm explicitly so that data produced under an
+/// older protocol version keeps verifying under the hash it was built with.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumString,
+    strum::Display,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum HashAlgorithm {
+    Sha256,
+    #[strum(serialize = "sha3_256")]
+    Sha3_256,
+    #[strum(serialize = "sha512_256")]
+    Sha512_256,
+}
+
+impl HashAlgorithm {
+    /// Calculates hash of given bytes.
+    pub fn hash_bytes(self, bytes: &[u8]) -> CryptoHash {
+        match self {
+            Self::Sha256 => CryptoHash::hash_bytes(bytes),
+            Self::Sha3_256 => digest_bytes::<sha3::Sha3_256>(bytes),
+            Self::Sha512_256 => digest_bytes::<sha2::Sha512_256>(bytes),
+        }
+    }
+
+    /// Calculates hash of borsh-serialised representation of an object.
+    ///
+    /// See [`CryptoHash::hash_borsh`] for caveats when hashing arrays.
+    pub fn hash_borsh<T: BorshSerialize>(self, value: T) -> CryptoHash {
+        match self {
+            Self::Sha256 => CryptoHash::hash_borsh(value),
+            Self::Sha3_256 => digest_borsh::<sha3::Sha3_256, T>(value),
+            Self::Sha512_256 => digest_borsh::<sha2::Sha512_256, T>(value),
+        }
+    }
+}
+
+fn digest_bytes<D: Digest>(bytes: &[u8]) -> CryptoHash {
+    let mut res = CryptoHash::new();
+    res.0.copy_from_slice(&D::digest(bytes));
+    res
+}
+
+fn digest_borsh<D: Digest + Write, T: BorshSerialize>(value: T) -> CryptoHash {
+    let mut hasher = D::new();
+    value.serialize(&mut hasher).unwrap();
+    let mut res = CryptoHash::new();
+    res.0.copy_from_slice(&hasher.finalize());
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +354,22 @@ mod tests {
         slice("CuoNgQBWsXnTqup6FY3UXNz6RRufnYyQVxx8HKZLUaRt", &[b'f', b'o', b'o']);
     }
 
+    #[test]
+    fn test_hash_algorithm() {
+        fn check(want: &str, algorithm: HashAlgorithm) {
+            assert_eq!(want, algorithm.hash_borsh("foo").to_string());
+            assert_eq!(want, algorithm.hash_bytes(&[3, 0, 0, 0, b'f', b'o', b'o']).to_string());
+            assert_eq!(algorithm, HashAlgorithm::from_str(&algorithm.to_string()).unwrap());
+            let json = serde_json::to_string(&algorithm).unwrap();
+            assert_eq!(format!("\"{algorithm}\""), json);
+            assert_eq!(algorithm, serde_json::from_str(&json).unwrap());
+        }
+
+        check("CuoNgQBWsXnTqup6FY3UXNz6RRufnYyQVxx8HKZLUaRt", HashAlgorithm::Sha256);
+        check("4Zjsof6w4PWzLno2pGyABwv1cjorLBZkbTwhb8cAJ4zo", HashAlgorithm::Sha3_256);
+        check("4Zvb8AZrKJex7TXMoJEpzDvV2JhADm95XX8v7j9wuhsf", HashAlgorithm::Sha512_256);
+    }
+
     #[test]
     fn test_base58_successes() {
         for (encoded, hash) in [
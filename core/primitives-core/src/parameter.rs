@@ -110,6 +110,12 @@ pub enum Parameter {
     WasmEcrecoverBase,
     WasmEd25519VerifyBase,
     WasmEd25519VerifyByte,
+    WasmMldsaVerifyBase,
+    WasmMldsaVerifyByte,
+    WasmSlhdsaVerifyBase,
+    WasmSlhdsaVerifyByte,
+    WasmMlKemEncapsulateBase,
+    WasmMlKemDecapsulateVerifyBase,
     WasmLogBase,
     WasmLogByte,
     WasmStorageWriteBase,
@@ -240,6 +246,12 @@ impl Parameter {
             Parameter::WasmEcrecoverBase,
             Parameter::WasmEd25519VerifyBase,
             Parameter::WasmEd25519VerifyByte,
+            Parameter::WasmMldsaVerifyBase,
+            Parameter::WasmMldsaVerifyByte,
+            Parameter::WasmSlhdsaVerifyBase,
+            Parameter::WasmSlhdsaVerifyByte,
+            Parameter::WasmMlKemEncapsulateBase,
+            Parameter::WasmMlKemDecapsulateVerifyBase,
             Parameter::WasmLogBase,
             Parameter::WasmLogByte,
             Parameter::WasmStorageWriteBase,
@@ -256,6 +256,18 @@ impl Cost {
         Cost::ExtCost { ext_cost_kind: ExtCosts::ed25519_verify_base },
         #[cfg(feature = "protocol_feature_ed25519_verify")]
         Cost::ExtCost { ext_cost_kind: ExtCosts::ed25519_verify_byte },
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::mldsa_verify_base },
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::mldsa_verify_byte },
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::slhdsa_verify_base },
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::slhdsa_verify_byte },
+        #[cfg(feature = "protocol_feature_ml_kem")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::ml_kem_encapsulate_base },
+        #[cfg(feature = "protocol_feature_ml_kem")]
+        Cost::ExtCost { ext_cost_kind: ExtCosts::ml_kem_decapsulate_verify_base },
     ];
 
     pub fn index(self) -> usize {
@@ -334,10 +346,39 @@ impl Cost {
             Cost::ExtCost { ext_cost_kind: ExtCosts::ed25519_verify_base } => 70,
             #[cfg(feature = "protocol_feature_ed25519_verify")]
             Cost::ExtCost { ext_cost_kind: ExtCosts::ed25519_verify_byte } => 71,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::mldsa_verify_base } => PQ_VERIFY_INDEX,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::mldsa_verify_byte } => PQ_VERIFY_INDEX + 1,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::slhdsa_verify_base } => PQ_VERIFY_INDEX + 2,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::slhdsa_verify_byte } => PQ_VERIFY_INDEX + 3,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::ml_kem_encapsulate_base } => ML_KEM_INDEX,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            Cost::ExtCost { ext_cost_kind: ExtCosts::ml_kem_decapsulate_verify_base } => {
+                ML_KEM_INDEX + 1
+            }
         }
     }
 }
 
+/// First profile index of the post-quantum signature verification costs.  They
+/// follow the ed25519 ones, which are only present if that feature is enabled.
+#[cfg(any(feature = "protocol_feature_post_quantum_verify", feature = "protocol_feature_ml_kem"))]
+const PQ_VERIFY_INDEX: usize =
+    if cfg!(feature = "protocol_feature_ed25519_verify") { 72 } else { 70 };
+
+/// First profile index of the ML-KEM costs, following the post-quantum
+/// signature verification ones if they are enabled.
+#[cfg(feature = "protocol_feature_ml_kem")]
+const ML_KEM_INDEX: usize = if cfg!(feature = "protocol_feature_post_quantum_verify") {
+    PQ_VERIFY_INDEX + 4
+} else {
+    PQ_VERIFY_INDEX
+};
+
 impl Index<Cost> for ProfileData {
     type Output = u64;
 
@@ -15,7 +15,7 @@ use crate::block::BlockValidityError::{
 pub use crate::block_header::*;
 use crate::challenge::{Challenges, ChallengesResult};
 use crate::hash::{hash, CryptoHash};
-use crate::merkle::{merklize, verify_path, MerklePath};
+use crate::merkle::{merkle_hash_algorithm, merklize, merklize_with, verify_path, MerklePath};
 use crate::num_rational::Rational32;
 use crate::sharding::{
     ChunkHashHeight, EncodedShardChunk, ReedSolomonWrapper, ShardChunk, ShardChunkHeader,
@@ -166,7 +166,7 @@ impl Block {
             genesis_protocol_version,
             height,
             Block::compute_state_root(&chunks),
-            Block::compute_chunk_receipts_root(&chunks),
+            Block::compute_chunk_receipts_root(genesis_protocol_version, &chunks),
             Block::compute_chunk_headers_root(&chunks).0,
             Block::compute_chunk_tx_root(&chunks),
             chunks.len() as u64,
@@ -270,10 +270,10 @@ impl Block {
             height,
             *prev.hash(),
             Block::compute_state_root(&chunks),
-            Block::compute_chunk_receipts_root(&chunks),
+            Block::compute_chunk_receipts_root(this_epoch_protocol_version, &chunks),
             Block::compute_chunk_headers_root(&chunks).0,
             Block::compute_chunk_tx_root(&chunks),
-            Block::compute_outcome_root(&chunks),
+            Block::compute_outcome_root(this_epoch_protocol_version, &chunks),
             time,
             Block::compute_challenges_root(&challenges),
             random_value,
@@ -360,10 +360,14 @@ impl Block {
         .0
     }
 
+    /// Computes the root of chunks’ outgoing receipts roots, hashing with the
+    /// algorithm of given protocol version of the block’s epoch.
     pub fn compute_chunk_receipts_root<'a, T: IntoIterator<Item = &'a ShardChunkHeader>>(
+        protocol_version: ProtocolVersion,
         chunks: T,
     ) -> CryptoHash {
-        merklize(
+        merklize_with(
+            merkle_hash_algorithm(protocol_version),
             &chunks
                 .into_iter()
                 .map(|chunk| chunk.outgoing_receipts_root())
@@ -389,11 +393,17 @@ impl Block {
         merklize(&chunks.into_iter().map(|chunk| chunk.tx_root()).collect::<Vec<CryptoHash>>()).0
     }
 
+    /// Computes the root of chunks’ outcome roots, hashing with the algorithm
+    /// of given protocol version of the block’s epoch.
     pub fn compute_outcome_root<'a, T: IntoIterator<Item = &'a ShardChunkHeader>>(
+        protocol_version: ProtocolVersion,
         chunks: T,
     ) -> CryptoHash {
-        merklize(&chunks.into_iter().map(|chunk| chunk.outcome_root()).collect::<Vec<CryptoHash>>())
-            .0
+        merklize_with(
+            merkle_hash_algorithm(protocol_version),
+            &chunks.into_iter().map(|chunk| chunk.outcome_root()).collect::<Vec<CryptoHash>>(),
+        )
+        .0
     }
 
     pub fn compute_challenges_root(challenges: &Challenges) -> CryptoHash {
@@ -483,8 +493,12 @@ impl Block {
         self.header().hash()
     }
 
-    /// Checks that block content matches block hash, with the possible exception of chunk signatures
-    pub fn check_validity(&self) -> Result<(), BlockValidityError> {
+    /// Checks that block content matches block hash, with the possible exception of chunk signatures.
+    /// `protocol_version` is the protocol version of the block’s epoch.
+    pub fn check_validity(
+        &self,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BlockValidityError> {
         // Check that state root stored in the header matches the state root of the chunks
         let state_root = Block::compute_state_root(self.chunks().iter());
         if self.header().prev_state_root() != &state_root {
@@ -492,7 +506,8 @@ impl Block {
         }
 
         // Check that chunk receipts root stored in the header matches the state root of the chunks
-        let chunk_receipts_root = Block::compute_chunk_receipts_root(self.chunks().iter());
+        let chunk_receipts_root =
+            Block::compute_chunk_receipts_root(protocol_version, self.chunks().iter());
         if self.header().chunk_receipts_root() != &chunk_receipts_root {
             return Err(InvalidReceiptRoot);
         }
@@ -5,7 +5,7 @@ use near_crypto::Signature;
 
 use crate::hash::CryptoHash;
 use crate::merkle::MerklePath;
-use crate::sharding::{EncodedShardChunk, ShardChunk, ShardChunkHeader};
+use crate::sharding::{EncodedShardChunk, ReceiptProof, ShardChunk, ShardChunkHeader};
 use crate::types::AccountId;
 use crate::validator_signer::ValidatorSigner;
 
@@ -50,11 +50,13 @@ pub enum MaybeEncodedShardChunk {
 }
 
 /// Doesn't match post-{state root, outgoing receipts, gas used, etc} results after applying previous chunk.
+/// Superseded by `ChunkStateV2`: it lacks the incoming receipts, so it can't be checked and is
+/// always considered malicious.
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug)]
 pub struct ChunkState {
     /// Encoded prev block header.
     pub prev_block_header: Vec<u8>,
-    /// Encoded block header that contains invalid chunnk.
+    /// Encoded block header that contains invalid chunk.
     pub block_header: Vec<u8>,
     /// Merkle proof in inclusion of prev chunk.
     pub prev_merkle_proof: MerklePath,
@@ -68,6 +70,44 @@ pub struct ChunkState {
     pub partial_state: PartialState,
 }
 
+/// Doesn't match post-{state root, outgoing receipts, gas used, etc} results after applying previous chunk.
+/// Accepted since `ProtocolFeature::ChunkStateChallenges`.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ChunkStateV2 {
+    /// Encoded prev block header.
+    pub prev_block_header: Vec<u8>,
+    /// Encoded block header that contains invalid chunk.
+    pub block_header: Vec<u8>,
+    /// Merkle proof in inclusion of prev chunk.
+    pub prev_merkle_proof: MerklePath,
+    /// Previous chunk that contains transactions.
+    pub prev_chunk: ShardChunk,
+    /// Merkle proof of inclusion of this chunk.
+    pub merkle_proof: MerklePath,
+    /// Invalid chunk header.
+    pub chunk_header: ShardChunkHeader,
+    /// Partial state that was affected by transactions of given chunk.
+    pub partial_state: PartialState,
+    /// Receipts the previous chunk was applied with, grouped by the blocks which included their
+    /// source chunks, starting from the previous block.
+    pub incoming_receipts: Vec<ChunkStateIncomingReceipts>,
+    /// Encoded header of the block which included the chunk of the same shard before the previous
+    /// chunk. The blocks of `incoming_receipts` have to lead exactly to this one.
+    pub last_chunk_block_header: Vec<u8>,
+}
+
+/// Receipts sent to the shard of a `ChunkStateV2` challenge by the new chunks of one block.
+/// Carries everything needed to check them against the block header, so that the challenge can be
+/// checked by the nodes which don't have the block.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ChunkStateIncomingReceipts {
+    /// Encoded block header.
+    pub block_header: Vec<u8>,
+    /// Receipt proofs in the order the receipts are applied in, each with the header of the chunk
+    /// which sent the receipts and the Merkle proof of its inclusion in the block.
+    pub receipt_proofs: Vec<(ReceiptProof, ShardChunkHeader, MerklePath)>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug)]
 // TODO(#1313): Use Box
 #[allow(clippy::large_enum_variant)]
@@ -75,6 +115,7 @@ pub enum ChallengeBody {
     BlockDoubleSign(BlockDoubleSign),
     ChunkProofs(ChunkProofs),
     ChunkState(ChunkState),
+    ChunkStateV2(ChunkStateV2),
 }
 
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Debug)]
@@ -140,6 +140,8 @@ pub enum InvalidTxError {
     ActionsValidation(ActionsValidationError),
     /// The size of serialized transaction exceeded the limit.
     TransactionSizeExceeded { size: u64, limit: u64 },
+    /// The transaction has a priority fee, but the protocol version doesn't support them yet.
+    PriorityFeeNotSupported,
 }
 
 impl std::error::Error for InvalidTxError {}
@@ -458,6 +460,9 @@ impl Display for InvalidTxError {
             InvalidTxError::TransactionSizeExceeded { size, limit } => {
                 write!(f, "Size of serialized transaction {} exceeded the limit {}", size, limit)
             }
+            InvalidTxError::PriorityFeeNotSupported => {
+                write!(f, "Transaction priority fees are not supported by the protocol version")
+            }
         }
     }
 }
@@ -1,8 +1,10 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::hash::CryptoHash;
+use crate::checked_feature;
+use crate::hash::{CryptoHash, HashAlgorithm};
 use crate::types::MerkleHash;
+use crate::version::ProtocolVersion;
 
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct MerklePathItem {
@@ -18,17 +20,52 @@ pub enum Direction {
     Right,
 }
 
+/// Returns the hash function used by Merkle trees whose roots are committed to
+/// by blocks of given protocol version.
+///
+/// This covers chunk receipts and outcome trees as well as the block Merkle
+/// tree.  The block Merkle tree accumulates hashes of all the blocks of the
+/// chain, so it isn’t rebuilt when the function changes: each of its complete
+/// subtrees keeps the hash of the block whose insertion completed it, and only
+/// the nodes above them are hashed with the function of the block committing to
+/// the root.  See [`PartialMerkleTree`].
+pub fn merkle_hash_algorithm(protocol_version: ProtocolVersion) -> HashAlgorithm {
+    if checked_feature!("protocol_feature_sha512_merkle", Sha512Merkle, protocol_version) {
+        HashAlgorithm::Sha512_256
+    } else if checked_feature!("protocol_feature_sha3_merkle", Sha3Merkle, protocol_version) {
+        HashAlgorithm::Sha3_256
+    } else {
+        HashAlgorithm::Sha256
+    }
+}
+
 pub fn combine_hash(hash1: &MerkleHash, hash2: &MerkleHash) -> MerkleHash {
-    CryptoHash::hash_borsh((hash1, hash2))
+    combine_hash_with(HashAlgorithm::Sha256, hash1, hash2)
+}
+
+pub fn combine_hash_with(
+    algorithm: HashAlgorithm,
+    hash1: &MerkleHash,
+    hash2: &MerkleHash,
+) -> MerkleHash {
+    algorithm.hash_borsh((hash1, hash2))
 }
 
 /// Merklize an array of items. If the array is empty, returns hash of 0
 pub fn merklize<T: BorshSerialize>(arr: &[T]) -> (MerkleHash, Vec<MerklePath>) {
+    merklize_with(HashAlgorithm::Sha256, arr)
+}
+
+/// Same as [`merklize`] but hashes leaves and nodes with given algorithm.
+pub fn merklize_with<T: BorshSerialize>(
+    algorithm: HashAlgorithm,
+    arr: &[T],
+) -> (MerkleHash, Vec<MerklePath>) {
     if arr.is_empty() {
         return (MerkleHash::default(), vec![]);
     }
     let mut len = arr.len().next_power_of_two();
-    let mut hashes = arr.iter().map(CryptoHash::hash_borsh).collect::<Vec<_>>();
+    let mut hashes = arr.iter().map(|item| algorithm.hash_borsh(item)).collect::<Vec<_>>();
 
     // degenerate case
     if len == 1 {
@@ -62,7 +99,7 @@ pub fn merklize<T: BorshSerialize>(arr: &[T]) -> (MerkleHash, Vec<MerklePath>) {
             } else if 2 * i + 1 >= arr_len {
                 hashes[2 * i]
             } else {
-                combine_hash(&hashes[2 * i], &hashes[2 * i + 1])
+                combine_hash_with(algorithm, &hashes[2 * i], &hashes[2 * i + 1])
             };
             hashes[i] = hash;
             if len > 1 {
@@ -90,22 +127,84 @@ pub fn merklize<T: BorshSerialize>(arr: &[T]) -> (MerkleHash, Vec<MerklePath>) {
 
 /// Verify merkle path for given item and corresponding path.
 pub fn verify_path<T: BorshSerialize>(root: MerkleHash, path: &MerklePath, item: T) -> bool {
-    verify_hash(root, path, CryptoHash::hash_borsh(item))
+    verify_path_with(HashAlgorithm::Sha256, root, path, item)
+}
+
+/// Same as [`verify_path`] for a tree built with given algorithm.
+pub fn verify_path_with<T: BorshSerialize>(
+    algorithm: HashAlgorithm,
+    root: MerkleHash,
+    path: &MerklePath,
+    item: T,
+) -> bool {
+    verify_hash_with(algorithm, root, path, algorithm.hash_borsh(item))
 }
 
 pub fn verify_hash(root: MerkleHash, path: &MerklePath, item_hash: MerkleHash) -> bool {
-    compute_root_from_path(path, item_hash) == root
+    verify_hash_with(HashAlgorithm::Sha256, root, path, item_hash)
+}
+
+pub fn verify_hash_with(
+    algorithm: HashAlgorithm,
+    root: MerkleHash,
+    path: &MerklePath,
+    item_hash: MerkleHash,
+) -> bool {
+    compute_root_from_path_with(algorithm, path, item_hash) == root
 }
 
 pub fn compute_root_from_path(path: &MerklePath, item_hash: MerkleHash) -> MerkleHash {
+    compute_root_from_path_with(HashAlgorithm::Sha256, path, item_hash)
+}
+
+pub fn compute_root_from_path_with(
+    algorithm: HashAlgorithm,
+    path: &MerklePath,
+    item_hash: MerkleHash,
+) -> MerkleHash {
     let mut res = item_hash;
     for item in path {
         match item.direction {
             Direction::Left => {
-                res = combine_hash(&item.hash, &res);
+                res = combine_hash_with(algorithm, &item.hash, &res);
             }
             Direction::Right => {
-                res = combine_hash(&res, &item.hash);
+                res = combine_hash_with(algorithm, &res, &item.hash);
+            }
+        }
+    }
+    res
+}
+
+/// Same as [`verify_hash_with`] for a path whose nodes were not all hashed
+/// with the same algorithm.  `algorithms` must hold the algorithm of each
+/// item of `path`.
+pub fn verify_hash_with_algorithms(
+    algorithms: &[HashAlgorithm],
+    root: MerkleHash,
+    path: &MerklePath,
+    item_hash: MerkleHash,
+) -> bool {
+    algorithms.len() == path.len()
+        && compute_root_from_path_with_algorithms(algorithms, path, item_hash) == root
+}
+
+/// Same as [`compute_root_from_path_with`] for a path whose nodes were not all
+/// hashed with the same algorithm.  Item `i` of `path` is combined using
+/// `algorithms[i]`; extra items on either side are ignored.
+pub fn compute_root_from_path_with_algorithms(
+    algorithms: &[HashAlgorithm],
+    path: &MerklePath,
+    item_hash: MerkleHash,
+) -> MerkleHash {
+    let mut res = item_hash;
+    for (item, &algorithm) in path.iter().zip(algorithms) {
+        match item.direction {
+            Direction::Left => {
+                res = combine_hash_with(algorithm, &item.hash, &res);
+            }
+            Direction::Right => {
+                res = combine_hash_with(algorithm, &res, &item.hash);
             }
         }
     }
@@ -116,7 +215,15 @@ pub fn compute_root_from_path_and_item<T: BorshSerialize>(
     path: &MerklePath,
     item: T,
 ) -> MerkleHash {
-    compute_root_from_path(path, CryptoHash::hash_borsh(item))
+    compute_root_from_path_and_item_with(HashAlgorithm::Sha256, path, item)
+}
+
+pub fn compute_root_from_path_and_item_with<T: BorshSerialize>(
+    algorithm: HashAlgorithm,
+    path: &MerklePath,
+    item: T,
+) -> MerkleHash {
+    compute_root_from_path_with(algorithm, path, algorithm.hash_borsh(item))
 }
 
 /// Merkle tree that only maintains the path for the next leaf, i.e,
@@ -124,6 +231,12 @@ pub fn compute_root_from_path_and_item<T: BorshSerialize>(
 /// The root can be computed by folding `path` from right but is not explicitly
 /// maintained to save space.
 /// The size of the object is O(log(n)) where n is the number of leaves in the tree, i.e, `size`.
+///
+/// The tree doesn’t record which hash function its nodes were built with.  Each
+/// element of `path` is hashed with the algorithm passed to the insertion that
+/// completed it, so the algorithm may change between insertions without
+/// rehashing older subtrees.  The root must be computed with the algorithm of
+/// the last insertion.
 #[derive(Default, Clone, BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug)]
 pub struct PartialMerkleTree {
     /// Path for the next leaf.
@@ -134,24 +247,32 @@ pub struct PartialMerkleTree {
 
 impl PartialMerkleTree {
     pub fn root(&self) -> MerkleHash {
+        self.root_with(HashAlgorithm::Sha256)
+    }
+
+    pub fn root_with(&self, algorithm: HashAlgorithm) -> MerkleHash {
         if self.path.is_empty() {
             CryptoHash::default()
         } else {
             let mut res = *self.path.last().unwrap();
             let len = self.path.len();
             for i in (0..len - 1).rev() {
-                res = combine_hash(&self.path[i], &res);
+                res = combine_hash_with(algorithm, &self.path[i], &res);
             }
             res
         }
     }
 
     pub fn insert(&mut self, elem: MerkleHash) {
+        self.insert_with(HashAlgorithm::Sha256, elem)
+    }
+
+    pub fn insert_with(&mut self, algorithm: HashAlgorithm, elem: MerkleHash) {
         let mut s = self.size;
         let mut node = elem;
         while s % 2 == 1 {
             let last_path_elem = self.path.pop().unwrap();
-            node = combine_hash(&last_path_elem, &node);
+            node = combine_hash_with(algorithm, &last_path_elem, &node);
             s /= 2;
         }
         self.path.push(node);
@@ -184,6 +305,15 @@ mod tests {
         for (i, item) in arr.iter().enumerate() {
             assert!(verify_path(root, &paths[i], item));
         }
+
+        for algorithm in [HashAlgorithm::Sha3_256, HashAlgorithm::Sha512_256] {
+            let (other_root, other_paths) = merklize_with(algorithm, &arr);
+            assert_ne!(root, other_root);
+            for (i, item) in arr.iter().enumerate() {
+                assert!(verify_path_with(algorithm, other_root, &other_paths[i], item));
+                assert!(!verify_path(other_root, &other_paths[i], item));
+            }
+        }
     }
 
     #[test]
@@ -214,7 +344,7 @@ mod tests {
     }
 
     /// Compute the merkle root of a given array.
-    fn compute_root(hashes: &[CryptoHash]) -> CryptoHash {
+    fn compute_root(algorithm: HashAlgorithm, hashes: &[CryptoHash]) -> CryptoHash {
         if hashes.is_empty() {
             CryptoHash::default()
         } else if hashes.len() == 1 {
@@ -222,9 +352,9 @@ mod tests {
         } else {
             let len = hashes.len();
             let subtree_len = len.next_power_of_two() / 2;
-            let left_root = compute_root(&hashes[0..subtree_len]);
-            let right_root = compute_root(&hashes[subtree_len..len]);
-            combine_hash(&left_root, &right_root)
+            let left_root = compute_root(algorithm, &hashes[0..subtree_len]);
+            let right_root = compute_root(algorithm, &hashes[subtree_len..len]);
+            combine_hash_with(algorithm, &left_root, &right_root)
         }
     }
 
@@ -233,13 +363,82 @@ mod tests {
         let mut tree = PartialMerkleTree::default();
         let mut hashes = vec![];
         for i in 0..50 {
-            assert_eq!(compute_root(&hashes), tree.root());
+            assert_eq!(compute_root(HashAlgorithm::Sha256, &hashes), tree.root());
             let cur_hash = CryptoHash::hash_bytes(&[i]);
             hashes.push(cur_hash);
             tree.insert(cur_hash);
         }
     }
 
+    #[test]
+    fn test_merkle_tree_with_algorithm() {
+        for algorithm in [HashAlgorithm::Sha3_256, HashAlgorithm::Sha512_256] {
+            let mut tree = PartialMerkleTree::default();
+            let mut hashes = vec![];
+            for i in 0..50 {
+                assert_eq!(compute_root(algorithm, &hashes), tree.root_with(algorithm));
+                let cur_hash = CryptoHash::hash_bytes(&[i]);
+                hashes.push(cur_hash);
+                tree.insert_with(algorithm, cur_hash);
+            }
+            assert_ne!(tree.root_with(algorithm), tree.root());
+        }
+    }
+
+    /// Compute the merkle root of a given array whose `i`-th element was
+    /// inserted with `algorithms[i]`.  Complete subtrees are hashed with the
+    /// algorithm of their last element, the nodes above them with `root_algorithm`.
+    fn compute_mixed_root(
+        algorithms: &[HashAlgorithm],
+        root_algorithm: HashAlgorithm,
+        hashes: &[CryptoHash],
+    ) -> CryptoHash {
+        if hashes.is_empty() {
+            CryptoHash::default()
+        } else if hashes.len() == 1 {
+            hashes[0]
+        } else {
+            let len = hashes.len();
+            let subtree_len = len.next_power_of_two() / 2;
+            let algorithm =
+                if len.is_power_of_two() { algorithms[len - 1] } else { root_algorithm };
+            let left_root = compute_mixed_root(
+                &algorithms[0..subtree_len],
+                root_algorithm,
+                &hashes[0..subtree_len],
+            );
+            let right_root = compute_mixed_root(
+                &algorithms[subtree_len..len],
+                root_algorithm,
+                &hashes[subtree_len..len],
+            );
+            combine_hash_with(algorithm, &left_root, &right_root)
+        }
+    }
+
+    #[test]
+    fn test_merkle_tree_algorithm_change() {
+        let algorithm_of = |i: usize| match i {
+            0..=12 => HashAlgorithm::Sha256,
+            13..=36 => HashAlgorithm::Sha3_256,
+            _ => HashAlgorithm::Sha512_256,
+        };
+        let mut tree = PartialMerkleTree::default();
+        let mut hashes = vec![];
+        let mut algorithms = vec![];
+        for i in 0..50 {
+            let cur_hash = CryptoHash::hash_bytes(&[i as u8]);
+            let algorithm = algorithm_of(i);
+            hashes.push(cur_hash);
+            algorithms.push(algorithm);
+            tree.insert_with(algorithm, cur_hash);
+            assert_eq!(
+                compute_mixed_root(&algorithms, algorithm, &hashes),
+                tree.root_with(algorithm)
+            );
+        }
+    }
+
     #[test]
     fn test_combine_hash_stability() {
         let a = MerkleHash::default();
@@ -252,5 +451,19 @@ mod tests {
                 61, 35, 32, 217, 240, 232, 234, 152, 49, 169, 39, 89, 251, 75
             ]
         );
+        assert_eq!(
+            combine_hash_with(HashAlgorithm::Sha3_256, &a, &b).0,
+            [
+                7, 15, 161, 171, 111, 204, 85, 126, 209, 77, 66, 148, 31, 25, 103, 105, 48, 72, 85,
+                30, 185, 4, 42, 141, 10, 5, 122, 251, 215, 94, 129, 224
+            ]
+        );
+        assert_eq!(
+            combine_hash_with(HashAlgorithm::Sha512_256, &a, &b).0,
+            [
+                138, 238, 207, 160, 185, 242, 172, 120, 24, 134, 59, 19, 98, 36, 30, 79, 50, 208,
+                107, 16, 10, 233, 209, 192, 251, 204, 78, 214, 27, 145, 177, 122
+            ]
+        );
     }
 }
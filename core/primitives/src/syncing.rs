@@ -191,10 +191,16 @@ impl ShardStateSyncResponseV1 {
 
 #[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
 pub struct EpochSyncFinalizationResponse {
+    /// Header of the first block of the epoch being synced to.
     pub cur_epoch_header: BlockHeader,
+    /// Headers of the previous epoch, starting at the block its light client block
+    /// was created for and ending at its last block, the parent of `cur_epoch_header`.
+    /// They link `cur_epoch_header` to an already validated block and are needed by
+    /// State Sync to validate the state header.
     pub prev_epoch_headers: Vec<BlockHeader>,
-    pub header_sync_init_header: BlockHeader,
-    pub header_sync_init_header_tree: PartialMerkleTree,
+    /// Block merkle tree of the last block of the previous epoch.  Header Sync
+    /// validates `block_merkle_root` of every next header against it.
+    pub prev_epoch_last_header_tree: PartialMerkleTree,
     // This Block Info is required by Epoch Manager when it checks if it's a good time to start a new Epoch.
     // Epoch Manager asks for height difference by obtaining first Block Info of the Epoch.
     pub prev_epoch_first_block_info: BlockInfo,
@@ -213,7 +219,9 @@ pub struct EpochSyncFinalizationResponse {
 
 #[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Clone)]
 pub enum EpochSyncResponse {
+    /// The requested epoch is the latest epoch known to the peer.
     UpToDate,
+    /// Light client block of the last final block of the requested epoch.
     Advance { light_client_block_view: LightClientBlockView },
 }
 
@@ -1,20 +1,20 @@
 //! Types for telemetry reporting. Can be received by any telemetry dashboard to display
 //! node count and their status across the network.
 use near_primitives_core::hash::CryptoHash;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::types::BlockHeight;
 
 use crate::types::AccountId;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TelemetryAgentInfo {
     pub name: String,
     pub version: String,
     pub build: String,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TelemetrySystemInfo {
     pub bandwidth_download: u64,
     pub bandwidth_upload: u64,
@@ -23,7 +23,7 @@ pub struct TelemetrySystemInfo {
     pub boot_time_seconds: i64,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TelemetryChainInfo {
     pub node_id: String,
     pub account_id: Option<AccountId>,
@@ -38,7 +38,7 @@ pub struct TelemetryChainInfo {
     pub max_block_wait_delay: f64,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TelemetryInfo {
     pub agent: TelemetryAgentInfo,
     pub system: TelemetrySystemInfo,
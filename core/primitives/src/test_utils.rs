@@ -9,7 +9,7 @@ use crate::block::Block;
 use crate::block_header::{BlockHeader, BlockHeaderV3};
 use crate::errors::EpochError;
 use crate::hash::CryptoHash;
-use crate::merkle::PartialMerkleTree;
+use crate::merkle::{merkle_hash_algorithm, PartialMerkleTree};
 use crate::num_rational::Ratio;
 use crate::sharding::ShardChunkHeader;
 use crate::transaction::{
@@ -108,6 +108,7 @@ impl SignedTransaction {
             receiver_id,
             block_hash,
             actions,
+            priority_fee: 0,
         }
         .sign(signer)
     }
@@ -354,7 +355,7 @@ impl Block {
         signer: &dyn ValidatorSigner,
         block_merkle_tree: &mut PartialMerkleTree,
     ) -> Self {
-        block_merkle_tree.insert(*prev.hash());
+        block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *prev.hash());
         Self::empty_with_approvals(
             prev,
             height,
@@ -363,7 +364,7 @@ impl Block {
             vec![],
             signer,
             next_bp_hash,
-            block_merkle_tree.root(),
+            block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
         )
     }
 
@@ -18,7 +18,7 @@ use crate::types::{AccountId, Balance, Gas, Nonce};
 
 pub type LogEntry = String;
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Transaction {
     /// An account on which behalf transaction is signed
     pub signer_id: AccountId,
@@ -34,6 +34,78 @@ pub struct Transaction {
     pub block_hash: CryptoHash,
     /// A list of actions to be applied
     pub actions: Vec<Action>,
+    /// Amount paid per unit of gas burnt for converting the transaction, on top of the gas
+    /// price.  Chunk producers may use it to prioritize transactions.
+    #[serde(default)]
+    pub priority_fee: u64,
+}
+
+/// The first byte of the Borsh encoding of transactions with a non-zero priority fee.
+/// Encodings of transactions without a priority fee start with the length of `signer_id`,
+/// which is between 2 and 64, so they never start with this byte.
+const PRIORITY_FEE_TRANSACTION_TAG: u8 = 1;
+
+/// Borsh layout of transactions without a priority fee.
+#[derive(BorshDeserialize)]
+struct LegacyTransaction {
+    signer_id: AccountId,
+    public_key: PublicKey,
+    nonce: Nonce,
+    receiver_id: AccountId,
+    block_hash: CryptoHash,
+    actions: Vec<Action>,
+}
+
+impl BorshSerialize for Transaction {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        // Transactions without a priority fee keep the legacy encoding, and thus their hashes.
+        if self.priority_fee != 0 {
+            PRIORITY_FEE_TRANSACTION_TAG.serialize(writer)?;
+        }
+        self.signer_id.serialize(writer)?;
+        self.public_key.serialize(writer)?;
+        self.nonce.serialize(writer)?;
+        self.receiver_id.serialize(writer)?;
+        self.block_hash.serialize(writer)?;
+        self.actions.serialize(writer)?;
+        if self.priority_fee != 0 {
+            self.priority_fee.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Transaction {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let has_priority_fee = buf.first() == Some(&PRIORITY_FEE_TRANSACTION_TAG);
+        if has_priority_fee {
+            *buf = &buf[1..];
+        }
+        let LegacyTransaction { signer_id, public_key, nonce, receiver_id, block_hash, actions } =
+            LegacyTransaction::deserialize(buf)?;
+        let priority_fee = if has_priority_fee {
+            let priority_fee = u64::deserialize(buf)?;
+            // Otherwise the same transaction would have two encodings and two hashes.
+            if priority_fee == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "zero priority fee must use the legacy transaction encoding",
+                ));
+            }
+            priority_fee
+        } else {
+            0
+        };
+        Ok(Transaction {
+            signer_id,
+            public_key,
+            nonce,
+            receiver_id,
+            block_hash,
+            actions,
+            priority_fee,
+        })
+    }
 }
 
 impl Transaction {
@@ -463,6 +535,7 @@ mod tests {
             receiver_id: "test".parse().unwrap(),
             block_hash: Default::default(),
             actions: vec![],
+            priority_fee: 0,
         }
         .sign(&signer);
         let wrong_public_key = PublicKey::from_seed(KeyType::ED25519, "wrong");
@@ -515,6 +588,7 @@ mod tests {
                     beneficiary_id: "123".parse().unwrap(),
                 }),
             ],
+            priority_fee: 0,
         };
         let signed_tx = SignedTransaction::new(Signature::empty(KeyType::ED25519), transaction);
         let new_signed_tx =
@@ -526,6 +600,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialize_transaction_with_priority_fee() {
+        let mut transaction = Transaction {
+            signer_id: "test.near".parse().unwrap(),
+            public_key: PublicKey::from_seed(KeyType::ED25519, "test.near"),
+            nonce: 1,
+            receiver_id: "123".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![Action::Transfer(TransferAction { deposit: 123 })],
+            priority_fee: 0,
+        };
+        let legacy_bytes = transaction.try_to_vec().unwrap();
+        assert_eq!(legacy_bytes[0], "test.near".len() as u8);
+        assert_eq!(Transaction::try_from_slice(&legacy_bytes).unwrap(), transaction);
+
+        transaction.priority_fee = 42;
+        let bytes = transaction.try_to_vec().unwrap();
+        assert_eq!(bytes[0], PRIORITY_FEE_TRANSACTION_TAG);
+        assert_eq!(&bytes[1..legacy_bytes.len() + 1], &legacy_bytes[..]);
+        assert_eq!(&bytes[legacy_bytes.len() + 1..], &42u64.to_le_bytes());
+        assert_eq!(Transaction::try_from_slice(&bytes).unwrap(), transaction);
+        assert_ne!(transaction.get_hash_and_size().0, hash(&legacy_bytes));
+
+        // A zero priority fee has a single encoding.
+        let mut bytes = bytes;
+        let len = bytes.len();
+        bytes[len - 8..].copy_from_slice(&0u64.to_le_bytes());
+        assert!(Transaction::try_from_slice(&bytes).is_err());
+    }
+
     #[test]
     fn test_outcome_to_hashes() {
         let outcome = ExecutionOutcome {
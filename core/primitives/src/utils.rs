@@ -4,6 +4,7 @@ use std::fmt;
 
 use chrono;
 use chrono::{DateTime, NaiveDateTime};
+use near_crypto::{key_conversion, PublicKey};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use serde;
@@ -311,6 +312,21 @@ pub fn create_random_seed(
     res.as_ref().to_vec()
 }
 
+/// Checks whether `public_key` can be used as a validator staking key.
+/// Hybrid ed25519 + ML-DSA keys are accepted starting with
+/// `PostQuantumValidatorKeys`, before that only ed25519 keys are.
+pub fn is_valid_staking_key(public_key: &PublicKey, protocol_version: ProtocolVersion) -> bool {
+    if checked_feature!(
+        "protocol_feature_post_quantum_validator_keys",
+        PostQuantumValidatorKeys,
+        protocol_version
+    ) {
+        key_conversion::is_valid_post_quantum_staking_key(public_key)
+    } else {
+        key_conversion::is_valid_staking_key(public_key)
+    }
+}
+
 /// Creates a new CryptoHash ID based on the protocol version.
 /// Before `CREATE_HASH_PROTOCOL_VERSION` it uses `create_nonce_with_nonce` with
 /// just `base` and `salt`. But after `CREATE_HASH_PROTOCOL_VERSION` it uses
@@ -151,6 +151,30 @@ pub enum ProtocolFeature {
     RejectBlocksWithOutdatedProtocolVersions,
     #[cfg(feature = "shardnet")]
     ShardnetShardLayoutUpgrade,
+    /// Allow validators to stake with hybrid ed25519 + ML-DSA keys.  Block, chunk
+    /// and approval signatures of such validators must verify under both halves.
+    #[cfg(feature = "protocol_feature_post_quantum_validator_keys")]
+    PostQuantumValidatorKeys,
+    /// Host functions for verifying ML-DSA and SLH-DSA signatures in contracts.
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    PostQuantumVerify,
+    /// Host functions for ML-KEM encapsulation and decapsulation checks in contracts.
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    MlKem,
+    /// Build chunk receipts, outcome and block Merkle trees with SHA3-256 instead of
+    /// SHA-256.
+    #[cfg(feature = "protocol_feature_sha3_merkle")]
+    Sha3Merkle,
+    /// Transactions may pay a priority fee per unit of gas burnt on top of the gas price.
+    #[cfg(feature = "protocol_feature_priority_fee")]
+    PriorityFee,
+    /// Accept challenges in blocks, validate chunk state challenges and slash the
+    /// validators named in `challenges_result`.
+    #[cfg(feature = "protocol_feature_chunk_state_challenges")]
+    ChunkStateChallenges,
+    /// Build Merkle trees with SHA-512/256 instead of SHA3-256.
+    #[cfg(feature = "protocol_feature_sha512_merkle")]
+    Sha512Merkle,
 }
 
 /// Both, outgoing and incoming tcp connections to peers, will be rejected if `peer's`
@@ -166,7 +190,7 @@ const STABLE_PROTOCOL_VERSION: ProtocolVersion = 57;
 /// Largest protocol version supported by the current binary.
 pub const PROTOCOL_VERSION: ProtocolVersion = if cfg!(feature = "nightly_protocol") {
     // On nightly, pick big enough version to support all features.
-    132
+    139
 } else if cfg!(feature = "shardnet") {
     102
 } else {
@@ -254,6 +278,20 @@ impl ProtocolFeature {
             }
             #[cfg(feature = "shardnet")]
             ProtocolFeature::ShardnetShardLayoutUpgrade => 102,
+            #[cfg(feature = "protocol_feature_post_quantum_validator_keys")]
+            ProtocolFeature::PostQuantumValidatorKeys => 133,
+            #[cfg(feature = "protocol_feature_post_quantum_verify")]
+            ProtocolFeature::PostQuantumVerify => 134,
+            #[cfg(feature = "protocol_feature_ml_kem")]
+            ProtocolFeature::MlKem => 135,
+            #[cfg(feature = "protocol_feature_sha3_merkle")]
+            ProtocolFeature::Sha3Merkle => 136,
+            #[cfg(feature = "protocol_feature_priority_fee")]
+            ProtocolFeature::PriorityFee => 137,
+            #[cfg(feature = "protocol_feature_chunk_state_challenges")]
+            ProtocolFeature::ChunkStateChallenges => 138,
+            #[cfg(feature = "protocol_feature_sha512_merkle")]
+            ProtocolFeature::Sha512Merkle => 139,
         }
     }
 }
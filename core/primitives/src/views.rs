@@ -215,6 +215,24 @@ pub struct ViewStateResult {
     pub proof: Vec<Arc<[u8]>>,
 }
 
+/// Account along with the trie nodes on the path to it, returned when
+/// `include_proof` is set in the request.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccountWithProofView {
+    #[serde(flatten)]
+    pub account: AccountView,
+    pub proof: Vec<Arc<[u8]>>,
+}
+
+/// Access key along with the trie nodes on the path to it, returned when
+/// `include_proof` is set in the request.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccessKeyWithProofView {
+    #[serde(flatten)]
+    pub access_key: AccessKeyView,
+    pub proof: Vec<Arc<[u8]>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct CallResult {
     pub result: Vec<u8>,
@@ -259,10 +277,12 @@ pub struct KnownPeerStateView {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum QueryResponseKind {
     ViewAccount(AccountView),
+    ViewAccountWithProof(AccountWithProofView),
     ViewCode(ContractCodeView),
     ViewState(ViewStateResult),
     CallResult(CallResult),
     AccessKey(AccessKeyView),
+    AccessKeyWithProof(AccessKeyWithProofView),
     AccessKeyList(AccessKeyList),
 }
 
@@ -271,6 +291,8 @@ pub enum QueryResponseKind {
 pub enum QueryRequest {
     ViewAccount {
         account_id: AccountId,
+        #[serde(default, skip_serializing_if = "is_false")]
+        include_proof: bool,
     },
     ViewCode {
         account_id: AccountId,
@@ -285,6 +307,8 @@ pub enum QueryRequest {
     ViewAccessKey {
         account_id: AccountId,
         public_key: PublicKey,
+        #[serde(default, skip_serializing_if = "is_false")]
+        include_proof: bool,
     },
     ViewAccessKeyList {
         account_id: AccountId,
@@ -391,6 +415,19 @@ pub struct PeerStoreView {
     pub peer_states: Vec<KnownPeerStateView>,
 }
 
+/// Number of the ready connections with peers of a subnet.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SubnetConnectionsView {
+    pub subnet: String,
+    pub inbound: u32,
+    pub outbound: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SubnetsView {
+    pub subnets: Vec<SubnetConnectionsView>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct ShardSyncDownloadView {
     pub downloads: Vec<DownloadStatusView>,
@@ -1086,6 +1123,8 @@ pub struct SignedTransactionView {
     pub actions: Vec<ActionView>,
     pub signature: Signature,
     pub hash: CryptoHash,
+    #[serde(default)]
+    pub priority_fee: u64,
 }
 
 impl From<SignedTransaction> for SignedTransactionView {
@@ -1104,6 +1143,7 @@ impl From<SignedTransaction> for SignedTransactionView {
                 .collect(),
             signature: signed_tx.signature,
             hash,
+            priority_fee: signed_tx.transaction.priority_fee,
         }
     }
 }
@@ -1139,6 +1179,24 @@ impl Default for FinalExecutionStatus {
     }
 }
 
+/// Stage of the execution of a transaction, in the order they are reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TxExecutionStatus {
+    /// The transaction is included into a chunk and its outcome is known.
+    Included,
+    /// The transaction and all the receipts it produced are executed.
+    Executed,
+    /// Same as `Executed`, and all the blocks with the outcomes are final.
+    Final,
+}
+
+impl Default for TxExecutionStatus {
+    fn default() -> Self {
+        TxExecutionStatus::Executed
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub enum ServerError {
     TxExecutionError(TxExecutionError),
@@ -1690,7 +1748,7 @@ pub struct GasPriceView {
 ///
 /// [serializable view]: ./index.html
 /// [`StateChangesRequest`]: ../types/struct.StateChangesRequest.html
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "changes_type", rename_all = "snake_case")]
 pub enum StateChangesRequestView {
     AccountChanges {
@@ -0,0 +1,129 @@
+//! Verification of the state proofs returned by the `query` RPC method.
+//!
+//! With `include_proof` set, the `view_account`, `view_access_key` and
+//! `view_state` requests return the trie nodes on the path from the state root
+//! to the requested values. This crate checks those nodes against a state
+//! root, so a client which knows a trusted state root doesn't need to trust
+//! the RPC node which served the query.
+//!
+//! The state returned by a query at block `N` is the state after the chunks
+//! of block `N` were applied, which is the `prev_state_root` of the chunks in
+//! the block following `N`. The header of that block, once verified by a light
+//! client, commits to the `prev_state_root` of all the chunks, see
+//! [`verify_chunk_state_roots`].
+use borsh::BorshSerialize;
+use near_crypto::PublicKey;
+use near_primitives::account::{AccessKey, Account};
+use near_primitives::hash::CryptoHash;
+use near_primitives::merkle::merklize;
+use near_primitives::trie_key::{trie_key_parsers, TrieKey};
+use near_primitives::types::{AccountId, ShardId, StateRoot};
+use near_primitives::views::{AccessKeyWithProofView, AccountWithProofView, ViewStateResult};
+
+mod trie;
+
+pub use trie::{ProofNodes, ValueRef};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("proof node {0} can't be decoded")]
+    InvalidNode(CryptoHash),
+    #[error("proof lacks the trie node {0}")]
+    MissingNode(CryptoHash),
+    #[error("proof has a key with an odd number of nibbles")]
+    OddKey,
+    #[error("proof shows that the key isn't in the state")]
+    KeyNotFound,
+    #[error("value doesn't match the one in the state")]
+    ValueMismatch,
+    #[error("{got} values were returned while the state has {want}")]
+    ValueCountMismatch { got: usize, want: usize },
+    #[error("shard {0} has no chunk")]
+    UnknownShard(ShardId),
+    #[error("chunk state roots don't match the state root of the block")]
+    StateRootMismatch,
+}
+
+fn verify_value(
+    state_root: &StateRoot,
+    proof: &ProofNodes,
+    key: &TrieKey,
+    value: &[u8],
+) -> Result<(), ProofError> {
+    let value_ref = proof.lookup(state_root, &key.to_vec())?.ok_or(ProofError::KeyNotFound)?;
+    if !value_ref.matches(value) {
+        return Err(ProofError::ValueMismatch);
+    }
+    Ok(())
+}
+
+/// Checks the response of a `view_account` query with `include_proof` set.
+pub fn verify_account(
+    state_root: &StateRoot,
+    account_id: &AccountId,
+    view: &AccountWithProofView,
+) -> Result<(), ProofError> {
+    let proof = ProofNodes::new(&view.proof)?;
+    let key = TrieKey::Account { account_id: account_id.clone() };
+    let value = Account::from(&view.account).try_to_vec().unwrap();
+    verify_value(state_root, &proof, &key, &value)
+}
+
+/// Checks the response of a `view_access_key` query with `include_proof` set.
+pub fn verify_access_key(
+    state_root: &StateRoot,
+    account_id: &AccountId,
+    public_key: &PublicKey,
+    view: &AccessKeyWithProofView,
+) -> Result<(), ProofError> {
+    let proof = ProofNodes::new(&view.proof)?;
+    let key = TrieKey::AccessKey { account_id: account_id.clone(), public_key: public_key.clone() };
+    let value = AccessKey::from(view.access_key.clone()).try_to_vec().unwrap();
+    verify_value(state_root, &proof, &key, &value)
+}
+
+/// Checks the response of a `view_state` query with `include_proof` set: the
+/// returned values must be exactly the contract data of `account_id` whose
+/// keys start with `prefix`.
+pub fn verify_view_state(
+    state_root: &StateRoot,
+    account_id: &AccountId,
+    prefix: &[u8],
+    view: &ViewStateResult,
+) -> Result<(), ProofError> {
+    let proof = ProofNodes::new(&view.proof)?;
+    let query = trie_key_parsers::get_raw_prefix_for_contract_data(account_id, prefix);
+    let data_prefix_len = query.len() - prefix.len();
+    let want = proof.values_with_prefix(state_root, &query)?;
+    if want.len() != view.values.len() {
+        return Err(ProofError::ValueCountMismatch { got: view.values.len(), want: want.len() });
+    }
+    for ((key, value_ref), item) in want.iter().zip(&view.values) {
+        if key[data_prefix_len..] != item.key[..] || !value_ref.matches(&item.value) {
+            return Err(ProofError::ValueMismatch);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `prev_state_root` of the chunk of `shard_id`, after checking
+/// that `chunk_state_roots` (the `prev_state_root` of every chunk of a block,
+/// ordered by shard) are the ones committed to by the `prev_state_root` of the
+/// block header.
+pub fn verify_chunk_state_roots(
+    block_prev_state_root: &CryptoHash,
+    chunk_state_roots: &[StateRoot],
+    shard_id: ShardId,
+) -> Result<StateRoot, ProofError> {
+    if merklize(chunk_state_roots).0 != *block_prev_state_root {
+        return Err(ProofError::StateRootMismatch);
+    }
+    usize::try_from(shard_id)
+        .ok()
+        .and_then(|index| chunk_state_roots.get(index))
+        .copied()
+        .ok_or(ProofError::UnknownShard(shard_id))
+}
+
+#[cfg(test)]
+mod tests;
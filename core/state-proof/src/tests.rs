@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use near_crypto::{KeyType, SecretKey};
+use near_primitives::shard_layout::ShardUId;
+use near_primitives::views::StateItem;
+use near_store::test_utils::{create_tries, test_populate_trie};
+use near_store::{ShardTries, Trie};
+
+use super::*;
+
+struct TestState {
+    tries: ShardTries,
+    root: StateRoot,
+    alice: AccountId,
+    alice_key: PublicKey,
+    account: Account,
+}
+
+impl TestState {
+    fn new() -> Self {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let alice_key = SecretKey::from_seed(KeyType::ED25519, "alice").public_key();
+        let account = Account::new(100, 10, CryptoHash::default(), 200);
+        let access_key = AccessKey { nonce: 7, ..AccessKey::full_access() };
+
+        let mut changes = vec![
+            (TrieKey::Account { account_id: alice.clone() }, account.try_to_vec().unwrap()),
+            (
+                TrieKey::Account { account_id: bob.clone() },
+                Account::new(1, 0, CryptoHash::default(), 100).try_to_vec().unwrap(),
+            ),
+            (
+                TrieKey::AccessKey { account_id: alice.clone(), public_key: alice_key.clone() },
+                access_key.try_to_vec().unwrap(),
+            ),
+        ];
+        for (account_id, key, value) in
+            [(&alice, "a1", "x"), (&alice, "a2", "yy"), (&alice, "b1", "zzz"), (&bob, "a1", "bob")]
+        {
+            changes.push((
+                TrieKey::ContractData { account_id: account_id.clone(), key: key.into() },
+                value.into(),
+            ));
+        }
+        let changes = changes.into_iter().map(|(key, value)| (key.to_vec(), Some(value))).collect();
+        let tries = create_tries();
+        let root = test_populate_trie(&tries, &Trie::EMPTY_ROOT, ShardUId::single_shard(), changes);
+        Self { tries, root, alice, alice_key, account }
+    }
+
+    /// Collects the nodes on the path to `key` the same way
+    /// `TrieViewer::view_proof` does.
+    fn proof(&self, key: &[u8]) -> Vec<Arc<[u8]>> {
+        let trie = self.tries.get_trie_for_shard(ShardUId::single_shard(), self.root);
+        let mut iter = trie.iter().unwrap();
+        iter.remember_visited_nodes(true);
+        iter.seek_prefix(key).unwrap();
+        iter.into_visited_nodes()
+    }
+
+    /// Collects the values and the proof the same way
+    /// `TrieViewer::view_state` does.
+    fn view_state(&self, prefix: &[u8]) -> ViewStateResult {
+        let query = trie_key_parsers::get_raw_prefix_for_contract_data(&self.alice, prefix);
+        let trie = self.tries.get_trie_for_shard(ShardUId::single_shard(), self.root);
+        let mut iter = trie.iter().unwrap();
+        iter.remember_visited_nodes(true);
+        iter.seek_prefix(&query).unwrap();
+        let data_prefix_len = query.len() - prefix.len();
+        let values = (&mut iter)
+            .map(|item| {
+                let (key, value) = item.unwrap();
+                StateItem { key: key[data_prefix_len..].to_vec(), value, proof: vec![] }
+            })
+            .collect();
+        ViewStateResult { values, proof: iter.into_visited_nodes() }
+    }
+}
+
+#[test]
+fn test_verify_account() {
+    let state = TestState::new();
+    let key = TrieKey::Account { account_id: state.alice.clone() }.to_vec();
+    let view = AccountWithProofView { account: (&state.account).into(), proof: state.proof(&key) };
+    assert_eq!(verify_account(&state.root, &state.alice, &view), Ok(()));
+
+    let mut tampered = view.clone();
+    tampered.account.amount += 1;
+    assert_eq!(
+        verify_account(&state.root, &state.alice, &tampered),
+        Err(ProofError::ValueMismatch)
+    );
+
+    // The proof doesn't cover other accounts.
+    assert_matches!(
+        verify_account(&state.root, &"carol.near".parse().unwrap(), &view),
+        Err(ProofError::MissingNode(_) | ProofError::KeyNotFound)
+    );
+    // Nor other state roots.
+    assert_matches!(
+        verify_account(&CryptoHash::hash_bytes(b"root"), &state.alice, &view),
+        Err(ProofError::MissingNode(_))
+    );
+
+    let mut truncated = view;
+    truncated.proof.pop();
+    assert_matches!(
+        verify_account(&state.root, &state.alice, &truncated),
+        Err(ProofError::MissingNode(_))
+    );
+}
+
+#[test]
+fn test_verify_access_key() {
+    let state = TestState::new();
+    let key =
+        TrieKey::AccessKey { account_id: state.alice.clone(), public_key: state.alice_key.clone() };
+    let view = AccessKeyWithProofView {
+        access_key: AccessKey { nonce: 7, ..AccessKey::full_access() }.into(),
+        proof: state.proof(&key.to_vec()),
+    };
+    assert_eq!(verify_access_key(&state.root, &state.alice, &state.alice_key, &view), Ok(()));
+
+    let mut tampered = view.clone();
+    tampered.access_key.nonce = 8;
+    assert_eq!(
+        verify_access_key(&state.root, &state.alice, &state.alice_key, &tampered),
+        Err(ProofError::ValueMismatch)
+    );
+
+    let other_key = SecretKey::from_seed(KeyType::ED25519, "other").public_key();
+    assert_matches!(
+        verify_access_key(&state.root, &state.alice, &other_key, &view),
+        Err(ProofError::MissingNode(_) | ProofError::KeyNotFound)
+    );
+}
+
+#[test]
+fn test_verify_view_state() {
+    let state = TestState::new();
+    for (prefix, want) in [(&b""[..], 3), (b"a", 2), (b"a2", 1), (b"c", 0)] {
+        let view = state.view_state(prefix);
+        assert_eq!(view.values.len(), want);
+        assert_eq!(verify_view_state(&state.root, &state.alice, prefix, &view), Ok(()));
+    }
+
+    let view = state.view_state(b"a");
+    // A value left out.
+    let mut tampered = view.clone();
+    tampered.values.pop();
+    assert_eq!(
+        verify_view_state(&state.root, &state.alice, b"a", &tampered),
+        Err(ProofError::ValueCountMismatch { got: 1, want: 2 })
+    );
+    // A changed value.
+    let mut tampered = view.clone();
+    tampered.values[0].value = b"z".to_vec();
+    assert_eq!(
+        verify_view_state(&state.root, &state.alice, b"a", &tampered),
+        Err(ProofError::ValueMismatch)
+    );
+    // A proof of a narrower prefix misses the rest of the values.
+    let narrow = state.view_state(b"a1");
+    assert_matches!(
+        verify_view_state(&state.root, &state.alice, b"a", &narrow),
+        Err(ProofError::MissingNode(_) | ProofError::ValueCountMismatch { .. })
+    );
+}
+
+#[test]
+fn test_verify_chunk_state_roots() {
+    let roots = [CryptoHash::hash_bytes(b"0"), CryptoHash::hash_bytes(b"1")];
+    let block_root = merklize(&roots).0;
+    assert_eq!(verify_chunk_state_roots(&block_root, &roots, 1), Ok(roots[1]));
+    assert_eq!(verify_chunk_state_roots(&block_root, &roots, 2), Err(ProofError::UnknownShard(2)));
+    assert_eq!(
+        verify_chunk_state_roots(&block_root, &[roots[1], roots[0]], 0),
+        Err(ProofError::StateRootMismatch)
+    );
+}
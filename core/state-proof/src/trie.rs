@@ -0,0 +1,310 @@
+//! Decoding and traversal of the trie nodes which make up a proof.
+//!
+//! The encoding mirrors `RawTrieNodeWithSize` of `near-store`, which isn't
+//! used directly so that the crate doesn't pull in the storage dependencies.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use near_primitives::hash::CryptoHash;
+
+use crate::ProofError;
+
+const LEAF_NODE: u8 = 0;
+const BRANCH_NODE_NO_VALUE: u8 = 1;
+const BRANCH_NODE_WITH_VALUE: u8 = 2;
+const EXTENSION_NODE: u8 = 3;
+
+/// Length and hash of a value stored in the trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueRef {
+    pub length: u32,
+    pub hash: CryptoHash,
+}
+
+impl ValueRef {
+    pub fn matches(&self, value: &[u8]) -> bool {
+        self.length as usize == value.len() && self.hash == CryptoHash::hash_bytes(value)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    /// Rest of the key in nibbles, and the value.
+    Leaf(Vec<u8>, ValueRef),
+    Branch(Box<[Option<CryptoHash>; 16]>, Option<ValueRef>),
+    /// Part of the key in nibbles, and the child.
+    Extension(Vec<u8>, CryptoHash),
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn hash(&mut self) -> Option<CryptoHash> {
+        Some(CryptoHash(self.bytes(32)?.try_into().unwrap()))
+    }
+
+    fn value(&mut self) -> Option<ValueRef> {
+        Some(ValueRef { length: self.u32()?, hash: self.hash()? })
+    }
+
+    fn key(&mut self) -> Option<Vec<u8>> {
+        let len = self.u32()? as usize;
+        decode_nibbles(self.bytes(len)?)
+    }
+
+    fn children(&mut self) -> Option<Box<[Option<CryptoHash>; 16]>> {
+        let bitmap = self.u16()?;
+        let mut children: Box<[Option<CryptoHash>; 16]> = Default::default();
+        for (i, child) in children.iter_mut().enumerate() {
+            if bitmap & (1 << i) != 0 {
+                *child = Some(self.hash()?);
+            }
+        }
+        Some(children)
+    }
+}
+
+/// Splits bytes into nibbles, high nibble first.
+pub(crate) fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Joins nibbles back into bytes, if there is an even number of them.
+pub(crate) fn from_nibbles(nibbles: &[u8]) -> Option<Vec<u8>> {
+    if nibbles.len() % 2 != 0 {
+        return None;
+    }
+    Some(nibbles.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect())
+}
+
+/// Decodes a key of a leaf or an extension node. The first byte holds flags:
+/// 0x10 for an odd number of nibbles, in which case its low bits hold the
+/// first nibble, and 0x20 for a leaf.
+fn decode_nibbles(encoded: &[u8]) -> Option<Vec<u8>> {
+    let (&first, rest) = encoded.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Some(nibbles)
+}
+
+fn decode_node(bytes: &[u8]) -> Option<Node> {
+    // The node is followed by 8 bytes of its memory usage.
+    let mut reader = Reader(bytes.get(..bytes.len().checked_sub(8)?)?);
+    let node = match reader.u8()? {
+        LEAF_NODE => {
+            let key = reader.key()?;
+            Node::Leaf(key, reader.value()?)
+        }
+        BRANCH_NODE_NO_VALUE => Node::Branch(reader.children()?, None),
+        BRANCH_NODE_WITH_VALUE => {
+            let value = reader.value()?;
+            Node::Branch(reader.children()?, Some(value))
+        }
+        EXTENSION_NODE => {
+            let key = reader.key()?;
+            Node::Extension(key, reader.hash()?)
+        }
+        _ => return None,
+    };
+    reader.0.is_empty().then_some(node)
+}
+
+/// Trie nodes of a proof, indexed by their hashes.
+pub struct ProofNodes {
+    nodes: HashMap<CryptoHash, Node>,
+}
+
+impl ProofNodes {
+    pub fn new(proof: &[Arc<[u8]>]) -> Result<Self, ProofError> {
+        let nodes = proof
+            .iter()
+            .map(|bytes| {
+                let hash = CryptoHash::hash_bytes(bytes);
+                let node = decode_node(bytes).ok_or(ProofError::InvalidNode(hash))?;
+                Ok((hash, node))
+            })
+            .collect::<Result<_, ProofError>>()?;
+        Ok(Self { nodes })
+    }
+
+    fn get(&self, hash: &CryptoHash) -> Result<&Node, ProofError> {
+        self.nodes.get(hash).ok_or(ProofError::MissingNode(*hash))
+    }
+
+    /// Looks up `key` in the trie with the given root. Returns `None` if the
+    /// proof shows that the key isn't in the trie.
+    pub fn lookup(&self, root: &CryptoHash, key: &[u8]) -> Result<Option<ValueRef>, ProofError> {
+        if *root == CryptoHash::default() {
+            return Ok(None);
+        }
+        let key = to_nibbles(key);
+        let mut key = &key[..];
+        let mut hash = root;
+        loop {
+            match self.get(hash)? {
+                Node::Leaf(leaf_key, value) => {
+                    return Ok((leaf_key[..] == *key).then_some(*value));
+                }
+                Node::Extension(ext_key, child) => {
+                    if !key.starts_with(ext_key) {
+                        return Ok(None);
+                    }
+                    key = &key[ext_key.len()..];
+                    hash = child;
+                }
+                Node::Branch(children, value) => match key.split_first() {
+                    None => return Ok(*value),
+                    Some((&nibble, rest)) => match &children[nibble as usize] {
+                        Some(child) => {
+                            key = rest;
+                            hash = child;
+                        }
+                        None => return Ok(None),
+                    },
+                },
+            }
+        }
+    }
+
+    /// Returns all the values whose keys start with `prefix`, ordered by key.
+    /// Every node of the subtree must be in the proof, so that no value can
+    /// be left out.
+    pub fn values_with_prefix(
+        &self,
+        root: &CryptoHash,
+        prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, ValueRef)>, ProofError> {
+        let mut values = vec![];
+        if *root == CryptoHash::default() {
+            return Ok(values);
+        }
+        let prefix = to_nibbles(prefix);
+        let mut rest = &prefix[..];
+        let mut path = vec![];
+        let mut hash = root;
+        loop {
+            if rest.is_empty() {
+                self.collect(hash, &mut path, &mut values)?;
+                break;
+            }
+            match self.get(hash)? {
+                Node::Leaf(leaf_key, value) => {
+                    if leaf_key.starts_with(rest) {
+                        path.extend_from_slice(leaf_key);
+                        values.push((path, *value));
+                    }
+                    break;
+                }
+                Node::Extension(ext_key, child) => {
+                    if ext_key.starts_with(rest) {
+                        path.extend_from_slice(ext_key);
+                        self.collect(child, &mut path, &mut values)?;
+                        break;
+                    }
+                    if !rest.starts_with(ext_key) {
+                        break;
+                    }
+                    path.extend_from_slice(ext_key);
+                    rest = &rest[ext_key.len()..];
+                    hash = child;
+                }
+                Node::Branch(children, _) => match &children[rest[0] as usize] {
+                    Some(child) => {
+                        path.push(rest[0]);
+                        rest = &rest[1..];
+                        hash = child;
+                    }
+                    None => break,
+                },
+            }
+        }
+        values
+            .into_iter()
+            .map(|(key, value)| Ok((from_nibbles(&key).ok_or(ProofError::OddKey)?, value)))
+            .collect()
+    }
+
+    /// Appends the values of the subtree at `hash` to `values`, in the order
+    /// of their keys. `path` is the key of the subtree in nibbles.
+    fn collect(
+        &self,
+        hash: &CryptoHash,
+        path: &mut Vec<u8>,
+        values: &mut Vec<(Vec<u8>, ValueRef)>,
+    ) -> Result<(), ProofError> {
+        let len = path.len();
+        match self.get(hash)? {
+            Node::Leaf(leaf_key, value) => {
+                path.extend_from_slice(leaf_key);
+                values.push((path.clone(), *value));
+            }
+            Node::Extension(ext_key, child) => {
+                path.extend_from_slice(ext_key);
+                self.collect(child, path, values)?;
+            }
+            Node::Branch(children, value) => {
+                if let Some(value) = value {
+                    values.push((path.clone(), *value));
+                }
+                for (nibble, child) in children.iter().enumerate() {
+                    if let Some(child) = child {
+                        path.push(nibble as u8);
+                        self.collect(child, path, values)?;
+                        path.pop();
+                    }
+                }
+            }
+        }
+        path.truncate(len);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nibbles() {
+        assert_eq!(to_nibbles(&[0x12, 0xab]), vec![1, 2, 0xa, 0xb]);
+        assert_eq!(from_nibbles(&[1, 2, 0xa, 0xb]), Some(vec![0x12, 0xab]));
+        assert_eq!(from_nibbles(&[1, 2, 0xa]), None);
+        // Even and odd number of nibbles, of an extension and of a leaf.
+        assert_eq!(decode_nibbles(&[0x00, 0x12]), Some(vec![1, 2]));
+        assert_eq!(decode_nibbles(&[0x20, 0x12]), Some(vec![1, 2]));
+        assert_eq!(decode_nibbles(&[0x13, 0x45]), Some(vec![3, 4, 5]));
+        assert_eq!(decode_nibbles(&[0x33, 0x45]), Some(vec![3, 4, 5]));
+        assert_eq!(decode_nibbles(&[]), None);
+    }
+
+    #[test]
+    fn test_invalid_node() {
+        let bytes: Arc<[u8]> = vec![LEAF_NODE, 1, 2, 3].into();
+        let hash = CryptoHash::hash_bytes(&bytes);
+        assert_eq!(ProofNodes::new(&[bytes]).err(), Some(ProofError::InvalidNode(hash)));
+    }
+}
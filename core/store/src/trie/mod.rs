@@ -24,7 +24,7 @@ pub use crate::trie::nibble_slice::NibbleSlice;
 pub use crate::trie::prefetching_trie_storage::PrefetchApi;
 pub use crate::trie::shard_tries::{KeyForStateChanges, ShardTries, WrappedTrieChanges};
 pub use crate::trie::trie_storage::{TrieCache, TrieCachingStorage, TrieStorage};
-use crate::trie::trie_storage::{TrieMemoryPartialStorage, TrieRecordingStorage};
+use crate::trie::trie_storage::{TrieMemoryPartialStorage, TrieNodesCounter, TrieRecordingStorage};
 use crate::StorageError;
 pub use near_primitives::types::TrieNodesCount;
 use std::fmt::Write;
@@ -576,6 +576,7 @@ impl Trie {
             store: storage.store.clone(),
             shard_uid: storage.shard_uid,
             recorded: RefCell::new(Default::default()),
+            nodes_counter: TrieNodesCounter::new(),
         };
         Trie { storage: Box::new(storage), root: self.root.clone(), flat_state: None }
     }
@@ -594,6 +595,7 @@ impl Trie {
         let storage = Box::new(TrieMemoryPartialStorage {
             recorded_storage,
             visited_nodes: Default::default(),
+            nodes_counter: TrieNodesCounter::new(),
         });
         Self::new(storage, root, None)
     }
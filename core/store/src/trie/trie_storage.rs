@@ -302,19 +302,61 @@ pub trait TrieStorage {
     }
 
     fn get_trie_nodes_count(&self) -> TrieNodesCount;
+
+    /// Sets the mode in which the read nodes are counted, see `TrieCacheMode`.
+    fn set_mode(&self, _state: TrieCacheMode) {}
+}
+
+/// Counts the trie node reads in the same way as `TrieCachingStorage` does,
+/// for the storages which don't have its caches. It is needed to charge the
+/// same gas for touching trie nodes when a chunk is applied to a recorded
+/// partial state, e.g. to generate or validate a `ChunkState` challenge.
+pub(crate) struct TrieNodesCounter {
+    cache_mode: Cell<TrieCacheMode>,
+    /// Nodes which would be in the chunk cache of `TrieCachingStorage`.
+    chunk_nodes: RefCell<HashSet<CryptoHash>>,
+    db_read_nodes: Cell<u64>,
+    mem_read_nodes: Cell<u64>,
+}
+
+impl TrieNodesCounter {
+    pub(crate) fn new() -> Self {
+        Self {
+            cache_mode: Cell::new(TrieCacheMode::CachingShard),
+            chunk_nodes: Default::default(),
+            db_read_nodes: Cell::new(0),
+            mem_read_nodes: Cell::new(0),
+        }
+    }
+
+    fn on_read(&self, hash: &CryptoHash) {
+        if self.chunk_nodes.borrow().contains(hash) {
+            self.mem_read_nodes.set(self.mem_read_nodes.get() + 1);
+            return;
+        }
+        self.db_read_nodes.set(self.db_read_nodes.get() + 1);
+        if let TrieCacheMode::CachingChunk = self.cache_mode.get() {
+            self.chunk_nodes.borrow_mut().insert(*hash);
+        }
+    }
+
+    fn get_trie_nodes_count(&self) -> TrieNodesCount {
+        TrieNodesCount { db_reads: self.db_read_nodes.get(), mem_reads: self.mem_read_nodes.get() }
+    }
 }
 
 /// Records every value read by retrieve_raw_bytes.
-/// Used for obtaining state parts (and challenges in the future).
-/// TODO (#6316): implement proper nodes counting logic as in TrieCachingStorage
+/// Used for obtaining state parts and the partial state of `ChunkState` challenges.
 pub struct TrieRecordingStorage {
     pub(crate) store: Store,
     pub(crate) shard_uid: ShardUId,
     pub(crate) recorded: RefCell<HashMap<CryptoHash, Arc<[u8]>>>,
+    pub(crate) nodes_counter: TrieNodesCounter,
 }
 
 impl TrieStorage for TrieRecordingStorage {
     fn retrieve_raw_bytes(&self, hash: &CryptoHash) -> Result<Arc<[u8]>, StorageError> {
+        self.nodes_counter.on_read(hash);
         if let Some(val) = self.recorded.borrow().get(hash).cloned() {
             return Ok(val);
         }
@@ -337,7 +379,11 @@ impl TrieStorage for TrieRecordingStorage {
     }
 
     fn get_trie_nodes_count(&self) -> TrieNodesCount {
-        unimplemented!();
+        self.nodes_counter.get_trie_nodes_count()
+    }
+
+    fn set_mode(&self, state: TrieCacheMode) {
+        self.nodes_counter.cache_mode.set(state);
     }
 }
 
@@ -346,6 +392,7 @@ impl TrieStorage for TrieRecordingStorage {
 pub struct TrieMemoryPartialStorage {
     pub(crate) recorded_storage: HashMap<CryptoHash, Arc<[u8]>>,
     pub(crate) visited_nodes: RefCell<HashSet<CryptoHash>>,
+    pub(crate) nodes_counter: TrieNodesCounter,
 }
 
 impl TrieStorage for TrieMemoryPartialStorage {
@@ -353,6 +400,7 @@ impl TrieStorage for TrieMemoryPartialStorage {
         let result = self.recorded_storage.get(hash).cloned().ok_or(StorageError::TrieNodeMissing);
         if result.is_ok() {
             self.visited_nodes.borrow_mut().insert(*hash);
+            self.nodes_counter.on_read(hash);
         }
         result
     }
@@ -362,7 +410,11 @@ impl TrieStorage for TrieMemoryPartialStorage {
     }
 
     fn get_trie_nodes_count(&self) -> TrieNodesCount {
-        unimplemented!();
+        self.nodes_counter.get_trie_nodes_count()
+    }
+
+    fn set_mode(&self, state: TrieCacheMode) {
+        self.nodes_counter.cache_mode.set(state);
     }
 }
 
@@ -614,6 +666,10 @@ impl TrieStorage for TrieCachingStorage {
     fn get_trie_nodes_count(&self) -> TrieNodesCount {
         TrieNodesCount { db_reads: self.db_read_nodes.get(), mem_reads: self.mem_read_nodes.get() }
     }
+
+    fn set_mode(&self, state: TrieCacheMode) {
+        TrieCachingStorage::set_mode(self, state)
+    }
 }
 
 impl TrieCachingStorage {
@@ -154,9 +154,7 @@ impl TrieUpdate {
     }
 
     pub fn set_trie_cache_mode(&self, state: TrieCacheMode) {
-        if let Some(storage) = self.trie.storage.as_caching_storage() {
-            storage.set_mode(state);
-        }
+        self.trie.storage.set_mode(state);
     }
 }
 
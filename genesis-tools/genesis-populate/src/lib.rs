@@ -199,7 +199,11 @@ impl GenesisBuilder {
 
         store_update.merge(
             self.runtime
-                .add_validator_proposals(BlockHeaderInfo::new(genesis.header(), 0))
+                .add_validator_proposals(BlockHeaderInfo::new(
+                    genesis.header(),
+                    0,
+                    self.genesis.config.protocol_version,
+                ))
                 .unwrap(),
         );
         store_update
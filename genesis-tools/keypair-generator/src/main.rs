@@ -29,6 +29,13 @@ fn main() {
                 .long("account-id")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("key-type")
+                .long("key-type")
+                .default_value("ed25519")
+                .help("Type of the generated keys, e.g. ed25519, mldsa or ed25519-mldsa")
+                .takes_value(true),
+        )
         .arg(
             Arg::new("generate-config")
                 .long("generate-config")
@@ -53,6 +60,8 @@ fn main() {
     fs::create_dir_all(home_dir).expect("Failed to create directory");
     let account_id = matches.value_of("account-id");
     let generate_config = matches.is_present("generate-config");
+    let key_type: KeyType =
+        matches.value_of("key-type").unwrap().parse().expect("Failed to parse key type.");
 
     match matches.subcommand() {
         Some(("signer-keys", args)) => {
@@ -61,7 +70,7 @@ fn main() {
                 .map(|x| x.parse().expect("Failed to parse number keys."))
                 .unwrap_or(3usize);
             let keys: Vec<SecretKey> =
-                (0..num_keys).map(|_| SecretKey::from_random(KeyType::ED25519)).collect();
+                (0..num_keys).map(|_| SecretKey::from_random(key_type)).collect();
             let mut pks = vec![];
             for (i, key) in keys.into_iter().enumerate() {
                 println!("Key#{}", i);
@@ -86,7 +95,7 @@ fn main() {
             println!("{}", pks.join(","));
         }
         Some(("validator-key", _)) => {
-            let key = SecretKey::from_random(KeyType::ED25519);
+            let key = SecretKey::from_random(key_type);
             println!("PK: {}", key.public_key());
             if generate_config {
                 let account_id =
@@ -100,7 +109,7 @@ fn main() {
             }
         }
         Some(("node-key", _args)) => {
-            let key = SecretKey::from_random(KeyType::ED25519);
+            let key = SecretKey::from_random(key_type);
             println!("PK: {}", key.public_key());
             if generate_config {
                 let mut path = home_dir.to_path_buf();
@@ -6,7 +6,7 @@ pub use crate::node::runtime_node::RuntimeNode;
 pub use crate::node::thread_node::ThreadNode;
 use crate::user::{AsyncUser, User};
 use near_chain_configs::Genesis;
-use near_crypto::{InMemorySigner, Signer};
+use near_crypto::{InMemorySigner, KeyType, Signer};
 use near_jsonrpc_primitives::errors::ServerError;
 use near_primitives::contract::ContractCode;
 use near_primitives::num_rational::Ratio;
@@ -144,15 +144,33 @@ fn near_configs_to_node_configs(
 }
 
 pub fn create_nodes(num_nodes: usize, prefix: &str) -> Vec<NodeConfig> {
-    let (configs, validator_signers, network_signers, genesis, _) =
-        create_testnet_configs(1, num_nodes as NumSeats, 0, prefix, true, false, false);
+    let (configs, validator_signers, network_signers, genesis, _) = create_testnet_configs(
+        1,
+        num_nodes as NumSeats,
+        0,
+        prefix,
+        true,
+        false,
+        false,
+        KeyType::ED25519,
+        KeyType::ED25519,
+    );
     near_configs_to_node_configs(configs, validator_signers, network_signers, genesis)
 }
 
 pub fn create_nodes_from_seeds(seeds: Vec<String>) -> Vec<NodeConfig> {
     let code = near_test_contracts::rs_contract();
     let (configs, validator_signers, network_signers, mut genesis) =
-        create_testnet_configs_from_seeds(seeds.clone(), 1, 0, true, false, None);
+        create_testnet_configs_from_seeds(
+            seeds.clone(),
+            1,
+            0,
+            true,
+            false,
+            None,
+            KeyType::ED25519,
+            KeyType::ED25519,
+        );
     genesis.config.gas_price_adjustment_rate = Ratio::from_integer(0);
     for seed in seeds {
         let mut is_account_record_found = false;
@@ -13,14 +13,16 @@ use near_network::test_utils::MockPeerManagerAdapter;
 use near_network::types::NetworkRequests;
 use near_o11y::testonly::init_test_logger;
 use near_primitives::challenge::{
-    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, MaybeEncodedShardChunk, StateItem,
+    BlockDoubleSign, Challenge, ChallengeBody, ChunkProofs, MaybeEncodedShardChunk,
 };
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
+use near_primitives::merkle::{
+    merkle_hash_algorithm, merklize_with, MerklePath, PartialMerkleTree,
+};
 use near_primitives::num_rational::Ratio;
 use near_primitives::receipt::Receipt;
 use near_primitives::shard_layout::ShardUId;
-use near_primitives::sharding::{EncodedShardChunk, ReedSolomonWrapper};
+use near_primitives::sharding::EncodedShardChunk;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, EpochId};
@@ -33,36 +35,6 @@ use nearcore::NightshadeRuntime;
 use std::path::Path;
 use std::sync::Arc;
 
-/// Check that block containing a challenge is rejected.
-/// TODO (#2445): Enable challenges when they are working correctly.
-#[test]
-fn test_block_with_challenges() {
-    let mut env = TestEnv::builder(ChainGenesis::test()).build();
-    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
-
-    let mut block = env.clients[0].produce_block(1).unwrap().unwrap();
-    let signer = env.clients[0].validator_signer.as_ref().unwrap().clone();
-
-    {
-        let body = match &mut block {
-            Block::BlockV1(_) => unreachable!(),
-            Block::BlockV2(body) => Arc::make_mut(body),
-        };
-        let challenge_body = ChallengeBody::BlockDoubleSign(BlockDoubleSign {
-            left_block_header: genesis.header().try_to_vec().unwrap(),
-            right_block_header: genesis.header().try_to_vec().unwrap(),
-        });
-        let challenge = Challenge::produce(challenge_body, &*signer);
-        body.challenges = vec![challenge];
-        block.mut_header().get_mut().inner_rest.challenges_root =
-            Block::compute_challenges_root(&body.challenges);
-        block.mut_header().resign(&*signer);
-    }
-
-    let result = env.clients[0].process_block_test(block.into(), Provenance::NONE);
-    assert_matches!(result.unwrap_err(), Error::InvalidChallengeRoot);
-}
-
 /// Check that attempt to process block on top of incorrect state root leads to InvalidChunkState error.
 #[test]
 fn test_invalid_chunk_state() {
@@ -102,7 +74,7 @@ fn test_verify_block_double_sign_challenge() {
     let signer =
         InMemoryValidatorSigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
     let mut block_merkle_tree = PartialMerkleTree::default();
-    block_merkle_tree.insert(*genesis.hash());
+    block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *genesis.hash());
     let b2 = Block::produce(
         PROTOCOL_VERSION,
         PROTOCOL_VERSION,
@@ -122,7 +94,7 @@ fn test_verify_block_double_sign_challenge() {
         vec![],
         &signer,
         *b1.header().next_bp_hash(),
-        block_merkle_tree.root(),
+        block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
         None,
     );
     let epoch_id = b1.header().epoch_id().clone();
@@ -321,172 +293,6 @@ fn challenge(
     )
 }
 
-#[test]
-fn test_verify_chunk_invalid_state_challenge() {
-    let store1 = create_test_store();
-    let genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
-    let mut env = TestEnv::builder(ChainGenesis::test())
-        .runtime_adapters(vec![Arc::new(nearcore::NightshadeRuntime::test(
-            Path::new("../../../.."),
-            store1,
-            &genesis,
-        ))])
-        .build();
-    let signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
-    let validator_signer =
-        InMemoryValidatorSigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
-    let genesis_hash = *env.clients[0].chain.genesis().hash();
-    env.produce_block(0, 1);
-    env.clients[0].process_tx(
-        SignedTransaction::send_money(
-            0,
-            "test0".parse().unwrap(),
-            "test1".parse().unwrap(),
-            &signer,
-            1000,
-            genesis_hash,
-        ),
-        false,
-        false,
-    );
-    env.produce_block(0, 2);
-
-    // Invalid chunk & block.
-    let last_block_hash = env.clients[0].chain.head().unwrap().last_block_hash;
-    let last_block = env.clients[0].chain.get_block(&last_block_hash).unwrap();
-    let total_parts = env.clients[0].runtime_adapter.num_total_parts();
-    let data_parts = env.clients[0].runtime_adapter.num_data_parts();
-    let parity_parts = total_parts - data_parts;
-    let mut rs = ReedSolomonWrapper::new(data_parts, parity_parts);
-    let (mut invalid_chunk, merkle_paths) = ShardsManager::create_encoded_shard_chunk(
-        *last_block.hash(),
-        Trie::EMPTY_ROOT,
-        CryptoHash::default(),
-        last_block.header().height() + 1,
-        0,
-        0,
-        1_000,
-        0,
-        vec![],
-        vec![],
-        &[],
-        last_block.chunks()[0].outgoing_receipts_root(),
-        CryptoHash::default(),
-        &validator_signer,
-        &mut rs,
-        PROTOCOL_VERSION,
-    )
-    .unwrap();
-
-    let client = &mut env.clients[0];
-
-    // Receive invalid chunk to the validator.
-    client
-        .persist_and_distribute_encoded_chunk(invalid_chunk.clone(), merkle_paths, vec![])
-        .unwrap();
-
-    match &mut invalid_chunk {
-        EncodedShardChunk::V1(ref mut chunk) => {
-            chunk.header.height_included = last_block.header().height() + 1;
-        }
-        EncodedShardChunk::V2(ref mut chunk) => {
-            *chunk.header.height_included_mut() = last_block.header().height() + 1;
-        }
-    }
-    let block_merkle_tree =
-        client.chain.mut_store().get_block_merkle_tree(last_block.hash()).unwrap();
-    let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-    block_merkle_tree.insert(*last_block.hash());
-    let block = Block::produce(
-        PROTOCOL_VERSION,
-        PROTOCOL_VERSION,
-        last_block.header(),
-        last_block.header().height() + 1,
-        last_block.header().block_ordinal() + 1,
-        vec![invalid_chunk.cloned_header()],
-        last_block.header().epoch_id().clone(),
-        last_block.header().next_epoch_id().clone(),
-        None,
-        vec![],
-        Ratio::from_integer(0),
-        0,
-        100,
-        None,
-        vec![],
-        vec![],
-        &validator_signer,
-        *last_block.header().next_bp_hash(),
-        block_merkle_tree.root(),
-        None,
-    );
-
-    let challenge_body =
-        client.chain.create_chunk_state_challenge(&last_block, &block, &block.chunks()[0]).unwrap();
-    {
-        let prev_merkle_proofs = Block::compute_chunk_headers_root(last_block.chunks().iter()).1;
-        let merkle_proofs = Block::compute_chunk_headers_root(block.chunks().iter()).1;
-        assert_eq!(prev_merkle_proofs[0], challenge_body.prev_merkle_proof);
-        assert_eq!(merkle_proofs[0], challenge_body.merkle_proof);
-        // TODO (#6316): enable storage proof generation
-        assert_eq!(challenge_body.partial_state.0, Vec::<StateItem>::new());
-        // assert_eq!(
-        //     challenge_body.partial_state.0,
-        //     vec![
-        //         vec![
-        //             1, 5, 0, 10, 178, 228, 151, 124, 13, 70, 6, 146, 31, 193, 111, 108, 60, 102,
-        //             227, 106, 220, 133, 45, 144, 104, 255, 30, 155, 129, 215, 15, 43, 202, 26, 122,
-        //             171, 30, 7, 228, 175, 99, 17, 113, 5, 94, 136, 200, 39, 136, 37, 110, 166, 241,
-        //             148, 128, 55, 131, 173, 97, 98, 201, 68, 82, 244, 223, 70, 86, 161, 5, 0, 0, 0,
-        //             0, 0, 0
-        //         ],
-        //         vec![
-        //             3, 1, 0, 0, 0, 16, 49, 233, 115, 11, 86, 10, 193, 50, 45, 253, 137, 126, 230,
-        //             236, 254, 86, 230, 148, 94, 141, 44, 46, 130, 154, 189, 73, 179, 223, 178, 17,
-        //             133, 232, 213, 5, 0, 0, 0, 0, 0, 0
-        //         ]
-        //     ],
-        // );
-    }
-    let challenge =
-        Challenge::produce(ChallengeBody::ChunkState(challenge_body), &validator_signer);
-    let runtime_adapter = client.chain.runtime_adapter.clone();
-    // Invalidate chunk state challenges because they are not supported yet.
-    // TODO (#2445): Enable challenges when they are working correctly.
-    assert_matches!(
-        validate_challenge(
-            &*runtime_adapter,
-            block.header().epoch_id(),
-            block.header().prev_hash(),
-            &challenge,
-        )
-        .unwrap_err(),
-        Error::MaliciousChallenge
-    );
-    // assert_eq!(
-    //     validate_challenge(
-    //         &*runtime_adapter,
-    //         block.header().epoch_id(),
-    //         block.header().prev_hash(),
-    //         &challenge,
-    //     )
-    //     .unwrap(),
-    //     (*block.hash(), vec!["test0".parse().unwrap()])
-    // );
-
-    // Process the block with invalid chunk and make sure it's marked as invalid at the end.
-    // And the same challenge created and sent out.
-    let result = client.process_block_test(block.into(), Provenance::NONE);
-    assert!(result.is_err());
-
-    let last_message = env.network_adapters[0].pop().unwrap().as_network_requests();
-
-    if let NetworkRequests::Challenge(network_challenge) = last_message {
-        assert_eq!(challenge, network_challenge);
-    } else {
-        assert!(false);
-    }
-}
-
 /// Receive invalid state transition in chunk as next chunk producer.
 /// TODO(2445): Enable challenges when they are working correctly.
 #[test]
@@ -510,7 +316,8 @@ fn test_receive_invalid_chunk_as_chunk_producer() {
     let shard_layout =
         env.clients[0].runtime_adapter.get_shard_layout(&EpochId::default()).unwrap();
     let receipts_hashes = Chain::build_receipts_hashes(&receipts, &shard_layout);
-    let (_receipts_root, receipts_proofs) = merklize(&receipts_hashes);
+    let (_receipts_root, receipts_proofs) =
+        merklize_with(merkle_hash_algorithm(PROTOCOL_VERSION), &receipts_hashes);
     let receipts_by_shard = Chain::group_receipts_by_shard(receipts, &shard_layout);
     let one_part_receipt_proofs = ShardsManager::receipts_recipient_filter(
         0,
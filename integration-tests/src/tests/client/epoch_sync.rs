@@ -0,0 +1,157 @@
+use super::process_blocks::create_nightshade_runtimes;
+use near_chain::{ChainGenesis, Error};
+use near_chain_configs::Genesis;
+use near_client::test_utils::TestEnv;
+use near_client::SyncStatus;
+use near_network::types::{FullPeerInfo, NetworkRequests, PeerChainInfoV2, PeerInfo};
+use near_o11y::testonly::init_test_logger;
+use near_primitives::syncing::EpochSyncResponse;
+use near_primitives::types::BlockHeight;
+use nearcore::config::GenesisExt;
+use std::time::Duration;
+
+const EPOCH_LENGTH: BlockHeight = 5;
+
+/// Creates an environment where the first client produces `num_epochs` epochs and the second
+/// client, which doesn't produce blocks, only knows the genesis block.
+fn setup_env(num_epochs: u64) -> TestEnv {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = EPOCH_LENGTH;
+    let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+        .clients_count(2)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 2))
+        .build();
+    for height in 1..=EPOCH_LENGTH * num_epochs + 2 {
+        env.produce_block(0, height);
+    }
+    env
+}
+
+fn peer(env: &TestEnv) -> FullPeerInfo {
+    FullPeerInfo {
+        peer_info: PeerInfo::random(),
+        chain_info: PeerChainInfoV2 {
+            genesis_id: Default::default(),
+            height: env.clients[0].chain.head().unwrap().height,
+            tracked_shards: vec![],
+            archival: false,
+        },
+        partial_edge_info: Default::default(),
+    }
+}
+
+/// Runs Epoch Sync of the second client against the chain of the first one, with `tamper`
+/// applied to every light client block.
+fn run_epoch_sync(env: &mut TestEnv, tamper: impl Fn(&mut EpochSyncResponse)) -> Result<(), Error> {
+    let peers = vec![peer(env)];
+    while !env.clients[1].epoch_sync.done {
+        let client = &mut env.clients[1];
+        let mut sync_status = SyncStatus::AwaitingPeers;
+        client.epoch_sync.run(&mut sync_status, &client.chain, &peers)?;
+        let request = match env.network_adapters[1].pop() {
+            Some(request) => request.as_network_requests(),
+            None => {
+                // Requests to the same peer are rate limited.
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+        };
+        match request {
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                let mut response = env.clients[0].chain.get_epoch_sync_response(&epoch_id)?;
+                tamper(&mut response);
+                let client = &mut env.clients[1];
+                client.epoch_sync.on_response(&mut client.chain, peer_id, response)?;
+            }
+            NetworkRequests::EpochSyncFinalizationRequest { epoch_id, peer_id } => {
+                let response =
+                    env.clients[0].chain.get_epoch_sync_finalization_response(&epoch_id)?;
+                let client = &mut env.clients[1];
+                client.epoch_sync.on_finalization_response(&mut client.chain, peer_id, response)?;
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+    }
+    Ok(())
+}
+
+/// A fresh node syncs to the first block of the latest epoch through the light client blocks
+/// of all the previous epochs and then continues with Header Sync from there.
+#[test]
+fn test_epoch_sync_bootstraps_node() {
+    init_test_logger();
+    let num_epochs = 10;
+    let mut env = setup_env(num_epochs);
+    run_epoch_sync(&mut env, |_| ()).unwrap();
+
+    let head = env.clients[0].chain.head().unwrap();
+    let sync_hash = env.clients[1].epoch_sync.sync_hash;
+    let sync_header = env.clients[0].chain.get_block_header(&sync_hash).unwrap();
+    let prev_header = env.clients[0].chain.get_block_header(sync_header.prev_hash()).unwrap();
+    assert_eq!(sync_header.epoch_id(), &head.epoch_id);
+    assert_ne!(prev_header.epoch_id(), &head.epoch_id);
+    let header_head = env.clients[1].chain.header_head().unwrap();
+    assert_eq!(header_head.last_block_hash, sync_hash);
+
+    // The epochs around the sync block are known and light client blocks can be served.
+    let runtime_adapter = &env.clients[1].runtime_adapter;
+    for epoch_id in [prev_header.epoch_id(), sync_header.epoch_id(), sync_header.next_epoch_id()] {
+        runtime_adapter.get_epoch_protocol_version(epoch_id).unwrap();
+    }
+    let genesis_epoch_id = env.clients[1].chain.genesis().epoch_id().clone();
+    for epoch_id in [genesis_epoch_id, prev_header.epoch_id().clone()] {
+        assert!(matches!(
+            env.clients[1].chain.get_epoch_sync_response(&epoch_id).unwrap(),
+            EpochSyncResponse::Advance { .. }
+        ));
+    }
+
+    // Header Sync continues from the sync block.
+    let headers = (sync_header.height() + 1..=head.height)
+        .map(|height| env.clients[0].chain.get_block_header_by_height(height).unwrap())
+        .collect();
+    env.clients[1].sync_block_headers(headers).unwrap();
+    assert_eq!(env.clients[1].chain.header_head().unwrap().last_block_hash, head.last_block_hash);
+
+    // Header Sync keeps working across the following epoch boundaries.
+    let mut height = head.height;
+    for _ in 0..2 * EPOCH_LENGTH {
+        height += 1;
+        env.produce_block(0, height);
+        let block = env.clients[0].chain.get_block_by_height(height).unwrap();
+        env.clients[1].sync_block_headers(vec![block.header().clone()]).unwrap();
+    }
+    assert_eq!(env.clients[1].chain.header_head().unwrap().height, height);
+}
+
+/// Epoch Sync finishes right away if the network is still in the genesis epoch.
+#[test]
+fn test_epoch_sync_in_genesis_epoch() {
+    init_test_logger();
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = EPOCH_LENGTH;
+    let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+        .clients_count(2)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 2))
+        .build();
+    for height in 1..EPOCH_LENGTH {
+        env.produce_block(0, height);
+    }
+    run_epoch_sync(&mut env, |_| ()).unwrap();
+    let genesis_hash = *env.clients[1].chain.genesis().hash();
+    assert_eq!(env.clients[1].chain.header_head().unwrap().last_block_hash, genesis_hash);
+}
+
+/// Light client blocks which are not endorsed by the known block producers are rejected.
+#[test]
+fn test_epoch_sync_rejects_invalid_light_client_block() {
+    init_test_logger();
+    let mut env = setup_env(3);
+    let result = run_epoch_sync(&mut env, |response| {
+        if let EpochSyncResponse::Advance { light_client_block_view } = response {
+            light_client_block_view.inner_lite.height += 1;
+        }
+    });
+    assert!(matches!(result, Err(Error::InvalidApprovals)), "{:?}", result);
+    assert!(!env.clients[1].epoch_sync.done);
+}
@@ -0,0 +1,193 @@
+use super::process_blocks::create_nightshade_runtimes;
+use borsh::BorshSerialize;
+use near_chain::{ChainGenesis, ChainStoreAccess};
+use near_chain_configs::Genesis;
+use near_client::state_parts::{FilesystemStatePartProvider, StatePartProvider};
+use near_client::sync::StateSync;
+use near_client::test_utils::TestEnv;
+use near_client_primitives::types::{DownloadStatus, ShardSyncDownload, ShardSyncStatus};
+use near_o11y::testonly::init_test_logger;
+use near_primitives::hash::CryptoHash;
+use near_primitives::state_part::PartId;
+use near_primitives::syncing::{get_num_state_parts, StatePartKey};
+use near_primitives::time::Clock;
+use near_primitives::types::BlockHeight;
+use near_store::DBCol;
+use nearcore::config::GenesisExt;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const EPOCH_LENGTH: BlockHeight = 5;
+
+/// Creates an environment where the first client dumps state parts to `dump_dir` and produces
+/// blocks into the second epoch, while the second client only knows the block headers.
+/// Returns the hash of the first block of the second epoch and the number of parts of shard 0.
+fn setup_env(dump_dir: &std::path::Path) -> (TestEnv, CryptoHash, u64) {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = EPOCH_LENGTH;
+    let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+        .clients_count(2)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 2))
+        .build();
+    env.clients[0].state_parts_dump =
+        Some(Arc::new(FilesystemStatePartProvider::new(dump_dir.to_path_buf(), "unittest")));
+    for height in 1..=EPOCH_LENGTH + 2 {
+        env.produce_block(0, height);
+    }
+    let headers = (1..=EPOCH_LENGTH + 2)
+        .map(|height| env.clients[0].chain.get_block_header_by_height(height).unwrap())
+        .collect::<Vec<_>>();
+    let sync_header = headers
+        .windows(2)
+        .find_map(|pair| (pair[0].epoch_id() != pair[1].epoch_id()).then(|| &pair[1]))
+        .unwrap();
+    let sync_hash = *sync_header.hash();
+    env.clients[1].sync_block_headers(headers).unwrap();
+
+    let state_header = env.clients[0].chain.get_state_response_header(0, sync_hash).unwrap();
+    let num_parts = get_num_state_parts(state_header.state_root_node().memory_usage);
+    env.clients[1].chain.set_state_header(0, sync_hash, state_header).unwrap();
+    (env, sync_hash, num_parts)
+}
+
+/// Waits until the first client has dumped all the parts in the background.
+fn wait_for_dump(provider: &dyn StatePartProvider, sync_hash: &CryptoHash, num_parts: u64) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    for part_id in 0..num_parts {
+        while provider.get_part(sync_hash, 0, PartId::new(part_id, num_parts)).is_err() {
+            assert!(Instant::now() < deadline, "state parts were not dumped in 10 seconds");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+/// Runs one step of State Sync of shard 0 on the second client.
+fn state_sync_step(
+    env: &mut TestEnv,
+    state_sync: &mut StateSync,
+    sync_hash: CryptoHash,
+    shard_sync: &mut HashMap<u64, ShardSyncDownload>,
+) {
+    let client = &mut env.clients[1];
+    let runtime_adapter = client.runtime_adapter.clone();
+    state_sync
+        .sync_shards_status(
+            &None,
+            sync_hash,
+            shard_sync,
+            &mut client.chain,
+            &runtime_adapter,
+            &[],
+            vec![0],
+            Clock::utc(),
+            &|_| {},
+            &|_| {},
+        )
+        .unwrap();
+}
+
+/// Runs State Sync of shard 0 on the second client, starting right after the shard state
+/// header has been downloaded, until the parts are downloaded or `timeout` passes.
+fn download_parts(
+    env: &mut TestEnv,
+    state_sync: &mut StateSync,
+    sync_hash: CryptoHash,
+    timeout: Duration,
+) -> ShardSyncDownload {
+    let now = Clock::utc();
+    let header_download = DownloadStatus {
+        start_time: now,
+        prev_update_time: now,
+        run_me: Arc::new(AtomicBool::new(false)),
+        error: false,
+        done: true,
+        state_requests_count: 1,
+        last_target: None,
+    };
+    let mut shard_sync = HashMap::from([(
+        0,
+        ShardSyncDownload {
+            downloads: vec![header_download],
+            status: ShardSyncStatus::StateDownloadHeader,
+        },
+    )]);
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        state_sync_step(env, state_sync, sync_hash, &mut shard_sync);
+        if shard_sync[&0].status == ShardSyncStatus::StateDownloadScheduling {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    // Nothing is requested from the peers.
+    assert!(env.network_adapters[1].pop().is_none());
+    shard_sync.remove(&0).unwrap()
+}
+
+fn stored_part(env: &TestEnv, sync_hash: CryptoHash, part_id: u64) -> Option<Vec<u8>> {
+    let key = StatePartKey(sync_hash, 0, part_id).try_to_vec().unwrap();
+    env.clients[1].chain.store().store().get(DBCol::StateParts, &key).unwrap().map(Vec::from)
+}
+
+/// A node dumps the state parts at the start of the epoch, and a syncing node downloads them
+/// from the dump instead of requesting them from the peers.
+#[test]
+fn test_state_sync_from_dump() {
+    init_test_logger();
+    let dump_dir = tempfile::tempdir().unwrap();
+    let (mut env, sync_hash, num_parts) = setup_env(dump_dir.path());
+    let provider = Arc::new(FilesystemStatePartProvider::new(dump_dir.path().into(), "unittest"));
+    wait_for_dump(provider.as_ref(), &sync_hash, num_parts);
+
+    let mut state_sync = StateSync::new(
+        env.network_adapters[1].clone(),
+        Duration::from_secs(10),
+        Some(provider.clone()),
+    );
+    let shard_sync = download_parts(&mut env, &mut state_sync, sync_hash, Duration::from_secs(10));
+    assert_eq!(shard_sync.status, ShardSyncStatus::StateDownloadScheduling);
+    for part_id in 0..num_parts {
+        let expected = env.clients[0].chain.get_state_response_part(0, part_id, sync_hash).unwrap();
+        assert_eq!(stored_part(&env, sync_hash, part_id), Some(expected));
+    }
+}
+
+/// Parts from the dump are validated against the state root, and an invalid part is
+/// downloaded again after the timeout.
+#[test]
+fn test_state_sync_from_dump_rejects_invalid_part() {
+    init_test_logger();
+    let dump_dir = tempfile::tempdir().unwrap();
+    let (mut env, sync_hash, num_parts) = setup_env(dump_dir.path());
+    let provider = Arc::new(FilesystemStatePartProvider::new(dump_dir.path().into(), "unittest"));
+    wait_for_dump(provider.as_ref(), &sync_hash, num_parts);
+    let part_id = PartId::new(0, num_parts);
+    let part = provider.get_part(&sync_hash, 0, part_id).unwrap();
+    let mut bad_part = part.clone();
+    bad_part[0] ^= 1;
+    provider.put_part(&sync_hash, 0, part_id, &bad_part).unwrap();
+
+    let mut state_sync = StateSync::new(
+        env.network_adapters[1].clone(),
+        Duration::from_millis(500),
+        Some(provider.clone()),
+    );
+    let shard_sync =
+        download_parts(&mut env, &mut state_sync, sync_hash, Duration::from_millis(300));
+    assert_eq!(shard_sync.status, ShardSyncStatus::StateDownloadParts);
+    assert!(!shard_sync.downloads[0].done);
+    assert_eq!(stored_part(&env, sync_hash, 0), None);
+
+    // Once the dump is fixed, the part is downloaded again after the timeout.
+    provider.put_part(&sync_hash, 0, part_id, &part).unwrap();
+    let mut shard_sync = HashMap::from([(0, shard_sync)]);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while shard_sync[&0].status == ShardSyncStatus::StateDownloadParts {
+        assert!(Instant::now() < deadline, "the part was not downloaded again");
+        state_sync_step(&mut env, &mut state_sync, sync_hash, &mut shard_sync);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(stored_part(&env, sync_hash, 0), Some(part));
+}
@@ -4,11 +4,17 @@ mod access_key_nonce_for_implicit_accounts;
 mod account_id_in_function_call_permission;
 mod cap_max_gas_price;
 mod chunk_nodes_cache;
+#[cfg(feature = "protocol_feature_chunk_state_challenges")]
+mod chunk_state_challenges;
 #[cfg(feature = "protocol_feature_fix_contract_loading_cost")]
 mod fix_contract_loading_cost;
 mod fix_storage_usage;
 mod increase_deployment_cost;
 mod limit_contract_functions_number;
 mod lower_storage_key_limit;
+#[cfg(feature = "protocol_feature_post_quantum_validator_keys")]
+mod post_quantum_validator_keys;
 mod restore_receipts_after_fix_apply_chunks;
+#[cfg(feature = "protocol_feature_sha512_merkle")]
+mod sha512_merkle;
 mod wasmer2;
@@ -0,0 +1,355 @@
+use assert_matches::assert_matches;
+use borsh::BorshSerialize;
+
+use crate::tests::client::process_blocks::create_nightshade_runtimes;
+use near_chain::validate::validate_challenge;
+use near_chain::{Block, ChainGenesis, ChainStoreAccess, Error, Provenance};
+use near_chain_configs::Genesis;
+use near_chunks::ShardsManager;
+use near_client::test_utils::{create_chunk_on_height, TestEnv};
+use near_crypto::{InMemorySigner, KeyType};
+use near_network::types::NetworkRequests;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody, SlashedValidator};
+use near_primitives::hash::CryptoHash;
+use near_primitives::merkle::{merkle_hash_algorithm, PartialMerkleTree};
+use near_primitives::num_rational::Ratio;
+use near_primitives::sharding::{EncodedShardChunk, ReedSolomonWrapper};
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::validator_signer::InMemoryValidatorSigner;
+use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use near_store::test_utils::create_test_store;
+use near_store::Trie;
+use nearcore::config::GenesisExt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Produces the next block of `env` with a malicious challenge of the genesis block.
+fn produce_block_with_challenge(env: &mut TestEnv) -> Block {
+    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
+    let mut block = env.clients[0].produce_block(1).unwrap().unwrap();
+    let signer = env.clients[0].validator_signer.as_ref().unwrap().clone();
+    {
+        let body = match &mut block {
+            Block::BlockV1(_) => unreachable!(),
+            Block::BlockV2(body) => Arc::make_mut(body),
+        };
+        let challenge_body = ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+            left_block_header: genesis.header().try_to_vec().unwrap(),
+            right_block_header: genesis.header().try_to_vec().unwrap(),
+        });
+        let challenge = Challenge::produce(challenge_body, &*signer);
+        body.challenges = vec![challenge];
+        block.mut_header().get_mut().inner_rest.challenges_root =
+            Block::compute_challenges_root(&body.challenges);
+        block.mut_header().resign(&*signer);
+    }
+    block
+}
+
+/// Blocks containing challenges are rejected before `ChunkStateChallenges`.
+#[test]
+fn test_block_with_challenges_before_upgrade() {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.protocol_version = ProtocolFeature::ChunkStateChallenges.protocol_version() - 1;
+    let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 1))
+        .build();
+    let block = produce_block_with_challenge(&mut env);
+    let result = env.clients[0].process_block_test(block.into(), Provenance::NONE);
+    assert_matches!(result.unwrap_err(), Error::InvalidChallengeRoot);
+}
+
+/// Check that a block containing a malicious challenge is accepted, and the challenger is slashed
+/// through the challenges result of the next block.
+#[test]
+fn test_block_with_challenges() {
+    let mut env = TestEnv::builder(ChainGenesis::test()).build();
+    let block = produce_block_with_challenge(&mut env);
+    env.process_block(0, block, Provenance::NONE);
+    let block = env.clients[0].produce_block(2).unwrap().unwrap();
+    assert_eq!(
+        block.header().challenges_result(),
+        &vec![SlashedValidator::new("test0".parse().unwrap(), false)]
+    );
+}
+
+/// A malicious challenge received from the network is neither gossiped further nor kept to be
+/// included in the next block.
+#[test]
+fn test_malicious_challenge_not_gossiped() {
+    let mut env = TestEnv::builder(ChainGenesis::test()).build();
+    let genesis = env.clients[0].chain.get_block_by_height(0).unwrap();
+    let signer = env.clients[0].validator_signer.as_ref().unwrap().clone();
+    let challenge_body = ChallengeBody::BlockDoubleSign(BlockDoubleSign {
+        left_block_header: genesis.header().try_to_vec().unwrap(),
+        right_block_header: genesis.header().try_to_vec().unwrap(),
+    });
+    let challenge = Challenge::produce(challenge_body, &*signer);
+    assert!(env.clients[0].process_challenge(challenge.clone()).is_err());
+    assert!(env.network_adapters[0].pop().is_none());
+    assert!(!env.clients[0].challenges.contains(&challenge.hash));
+}
+
+#[test]
+fn test_verify_chunk_invalid_state_challenge() {
+    let store1 = create_test_store();
+    let genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    let mut env = TestEnv::builder(ChainGenesis::test())
+        .runtime_adapters(vec![Arc::new(nearcore::NightshadeRuntime::test(
+            Path::new("../../../.."),
+            store1,
+            &genesis,
+        ))])
+        .build();
+    let signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let validator_signer =
+        InMemoryValidatorSigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    env.produce_block(0, 1);
+    env.clients[0].process_tx(
+        SignedTransaction::send_money(
+            0,
+            "test0".parse().unwrap(),
+            "test1".parse().unwrap(),
+            &signer,
+            1000,
+            genesis_hash,
+        ),
+        false,
+        false,
+    );
+    env.produce_block(0, 2);
+
+    // Invalid chunk & block.
+    let last_block_hash = env.clients[0].chain.head().unwrap().last_block_hash;
+    let last_block = env.clients[0].chain.get_block(&last_block_hash).unwrap();
+    let total_parts = env.clients[0].runtime_adapter.num_total_parts();
+    let data_parts = env.clients[0].runtime_adapter.num_data_parts();
+    let parity_parts = total_parts - data_parts;
+    let mut rs = ReedSolomonWrapper::new(data_parts, parity_parts);
+    let (mut invalid_chunk, merkle_paths) = ShardsManager::create_encoded_shard_chunk(
+        *last_block.hash(),
+        Trie::EMPTY_ROOT,
+        CryptoHash::default(),
+        last_block.header().height() + 1,
+        0,
+        0,
+        1_000,
+        0,
+        vec![],
+        vec![],
+        &[],
+        last_block.chunks()[0].outgoing_receipts_root(),
+        CryptoHash::default(),
+        &validator_signer,
+        &mut rs,
+        PROTOCOL_VERSION,
+    )
+    .unwrap();
+
+    let client = &mut env.clients[0];
+
+    // Receive invalid chunk to the validator.
+    client
+        .persist_and_distribute_encoded_chunk(invalid_chunk.clone(), merkle_paths, vec![])
+        .unwrap();
+
+    match &mut invalid_chunk {
+        EncodedShardChunk::V1(ref mut chunk) => {
+            chunk.header.height_included = last_block.header().height() + 1;
+        }
+        EncodedShardChunk::V2(ref mut chunk) => {
+            *chunk.header.height_included_mut() = last_block.header().height() + 1;
+        }
+    }
+    let block_merkle_tree =
+        client.chain.mut_store().get_block_merkle_tree(last_block.hash()).unwrap();
+    let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
+    block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *last_block.hash());
+    let block = Block::produce(
+        PROTOCOL_VERSION,
+        PROTOCOL_VERSION,
+        last_block.header(),
+        last_block.header().height() + 1,
+        last_block.header().block_ordinal() + 1,
+        vec![invalid_chunk.cloned_header()],
+        last_block.header().epoch_id().clone(),
+        last_block.header().next_epoch_id().clone(),
+        None,
+        vec![],
+        Ratio::from_integer(0),
+        0,
+        100,
+        None,
+        vec![],
+        vec![],
+        &validator_signer,
+        *last_block.header().next_bp_hash(),
+        block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
+        None,
+    );
+
+    let challenge_body =
+        client.chain.create_chunk_state_challenge(&last_block, &block, &block.chunks()[0]).unwrap();
+    let mut chunk_state = match &challenge_body {
+        ChallengeBody::ChunkStateV2(chunk_state) => chunk_state.clone(),
+        body => panic!("unexpected challenge body {:?}", body),
+    };
+    let prev_merkle_proofs = Block::compute_chunk_headers_root(last_block.chunks().iter()).1;
+    let merkle_proofs = Block::compute_chunk_headers_root(block.chunks().iter()).1;
+    assert_eq!(prev_merkle_proofs[0], chunk_state.prev_merkle_proof);
+    assert_eq!(merkle_proofs[0], chunk_state.merkle_proof);
+    assert!(!chunk_state.partial_state.0.is_empty());
+    assert!(!chunk_state.incoming_receipts.is_empty());
+    let last_chunk_block_header =
+        client.chain.get_block_header(last_block.header().prev_hash()).unwrap();
+    assert_eq!(chunk_state.last_chunk_block_header, last_chunk_block_header.try_to_vec().unwrap());
+
+    // A challenge which leaves out the receipts of a block is malicious.
+    chunk_state.incoming_receipts.pop();
+    let truncated_challenge =
+        Challenge::produce(ChallengeBody::ChunkStateV2(chunk_state), &validator_signer);
+    let runtime_adapter = client.chain.runtime_adapter.clone();
+    assert_matches!(
+        validate_challenge(
+            &*runtime_adapter,
+            block.header().epoch_id(),
+            block.header().prev_hash(),
+            &truncated_challenge,
+        )
+        .unwrap_err(),
+        Error::MaliciousChallenge
+    );
+
+    let challenge = Challenge::produce(challenge_body, &validator_signer);
+    assert_eq!(
+        validate_challenge(
+            &*runtime_adapter,
+            block.header().epoch_id(),
+            block.header().prev_hash(),
+            &challenge,
+        )
+        .unwrap(),
+        (*block.hash(), vec!["test0".parse().unwrap()])
+    );
+
+    // Process the block with invalid chunk and make sure it's marked as invalid at the end.
+    // And the same challenge created and sent out.
+    let result = client.process_block_test(block.into(), Provenance::NONE);
+    assert!(result.is_err());
+
+    let last_message = env.network_adapters[0].pop().unwrap().as_network_requests();
+
+    if let NetworkRequests::Challenge(network_challenge) = last_message {
+        assert_eq!(challenge, network_challenge);
+    } else {
+        assert!(false);
+    }
+}
+
+/// A chunk producer commits to a wrong state root in its chunk. Both validators reject the block
+/// with it and send out a chunk state challenge, the next block producer includes the challenge,
+/// and the chunk producer gets its stake slashed through the challenges result of the block after.
+#[test]
+fn test_invalid_chunk_state_producer_slashed() {
+    init_test_logger();
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 2);
+    genesis.config.epoch_length = 100;
+    let chain_genesis = ChainGenesis::new(&genesis);
+    let mut env = TestEnv::builder(chain_genesis)
+        .clients_count(2)
+        .validator_seats(2)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 2))
+        .build();
+    let invalid_height = 3;
+    let mut adversary = None;
+    for height in 1..=invalid_height + 3 {
+        let head = env.clients[0].chain.head().unwrap();
+        let epoch_id = env.clients[0]
+            .runtime_adapter
+            .get_epoch_id_from_prev_block(&head.last_block_hash)
+            .unwrap();
+        let chunk_producer =
+            env.clients[0].runtime_adapter.get_chunk_producer(&epoch_id, height, 0).unwrap();
+        let index = if chunk_producer.as_ref() == "test0" { 0 } else { 1 };
+        let (mut encoded_chunk, mut merkle_paths, receipts) =
+            create_chunk_on_height(&mut env.clients[index], height);
+        if height == invalid_height {
+            // Re-encode the chunk so that it commits to a wrong result of applying the previous one.
+            let client = &mut env.clients[index];
+            let total_parts = client.runtime_adapter.num_total_parts();
+            let data_parts = client.runtime_adapter.num_data_parts();
+            let decoded_chunk = encoded_chunk.decode_chunk(data_parts).unwrap();
+            let mut rs = ReedSolomonWrapper::new(data_parts, total_parts - data_parts);
+            let signer = client.validator_signer.as_ref().unwrap().clone();
+            let header = encoded_chunk.cloned_header();
+            let (invalid_chunk, invalid_merkle_paths) = EncodedShardChunk::new(
+                *header.prev_block_hash(),
+                Trie::EMPTY_ROOT,
+                header.outcome_root(),
+                header.height_created(),
+                header.shard_id(),
+                &mut rs,
+                header.gas_used(),
+                header.gas_limit(),
+                header.balance_burnt(),
+                header.tx_root(),
+                header.validator_proposals().collect(),
+                decoded_chunk.transactions().to_vec(),
+                decoded_chunk.receipts(),
+                header.outgoing_receipts_root(),
+                &*signer,
+                PROTOCOL_VERSION,
+            )
+            .unwrap();
+            encoded_chunk = invalid_chunk;
+            merkle_paths = invalid_merkle_paths;
+            adversary = Some(chunk_producer);
+        }
+        for j in 0..2 {
+            env.clients[j]
+                .persist_and_distribute_encoded_chunk(
+                    encoded_chunk.clone(),
+                    merkle_paths.clone(),
+                    receipts.clone(),
+                )
+                .unwrap();
+        }
+
+        let block_producer =
+            env.clients[0].runtime_adapter.get_block_producer(&epoch_id, height).unwrap();
+        let index = if block_producer.as_ref() == "test0" { 0 } else { 1 };
+        let block = env.clients[index].produce_block(height).unwrap().unwrap();
+        for j in 0..2 {
+            let result = env.clients[j]
+                .process_block_test_no_produce_chunk(block.clone().into(), Provenance::NONE);
+            if height == invalid_height {
+                assert_matches!(result.unwrap_err(), Error::InvalidChunkState(_));
+                let last_message =
+                    env.network_adapters[j].pop_most_recent().unwrap().as_network_requests();
+                assert_matches!(
+                    last_message,
+                    NetworkRequests::Challenge(Challenge {
+                        body: ChallengeBody::ChunkStateV2(_),
+                        ..
+                    })
+                );
+            } else {
+                result.unwrap();
+            }
+        }
+    }
+    let adversary = adversary.unwrap();
+
+    // The block after the rejected one carries the challenge, and the one after it slashes the
+    // chunk producer, which the state reflects once the chunk of that block is applied.
+    let block = env.clients[0].chain.get_block_by_height(invalid_height + 1).unwrap();
+    assert_eq!(block.challenges().len(), 1);
+    let block = env.clients[0].chain.get_block_by_height(invalid_height + 2).unwrap();
+    assert_eq!(
+        block.header().challenges_result(),
+        &vec![SlashedValidator::new(adversary.clone(), false)]
+    );
+    assert_eq!(env.query_account(adversary).locked, 0);
+}
@@ -0,0 +1,57 @@
+use super::super::process_blocks::create_nightshade_runtimes;
+use near_chain::ChainGenesis;
+use near_chain_configs::genesis_validate::validate_genesis;
+use near_chain_configs::Genesis;
+use near_client::test_utils::TestEnv;
+use near_crypto::KeyType;
+use near_primitives::shard_layout::ShardLayout;
+use near_primitives::version::ProtocolFeature;
+use nearcore::config::GenesisExt;
+
+fn hybrid_genesis() -> Genesis {
+    Genesis::test_with_seeds(
+        vec!["test0".parse().unwrap(), "test1".parse().unwrap()],
+        1,
+        vec![1],
+        ShardLayout::v0_single_shard(),
+        KeyType::ED25519MLDSA,
+    )
+}
+
+/// Hybrid staking keys are rejected before `PostQuantumValidatorKeys`.
+#[test]
+#[should_panic(expected = "validator staking key is not valid")]
+fn test_hybrid_validator_key_before_upgrade() {
+    let mut genesis = hybrid_genesis();
+    genesis.config.protocol_version =
+        ProtocolFeature::PostQuantumValidatorKeys.protocol_version() - 1;
+    validate_genesis(&genesis);
+}
+
+/// A validator with a hybrid key produces blocks and chunks which pass header,
+/// chunk, approval and randomness beacon checks.
+#[test]
+fn test_hybrid_validator_produces_blocks() {
+    let mut genesis = hybrid_genesis();
+    genesis.config.protocol_version = ProtocolFeature::PostQuantumValidatorKeys.protocol_version();
+    validate_genesis(&genesis);
+
+    let mut env = TestEnv::builder(ChainGenesis::new(&genesis))
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 1))
+        .validator_key_type(KeyType::ED25519MLDSA)
+        .build();
+    for height in 1..=10 {
+        env.produce_block(0, height);
+    }
+
+    let chain = &env.clients[0].chain;
+    assert_eq!(chain.head().unwrap().height, 10);
+    let block = chain.get_block_by_height(10).unwrap();
+    assert!(matches!(block.header().signature().key_type(), KeyType::ED25519MLDSA));
+    for approval in block.header().approvals().iter().flatten() {
+        assert!(matches!(approval.key_type(), KeyType::ED25519MLDSA));
+    }
+    for chunk in block.chunks().iter() {
+        assert!(matches!(chunk.signature().key_type(), KeyType::ED25519MLDSA));
+    }
+}
@@ -0,0 +1,58 @@
+use near_chain::ChainGenesis;
+use near_chain_configs::Genesis;
+use near_client::test_utils::TestEnv;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::merkle::{merkle_hash_algorithm, verify_hash_with_algorithms};
+use near_primitives::types::BlockHeight;
+use near_primitives::version::ProtocolFeature;
+use nearcore::config::GenesisExt;
+
+use crate::tests::client::process_blocks::{
+    create_nightshade_runtimes, produce_blocks_from_height_with_protocol_version,
+};
+
+/// Check that blocks keep being accepted when the block Merkle tree switches
+/// its hash function, and that proofs of blocks from before the switch verify
+/// against the root of a block after it.
+#[test]
+fn protocol_upgrade() {
+    init_test_logger();
+
+    let old_protocol_version = ProtocolFeature::Sha512Merkle.protocol_version() - 1;
+    let new_protocol_version = old_protocol_version + 1;
+    let epoch_length: BlockHeight = 5;
+
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    genesis.config.protocol_version = old_protocol_version;
+    let chain_genesis = ChainGenesis::new(&genesis);
+    let mut env = TestEnv::builder(chain_genesis)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 1))
+        .build();
+    let head_height = produce_blocks_from_height_with_protocol_version(
+        &mut env,
+        4 * epoch_length,
+        1,
+        new_protocol_version,
+    ) - 1;
+
+    let head = env.clients[0].chain.get_block_by_height(head_height).unwrap();
+    let head_protocol_version = env.clients[0]
+        .runtime_adapter
+        .get_epoch_protocol_version(head.header().epoch_id())
+        .unwrap();
+    assert_eq!(head_protocol_version, new_protocol_version);
+
+    let old_hash_algorithm = merkle_hash_algorithm(old_protocol_version);
+    assert_ne!(old_hash_algorithm, merkle_hash_algorithm(new_protocol_version));
+    let root = *head.header().block_merkle_root();
+    let mut old_hash_algorithm_used = false;
+    for height in 0..head_height {
+        let block = env.clients[0].chain.get_block_by_height(height).unwrap();
+        let (proof, hash_algorithms) =
+            env.clients[0].chain.get_block_proof(block.hash(), head.hash()).unwrap();
+        assert!(verify_hash_with_algorithms(&hash_algorithms, root, &proof, *block.hash()));
+        old_hash_algorithm_used |= hash_algorithms.contains(&old_hash_algorithm);
+    }
+    assert!(old_hash_algorithm_used);
+}
@@ -3,6 +3,8 @@ mod challenges;
 mod chunks_management;
 #[cfg(feature = "cold_store")]
 mod cold_storage;
+mod epoch_sync;
+mod external_state_sync;
 mod features;
 mod process_blocks;
 mod runtimes;
@@ -43,7 +43,9 @@ use near_primitives::epoch_manager::RngSeed;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::errors::TxExecutionError;
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::merkle::{verify_hash, PartialMerkleTree};
+use near_primitives::merkle::{
+    merkle_hash_algorithm, verify_hash_with_algorithms, PartialMerkleTree,
+};
 use near_primitives::receipt::DelayedReceiptIndices;
 use near_primitives::runtime::config::RuntimeConfig;
 use near_primitives::runtime::config_store::RuntimeConfigStore;
@@ -370,7 +372,8 @@ fn receive_network_block() {
         let actor = actor.then(move |res| {
             let (last_block, block_merkle_tree) = res.unwrap().unwrap();
             let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-            block_merkle_tree.insert(last_block.header.hash);
+            block_merkle_tree
+                .insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), last_block.header.hash);
             let signer = InMemoryValidatorSigner::from_seed(
                 "test1".parse().unwrap(),
                 KeyType::ED25519,
@@ -400,7 +403,7 @@ fn receive_network_block() {
                 vec![],
                 &signer,
                 last_block.header.next_bp_hash,
-                block_merkle_tree.root(),
+                block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
                 None,
             );
             client.do_send(
@@ -453,7 +456,8 @@ fn produce_block_with_approvals() {
         let actor = actor.then(move |res| {
             let (last_block, block_merkle_tree) = res.unwrap().unwrap();
             let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-            block_merkle_tree.insert(last_block.header.hash);
+            block_merkle_tree
+                .insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), last_block.header.hash);
             let signer1 = InMemoryValidatorSigner::from_seed(
                 "test2".parse().unwrap(),
                 KeyType::ED25519,
@@ -483,7 +487,7 @@ fn produce_block_with_approvals() {
                 vec![],
                 &signer1,
                 last_block.header.next_bp_hash,
-                block_merkle_tree.root(),
+                block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
                 None,
             );
             client.do_send(
@@ -666,7 +670,8 @@ fn invalid_blocks_common(is_requested: bool) {
         let actor = actor.then(move |res| {
             let (last_block, block_merkle_tree) = res.unwrap().unwrap();
             let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
-            block_merkle_tree.insert(last_block.header.hash);
+            block_merkle_tree
+                .insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), last_block.header.hash);
             let signer = InMemoryValidatorSigner::from_seed(
                 "test".parse().unwrap(),
                 KeyType::ED25519,
@@ -696,7 +701,7 @@ fn invalid_blocks_common(is_requested: bool) {
                 vec![],
                 &signer,
                 last_block.header.next_bp_hash,
-                block_merkle_tree.root(),
+                block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
                 None,
             );
             // Send block with invalid chunk mask
@@ -1078,6 +1083,7 @@ fn test_process_invalid_tx() {
             receiver_id: "test".parse().unwrap(),
             block_hash: *env.clients[0].chain.genesis().hash(),
             actions: vec![],
+            priority_fee: 0,
         },
     );
     for i in 1..12 {
@@ -1096,6 +1102,7 @@ fn test_process_invalid_tx() {
             receiver_id: "test".parse().unwrap(),
             block_hash: hash(&[1]),
             actions: vec![],
+            priority_fee: 0,
         },
     );
     assert_eq!(
@@ -1401,7 +1408,7 @@ fn test_bad_chunk_mask() {
             block.mut_header().get_mut().inner_rest.chunk_tx_root =
                 Block::compute_chunk_tx_root(&chunk_headers);
             block.mut_header().get_mut().inner_rest.chunk_receipts_root =
-                Block::compute_chunk_receipts_root(&chunk_headers);
+                Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
             block.mut_header().get_mut().inner_lite.prev_state_root =
                 Block::compute_state_root(&chunk_headers);
             block.mut_header().get_mut().inner_rest.chunk_mask = vec![true, false];
@@ -2028,8 +2035,9 @@ fn test_block_merkle_proof_with_len(n: NumBlocks, rng: &mut StdRng) {
         }
     }
     for block in blocks {
-        let proof = env.clients[0].chain.get_block_proof(block.hash(), head.hash()).unwrap();
-        assert!(verify_hash(*root, &proof, *block.hash()));
+        let (proof, hash_algorithms) =
+            env.clients[0].chain.get_block_proof(block.hash(), head.hash()).unwrap();
+        assert!(verify_hash_with_algorithms(&hash_algorithms, *root, &proof, *block.hash()));
     }
 }
 
@@ -2045,9 +2053,10 @@ fn test_block_merkle_proof() {
 fn test_block_merkle_proof_same_hash() {
     let env = TestEnv::builder(ChainGenesis::test()).build();
     let genesis_block = env.clients[0].chain.get_block_by_height(0).unwrap();
-    let proof =
+    let (proof, hash_algorithms) =
         env.clients[0].chain.get_block_proof(genesis_block.hash(), genesis_block.hash()).unwrap();
     assert!(proof.is_empty());
+    assert!(hash_algorithms.is_empty());
 }
 
 #[test]
@@ -2087,7 +2096,10 @@ fn test_data_reset_before_state_sync() {
             &head.prev_block_hash,
             &head.last_block_hash,
             head_block.header().epoch_id(),
-            &QueryRequest::ViewAccount { account_id: "test_account".parse().unwrap() },
+            &QueryRequest::ViewAccount {
+                account_id: "test_account".parse().unwrap(),
+                include_proof: false,
+            },
         )
         .unwrap();
     assert_matches!(response.kind, QueryResponseKind::ViewAccount(_));
@@ -2101,7 +2113,10 @@ fn test_data_reset_before_state_sync() {
         &head.prev_block_hash,
         &head.last_block_hash,
         head_block.header().epoch_id(),
-        &QueryRequest::ViewAccount { account_id: "test_account".parse().unwrap() },
+        &QueryRequest::ViewAccount {
+            account_id: "test_account".parse().unwrap(),
+            include_proof: false,
+        },
     );
     // TODO(#3742): ViewClient still has data in cache by current design.
     assert!(response.is_ok());
@@ -2235,7 +2250,7 @@ fn test_validate_chunk_extra() {
         block.mut_header().get_mut().inner_rest.chunk_tx_root =
             Block::compute_chunk_tx_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_receipts_root =
-            Block::compute_chunk_receipts_root(&chunk_headers);
+            Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
         block.mut_header().get_mut().inner_lite.prev_state_root =
             Block::compute_state_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_mask = vec![true];
@@ -2860,7 +2875,7 @@ fn test_query_final_state() {
                 &final_head.prev_block_hash,
                 last_final_block.hash(),
                 last_final_block.header().epoch_id(),
-                &QueryRequest::ViewAccount { account_id },
+                &QueryRequest::ViewAccount { account_id, include_proof: false },
             )
             .unwrap();
         match response.kind {
@@ -2930,7 +2945,7 @@ fn test_fork_receipt_ids() {
         block.mut_header().get_mut().inner_rest.chunk_tx_root =
             Block::compute_chunk_tx_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_receipts_root =
-            Block::compute_chunk_receipts_root(&chunk_headers);
+            Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
         block.mut_header().get_mut().inner_lite.prev_state_root =
             Block::compute_state_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_mask = vec![true];
@@ -2978,7 +2993,7 @@ fn test_fork_execution_outcome() {
         block.mut_header().get_mut().inner_rest.chunk_tx_root =
             Block::compute_chunk_tx_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_receipts_root =
-            Block::compute_chunk_receipts_root(&chunk_headers);
+            Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
         block.mut_header().get_mut().inner_lite.prev_state_root =
             Block::compute_state_root(&chunk_headers);
         block.mut_header().get_mut().inner_rest.chunk_mask = vec![true];
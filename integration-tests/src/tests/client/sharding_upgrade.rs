@@ -412,7 +412,10 @@ fn check_account(env: &mut TestEnv, account_id: &AccountId, block: &Block) {
                     prev_hash,
                     block.hash(),
                     block.header().epoch_id(),
-                    &QueryRequest::ViewAccount { account_id: account_id.clone() },
+                    &QueryRequest::ViewAccount {
+                        account_id: account_id.clone(),
+                        include_proof: false,
+                    },
                 )
                 .unwrap();
 
@@ -428,7 +431,10 @@ fn check_account(env: &mut TestEnv, account_id: &AccountId, block: &Block) {
                         block.header().prev_hash(),
                         block.hash(),
                         block.header().epoch_id(),
-                        &QueryRequest::ViewAccount { account_id: account_id.clone() },
+                        &QueryRequest::ViewAccount {
+                            account_id: account_id.clone(),
+                            include_proof: false,
+                        },
                     )
                     .unwrap();
             }
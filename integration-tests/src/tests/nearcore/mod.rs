@@ -1,5 +1,6 @@
 mod node_cluster;
 mod rpc_error_structs;
+mod rosetta;
 mod rpc_nodes;
 mod run_nodes;
 mod stake_nodes;
@@ -0,0 +1,216 @@
+use crate::test_helpers::heavy_test;
+use actix::clock::sleep;
+use actix::System;
+use near_actix_test_utils::{run_actix, spawn_interruptible};
+use near_chain_configs::Genesis;
+use near_crypto::{InMemorySigner, KeyType, Signature, Signer};
+use near_network::test_utils::open_port;
+use near_o11y::testonly::init_integration_logger;
+use near_primitives::account::AccessKey;
+use near_primitives::state_record::StateRecord;
+use near_primitives::types::Balance;
+use near_rosetta_rpc::RosettaRpcConfig;
+use nearcore::{config::GenesisExt, load_test_config, start_with_config};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+const TRANSFER_AMOUNT: Balance = 1_000_000_000;
+
+struct RosettaClient {
+    addr: String,
+    network_identifier: Value,
+}
+
+impl RosettaClient {
+    async fn new(addr: String) -> Self {
+        let mut client = Self { addr, network_identifier: Value::Null };
+        let response = client.post("/network/list", json!({ "metadata": {} })).await.unwrap();
+        client.network_identifier = response["network_identifiers"][0].clone();
+        client
+    }
+
+    async fn post(&self, path: &str, request: Value) -> Result<Value, Value> {
+        let mut response = awc::Client::new()
+            .post(format!("http://{}{}", self.addr, path))
+            .send_json(&request)
+            .await
+            .unwrap();
+        let body: Value = response.json().limit(1 << 20).await.unwrap();
+        if response.status().is_success() {
+            Ok(body)
+        } else {
+            Err(body)
+        }
+    }
+
+    /// Calls `path` on the configured network and returns the response body,
+    /// or the Rosetta error object if the request failed.
+    async fn call(&self, path: &str, mut request: Value) -> Result<Value, Value> {
+        request["network_identifier"] = self.network_identifier.clone();
+        self.post(path, request).await
+    }
+
+    async fn liquid_balance(&self, account_id: &str) -> Balance {
+        let request = json!({ "account_identifier": { "address": account_id } });
+        let response = self.call("/account/balance", request).await.unwrap();
+        response["balances"][0]["value"].as_str().unwrap().parse().unwrap()
+    }
+}
+
+fn rosetta_public_key(signer: &InMemorySigner) -> Value {
+    let curve_type = match signer.public_key.key_type() {
+        KeyType::MLDSA => "ml_dsa_65",
+        KeyType::SLHDSA => "slh_dsa_sha2_128s",
+        KeyType::ED25519MLDSA => "edwards25519_ml_dsa_65",
+        key_type => unreachable!("{key_type}"),
+    };
+    json!({
+        "hex_bytes": hex::encode(signer.public_key.key_data()),
+        "curve_type": curve_type,
+    })
+}
+
+fn signature_bytes(signature: &Signature) -> Vec<u8> {
+    match signature {
+        Signature::MLDSA(signature) => signature.as_ref().to_vec(),
+        Signature::SLHDSA(signature) => signature.as_ref().to_vec(),
+        Signature::ED25519MLDSA(signature) => signature.as_ref().to_vec(),
+        _ => unreachable!(),
+    }
+}
+
+fn transfer_operations(amount: Balance) -> Value {
+    let currency = json!({ "symbol": "NEAR", "decimals": 24 });
+    json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "TRANSFER",
+            "account": { "address": "near.0" },
+            "amount": { "value": format!("-{amount}"), "currency": currency },
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "related_operations": [{ "index": 0 }],
+            "type": "TRANSFER",
+            "account": { "address": "near.1" },
+            "amount": { "value": amount.to_string(), "currency": currency },
+        },
+    ])
+}
+
+/// Goes through the Construction API flow for a transfer from `near.0` to
+/// `near.1` signed offline with `signer` and returns the submitted
+/// transaction identifier.
+async fn construct_and_submit_transfer(rosetta: &RosettaClient, signer: &InMemorySigner) -> Value {
+    let public_key = rosetta_public_key(signer);
+    let operations = transfer_operations(TRANSFER_AMOUNT);
+
+    // Implicit accounts can only be derived from ed25519 keys.
+    let derive = json!({ "public_key": public_key });
+    let err = rosetta.call("/construction/derive", derive).await.unwrap_err();
+    assert_eq!(err["code"], 400, "{err}");
+
+    let preprocess = json!({ "operations": operations });
+    let options =
+        rosetta.call("/construction/preprocess", preprocess).await.unwrap()["options"].clone();
+    let metadata = json!({ "options": options, "public_keys": [public_key] });
+    let metadata =
+        rosetta.call("/construction/metadata", metadata).await.unwrap()["metadata"].clone();
+    let payloads = json!({
+        "operations": operations,
+        "public_keys": [public_key],
+        "metadata": metadata,
+    });
+    let payloads = rosetta.call("/construction/payloads", payloads).await.unwrap();
+    let payload = payloads["payloads"][0].clone();
+    let signature_type = payload["signature_type"].clone();
+    assert_eq!(
+        signature_type,
+        match signer.public_key.key_type() {
+            KeyType::MLDSA => "ml_dsa_65",
+            KeyType::SLHDSA => "slh_dsa_sha2_128s",
+            _ => "ed25519_ml_dsa_65",
+        }
+    );
+
+    // Sign offline, i.e. without handing the secret key to the node.
+    let message = hex::decode(payload["hex_bytes"].as_str().unwrap()).unwrap();
+    let signature = signature_bytes(&signer.sign(&message));
+    let combine = |signature: &[u8]| {
+        json!({
+            "unsigned_transaction": payloads["unsigned_transaction"],
+            "signatures": [{
+                "signing_payload": payload,
+                "public_key": public_key,
+                "signature_type": signature_type,
+                "hex_bytes": hex::encode(signature),
+            }],
+        })
+    };
+
+    let mut bad_signature = signature.clone();
+    bad_signature[0] ^= 1;
+    let err = rosetta.call("/construction/combine", combine(&bad_signature)).await.unwrap_err();
+    assert_eq!(err["code"], 400, "{err}");
+
+    let signed_transaction =
+        rosetta.call("/construction/combine", combine(&signature)).await.unwrap()
+            ["signed_transaction"]
+            .clone();
+    let submit = json!({ "signed_transaction": signed_transaction });
+    rosetta.call("/construction/submit", submit).await.unwrap()["transaction_identifier"].clone()
+}
+
+/// Constructs, signs offline and submits a transfer through the Rosetta
+/// Construction API with each of the post-quantum access key types.
+#[test]
+#[cfg_attr(not(feature = "expensive_tests"), ignore)]
+fn test_rosetta_post_quantum_transfers() {
+    init_integration_logger();
+
+    let signers = [KeyType::MLDSA, KeyType::SLHDSA, KeyType::ED25519MLDSA]
+        .map(|key_type| InMemorySigner::from_seed("near.0".parse().unwrap(), key_type, "rosetta"));
+
+    let mut genesis = Genesis::test(vec!["near.0".parse().unwrap(), "near.1".parse().unwrap()], 1);
+    let records = genesis.force_read_records().as_mut();
+    for signer in &signers {
+        records.push(StateRecord::AccessKey {
+            account_id: signer.account_id.clone(),
+            public_key: signer.public_key.clone(),
+            access_key: AccessKey::full_access(),
+        });
+    }
+
+    let mut near_config = load_test_config("near.0", open_port(), genesis);
+    near_config.client_config.min_num_peers = 0;
+    near_config.client_config.epoch_sync_enabled = false;
+    let rosetta_port = open_port();
+    near_config.rosetta_rpc_config =
+        Some(RosettaRpcConfig::new(&format!("0.0.0.0:{rosetta_port}")));
+
+    heavy_test(|| {
+        let temp_dir = tempfile::tempdir().unwrap();
+        run_actix(async {
+            start_with_config(temp_dir.path(), near_config).expect("start_with_config");
+
+            spawn_interruptible(async move {
+                let rosetta = RosettaClient::new(format!("127.0.0.1:{rosetta_port}")).await;
+                let initial_balance = rosetta.liquid_balance("near.1").await;
+
+                for signer in &signers {
+                    let transaction_identifier =
+                        construct_and_submit_transfer(&rosetta, signer).await;
+                    assert!(transaction_identifier["hash"].as_str().unwrap().starts_with("tx:"));
+                }
+
+                let expected_balance = initial_balance + TRANSFER_AMOUNT * signers.len() as u128;
+                let deadline = Instant::now() + Duration::from_secs(30);
+                while rosetta.liquid_balance("near.1").await != expected_balance {
+                    assert!(Instant::now() < deadline, "transfers did not land in 30 seconds");
+                    sleep(Duration::from_millis(200)).await;
+                }
+                System::current().stop();
+            });
+        });
+    });
+}
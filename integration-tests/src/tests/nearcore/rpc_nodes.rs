@@ -14,14 +14,16 @@ use near_network::test_utils::WaitOrTimeoutActor;
 use near_o11y::testonly::init_integration_logger;
 use near_o11y::WithSpanContextExt;
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::merkle::{compute_root_from_path_and_item, verify_path};
+use near_primitives::merkle::{
+    compute_root_from_path_and_item_with, merkle_hash_algorithm, verify_path_with,
+};
 use near_primitives::runtime::config_store::RuntimeConfigStore;
 use near_primitives::serialize::to_base64;
 use near_primitives::transaction::{PartialExecutionStatus, SignedTransaction};
 use near_primitives::types::{
     BlockId, BlockReference, EpochId, EpochReference, Finality, TransactionOrReceiptId,
 };
-use near_primitives::version::ProtocolVersion;
+use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{ExecutionOutcomeView, ExecutionStatusView};
 use std::time::Duration;
 
@@ -175,12 +177,16 @@ fn test_get_execution_outcome(is_tx_successful: bool) {
                                             outcome_with_id_to_hash.extend(outcome_view_to_hashes(
                                                 &execution_outcome_response.outcome_proof.outcome,
                                             ));
+                                            let hash_algorithm =
+                                                merkle_hash_algorithm(PROTOCOL_VERSION);
                                             let chunk_outcome_root =
-                                                compute_root_from_path_and_item(
+                                                compute_root_from_path_and_item_with(
+                                                    hash_algorithm,
                                                     &execution_outcome_response.outcome_proof.proof,
                                                     &outcome_with_id_to_hash,
                                                 );
-                                            assert!(verify_path(
+                                            assert!(verify_path_with(
+                                                hash_algorithm,
                                                 res.header.outcome_root,
                                                 &execution_outcome_response.outcome_root_proof,
                                                 &chunk_outcome_root
@@ -279,6 +285,7 @@ fn test_query_rpc_account_view_must_succeed() {
                 block_reference: near_primitives::types::BlockReference::Finality(Finality::Final),
                 request: near_primitives::views::QueryRequest::ViewAccount {
                     account_id: "near.0".parse().unwrap(),
+                    include_proof: false,
                 },
             })
             .await
@@ -319,6 +326,7 @@ fn test_query_rpc_account_view_account_doesnt_exist_must_return_error() {
                     block_reference: near_primitives::types::BlockReference::Finality(Finality::Final),
                     request: near_primitives::views::QueryRequest::ViewAccount {
                         account_id: "accountdoesntexist.0".parse().unwrap(),
+                        include_proof: false,
                     },
                 })
                 .await;
@@ -262,6 +262,7 @@ fn test_validator_kickout() {
                                         BlockReference::latest(),
                                         QueryRequest::ViewAccount {
                                             account_id: test_nodes[i as usize].account_id.clone(),
+                                            include_proof: false,
                                         },
                                     )
                                     .with_span_context(),
@@ -288,6 +289,7 @@ fn test_validator_kickout() {
                                         BlockReference::latest(),
                                         QueryRequest::ViewAccount {
                                             account_id: test_nodes[i as usize].account_id.clone(),
+                                            include_proof: false,
                                         },
                                     )
                                     .with_span_context(),
@@ -433,6 +435,7 @@ fn test_validator_join() {
                                     BlockReference::latest(),
                                     QueryRequest::ViewAccount {
                                         account_id: test_nodes[1].account_id.clone(),
+                                        include_proof: false,
                                     },
                                 )
                                 .with_span_context(),
@@ -452,6 +455,7 @@ fn test_validator_join() {
                                     BlockReference::latest(),
                                     QueryRequest::ViewAccount {
                                         account_id: test_nodes[2].account_id.clone(),
+                                        include_proof: false,
                                     },
                                 )
                                 .with_span_context(),
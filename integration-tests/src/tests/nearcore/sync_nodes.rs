@@ -18,7 +18,7 @@ use near_o11y::testonly::init_integration_logger;
 use near_o11y::WithSpanContextExt;
 use near_primitives::block::Approval;
 use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::PartialMerkleTree;
+use near_primitives::merkle::{merkle_hash_algorithm, PartialMerkleTree};
 use near_primitives::num_rational::Ratio;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::validator_stake::ValidatorStake;
@@ -39,7 +39,7 @@ fn add_blocks(
     let mut prev = &blocks[blocks.len() - 1];
     let mut block_merkle_tree = PartialMerkleTree::default();
     for block in blocks.iter() {
-        block_merkle_tree.insert(*block.hash());
+        block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *block.hash());
     }
     for _ in 0..num {
         let epoch_id = match prev.header().height() + 1 {
@@ -83,10 +83,10 @@ fn add_blocks(
             vec![],
             signer,
             next_bp_hash,
-            block_merkle_tree.root(),
+            block_merkle_tree.root_with(merkle_hash_algorithm(PROTOCOL_VERSION)),
             None,
         );
-        block_merkle_tree.insert(*block.hash());
+        block_merkle_tree.insert_with(merkle_hash_algorithm(PROTOCOL_VERSION), *block.hash());
         let _ = client.do_send(
             BlockResponse {
                 block: block.clone(),
@@ -63,7 +63,8 @@ impl RpcUser {
 
 impl User for RpcUser {
     fn view_account(&self, account_id: &AccountId) -> Result<AccountView, String> {
-        let query = QueryRequest::ViewAccount { account_id: account_id.clone() };
+        let query =
+            QueryRequest::ViewAccount { account_id: account_id.clone(), include_proof: false };
         match self.query(query)?.kind {
             near_jsonrpc_primitives::types::query::QueryResponseKind::ViewAccount(account_view) => {
                 Ok(account_view)
@@ -197,6 +198,7 @@ impl User for RpcUser {
         let query = QueryRequest::ViewAccessKey {
             account_id: account_id.clone(),
             public_key: public_key.clone(),
+            include_proof: false,
         };
         match self.query(query)?.kind {
             near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(access_key) => {
@@ -16,7 +16,7 @@ use tracing::{info, warn};
 
 use near_chain_configs::{
     get_initial_supply, ClientConfig, GCConfig, Genesis, GenesisConfig, GenesisValidationMode,
-    LogSummaryStyle,
+    LogSummaryStyle, StateSyncConfig, TransactionPoolConfig,
 };
 use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, Signer};
 #[cfg(feature = "json_rpc")]
@@ -321,6 +321,12 @@ pub struct Config {
     /// If set, overrides value in genesis configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_gas_burnt_view: Option<Gas>,
+    /// Capacity and eviction policy of the transaction pools.
+    #[serde(default)]
+    pub transaction_pool: TransactionPoolConfig,
+    /// External storage to dump state parts to and to sync state from.
+    #[serde(default)]
+    pub state_sync: StateSyncConfig,
     /// Different parameters to configure underlying storage.
     pub store: near_store::StoreConfig,
     /// Different parameters to configure underlying cold storage.
@@ -369,6 +375,8 @@ impl Default for Config {
             view_client_throttle_period: default_view_client_throttle_period(),
             trie_viewer_state_size_limit: default_trie_viewer_state_size_limit(),
             max_gas_burnt_view: None,
+            transaction_pool: TransactionPoolConfig::default(),
+            state_sync: StateSyncConfig::default(),
             db_migration_snapshot_path: None,
             use_db_migration_snapshot: None,
             store: near_store::StoreConfig::default(),
@@ -440,17 +448,18 @@ impl Config {
 impl Genesis {
     // Creates new genesis with a given set of accounts and shard layout.
     // The first num_validator_seats from accounts will be treated as 'validators'.
+    // Account keys are derived from the account ids and are of the given key_type.
     pub fn test_with_seeds(
         accounts: Vec<AccountId>,
         num_validator_seats: NumSeats,
         num_validator_seats_per_shard: Vec<NumSeats>,
         shard_layout: ShardLayout,
+        key_type: KeyType,
     ) -> Self {
         let mut validators = vec![];
         let mut records = vec![];
         for (i, account) in accounts.into_iter().enumerate() {
-            let signer =
-                InMemorySigner::from_seed(account.clone(), KeyType::ED25519, account.as_ref());
+            let signer = InMemorySigner::from_seed(account.clone(), key_type, account.as_ref());
             let i = i as u64;
             if i < num_validator_seats {
                 validators.push(AccountInfo {
@@ -505,6 +514,7 @@ impl Genesis {
             num_validator_seats,
             vec![num_validator_seats],
             ShardLayout::v0_single_shard(),
+            KeyType::ED25519,
         )
     }
 
@@ -519,6 +529,7 @@ impl Genesis {
             num_validator_seats,
             num_validator_seats_per_shard,
             ShardLayout::v0(num_shards, 0),
+            KeyType::ED25519,
         )
     }
 
@@ -533,6 +544,7 @@ impl Genesis {
             num_validator_seats,
             num_validator_seats_per_shard,
             ShardLayout::v0(num_shards, 1),
+            KeyType::ED25519,
         )
     }
 }
@@ -605,6 +617,8 @@ impl NearConfig {
                 trie_viewer_state_size_limit: config.trie_viewer_state_size_limit,
                 max_gas_burnt_view: config.max_gas_burnt_view,
                 enable_statistics_export: config.store.enable_statistics_export,
+                transaction_pool: config.transaction_pool,
+                state_sync: config.state_sync,
             },
             network_config: NetworkConfig::new(
                 config.network,
@@ -1027,17 +1041,19 @@ pub fn create_testnet_configs_from_seeds(
     local_ports: bool,
     archive: bool,
     fixed_shards: Option<Vec<String>>,
+    validator_key_type: KeyType,
+    node_key_type: KeyType,
 ) -> (Vec<Config>, Vec<InMemoryValidatorSigner>, Vec<InMemorySigner>, Genesis) {
     let num_validator_seats = (seeds.len() - num_non_validator_seats as usize) as NumSeats;
     let validator_signers = seeds
         .iter()
         .map(|seed| {
-            InMemoryValidatorSigner::from_seed(seed.parse().unwrap(), KeyType::ED25519, seed)
+            InMemoryValidatorSigner::from_seed(seed.parse().unwrap(), validator_key_type, seed)
         })
         .collect::<Vec<_>>();
     let network_signers = seeds
         .iter()
-        .map(|seed| InMemorySigner::from_seed("node".parse().unwrap(), KeyType::ED25519, seed))
+        .map(|seed| InMemorySigner::from_seed("node".parse().unwrap(), node_key_type, seed))
         .collect::<Vec<_>>();
 
     let shard_layout = if let Some(ref fixed_shards) = fixed_shards {
@@ -1066,6 +1082,7 @@ pub fn create_testnet_configs_from_seeds(
         num_validator_seats,
         get_num_seats_per_shard(num_shards, num_validator_seats),
         shard_layout,
+        validator_key_type,
     );
     let mut configs = vec![];
     let first_node_port = open_port();
@@ -1103,6 +1120,8 @@ pub fn create_testnet_configs(
     local_ports: bool,
     archive: bool,
     fixed_shards: bool,
+    validator_key_type: KeyType,
+    node_key_type: KeyType,
 ) -> (Vec<Config>, Vec<InMemoryValidatorSigner>, Vec<InMemorySigner>, Genesis, Vec<InMemorySigner>)
 {
     let fixed_shards = if fixed_shards {
@@ -1128,6 +1147,8 @@ pub fn create_testnet_configs(
         local_ports,
         archive,
         fixed_shards,
+        validator_key_type,
+        node_key_type,
     );
 
     (configs, validator_signers, network_signers, genesis, shard_keys)
@@ -1141,6 +1162,8 @@ pub fn init_testnet_configs(
     prefix: &str,
     archive: bool,
     fixed_shards: bool,
+    validator_key_type: KeyType,
+    node_key_type: KeyType,
 ) {
     let (configs, validator_signers, network_signers, genesis, shard_keys) = create_testnet_configs(
         num_shards,
@@ -1150,6 +1173,8 @@ pub fn init_testnet_configs(
         false,
         archive,
         fixed_shards,
+        validator_key_type,
+        node_key_type,
     );
     for i in 0..(num_validator_seats + num_non_validator_seats) as usize {
         let node_dir = dir.join(format!("{}{}", prefix, i));
@@ -35,6 +35,7 @@ use near_primitives::state_part::PartId;
 use near_primitives::state_record::{state_record_to_account_id, StateRecord};
 use near_primitives::syncing::{get_num_state_parts, STATE_PART_MEMORY_LIMIT};
 use near_primitives::transaction::SignedTransaction;
+use near_primitives::trie_key::TrieKey;
 use near_primitives::types::validator_stake::ValidatorStakeIter;
 use near_primitives::types::{
     AccountId, Balance, BlockHeight, CompiledContractCache, EpochHeight, EpochId,
@@ -43,8 +44,8 @@ use near_primitives::types::{
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::{
-    AccessKeyInfoView, CallResult, QueryRequest, QueryResponse, QueryResponseKind, ViewApplyState,
-    ViewStateResult,
+    AccessKeyInfoView, AccessKeyWithProofView, AccountWithProofView, CallResult, QueryRequest,
+    QueryResponse, QueryResponseKind, ViewApplyState, ViewStateResult,
 };
 #[cfg(feature = "protocol_feature_flat_state")]
 use near_store::flat_state::ChainAccessForFlatStorage;
@@ -621,6 +622,18 @@ fn format_total_gas_burnt(gas: Gas) -> String {
     format!("{:.0}", ((gas as f64) / 1e14).ceil() * 100.0)
 }
 
+fn proof_error(
+    err: StorageError,
+    block_height: BlockHeight,
+    block_hash: &CryptoHash,
+) -> near_chain::near_chain_primitives::error::QueryError {
+    near_chain::near_chain_primitives::error::QueryError::InternalError {
+        error_message: format!("failed to collect proof: {}", err),
+        block_height,
+        block_hash: *block_hash,
+    }
+}
+
 fn apply_delayed_receipts<'a>(
     tries: &ShardTries,
     orig_shard_uid: ShardUId,
@@ -936,7 +949,6 @@ impl RuntimeAdapter for NightshadeRuntime {
         Ok(epoch_manager.get_epoch_info(epoch_id)?.minted_amount())
     }
 
-    // TODO #3488 this likely to be updated
     fn get_epoch_sync_data(
         &self,
         prev_epoch_last_block_hash: &CryptoHash,
@@ -1061,11 +1073,7 @@ impl RuntimeAdapter for NightshadeRuntime {
             use_flat_storage,
         )?;
 
-        // TODO (#6316): support chunk nodes caching for TrieRecordingStorage
-        if generate_storage_proof {
-            panic!("Storage proof generation is not enabled yet");
-        }
-        // let trie = if generate_storage_proof { trie.recording_reads() } else { trie };
+        let trie = if generate_storage_proof { trie.recording_reads() } else { trie };
         match self.process_state_update(
             trie,
             shard_id,
@@ -1147,7 +1155,7 @@ impl RuntimeAdapter for NightshadeRuntime {
         request: &QueryRequest,
     ) -> Result<QueryResponse, near_chain::near_chain_primitives::error::QueryError> {
         match request {
-            QueryRequest::ViewAccount { account_id } => {
+            QueryRequest::ViewAccount { account_id, include_proof } => {
                 let account = self
                     .view_account(&shard_uid, *state_root, account_id)
                     .map_err(|err| {
@@ -1157,11 +1165,19 @@ impl RuntimeAdapter for NightshadeRuntime {
                         *block_hash,
                     )
                 })?;
-                Ok(QueryResponse {
-                    kind: QueryResponseKind::ViewAccount(account.into()),
-                    block_height,
-                    block_hash: *block_hash,
-                })
+                let kind = if *include_proof {
+                    let key = TrieKey::Account { account_id: account_id.clone() };
+                    let proof = self
+                        .view_proof(&shard_uid, *state_root, &key)
+                        .map_err(|err| proof_error(err, block_height, block_hash))?;
+                    QueryResponseKind::ViewAccountWithProof(AccountWithProofView {
+                        account: account.into(),
+                        proof,
+                    })
+                } else {
+                    QueryResponseKind::ViewAccount(account.into())
+                };
+                Ok(QueryResponse { kind, block_height, block_hash: *block_hash })
             }
             QueryRequest::ViewCode { account_id } => {
                 let contract_code = self
@@ -1259,7 +1275,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
-            QueryRequest::ViewAccessKey { account_id, public_key } => {
+            QueryRequest::ViewAccessKey { account_id, public_key, include_proof } => {
                 let access_key = self
                     .view_access_key(&shard_uid, *state_root, account_id, public_key)
                     .map_err(|err| {
@@ -1269,11 +1285,22 @@ impl RuntimeAdapter for NightshadeRuntime {
                             *block_hash,
                         )
                     })?;
-                Ok(QueryResponse {
-                    kind: QueryResponseKind::AccessKey(access_key.into()),
-                    block_height,
-                    block_hash: *block_hash,
-                })
+                let kind = if *include_proof {
+                    let key = TrieKey::AccessKey {
+                        account_id: account_id.clone(),
+                        public_key: public_key.clone(),
+                    };
+                    let proof = self
+                        .view_proof(&shard_uid, *state_root, &key)
+                        .map_err(|err| proof_error(err, block_height, block_hash))?;
+                    QueryResponseKind::AccessKeyWithProof(AccessKeyWithProofView {
+                        access_key: access_key.into(),
+                        proof,
+                    })
+                } else {
+                    QueryResponseKind::AccessKey(access_key.into())
+                };
+                Ok(QueryResponse { kind, block_height, block_hash: *block_hash })
             }
         }
     }
@@ -1626,6 +1653,16 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
         self.trie_viewer.view_state(&state_update, account_id, prefix, include_proof)
     }
+
+    fn view_proof(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        key: &TrieKey,
+    ) -> Result<Vec<Arc<[u8]>>, StorageError> {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_proof(&state_update, key)
+    }
 }
 
 #[cfg(test)]
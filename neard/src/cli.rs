@@ -3,6 +3,7 @@ use anyhow::Context;
 use clap::{Args, Parser};
 use near_amend_genesis::AmendGenesisCommand;
 use near_chain_configs::GenesisValidationMode;
+use near_crypto::KeyType;
 use near_jsonrpc_primitives::types::light_client::RpcLightClientExecutionProofResponse;
 use near_mirror::MirrorCommand;
 use near_o11y::tracing_subscriber::EnvFilter;
@@ -10,8 +11,10 @@ use near_o11y::{
     default_subscriber, default_subscriber_with_opentelemetry, BuildEnvFilterError,
     EnvFilterBuilder, OpenTelemetryLevel,
 };
-use near_primitives::hash::CryptoHash;
-use near_primitives::merkle::compute_root_from_path;
+use near_primitives::hash::{CryptoHash, HashAlgorithm};
+use near_primitives::merkle::{
+    compute_root_from_path, compute_root_from_path_with, compute_root_from_path_with_algorithms,
+};
 use near_primitives::types::{Gas, NumSeats, NumShards};
 use near_state_viewer::StateViewerSubCommand;
 use near_store::db::RocksDB;
@@ -517,6 +520,14 @@ pub(super) struct LocalnetCmd {
     /// Whether to configure nodes as archival.
     #[clap(long)]
     archival_nodes: bool,
+    /// Key type of the validator keys, e.g. ‘ed25519’ or ‘ed25519-mldsa’.
+    /// Validators with post-quantum keys require a protocol version which
+    /// supports them.
+    #[clap(long, default_value = "ed25519")]
+    validator_key_type: KeyType,
+    /// Key type of the node keys, e.g. ‘ed25519’, ‘mldsa’ or ‘ed25519-mldsa’.
+    #[clap(long, default_value = "ed25519")]
+    node_key_type: KeyType,
 }
 
 impl LocalnetCmd {
@@ -529,6 +540,8 @@ impl LocalnetCmd {
             &self.prefix,
             self.archival_nodes,
             self.fixed_shards,
+            self.validator_key_type,
+            self.node_key_type,
         );
     }
 }
@@ -580,12 +593,20 @@ pub enum VerifyProofError {
     InvalidOutcomeRootProof,
     #[error("invalid block hash proof")]
     InvalidBlockHashProof,
+    #[error("invalid block merkle proof")]
+    InvalidBlockMerkleProof,
 }
 
 #[derive(Parser)]
 pub struct VerifyProofSubCommand {
     #[clap(long)]
     json_file_path: String,
+    /// Hash function the outcome Merkle trees of the proof are built with:
+    /// `sha256`, or `sha3_256` or `sha512_256` for blocks of a protocol version
+    /// with SHA3 or SHA-512/256 Merkle trees.  The block Merkle proof is checked
+    /// with the hash functions listed in the proof.
+    #[clap(long, default_value = "sha256")]
+    hash_algorithm: HashAlgorithm,
 }
 
 impl VerifyProofSubCommand {
@@ -598,11 +619,12 @@ impl VerifyProofSubCommand {
         let reader = BufReader::new(file);
         let light_client_rpc_response: Value =
             serde_json::from_reader(reader).with_context(|| "Failed to deserialize JSON.").unwrap();
-        Self::verify_json(light_client_rpc_response).unwrap()
+        Self::verify_json(light_client_rpc_response, self.hash_algorithm).unwrap()
     }
 
     pub fn verify_json(
         light_client_rpc_response: Value,
+        hash_algorithm: HashAlgorithm,
     ) -> Result<((CryptoHash, u64), CryptoHash), VerifyProofError> {
         let light_client_proof: RpcLightClientExecutionProofResponse =
             serde_json::from_value(light_client_rpc_response["result"].clone()).unwrap();
@@ -614,15 +636,19 @@ impl VerifyProofSubCommand {
         let outcome_hashes = light_client_proof.outcome_proof.clone().to_hashes();
         println!("Hashes of the outcome are: {:?}", outcome_hashes);
 
-        let outcome_hash = CryptoHash::hash_borsh(&outcome_hashes);
+        let outcome_hash = hash_algorithm.hash_borsh(&outcome_hashes);
         println!("Hash of the outcome is: {:?}", outcome_hash);
 
-        let outcome_shard_root =
-            compute_root_from_path(&light_client_proof.outcome_proof.proof, outcome_hash);
+        let outcome_shard_root = compute_root_from_path_with(
+            hash_algorithm,
+            &light_client_proof.outcome_proof.proof,
+            outcome_hash,
+        );
         println!("Shard outcome root is: {:?}", outcome_shard_root);
-        let block_outcome_root = compute_root_from_path(
+        let block_outcome_root = compute_root_from_path_with(
+            hash_algorithm,
             &light_client_proof.outcome_root_proof,
-            CryptoHash::hash_borsh(&outcome_shard_root),
+            hash_algorithm.hash_borsh(&outcome_shard_root),
         );
         println!("Block outcome root is: {:?}", block_outcome_root);
 
@@ -660,8 +686,28 @@ impl VerifyProofSubCommand {
 
         // And now check that block exists in the light client.
 
-        let light_block_merkle_root =
-            compute_root_from_path(&light_client_proof.block_proof, block_hash);
+        // Proofs from nodes which don't list the hash functions of the block Merkle proof are
+        // built with SHA-256.
+        let block_proof_hash_algorithms = &light_client_proof.block_proof_hash_algorithms;
+        let light_block_merkle_root = if block_proof_hash_algorithms.is_empty() {
+            compute_root_from_path(&light_client_proof.block_proof, block_hash)
+        } else if block_proof_hash_algorithms.len() == light_client_proof.block_proof.len() {
+            compute_root_from_path_with_algorithms(
+                block_proof_hash_algorithms,
+                &light_client_proof.block_proof,
+                block_hash,
+            )
+        } else {
+            println!(
+                "{}",
+                ansi_term::Colour::Red.bold().paint(format!(
+                    "ERROR: block merkle proof has {} items but {} hash functions.",
+                    light_client_proof.block_proof.len(),
+                    block_proof_hash_algorithms.len()
+                ))
+            );
+            return Err(VerifyProofError::InvalidBlockMerkleProof);
+        };
 
         println!(
             "Please verify that your light block has the following block merkle root: {:?}",
@@ -724,7 +770,8 @@ mod tests {
     fn verify_proof_test() {
         assert_eq!(
             VerifyProofSubCommand::verify_json(
-                serde_json::from_slice(include_bytes!("../res/proof_example.json")).unwrap()
+                serde_json::from_slice(include_bytes!("../res/proof_example.json")).unwrap(),
+                HashAlgorithm::Sha256,
             )
             .unwrap(),
             (
@@ -739,7 +786,8 @@ mod tests {
         // Proof with a wroing outcome (as user specified wrong shard).
         assert_eq!(
             VerifyProofSubCommand::verify_json(
-                serde_json::from_slice(include_bytes!("../res/invalid_proof.json")).unwrap()
+                serde_json::from_slice(include_bytes!("../res/invalid_proof.json")).unwrap(),
+                HashAlgorithm::Sha256,
             )
             .unwrap_err(),
             VerifyProofError::InvalidOutcomeRootProof
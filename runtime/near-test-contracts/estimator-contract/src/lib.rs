@@ -62,6 +62,41 @@ extern "C" {
         pub_key_len: u64,
         pub_key_ptr: u64,
     ) -> u64;
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    fn mldsa_verify(
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64;
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    fn slhdsa_verify(
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64;
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    fn ml_kem_encapsulate(
+        encaps_key_len: u64,
+        encaps_key_ptr: u64,
+        seed_len: u64,
+        seed_ptr: u64,
+        register_id: u64,
+    );
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    fn ml_kem_decapsulate_verify(
+        decaps_key_len: u64,
+        decaps_key_ptr: u64,
+        ciphertext_len: u64,
+        ciphertext_ptr: u64,
+        shared_secret_len: u64,
+        shared_secret_ptr: u64,
+    ) -> u64;
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -512,6 +547,147 @@ pub unsafe fn ed25519_verify_10k() {
     }
 }
 
+// Functions to measure `mldsa_verify_base` and `slhdsa_verify_base`. Like `ed25519_verify_10k`
+// they also measure `base` and reading the inputs from memory, which is negligible compared to
+// the signature verification itself.
+// The signatures are over the same 32 byte message as in `ed25519_verify_10k`, with an empty
+// context string. They were produced with OpenSSL 3.5 (`openssl pkeyutl -sign -rawin`) and the
+// public keys are the raw keys from the corresponding `SubjectPublicKeyInfo`.
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+const PQ_VERIFY_MESSAGE: [u8; 32] = [
+    107, 97, 106, 100, 108, 102, 107, 106, 97, 108, 107, 102, 106, 97, 107, 108, 102, 106, 100,
+    107, 108, 97, 100, 106, 102, 107, 108, 106, 97, 100, 115, 107,
+];
+
+// Compute mldsa_verify 10k times.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+pub unsafe fn mldsa_verify_10k() {
+    let signature = include_bytes!("../res/mldsa_signature.bin");
+    let public_key = include_bytes!("../res/mldsa_public_key.bin");
+
+    for _ in 0..10_000 {
+        mldsa_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            PQ_VERIFY_MESSAGE.len() as _,
+            PQ_VERIFY_MESSAGE.as_ptr() as _,
+            public_key.len() as _,
+            public_key.as_ptr() as _,
+        );
+    }
+}
+
+// Function to measure `mldsa_verify_byte`, together with `mldsa_verify_10k`.
+// The signature is over `PQ_VERIFY_MESSAGE`, so verification of the 10kib message fails. It still
+// hashes the whole message and does the same amount of work as a successful verification, there
+// is no early exit on a mismatching message.
+// Compute mldsa_verify on 10kib 10k times.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+pub unsafe fn mldsa_verify_10kib_10k() {
+    let signature = include_bytes!("../res/mldsa_signature.bin");
+    let public_key = include_bytes!("../res/mldsa_public_key.bin");
+    let message = [65u8; 10240];
+
+    for _ in 0..10_000 {
+        mldsa_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            public_key.len() as _,
+            public_key.as_ptr() as _,
+        );
+    }
+}
+
+// Compute slhdsa_verify 1k times.  SLH-DSA verification is an order of magnitude slower than
+// ML-DSA verification, so fewer iterations suffice.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+pub unsafe fn slhdsa_verify_1k() {
+    let signature = include_bytes!("../res/slhdsa_signature.bin");
+    let public_key = include_bytes!("../res/slhdsa_public_key.bin");
+
+    for _ in 0..1_000 {
+        slhdsa_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            PQ_VERIFY_MESSAGE.len() as _,
+            PQ_VERIFY_MESSAGE.as_ptr() as _,
+            public_key.len() as _,
+            public_key.as_ptr() as _,
+        );
+    }
+}
+
+// Function to measure `slhdsa_verify_byte`, together with `slhdsa_verify_1k`. As in
+// `mldsa_verify_10kib_10k` the verification fails but does the full amount of work.
+// Compute slhdsa_verify on 10kib 1k times.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+pub unsafe fn slhdsa_verify_10kib_1k() {
+    let signature = include_bytes!("../res/slhdsa_signature.bin");
+    let public_key = include_bytes!("../res/slhdsa_public_key.bin");
+    let message = [65u8; 10240];
+
+    for _ in 0..1_000 {
+        slhdsa_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            public_key.len() as _,
+            public_key.as_ptr() as _,
+        );
+    }
+}
+
+// Function to measure `ml_kem_encapsulate_base`. Also measures `base`, `write_register_base`, and
+// `write_register_byte`. However encapsulation is more expensive than register writing so we are
+// okay overcharging it.
+// Compute ml_kem_encapsulate 10k times.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_ml_kem")]
+pub unsafe fn ml_kem_encapsulate_10k() {
+    let encaps_key = include_bytes!("../res/ml_kem_encaps_key.bin");
+    let seed = [1u8; 32];
+
+    for _ in 0..10_000 {
+        ml_kem_encapsulate(
+            encaps_key.len() as _,
+            encaps_key.as_ptr() as _,
+            seed.len() as _,
+            seed.as_ptr() as _,
+            0,
+        );
+    }
+}
+
+// Function to measure `ml_kem_decapsulate_verify_base`. The key pair, ciphertext and shared secret
+// were produced with OpenSSL 3.5 (`openssl pkeyutl -encap`), the decapsulation key is the expanded
+// 2400 byte key.
+// Compute ml_kem_decapsulate_verify 10k times.
+#[no_mangle]
+#[cfg(feature = "protocol_feature_ml_kem")]
+pub unsafe fn ml_kem_decapsulate_verify_10k() {
+    let decaps_key = include_bytes!("../res/ml_kem_decaps_key.bin");
+    let ciphertext = include_bytes!("../res/ml_kem_ciphertext.bin");
+    let shared_secret = include_bytes!("../res/ml_kem_shared_secret.bin");
+
+    for _ in 0..10_000 {
+        ml_kem_decapsulate_verify(
+            decaps_key.len() as _,
+            decaps_key.as_ptr() as _,
+            ciphertext.len() as _,
+            ciphertext.as_ptr() as _,
+            shared_secret.len() as _,
+            shared_secret.as_ptr() as _,
+        );
+    }
+}
+
 #[repr(C)]
 struct MultiexpElem([u8; 64], [u8; 32]);
 
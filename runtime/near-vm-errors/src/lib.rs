@@ -280,6 +280,15 @@ pub enum HostError {
     /// Invalid input to ed25519 signature verification function (e.g. signature cannot be
     /// derived from bytes).
     Ed25519VerifyInvalidInput { msg: String },
+    /// Invalid input to ML-DSA signature verification function (e.g. signature or public key
+    /// of the wrong length).
+    MlDsaVerifyInvalidInput { msg: String },
+    /// Invalid input to SLH-DSA signature verification function (e.g. signature or public key
+    /// of the wrong length).
+    SlhDsaVerifyInvalidInput { msg: String },
+    /// Invalid input to ML-KEM family of functions (e.g. encapsulation key which fails
+    /// the modulus check).
+    MlKemInvalidInput { msg: String },
 }
 
 #[derive(Debug, PartialEq)]
@@ -485,6 +494,9 @@ impl std::fmt::Display for HostError {
             AltBn128InvalidInput { msg } => write!(f, "AltBn128 invalid input: {}", msg),
             ECRecoverError { msg } => write!(f, "ECDSA recover error: {}", msg),
             Ed25519VerifyInvalidInput { msg } => write!(f, "ED25519 signature verification error: {}", msg),
+            MlDsaVerifyInvalidInput { msg } => write!(f, "ML-DSA signature verification error: {}", msg),
+            SlhDsaVerifyInvalidInput { msg } => write!(f, "SLH-DSA signature verification error: {}", msg),
+            MlKemInvalidInput { msg } => write!(f, "ML-KEM invalid input: {}", msg),
         }
     }
 }
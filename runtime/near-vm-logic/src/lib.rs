@@ -6,6 +6,8 @@ mod context;
 mod dependencies;
 pub mod gas_counter;
 mod logic;
+#[cfg(feature = "protocol_feature_ml_kem")]
+mod ml_kem;
 pub mod mocks;
 pub(crate) mod receipt_manager;
 #[cfg(test)]
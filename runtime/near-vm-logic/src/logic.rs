@@ -920,6 +920,111 @@ impl<'a> VMLogic<'a> {
         Ok(res as u64)
     }
 
+    /// Encapsulates a shared secret to an ML-KEM-768 (FIPS 203) encapsulation key
+    /// and writes the ciphertext (1088 bytes) followed by the shared secret (32
+    /// bytes) into `register_id`.
+    ///
+    /// Encapsulation is deterministic: `seed` is the 32 bytes of randomness `m`
+    /// of `ML-KEM.Encaps_internal`.  The function is meant for checking that a
+    /// ciphertext published earlier was produced from a seed revealed later,
+    /// for example in the reveal phase of a sealed-bid auction.
+    ///
+    /// # Security
+    ///
+    /// This function provides no confidentiality.  The seed is an input of
+    /// the contract call and therefore public, and anybody can recompute the
+    /// shared secret from it.  Never use the shared secret to encrypt data.
+    ///
+    /// # Arguments
+    ///
+    /// * `encaps_key` - 1184 bytes ML-KEM-768 encapsulation key.
+    /// * `seed` - 32 bytes of encapsulation randomness.
+    ///
+    /// # Errors
+    ///
+    /// If `encaps_key_len + encaps_key_ptr` or `seed_len + seed_ptr` point
+    /// outside the memory or the registers use more memory than the limit, the
+    /// function returns `MemoryAccessViolation`.
+    ///
+    /// If the inputs have the wrong length or the encapsulation key fails the
+    /// modulus check, the function returns `MlKemInvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `input_cost(num_bytes_encaps_key) + input_cost(num_bytes_seed) +
+    ///  write_register_base + write_register_byte * 1120 + ml_kem_encapsulate_base`
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    pub fn ml_kem_encapsulate(
+        &mut self,
+        encaps_key_len: u64,
+        encaps_key_ptr: u64,
+        seed_len: u64,
+        seed_ptr: u64,
+        register_id: u64,
+    ) -> Result<()> {
+        self.gas_counter.pay_base(ml_kem_encapsulate_base)?;
+        let encaps_key = self.get_vec_from_memory_or_register(encaps_key_ptr, encaps_key_len)?;
+        let seed = self.get_vec_from_memory_or_register(seed_ptr, seed_len)?;
+
+        let res = crate::ml_kem::encapsulate(&encaps_key, &seed)?;
+
+        self.internal_write_register(register_id, res)
+    }
+
+    /// Decapsulates an ML-KEM-768 (FIPS 203) ciphertext with a decapsulation key
+    /// and checks that it yields the given shared secret.
+    ///
+    /// # Returns
+    ///
+    /// - 1 if decapsulation yields `shared_secret`
+    /// - 0 otherwise, including for ciphertexts which ML-KEM implicitly rejects
+    ///
+    /// # Security
+    ///
+    /// This function provides no confidentiality.  The decapsulation key is an
+    /// input of the contract call and therefore public: once it is passed here
+    /// anybody can decapsulate every ciphertext encapsulated to its key pair.
+    /// Only pass decapsulation keys that are meant to be revealed.
+    ///
+    /// # Arguments
+    ///
+    /// * `decaps_key` - 2400 bytes ML-KEM-768 decapsulation key.
+    /// * `ciphertext` - 1088 bytes ciphertext.
+    /// * `shared_secret` - 32 bytes shared secret.
+    ///
+    /// # Errors
+    ///
+    /// If any of the inputs point outside the memory or the registers use more
+    /// memory than the limit, the function returns `MemoryAccessViolation`.
+    ///
+    /// If the inputs have the wrong length or the decapsulation key is
+    /// malformed, the function returns `MlKemInvalidInput`.
+    ///
+    /// # Cost
+    ///
+    /// `input_cost(num_bytes_decaps_key) + input_cost(num_bytes_ciphertext) +
+    ///  input_cost(num_bytes_shared_secret) + ml_kem_decapsulate_verify_base`
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    pub fn ml_kem_decapsulate_verify(
+        &mut self,
+        decaps_key_len: u64,
+        decaps_key_ptr: u64,
+        ciphertext_len: u64,
+        ciphertext_ptr: u64,
+        shared_secret_len: u64,
+        shared_secret_ptr: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(ml_kem_decapsulate_verify_base)?;
+        let decaps_key = self.get_vec_from_memory_or_register(decaps_key_ptr, decaps_key_len)?;
+        let ciphertext = self.get_vec_from_memory_or_register(ciphertext_ptr, ciphertext_len)?;
+        let shared_secret =
+            self.get_vec_from_memory_or_register(shared_secret_ptr, shared_secret_len)?;
+
+        let res = crate::ml_kem::decapsulate_verify(&decaps_key, &ciphertext, &shared_secret)?;
+
+        Ok(res as u64)
+    }
+
     /// Writes random seed into the register.
     ///
     /// # Errors
@@ -1190,6 +1295,114 @@ impl<'a> VMLogic<'a> {
         }
     }
 
+    /// Verify an ML-DSA-65 (FIPS 204) signature given a message and a public key.  The signature
+    /// must have been produced with an empty context string, which is what `near-crypto`
+    /// signers do.
+    /// # Returns
+    /// - 1 meaning the boolean expression true to encode that the signature was properly verified
+    /// - 0 meaning the boolean expression false to encode that the signature failed to be verified
+    ///
+    /// # Cost
+    ///
+    /// Inputs are read as in [`VMLogic::ed25519_verify`], i.e. each of them costs
+    /// `input_cost(num_bytes)`.
+    ///
+    /// `input_cost(num_bytes_signature) + input_cost(num_bytes_message) + input_cost(num_bytes_public_key) +
+    ///  mldsa_verify_base + mldsa_verify_byte * num_bytes_message`
+    ///
+    /// # Error
+    ///
+    /// If the public key's size is not equal to 1952 returns [HostError::MlDsaVerifyInvalidInput].
+    /// If the signature size is not equal to 3309 returns [HostError::MlDsaVerifyInvalidInput].
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub fn mldsa_verify(
+        &mut self,
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> Result<u64> {
+        use near_crypto::{MlDsaPublicKey, MlDsaSignature, PublicKey, Signature};
+
+        self.gas_counter.pay_base(mldsa_verify_base)?;
+
+        let signature_array = self.get_vec_from_memory_or_register(sig_ptr, sig_len)?;
+        let signature = MlDsaSignature::try_from(signature_array.as_slice()).map_err(|_| {
+            VMLogicError::HostError(HostError::MlDsaVerifyInvalidInput {
+                msg: "invalid signature length".to_string(),
+            })
+        })?;
+
+        let msg = self.get_vec_from_memory_or_register(msg_ptr, msg_len)?;
+        let num_bytes = msg.len();
+        self.gas_counter.pay_per(mldsa_verify_byte, num_bytes as _)?;
+
+        let pub_key_array = self.get_vec_from_memory_or_register(pub_key_ptr, pub_key_len)?;
+        let pub_key = MlDsaPublicKey::try_from(pub_key_array.as_slice()).map_err(|_| {
+            VMLogicError::HostError(HostError::MlDsaVerifyInvalidInput {
+                msg: "invalid public key length".to_string(),
+            })
+        })?;
+
+        Ok(Signature::MLDSA(signature).verify(&msg, &PublicKey::MLDSA(pub_key)) as u64)
+    }
+
+    /// Verify an SLH-DSA-SHA2-128s (FIPS 205) signature given a message and a public key.  The signature
+    /// must have been produced with an empty context string, which is what `near-crypto`
+    /// signers do.
+    /// # Returns
+    /// - 1 meaning the boolean expression true to encode that the signature was properly verified
+    /// - 0 meaning the boolean expression false to encode that the signature failed to be verified
+    ///
+    /// # Cost
+    ///
+    /// Inputs are read as in [`VMLogic::ed25519_verify`], i.e. each of them costs
+    /// `input_cost(num_bytes)`.
+    ///
+    /// `input_cost(num_bytes_signature) + input_cost(num_bytes_message) + input_cost(num_bytes_public_key) +
+    ///  slhdsa_verify_base + slhdsa_verify_byte * num_bytes_message`
+    ///
+    /// # Error
+    ///
+    /// If the public key's size is not equal to 32 returns [HostError::SlhDsaVerifyInvalidInput].
+    /// If the signature size is not equal to 7856 returns [HostError::SlhDsaVerifyInvalidInput].
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    pub fn slhdsa_verify(
+        &mut self,
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> Result<u64> {
+        use near_crypto::{PublicKey, Signature, SlhDsaPublicKey, SlhDsaSignature};
+
+        self.gas_counter.pay_base(slhdsa_verify_base)?;
+
+        let signature_array = self.get_vec_from_memory_or_register(sig_ptr, sig_len)?;
+        let signature = SlhDsaSignature::try_from(signature_array.as_slice()).map_err(|_| {
+            VMLogicError::HostError(HostError::SlhDsaVerifyInvalidInput {
+                msg: "invalid signature length".to_string(),
+            })
+        })?;
+
+        let msg = self.get_vec_from_memory_or_register(msg_ptr, msg_len)?;
+        let num_bytes = msg.len();
+        self.gas_counter.pay_per(slhdsa_verify_byte, num_bytes as _)?;
+
+        let pub_key_array = self.get_vec_from_memory_or_register(pub_key_ptr, pub_key_len)?;
+        let pub_key = SlhDsaPublicKey::try_from(pub_key_array.as_slice()).map_err(|_| {
+            VMLogicError::HostError(HostError::SlhDsaVerifyInvalidInput {
+                msg: "invalid public key length".to_string(),
+            })
+        })?;
+
+        Ok(Signature::SLHDSA(signature).verify(&msg, &PublicKey::SLHDSA(pub_key)) as u64)
+    }
+
     /// Called by gas metering injected into Wasm. Counts both towards `burnt_gas` and `used_gas`.
     ///
     /// # Errors
@@ -0,0 +1,93 @@
+//! ML-KEM-768 (FIPS 203) primitives backing the `ml_kem_*` host functions.
+//!
+//! **These functions provide no confidentiality.**  Every input of a contract
+//! call is public: the encapsulation seed, the decapsulation key and the shared
+//! secret are visible to everyone who can read the chain, and so is anything
+//! derived from them.  Anybody can recompute the shared secret of an
+//! encapsulation from its seed, and anybody can decapsulate with a
+//! decapsulation key that was passed to a contract.  The functions are only
+//! meant for verifying, on chain, claims about encapsulations made off chain,
+//! e.g. that a ciphertext committed to earlier was produced from a seed revealed
+//! later.  A decapsulation key must never be passed to
+//! `decapsulate_verify` unless it is already meant to be public.
+
+use fips203::ml_kem_768;
+use fips203::traits::{Decaps, Encaps, SerDes};
+use near_vm_errors::{HostError, VMLogicError};
+
+const ENCAPS_KEY_SIZE: usize = ml_kem_768::EK_LEN;
+const DECAPS_KEY_SIZE: usize = ml_kem_768::DK_LEN;
+const CIPHERTEXT_SIZE: usize = ml_kem_768::CT_LEN;
+const SHARED_SECRET_SIZE: usize = 256 / 8;
+const SEED_SIZE: usize = 256 / 8;
+
+pub(crate) struct InvalidInput {
+    pub(crate) msg: String,
+}
+
+impl From<InvalidInput> for VMLogicError {
+    fn from(err: InvalidInput) -> Self {
+        HostError::MlKemInvalidInput { msg: err.msg }.into()
+    }
+}
+
+fn decode_array<const SIZE: usize>(what: &str, data: &[u8]) -> Result<[u8; SIZE], InvalidInput> {
+    data.try_into().map_err(|_| {
+        let msg = format!("invalid {what} length {}, expected {}", data.len(), SIZE);
+        InvalidInput { msg }
+    })
+}
+
+fn decode_encaps_key(data: &[u8]) -> Result<ml_kem_768::EncapsKey, InvalidInput> {
+    let bytes = decode_array::<ENCAPS_KEY_SIZE>("encapsulation key", data)?;
+    ml_kem_768::EncapsKey::try_from_bytes(bytes)
+        .map_err(|err| InvalidInput { msg: format!("invalid encapsulation key: {err}") })
+}
+
+fn decode_decaps_key(data: &[u8]) -> Result<ml_kem_768::DecapsKey, InvalidInput> {
+    let bytes = decode_array::<DECAPS_KEY_SIZE>("decapsulation key", data)?;
+    ml_kem_768::DecapsKey::try_from_bytes(bytes)
+        .map_err(|err| InvalidInput { msg: format!("invalid decapsulation key: {err}") })
+}
+
+fn decode_ciphertext(data: &[u8]) -> Result<ml_kem_768::CipherText, InvalidInput> {
+    let bytes = decode_array::<CIPHERTEXT_SIZE>("ciphertext", data)?;
+    ml_kem_768::CipherText::try_from_bytes(bytes)
+        .map_err(|err| InvalidInput { msg: format!("invalid ciphertext: {err}") })
+}
+
+/// Encapsulates to `encaps_key` using `seed` as the encapsulation randomness
+/// and returns the ciphertext followed by the shared secret.
+///
+/// The shared secret is a public function of `encaps_key` and `seed`.
+pub(crate) fn encapsulate(encaps_key: &[u8], seed: &[u8]) -> Result<Vec<u8>, InvalidInput> {
+    let encaps_key = decode_encaps_key(encaps_key)?;
+    let seed = decode_array::<SEED_SIZE>("seed", seed)?;
+
+    let (shared_secret, ciphertext) = encaps_key.encaps_from_seed(&seed);
+
+    let mut res = Vec::with_capacity(CIPHERTEXT_SIZE + SHARED_SECRET_SIZE);
+    res.extend_from_slice(&ciphertext.into_bytes());
+    res.extend_from_slice(&shared_secret.into_bytes());
+    Ok(res)
+}
+
+/// Decapsulates `ciphertext` with `decaps_key` and checks that the result is
+/// `shared_secret`.
+///
+/// `decaps_key` is a public input, calling this publishes the key.
+pub(crate) fn decapsulate_verify(
+    decaps_key: &[u8],
+    ciphertext: &[u8],
+    shared_secret: &[u8],
+) -> Result<bool, InvalidInput> {
+    let decaps_key = decode_decaps_key(decaps_key)?;
+    let ciphertext = decode_ciphertext(ciphertext)?;
+    let shared_secret = decode_array::<SHARED_SECRET_SIZE>("shared secret", shared_secret)?;
+
+    let res = decaps_key
+        .try_decaps(&ciphertext)
+        .map_err(|err| InvalidInput { msg: format!("decapsulation failed: {err}") })?;
+
+    Ok(res.into_bytes() == shared_secret)
+}
@@ -0,0 +1,178 @@
+use crate::tests::fixtures::get_context;
+use crate::tests::helpers::*;
+use crate::tests::vm_logic_builder::VMLogicBuilder;
+use crate::VMLogic;
+use crate::{map, ExtCosts};
+use fips203::ml_kem_768;
+use fips203::traits::{Decaps, KeyGen, SerDes};
+use near_vm_errors::{HostError, VMLogicError};
+
+const CIPHERTEXT_SIZE: usize = ml_kem_768::CT_LEN;
+
+fn keygen() -> (Vec<u8>, Vec<u8>) {
+    let (encaps_key, decaps_key) = ml_kem_768::KG::try_keygen().unwrap();
+    (encaps_key.into_bytes().to_vec(), decaps_key.into_bytes().to_vec())
+}
+
+#[track_caller]
+fn check_invalid_input<T: std::fmt::Debug>(result: Result<T, VMLogicError>, expected: &str) {
+    match result {
+        Err(VMLogicError::HostError(HostError::MlKemInvalidInput { msg })) => {
+            assert!(msg.contains(expected), "expected `{expected}` error, got {msg}")
+        }
+        other => panic!("expected `{expected}` error, got {other:?}"),
+    }
+}
+
+fn encapsulate(
+    logic: &mut VMLogic,
+    encaps_key: &[u8],
+    seed: &[u8],
+) -> Result<Vec<u8>, VMLogicError> {
+    logic.ml_kem_encapsulate(
+        encaps_key.len() as _,
+        encaps_key.as_ptr() as _,
+        seed.len() as _,
+        seed.as_ptr() as _,
+        0,
+    )?;
+    let len = logic.register_len(0).unwrap();
+    let mut res = vec![0u8; len as usize];
+    logic.read_register(0, res.as_mut_ptr() as _).unwrap();
+    Ok(res)
+}
+
+fn decapsulate_verify(
+    logic: &mut VMLogic,
+    decaps_key: &[u8],
+    ciphertext: &[u8],
+    shared_secret: &[u8],
+) -> Result<u64, VMLogicError> {
+    logic.ml_kem_decapsulate_verify(
+        decaps_key.len() as _,
+        decaps_key.as_ptr() as _,
+        ciphertext.len() as _,
+        ciphertext.as_ptr() as _,
+        shared_secret.len() as _,
+        shared_secret.as_ptr() as _,
+    )
+}
+
+#[test]
+fn test_ml_kem_encapsulate() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let (encaps_key, decaps_key) = keygen();
+
+    let res = encapsulate(&mut logic, &encaps_key, &[1; 32]).unwrap();
+    assert_eq!(res.len(), CIPHERTEXT_SIZE + 32);
+    let (ciphertext, shared_secret) = res.split_at(CIPHERTEXT_SIZE);
+
+    let decaps_key = ml_kem_768::DecapsKey::try_from_bytes(decaps_key.try_into().unwrap()).unwrap();
+    let ciphertext_bytes = ciphertext.try_into().unwrap();
+    let ciphertext = ml_kem_768::CipherText::try_from_bytes(ciphertext_bytes).unwrap();
+    let expected = decaps_key.try_decaps(&ciphertext).unwrap().into_bytes();
+    assert_eq!(shared_secret, expected);
+
+    // Encapsulation is deterministic in the seed.
+    assert_eq!(encapsulate(&mut logic, &encaps_key, &[1; 32]).unwrap(), res);
+    assert_ne!(encapsulate(&mut logic, &encaps_key, &[2; 32]).unwrap(), res);
+}
+
+#[test]
+fn test_ml_kem_encapsulate_costs() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let (encaps_key, _) = keygen();
+    let seed = [1u8; 32];
+
+    logic
+        .ml_kem_encapsulate(
+            encaps_key.len() as _,
+            encaps_key.as_ptr() as _,
+            seed.len() as _,
+            seed.as_ptr() as _,
+            0,
+        )
+        .unwrap();
+    assert_costs(map! {
+        ExtCosts::read_memory_base: 2,
+        ExtCosts::read_memory_byte: encaps_key.len() as u64 + 32,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: CIPHERTEXT_SIZE as u64 + 32,
+        ExtCosts::ml_kem_encapsulate_base: 1,
+    });
+}
+
+#[test]
+fn test_ml_kem_encapsulate_errors() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let (encaps_key, _) = keygen();
+
+    check_invalid_input(
+        encapsulate(&mut logic, &encaps_key[1..], &[1; 32]),
+        "invalid encapsulation key length 1183, expected 1184",
+    );
+    check_invalid_input(
+        encapsulate(&mut logic, &encaps_key, &[1; 31]),
+        "invalid seed length 31, expected 32",
+    );
+    // Coefficients of 4095 are not reduced modulo q = 3329.
+    check_invalid_input(
+        encapsulate(&mut logic, &[0xff; 1184], &[1; 32]),
+        "invalid encapsulation key",
+    );
+}
+
+#[test]
+fn test_ml_kem_decapsulate_verify() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let (encaps_key, decaps_key) = keygen();
+    let (_, other_decaps_key) = keygen();
+
+    let res = encapsulate(&mut logic, &encaps_key, &[1; 32]).unwrap();
+    let (ciphertext, shared_secret) = res.split_at(CIPHERTEXT_SIZE);
+    reset_costs_counter();
+
+    assert_eq!(decapsulate_verify(&mut logic, &decaps_key, ciphertext, shared_secret), Ok(1));
+    assert_costs(map! {
+        ExtCosts::read_memory_base: 3,
+        ExtCosts::read_memory_byte: (decaps_key.len() + ciphertext.len() + 32) as u64,
+        ExtCosts::ml_kem_decapsulate_verify_base: 1,
+    });
+
+    let mut bad_shared_secret = shared_secret.to_vec();
+    bad_shared_secret[0] ^= 1;
+    assert_eq!(decapsulate_verify(&mut logic, &decaps_key, ciphertext, &bad_shared_secret), Ok(0));
+
+    // Tampered ciphertexts are implicitly rejected with a pseudorandom secret.
+    let mut bad_ciphertext = ciphertext.to_vec();
+    bad_ciphertext[0] ^= 1;
+    assert_eq!(decapsulate_verify(&mut logic, &decaps_key, &bad_ciphertext, shared_secret), Ok(0));
+
+    assert_eq!(decapsulate_verify(&mut logic, &other_decaps_key, ciphertext, shared_secret), Ok(0));
+}
+
+#[test]
+fn test_ml_kem_decapsulate_verify_errors() {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+    let (encaps_key, decaps_key) = keygen();
+    let res = encapsulate(&mut logic, &encaps_key, &[1; 32]).unwrap();
+    let (ciphertext, shared_secret) = res.split_at(CIPHERTEXT_SIZE);
+
+    check_invalid_input(
+        decapsulate_verify(&mut logic, &decaps_key[1..], ciphertext, shared_secret),
+        "invalid decapsulation key length 2399, expected 2400",
+    );
+    check_invalid_input(
+        decapsulate_verify(&mut logic, &decaps_key, &ciphertext[1..], shared_secret),
+        "invalid ciphertext length 1087, expected 1088",
+    );
+    check_invalid_input(
+        decapsulate_verify(&mut logic, &decaps_key, ciphertext, &shared_secret[1..]),
+        "invalid shared secret length 31, expected 32",
+    );
+}
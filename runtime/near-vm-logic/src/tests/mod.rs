@@ -7,6 +7,10 @@ mod gas_counter;
 mod helpers;
 mod iterators;
 mod miscs;
+#[cfg(feature = "protocol_feature_ml_kem")]
+mod ml_kem;
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+mod post_quantum_verify;
 mod promises;
 mod registers;
 mod storage_read_write;
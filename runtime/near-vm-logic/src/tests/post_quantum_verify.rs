@@ -0,0 +1,234 @@
+use crate::tests::fixtures::get_context;
+use crate::tests::helpers::*;
+use crate::tests::vm_logic_builder::VMLogicBuilder;
+use crate::VMLogic;
+use crate::{map, ExtCosts};
+use near_crypto::{KeyType, SecretKey, Signature};
+use near_vm_errors::HostError;
+use near_vm_errors::VMLogicError;
+
+const MESSAGE: &[u8] = b"kajdlfkjalkfjaklfjdkladjfkljadsk";
+
+/// Returns the raw signature of `MESSAGE` and the raw public key of a key
+/// derived from `seed`.
+fn create_signature_and_public_key(key_type: KeyType, seed: &str) -> (Vec<u8>, Vec<u8>) {
+    let secret_key = SecretKey::from_seed(key_type, seed);
+    let signature = match secret_key.sign(MESSAGE) {
+        Signature::MLDSA(signature) => signature.0.to_vec(),
+        Signature::SLHDSA(signature) => signature.0.to_vec(),
+        _ => unreachable!(),
+    };
+    (signature, secret_key.public_key().key_data().to_vec())
+}
+
+fn verify(
+    logic: &mut VMLogic,
+    key_type: KeyType,
+    sig_len: u64,
+    sig_ptr: u64,
+    msg_len: u64,
+    msg_ptr: u64,
+    pub_key_len: u64,
+    pub_key_ptr: u64,
+) -> Result<u64, VMLogicError> {
+    match key_type {
+        KeyType::MLDSA => {
+            logic.mldsa_verify(sig_len, sig_ptr, msg_len, msg_ptr, pub_key_len, pub_key_ptr)
+        }
+        KeyType::SLHDSA => {
+            logic.slhdsa_verify(sig_len, sig_ptr, msg_len, msg_ptr, pub_key_len, pub_key_ptr)
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn invalid_input(key_type: KeyType, msg: &str) -> VMLogicError {
+    let msg = msg.to_string();
+    VMLogicError::HostError(match key_type {
+        KeyType::MLDSA => HostError::MlDsaVerifyInvalidInput { msg },
+        KeyType::SLHDSA => HostError::SlhDsaVerifyInvalidInput { msg },
+        _ => unreachable!(),
+    })
+}
+
+fn verify_costs(key_type: KeyType) -> (ExtCosts, ExtCosts) {
+    match key_type {
+        KeyType::MLDSA => (ExtCosts::mldsa_verify_base, ExtCosts::mldsa_verify_byte),
+        KeyType::SLHDSA => (ExtCosts::slhdsa_verify_base, ExtCosts::slhdsa_verify_byte),
+        _ => unreachable!(),
+    }
+}
+
+fn check_behavior_and_errors(key_type: KeyType) {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+
+    let (signature, public_key) = create_signature_and_public_key(key_type, "test");
+    let (_, other_public_key) = create_signature_and_public_key(key_type, "other");
+    let bad_message = b"kajdlfkjalkfjaklfjdkladjfkljadsl";
+    let (base, byte) = verify_costs(key_type);
+    let all_bytes = (signature.len() + MESSAGE.len() + public_key.len()) as u64;
+
+    let scenarios = [
+        (
+            signature.len(),
+            MESSAGE,
+            public_key.as_slice(),
+            public_key.len(),
+            Ok(1),
+            map! {
+                ExtCosts::read_memory_byte: all_bytes,
+                ExtCosts::read_memory_base: 3,
+                base: 1,
+                byte: 32,
+            },
+        ),
+        (
+            signature.len(),
+            bad_message.as_slice(),
+            public_key.as_slice(),
+            public_key.len(),
+            Ok(0),
+            map! {
+                ExtCosts::read_memory_byte: all_bytes,
+                ExtCosts::read_memory_base: 3,
+                base: 1,
+                byte: 32,
+            },
+        ),
+        (
+            signature.len(),
+            MESSAGE,
+            other_public_key.as_slice(),
+            other_public_key.len(),
+            Ok(0),
+            map! {
+                ExtCosts::read_memory_byte: all_bytes,
+                ExtCosts::read_memory_base: 3,
+                base: 1,
+                byte: 32,
+            },
+        ),
+        (
+            signature.len(),
+            MESSAGE,
+            public_key.as_slice(),
+            public_key.len() - 1,
+            Err(invalid_input(key_type, "invalid public key length")),
+            map! {
+                ExtCosts::read_memory_byte: all_bytes - 1,
+                ExtCosts::read_memory_base: 3,
+                base: 1,
+                byte: 32,
+            },
+        ),
+        (
+            signature.len() - 1,
+            MESSAGE,
+            public_key.as_slice(),
+            public_key.len(),
+            Err(invalid_input(key_type, "invalid signature length")),
+            map! {
+                ExtCosts::read_memory_byte: signature.len() as u64 - 1,
+                ExtCosts::read_memory_base: 1,
+                base: 1,
+            },
+        ),
+    ];
+
+    for (signature_len, message, public_key, public_key_len, want, want_costs) in scenarios {
+        let result = verify(
+            &mut logic,
+            key_type,
+            signature_len as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            public_key_len as _,
+            public_key.as_ptr() as _,
+        );
+        assert_eq!(want, result);
+        assert_costs(want_costs);
+    }
+}
+
+fn check_registers(key_type: KeyType) {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build(get_context(vec![], false));
+
+    let (signature, public_key) = create_signature_and_public_key(key_type, "test");
+    let mut bad_signature = signature.clone();
+    bad_signature[0] ^= 1;
+
+    logic.wrapped_internal_write_register(1, &signature).unwrap();
+    let result = verify(
+        &mut logic,
+        key_type,
+        u64::MAX,
+        1,
+        MESSAGE.len() as _,
+        MESSAGE.as_ptr() as _,
+        public_key.len() as _,
+        public_key.as_ptr() as _,
+    );
+    assert_eq!(Ok(1), result);
+
+    logic.wrapped_internal_write_register(1, &bad_signature).unwrap();
+    let result = verify(
+        &mut logic,
+        key_type,
+        u64::MAX,
+        1,
+        MESSAGE.len() as _,
+        MESSAGE.as_ptr() as _,
+        public_key.len() as _,
+        public_key.as_ptr() as _,
+    );
+    assert_eq!(Ok(0), result);
+
+    logic.wrapped_internal_write_register(1, MESSAGE).unwrap();
+    let result = verify(
+        &mut logic,
+        key_type,
+        signature.len() as _,
+        signature.as_ptr() as _,
+        u64::MAX,
+        1,
+        public_key.len() as _,
+        public_key.as_ptr() as _,
+    );
+    assert_eq!(Ok(1), result);
+
+    logic.wrapped_internal_write_register(1, &public_key).unwrap();
+    let result = verify(
+        &mut logic,
+        key_type,
+        signature.len() as _,
+        signature.as_ptr() as _,
+        MESSAGE.len() as _,
+        MESSAGE.as_ptr() as _,
+        u64::MAX,
+        1,
+    );
+    assert_eq!(Ok(1), result);
+}
+
+#[test]
+fn test_mldsa_verify_behavior_and_errors() {
+    check_behavior_and_errors(KeyType::MLDSA);
+}
+
+#[test]
+fn test_mldsa_verify_check_registers() {
+    check_registers(KeyType::MLDSA);
+}
+
+#[test]
+fn test_slhdsa_verify_behavior_and_errors() {
+    check_behavior_and_errors(KeyType::SLHDSA);
+}
+
+#[test]
+fn test_slhdsa_verify_check_registers() {
+    check_registers(KeyType::SLHDSA);
+}
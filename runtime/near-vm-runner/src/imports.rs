@@ -110,6 +110,22 @@ imports! {
         pub_key_len: u64,
         pub_key_ptr: u64
     ] -> [u64]>,
+    #["protocol_feature_post_quantum_verify", PostQuantumVerify] mldsa_verify<[
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64
+    ] -> [u64]>,
+    #["protocol_feature_post_quantum_verify", PostQuantumVerify] slhdsa_verify<[
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64
+    ] -> [u64]>,
     #[MathExtension] ripemd160<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[MathExtension] ecrecover<[hash_len: u64, hash_ptr: u64, sign_len: u64, sig_ptr: u64, v: u64, malleability_flag: u64, register_id: u64] -> [u64]>,
     // #####################
@@ -235,6 +251,24 @@ imports! {
     #[AltBn128] alt_bn128_g1_multiexp<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[AltBn128] alt_bn128_g1_sum<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[AltBn128] alt_bn128_pairing_check<[value_len: u64, value_ptr: u64] -> [u64]>,
+    // ##########
+    // # ML-KEM #
+    // ##########
+    #["protocol_feature_ml_kem", MlKem] ml_kem_encapsulate<[
+        encaps_key_len: u64,
+        encaps_key_ptr: u64,
+        seed_len: u64,
+        seed_ptr: u64,
+        register_id: u64
+    ] -> []>,
+    #["protocol_feature_ml_kem", MlKem] ml_kem_decapsulate_verify<[
+        decaps_key_len: u64,
+        decaps_key_ptr: u64,
+        ciphertext_len: u64,
+        ciphertext_ptr: u64,
+        shared_secret_len: u64,
+        shared_secret_ptr: u64
+    ] -> [u64]>,
     // #############
     // #  Sandbox  #
     // #############
@@ -360,6 +360,25 @@ pub enum Cost {
     /// Estimates `ed25519_verify_byte`, the cost charged per input byte in calls to the
     /// ed25519_verify host function.
     Ed25519VerifyByte,
+    /// Estimates `mldsa_verify_base`, which covers the base cost of the host
+    /// function `mldsa_verify` to verify an ML-DSA signature.
+    MlDsaVerifyBase,
+    /// Estimates `mldsa_verify_byte`, the cost charged per message byte in calls to the
+    /// mldsa_verify host function.
+    MlDsaVerifyByte,
+    /// Estimates `slhdsa_verify_base`, which covers the base cost of the host
+    /// function `slhdsa_verify` to verify an SLH-DSA signature.
+    SlhDsaVerifyBase,
+    /// Estimates `slhdsa_verify_byte`, the cost charged per message byte in calls to the
+    /// slhdsa_verify host function.
+    SlhDsaVerifyByte,
+    /// Estimates `ml_kem_encapsulate_base`, which covers the full cost of the
+    /// host function `ml_kem_encapsulate` to encapsulate to an ML-KEM-768 key.
+    MlKemEncapsulateBase,
+    /// Estimates `ml_kem_decapsulate_verify_base`, which covers the full cost
+    /// of the host function `ml_kem_decapsulate_verify` to decapsulate an
+    /// ML-KEM-768 ciphertext and compare the shared secret.
+    MlKemDecapsulateVerifyBase,
     // `storage_write` records a single key-value pair, initially in the
     // prospective changes in-memory hash map, and then once a full block has
     // been processed, in the on-disk trie. If there was already a value
@@ -118,6 +118,18 @@ fn ext_costs_config(cost_table: &CostTable) -> anyhow::Result<ExtCostsConfig> {
         ed25519_verify_base: get(Cost::Ed25519VerifyBase)?,
         #[cfg(feature = "protocol_feature_ed25519_verify")]
         ed25519_verify_byte: get(Cost::Ed25519VerifyByte)?,
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        mldsa_verify_base: get(Cost::MlDsaVerifyBase)?,
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        mldsa_verify_byte: get(Cost::MlDsaVerifyByte)?,
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        slhdsa_verify_base: get(Cost::SlhDsaVerifyBase)?,
+        #[cfg(feature = "protocol_feature_post_quantum_verify")]
+        slhdsa_verify_byte: get(Cost::SlhDsaVerifyByte)?,
+        #[cfg(feature = "protocol_feature_ml_kem")]
+        ml_kem_encapsulate_base: get(Cost::MlKemEncapsulateBase)?,
+        #[cfg(feature = "protocol_feature_ml_kem")]
+        ml_kem_decapsulate_verify_base: get(Cost::MlKemDecapsulateVerifyBase)?,
         log_base: get(Cost::LogBase)?,
         log_byte: get(Cost::LogByte)?,
         storage_write_base: get(Cost::StorageWriteBase)?,
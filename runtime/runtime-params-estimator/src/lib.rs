@@ -172,6 +172,18 @@ static ALL_COSTS: &[(Cost, fn(&mut EstimatorContext) -> GasCost)] = &[
     (Cost::Ed25519VerifyBase, ed25519_verify_base),
     #[cfg(feature = "protocol_feature_ed25519_verify")]
     (Cost::Ed25519VerifyByte, ed25519_verify_byte),
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    (Cost::MlDsaVerifyBase, mldsa_verify_base),
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    (Cost::MlDsaVerifyByte, mldsa_verify_byte),
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    (Cost::SlhDsaVerifyBase, slhdsa_verify_base),
+    #[cfg(feature = "protocol_feature_post_quantum_verify")]
+    (Cost::SlhDsaVerifyByte, slhdsa_verify_byte),
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    (Cost::MlKemEncapsulateBase, ml_kem_encapsulate_base),
+    #[cfg(feature = "protocol_feature_ml_kem")]
+    (Cost::MlKemDecapsulateVerifyBase, ml_kem_decapsulate_verify_base),
     (Cost::AltBn128G1MultiexpBase, alt_bn128g1_multiexp_base),
     (Cost::AltBn128G1MultiexpElement, alt_bn128g1_multiexp_element),
     (Cost::AltBn128G1SumBase, alt_bn128g1_sum_base),
@@ -943,6 +955,57 @@ fn ed25519_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost(ctx, "ed25519_verify_10k", ExtCosts::ed25519_verify_byte, 960000)
 }
 
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+fn mldsa_verify_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "mldsa_verify_10k", ExtCosts::mldsa_verify_base, 10_000)
+}
+
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+fn mldsa_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
+    // The base cost dominates, so subtract the same verification over a 32 byte message instead
+    // of a noop function call.
+    let block_latency = 0;
+    let (total_cost, total_bytes) =
+        fn_cost_count(ctx, "mldsa_verify_10kib_10k", ExtCosts::mldsa_verify_byte, block_latency);
+    let (base_cost, base_bytes) =
+        fn_cost_count(ctx, "mldsa_verify_10k", ExtCosts::mldsa_verify_byte, block_latency);
+    assert_eq!(total_bytes, 10 * 1024 * 10_000);
+    assert_eq!(base_bytes, 32 * 10_000);
+
+    total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE)
+        / (total_bytes - base_bytes)
+}
+
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+fn slhdsa_verify_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "slhdsa_verify_1k", ExtCosts::slhdsa_verify_base, 1_000)
+}
+
+#[cfg(feature = "protocol_feature_post_quantum_verify")]
+fn slhdsa_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
+    // Same as `mldsa_verify_byte`.
+    let block_latency = 0;
+    let (total_cost, total_bytes) =
+        fn_cost_count(ctx, "slhdsa_verify_10kib_1k", ExtCosts::slhdsa_verify_byte, block_latency);
+    let (base_cost, base_bytes) =
+        fn_cost_count(ctx, "slhdsa_verify_1k", ExtCosts::slhdsa_verify_byte, block_latency);
+    assert_eq!(total_bytes, 10 * 1024 * 1_000);
+    assert_eq!(base_bytes, 32 * 1_000);
+
+    total_cost.saturating_sub(&base_cost, &NonNegativeTolerance::PER_MILLE)
+        / (total_bytes - base_bytes)
+}
+
+#[cfg(feature = "protocol_feature_ml_kem")]
+fn ml_kem_encapsulate_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "ml_kem_encapsulate_10k", ExtCosts::ml_kem_encapsulate_base, 10_000)
+}
+
+#[cfg(feature = "protocol_feature_ml_kem")]
+fn ml_kem_decapsulate_verify_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost(ctx, "ml_kem_decapsulate_verify_10k", ExtCosts::ml_kem_decapsulate_verify_base, 10_000)
+}
+
 fn alt_bn128g1_multiexp_base(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost(ctx, "alt_bn128_g1_multiexp_1_10", ExtCosts::alt_bn128_g1_multiexp_base, 10)
 }
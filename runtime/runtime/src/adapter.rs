@@ -2,12 +2,15 @@ use crate::near_primitives::shard_layout::ShardUId;
 use near_crypto::PublicKey;
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::contract::ContractCode;
+use near_primitives::errors::StorageError;
 use near_primitives::hash::CryptoHash;
+use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{
     AccountId, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, MerkleHash,
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::ViewStateResult;
+use std::sync::Arc;
 
 /// Adapter for querying runtime.
 pub trait ViewRuntimeAdapter {
@@ -66,4 +69,11 @@ pub trait ViewRuntimeAdapter {
         prefix: &[u8],
         include_proof: bool,
     ) -> Result<ViewStateResult, crate::state_viewer::errors::ViewStateError>;
+
+    fn view_proof(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        key: &TrieKey,
+    ) -> Result<Vec<Arc<[u8]>>, StorageError>;
 }
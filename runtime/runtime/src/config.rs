@@ -223,7 +223,9 @@ pub fn tx_cost(
             current_protocol_version,
         )?,
     )?;
-    let burnt_amount = safe_gas_to_balance(gas_price, gas_burnt)?;
+    // The priority fee is paid on top of the gas price for the gas burnt now and is burnt with it.
+    let burnt_gas_price = safe_add_balance(gas_price, Balance::from(transaction.priority_fee))?;
+    let burnt_amount = safe_gas_to_balance(burnt_gas_price, gas_burnt)?;
     let remaining_gas_amount = safe_gas_to_balance(receipt_gas_price, gas_remaining)?;
     let mut total_cost = safe_add_balance(burnt_amount, remaining_gas_amount)?;
     total_cost = safe_add_balance(total_cost, total_deposit(&transaction.actions)?)?;
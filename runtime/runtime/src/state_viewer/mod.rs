@@ -6,6 +6,7 @@ use near_primitives::{
     account::{AccessKey, Account},
     borsh::BorshDeserialize,
     contract::ContractCode,
+    errors::StorageError,
     hash::CryptoHash,
     receipt::ActionReceipt,
     runtime::{
@@ -13,7 +14,7 @@ use near_primitives::{
         migration_data::{MigrationData, MigrationFlags},
     },
     transaction::FunctionCallAction,
-    trie_key::trie_key_parsers,
+    trie_key::{trie_key_parsers, TrieKey},
     types::{AccountId, EpochInfoProvider, Gas},
     views::{StateItem, ViewApplyState, ViewStateResult},
 };
@@ -158,6 +159,19 @@ impl TrieViewer {
         Ok(ViewStateResult { values, proof })
     }
 
+    /// Returns the trie nodes on the path from the root to `key`, which prove
+    /// the value of `key` against the state root.
+    pub fn view_proof(
+        &self,
+        state_update: &TrieUpdate,
+        key: &TrieKey,
+    ) -> Result<Vec<Arc<[u8]>>, StorageError> {
+        let mut iter = state_update.trie().iter()?;
+        iter.remember_visited_nodes(true);
+        iter.seek_prefix(key.to_vec())?;
+        Ok(iter.into_visited_nodes())
+    }
+
     pub fn call_function(
         &self,
         mut state_update: TrieUpdate,
@@ -1,4 +1,3 @@
-use near_crypto::key_conversion::is_valid_staking_key;
 use near_primitives::runtime::get_insufficient_storage_stake;
 use near_primitives::{
     account::AccessKeyPermission,
@@ -13,6 +12,7 @@ use near_primitives::{
         StakeAction,
     },
     types::{AccountId, Balance},
+    utils::is_valid_staking_key,
     version::ProtocolVersion,
 };
 use near_store::{
@@ -45,6 +45,12 @@ pub fn validate_transaction(
         return Err(InvalidTxError::InvalidSignature.into());
     }
 
+    if transaction.priority_fee != 0
+        && !checked_feature!("protocol_feature_priority_fee", PriorityFee, current_protocol_version)
+    {
+        return Err(InvalidTxError::PriorityFeeNotSupported.into());
+    }
+
     let transaction_size = signed_transaction.get_size();
     let max_transaction_size = config.wasm_config.limit_config.max_transaction_size;
     if transaction_size > max_transaction_size {
@@ -55,8 +61,12 @@ pub fn validate_transaction(
         .into());
     }
 
-    validate_actions(&config.wasm_config.limit_config, &transaction.actions)
-        .map_err(InvalidTxError::ActionsValidation)?;
+    validate_actions(
+        &config.wasm_config.limit_config,
+        &transaction.actions,
+        current_protocol_version,
+    )
+    .map_err(InvalidTxError::ActionsValidation)?;
 
     let sender_is_receiver = &transaction.receiver_id == signer_id;
 
@@ -225,6 +235,7 @@ pub fn verify_and_charge_transaction(
 pub(crate) fn validate_receipt(
     limit_config: &VMLimitConfig,
     receipt: &Receipt,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<(), ReceiptValidationError> {
     // We retain these checks here as to maintain backwards compatibility
     // with AccountId validation since we illegally parse an AccountId
@@ -240,7 +251,7 @@ pub(crate) fn validate_receipt(
 
     match &receipt.receipt {
         ReceiptEnum::Action(action_receipt) => {
-            validate_action_receipt(limit_config, action_receipt)
+            validate_action_receipt(limit_config, action_receipt, current_protocol_version)
         }
         ReceiptEnum::Data(data_receipt) => validate_data_receipt(limit_config, data_receipt),
     }
@@ -250,6 +261,7 @@ pub(crate) fn validate_receipt(
 fn validate_action_receipt(
     limit_config: &VMLimitConfig,
     receipt: &ActionReceipt,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<(), ReceiptValidationError> {
     if receipt.input_data_ids.len() as u64 > limit_config.max_number_input_data_dependencies {
         return Err(ReceiptValidationError::NumberInputDataDependenciesExceeded {
@@ -257,7 +269,7 @@ fn validate_action_receipt(
             limit: limit_config.max_number_input_data_dependencies,
         });
     }
-    validate_actions(limit_config, &receipt.actions)
+    validate_actions(limit_config, &receipt.actions, current_protocol_version)
         .map_err(ReceiptValidationError::ActionsValidation)
 }
 
@@ -285,6 +297,7 @@ fn validate_data_receipt(
 pub(crate) fn validate_actions(
     limit_config: &VMLimitConfig,
     actions: &[Action],
+    current_protocol_version: ProtocolVersion,
 ) -> Result<(), ActionsValidationError> {
     if actions.len() as u64 > limit_config.max_actions_per_receipt {
         return Err(ActionsValidationError::TotalNumberOfActionsExceeded {
@@ -300,7 +313,7 @@ pub(crate) fn validate_actions(
                 return Err(ActionsValidationError::DeleteActionMustBeFinal);
             }
         }
-        validate_action(limit_config, action)?;
+        validate_action(limit_config, action, current_protocol_version)?;
     }
 
     let total_prepaid_gas =
@@ -319,13 +332,14 @@ pub(crate) fn validate_actions(
 pub fn validate_action(
     limit_config: &VMLimitConfig,
     action: &Action,
+    current_protocol_version: ProtocolVersion,
 ) -> Result<(), ActionsValidationError> {
     match action {
         Action::CreateAccount(_) => Ok(()),
         Action::DeployContract(a) => validate_deploy_contract_action(limit_config, a),
         Action::FunctionCall(a) => validate_function_call_action(limit_config, a),
         Action::Transfer(_) => Ok(()),
-        Action::Stake(a) => validate_stake_action(a),
+        Action::Stake(a) => validate_stake_action(a, current_protocol_version),
         Action::AddKey(a) => validate_add_key_action(limit_config, a),
         Action::DeleteKey(_) => Ok(()),
         Action::DeleteAccount(_) => Ok(()),
@@ -375,8 +389,11 @@ fn validate_function_call_action(
 }
 
 /// Validates `StakeAction`. Checks that the `public_key` is a valid staking key.
-fn validate_stake_action(action: &StakeAction) -> Result<(), ActionsValidationError> {
-    if !is_valid_staking_key(&action.public_key) {
+fn validate_stake_action(
+    action: &StakeAction,
+    current_protocol_version: ProtocolVersion,
+) -> Result<(), ActionsValidationError> {
+    if !is_valid_staking_key(&action.public_key, current_protocol_version) {
         return Err(ActionsValidationError::UnsuitableStakingKey {
             public_key: action.public_key.clone(),
         });
@@ -467,7 +484,7 @@ mod tests {
         CreateAccountAction, DeleteAccountAction, DeleteKeyAction, StakeAction, TransferAction,
     };
     use near_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
-    use near_primitives::version::PROTOCOL_VERSION;
+    use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
     use near_store::test_utils::create_tries;
     use testlib::runtime_utils::{alice_account, bob_account, eve_dot_alice_account};
 
@@ -1185,13 +1202,74 @@ mod tests {
         .expect("valid transaction");
     }
 
+    #[test]
+    fn test_validate_transaction_priority_fee() {
+        let config = RuntimeConfig::test();
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+
+        let mut transaction = SignedTransaction::send_money(
+            1,
+            alice_account(),
+            bob_account(),
+            &*signer,
+            100,
+            CryptoHash::default(),
+        )
+        .transaction;
+        transaction.priority_fee = 7;
+        let transaction = transaction.sign(&*signer);
+
+        #[cfg(not(feature = "protocol_feature_priority_fee"))]
+        assert_err_both_validations(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            RuntimeError::InvalidTxError(InvalidTxError::PriorityFeeNotSupported),
+        );
+        #[cfg(feature = "protocol_feature_priority_fee")]
+        {
+            assert_eq!(
+                validate_transaction(
+                    &config,
+                    gas_price,
+                    &transaction,
+                    true,
+                    ProtocolFeature::PriorityFee.protocol_version() - 1,
+                )
+                .expect_err("expected an error"),
+                RuntimeError::InvalidTxError(InvalidTxError::PriorityFeeNotSupported),
+            );
+            let verification_result = verify_and_charge_transaction(
+                &config,
+                &mut state_update,
+                gas_price,
+                &transaction,
+                true,
+                None,
+                PROTOCOL_VERSION,
+            )
+            .expect("valid transaction");
+            // The priority fee is burnt on top of the gas price.
+            assert_eq!(
+                verification_result.burnt_amount,
+                Balance::from(verification_result.gas_burnt) * (gas_price + 7)
+            );
+        }
+    }
+
     // Receipts
 
     #[test]
     fn test_validate_receipt_valid() {
         let limit_config = VMLimitConfig::test();
-        validate_receipt(&limit_config, &Receipt::new_balance_refund(&alice_account(), 10))
-            .expect("valid receipt");
+        validate_receipt(
+            &limit_config,
+            &Receipt::new_balance_refund(&alice_account(), 10),
+            PROTOCOL_VERSION,
+        )
+        .expect("valid receipt");
     }
 
     #[test]
@@ -1208,7 +1286,8 @@ mod tests {
                     output_data_receivers: vec![],
                     input_data_ids: vec![CryptoHash::default(), CryptoHash::default()],
                     actions: vec![]
-                }
+                },
+                PROTOCOL_VERSION
             )
             .expect_err("expected an error"),
             ReceiptValidationError::NumberInputDataDependenciesExceeded {
@@ -1259,7 +1338,7 @@ mod tests {
     #[test]
     fn test_validate_actions_empty() {
         let limit_config = VMLimitConfig::test();
-        validate_actions(&limit_config, &[]).expect("empty actions");
+        validate_actions(&limit_config, &[], PROTOCOL_VERSION).expect("empty actions");
     }
 
     #[test]
@@ -1273,6 +1352,7 @@ mod tests {
                 gas: 100,
                 deposit: 0,
             })],
+            PROTOCOL_VERSION,
         )
         .expect("valid function call action");
     }
@@ -1297,7 +1377,8 @@ mod tests {
                         gas: 150,
                         deposit: 0,
                     })
-                ]
+                ],
+                PROTOCOL_VERSION
             )
             .expect_err("expected an error"),
             ActionsValidationError::TotalPrepaidGasExceeded { total_prepaid_gas: 250, limit: 220 }
@@ -1324,7 +1405,8 @@ mod tests {
                         gas: u64::max_value() / 2 + 1,
                         deposit: 0,
                     })
-                ]
+                ],
+                PROTOCOL_VERSION
             )
             .expect_err("Expected an error"),
             ActionsValidationError::IntegerOverflow,
@@ -1341,7 +1423,8 @@ mod tests {
                 &[
                     Action::CreateAccount(CreateAccountAction {}),
                     Action::CreateAccount(CreateAccountAction {}),
-                ]
+                ],
+                PROTOCOL_VERSION
             )
             .expect_err("Expected an error"),
             ActionsValidationError::TotalNumberOfActionsExceeded {
@@ -1363,7 +1446,8 @@ mod tests {
                         beneficiary_id: "bob".parse().unwrap()
                     }),
                     Action::CreateAccount(CreateAccountAction {}),
-                ]
+                ],
+                PROTOCOL_VERSION
             )
             .expect_err("Expected an error"),
             ActionsValidationError::DeleteActionMustBeFinal,
@@ -1382,7 +1466,8 @@ mod tests {
                     Action::DeleteAccount(DeleteAccountAction {
                         beneficiary_id: "bob".parse().unwrap()
                     }),
-                ]
+                ],
+                PROTOCOL_VERSION
             ),
             Ok(()),
         );
@@ -1392,8 +1477,12 @@ mod tests {
 
     #[test]
     fn test_validate_action_valid_create_account() {
-        validate_action(&VMLimitConfig::test(), &Action::CreateAccount(CreateAccountAction {}))
-            .expect("valid action");
+        validate_action(
+            &VMLimitConfig::test(),
+            &Action::CreateAccount(CreateAccountAction {}),
+            PROTOCOL_VERSION,
+        )
+        .expect("valid action");
     }
 
     #[test]
@@ -1406,6 +1495,7 @@ mod tests {
                 gas: 100,
                 deposit: 0,
             }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1421,6 +1511,7 @@ mod tests {
                     gas: 0,
                     deposit: 0,
                 }),
+                PROTOCOL_VERSION,
             )
             .expect_err("expected an error"),
             ActionsValidationError::FunctionCallZeroAttachedGas,
@@ -1429,8 +1520,12 @@ mod tests {
 
     #[test]
     fn test_validate_action_valid_transfer() {
-        validate_action(&VMLimitConfig::test(), &Action::Transfer(TransferAction { deposit: 10 }))
-            .expect("valid action");
+        validate_action(
+            &VMLimitConfig::test(),
+            &Action::Transfer(TransferAction { deposit: 10 }),
+            PROTOCOL_VERSION,
+        )
+        .expect("valid action");
     }
 
     #[test]
@@ -1441,6 +1536,7 @@ mod tests {
                 stake: 100,
                 public_key: "ed25519:KuTCtARNzxZQ3YvXDeLjx83FDqxv2SdQTSbiq876zR7".parse().unwrap(),
             }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1454,6 +1550,7 @@ mod tests {
                     stake: 100,
                     public_key: PublicKey::empty(KeyType::ED25519),
                 }),
+                PROTOCOL_VERSION,
             )
             .expect_err("Expected an error"),
             ActionsValidationError::UnsuitableStakingKey {
@@ -1462,6 +1559,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_action_hybrid_staking_key() {
+        let public_key = PublicKey::from_seed(KeyType::ED25519MLDSA, "test");
+        let action = Action::Stake(StakeAction { stake: 100, public_key: public_key.clone() });
+        assert_eq!(
+            validate_action(
+                &VMLimitConfig::test(),
+                &action,
+                ProtocolFeature::AccountIdInFunctionCallPermission.protocol_version(),
+            )
+            .expect_err("Expected an error"),
+            ActionsValidationError::UnsuitableStakingKey { public_key },
+        );
+        #[cfg(feature = "protocol_feature_post_quantum_validator_keys")]
+        validate_action(
+            &VMLimitConfig::test(),
+            &action,
+            ProtocolFeature::PostQuantumValidatorKeys.protocol_version(),
+        )
+        .expect("valid action");
+    }
+
     #[test]
     fn test_validate_action_valid_add_key_full_permission() {
         validate_action(
@@ -1470,6 +1589,7 @@ mod tests {
                 public_key: PublicKey::empty(KeyType::ED25519),
                 access_key: AccessKey::full_access(),
             }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1489,6 +1609,7 @@ mod tests {
                     }),
                 },
             }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1498,6 +1619,7 @@ mod tests {
         validate_action(
             &VMLimitConfig::test(),
             &Action::DeleteKey(DeleteKeyAction { public_key: PublicKey::empty(KeyType::ED25519) }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1507,6 +1629,7 @@ mod tests {
         validate_action(
             &VMLimitConfig::test(),
             &Action::DeleteAccount(DeleteAccountAction { beneficiary_id: alice_account() }),
+            PROTOCOL_VERSION,
         )
         .expect("valid action");
     }
@@ -1,6 +1,7 @@
 use near_chain::{Block, BlockHeader};
 use near_crypto::KeyType;
 use near_primitives::validator_signer::InMemoryValidatorSigner;
+use near_primitives::version::PROTOCOL_VERSION;
 use std::sync::Arc;
 
 pub fn set_no_chunk_in_block(block: &mut Block, prev_block: &Block) {
@@ -13,7 +14,7 @@ pub fn set_no_chunk_in_block(block: &mut Block, prev_block: &Block) {
                 Block::compute_chunk_headers_root(&chunk_headers).0;
             header.inner_rest.chunk_tx_root = Block::compute_chunk_tx_root(&chunk_headers);
             header.inner_rest.chunk_receipts_root =
-                Block::compute_chunk_receipts_root(&chunk_headers);
+                Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
             header.inner_lite.prev_state_root = Block::compute_state_root(&chunk_headers);
             header.inner_rest.chunk_mask = vec![false];
             header.inner_rest.gas_price = prev_block.header().gas_price();
@@ -24,7 +25,7 @@ pub fn set_no_chunk_in_block(block: &mut Block, prev_block: &Block) {
                 Block::compute_chunk_headers_root(&chunk_headers).0;
             header.inner_rest.chunk_tx_root = Block::compute_chunk_tx_root(&chunk_headers);
             header.inner_rest.chunk_receipts_root =
-                Block::compute_chunk_receipts_root(&chunk_headers);
+                Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
             header.inner_lite.prev_state_root = Block::compute_state_root(&chunk_headers);
             header.inner_rest.chunk_mask = vec![false];
             header.inner_rest.gas_price = prev_block.header().gas_price();
@@ -35,7 +36,7 @@ pub fn set_no_chunk_in_block(block: &mut Block, prev_block: &Block) {
                 Block::compute_chunk_headers_root(&chunk_headers).0;
             header.inner_rest.chunk_tx_root = Block::compute_chunk_tx_root(&chunk_headers);
             header.inner_rest.chunk_receipts_root =
-                Block::compute_chunk_receipts_root(&chunk_headers);
+                Block::compute_chunk_receipts_root(PROTOCOL_VERSION, &chunk_headers);
             header.inner_lite.prev_state_root = Block::compute_state_root(&chunk_headers);
             header.inner_rest.chunk_mask = vec![false];
             header.inner_rest.gas_price = prev_block.header().gas_price();
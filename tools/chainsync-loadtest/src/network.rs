@@ -15,6 +15,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::ShardChunkHeader;
 use near_primitives::sharding::{ChunkHash, PartialEncodedChunk};
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::time::Clock;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
@@ -329,6 +330,33 @@ impl near_network::client::Client for Network {
         Ok(())
     }
 
+    async fn epoch_sync_request(&self, _epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        None
+    }
+
+    async fn epoch_sync_response(
+        &self,
+        _response: Box<EpochSyncResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        _epoch_id: EpochId,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        None
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) -> Result<(), ReasonForBan> {
+        Ok(())
+    }
+
     async fn challenge(&self, _challenge: Challenge) {}
 
     async fn network_info(&self, info: NetworkInfo) {
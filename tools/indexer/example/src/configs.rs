@@ -1,6 +1,6 @@
 use clap::Parser;
 
-use near_indexer::near_primitives::types::Gas;
+use near_indexer::near_primitives::types::{AccountId, Gas};
 
 /// NEAR Indexer Example
 /// Watches for stream of blocks from the chain
@@ -18,11 +18,25 @@ pub(crate) struct Opts {
 #[derive(Parser, Debug)]
 pub(crate) enum SubCommand {
     /// Run NEAR Indexer Example. Start observe the network
-    Run,
+    Run(RunArgs),
     /// Initialize necessary configs
     Init(InitConfigArgs),
 }
 
+#[derive(Parser, Debug)]
+pub(crate) struct RunArgs {
+    /// Name of the consumer, the streaming resumes from its last acknowledged block
+    #[clap(long, default_value = "indexer-example")]
+    pub consumer: String,
+    /// Only stream the data of these accounts. Streams everything if none is given
+    #[clap(long = "account")]
+    pub accounts: Vec<AccountId>,
+    /// JSON file with the `near_indexer::sinks::SinkConfig` to write the blocks to,
+    /// e.g. `{"files": {"dir": "blocks", "compression": "zstd"}}`. Only logs the blocks if not given
+    #[clap(long)]
+    pub sink: Option<std::path::PathBuf>,
+}
+
 #[derive(Parser, Debug)]
 pub(crate) struct InitConfigArgs {
     /// chain/network id (localnet, testnet, devnet, betanet)
@@ -1,17 +1,16 @@
 use actix;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use tokio::sync::mpsc;
-use tracing::info;
+use tracing::{error, info};
 
 use configs::{Opts, SubCommand};
 use near_indexer;
 
 mod configs;
 
-async fn listen_blocks(mut stream: mpsc::Receiver<near_indexer::StreamerMessage>) {
-    while let Some(streamer_message) = stream.recv().await {
+async fn listen_blocks(mut consumer: near_indexer::Consumer) {
+    while let Some(streamer_message) = consumer.recv().await {
         // TODO: handle data as you need
         // Example of `StreamerMessage` with all the data (the data is synthetic)
         //
@@ -253,6 +252,8 @@ async fn listen_blocks(mut stream: mpsc::Receiver<near_indexer::StreamerMessage>
             streamer_message.shards.iter().map(|shard| if let Some(chunk) = &shard.chunk { chunk.receipts.len() } else { 0usize }).sum::<usize>(),
             streamer_message.shards.iter().map(|shard| shard.receipt_execution_outcomes.len()).sum::<usize>(),
         );
+        // The block is streamed again after a restart unless it's acknowledged
+        consumer.ack(streamer_message.block.header.height);
     }
 }
 
@@ -270,7 +271,16 @@ fn main() -> Result<()> {
     let home_dir = opts.home_dir.unwrap_or(near_indexer::get_default_home());
 
     match opts.subcmd {
-        SubCommand::Run => {
+        SubCommand::Run(args) => {
+            let sink_config: Option<near_indexer::sinks::SinkConfig> = args
+                .sink
+                .map(|path| -> Result<_> {
+                    let file = std::fs::File::open(&path)
+                        .with_context(|| format!("failed to open {}", path.display()))?;
+                    serde_json::from_reader(file)
+                        .with_context(|| format!("failed to parse {}", path.display()))
+                })
+                .transpose()?;
             let indexer_config = near_indexer::IndexerConfig {
                 home_dir,
                 sync_mode: near_indexer::SyncModeEnum::FromInterruption,
@@ -279,8 +289,31 @@ fn main() -> Result<()> {
             let system = actix::System::new();
             system.block_on(async move {
                 let indexer = near_indexer::Indexer::new(indexer_config).expect("Indexer::new()");
-                let stream = indexer.streamer();
-                actix::spawn(listen_blocks(stream));
+                let consumer = indexer
+                    .consumer(near_indexer::ConsumerConfig {
+                        name: args.consumer,
+                        sync_mode: near_indexer::SyncModeEnum::FromInterruption,
+                        filter: near_indexer::StreamerMessageFilter {
+                            accounts: args.accounts.into_iter().collect(),
+                            ..Default::default()
+                        },
+                    })
+                    .expect("Indexer::consumer()");
+                match sink_config {
+                    Some(sink_config) => {
+                        let sink = near_indexer::sinks::open(&sink_config)
+                            .await
+                            .expect("near_indexer::sinks::open()");
+                        actix::spawn(async move {
+                            if let Err(err) = near_indexer::sinks::run(consumer, sink).await {
+                                error!(target: "indexer_example", "Sink failed: {}", err);
+                            }
+                        });
+                    }
+                    None => {
+                        actix::spawn(listen_blocks(consumer));
+                    }
+                }
             });
             system.run()?;
         }
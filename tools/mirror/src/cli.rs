@@ -1,3 +1,4 @@
+use crate::key_mapping::TargetKeyType;
 use anyhow::Context;
 use clap::Parser;
 use std::cell::Cell;
@@ -35,6 +36,11 @@ struct RunCmd {
     /// that does contain a secret, the mirror will refuse to start
     #[clap(long)]
     no_secret: bool,
+    /// Type of the keys the source chain keys are mapped to, as given
+    /// to the `prepare` command. Only needed if --secret-file is not
+    /// given, as the type is recorded there.
+    #[clap(long)]
+    target_key_type: Option<TargetKeyType>,
 }
 
 impl RunCmd {
@@ -42,26 +48,40 @@ impl RunCmd {
         openssl_probe::init_ssl_cert_env_vars();
         let runtime = tokio::runtime::Runtime::new().context("failed to start tokio runtime")?;
 
-        let secret = if let Some(secret_file) = &self.secret_file {
-            let secret = crate::secret::load(secret_file)
+        let (secret, target_key_type) = if let Some(secret_file) = &self.secret_file {
+            let (secret, target_key_type) = crate::secret::load(secret_file)
                 .with_context(|| format!("Failed to load secret from {:?}", secret_file))?;
             if secret.is_some() && self.no_secret {
                 anyhow::bail!(
                     "--no-secret given with --secret-file indicating that a secret should be used"
                 );
             }
-            secret
+            if let Some(key_type) = self.target_key_type {
+                if key_type != target_key_type {
+                    anyhow::bail!(
+                        "--target-key-type {} given with --secret-file indicating that the target chain keys are of type {}",
+                        key_type, target_key_type
+                    );
+                }
+            }
+            (secret, target_key_type)
         } else {
             if !self.no_secret {
                 anyhow::bail!("Please give either --secret-file or --no-secret");
             }
-            None
+            (None, self.target_key_type.unwrap_or_default())
         };
 
         let system = new_actix_system(runtime);
         system
             .block_on(async move {
-                actix::spawn(crate::run(self.source_home, self.target_home, secret)).await
+                actix::spawn(crate::run(
+                    self.source_home,
+                    self.target_home,
+                    secret,
+                    target_key_type,
+                ))
+                .await
             })
             .unwrap()
     }
@@ -95,6 +115,12 @@ struct PrepareCmd {
     /// longer be able to mirror any traffic.
     #[clap(long)]
     secret_file_out: PathBuf,
+    /// Type of the keys the source chain keys are mapped to: `source`
+    /// keeps the type of each key, while `mldsa` and `ed25519-mldsa`
+    /// map every key to a post-quantum one, except for the keys
+    /// implicit accounts are named after, which stay ed25519 keys.
+    #[clap(long, default_value = "source")]
+    target_key_type: TargetKeyType,
 }
 
 impl PrepareCmd {
@@ -104,6 +130,7 @@ impl PrepareCmd {
             &self.records_file_out,
             self.no_secret,
             &self.secret_file_out,
+            self.target_key_type,
         )
     }
 }
@@ -1,3 +1,4 @@
+use crate::key_mapping::TargetKeyType;
 use near_primitives::state_record::StateRecord;
 use serde::ser::{SerializeSeq, Serializer};
 use std::fs::File;
@@ -9,27 +10,39 @@ pub fn map_records<P: AsRef<Path>>(
     records_file_out: P,
     no_secret: bool,
     secret_file_out: P,
+    target_key_type: TargetKeyType,
 ) -> anyhow::Result<()> {
     let secret = if !no_secret {
-        Some(crate::secret::generate(secret_file_out)?)
+        Some(crate::secret::generate(secret_file_out, target_key_type)?)
     } else {
-        crate::secret::write_empty(secret_file_out)?;
+        crate::secret::write_empty(secret_file_out, target_key_type)?;
         None
     };
     let reader = BufReader::new(File::open(records_file_in)?);
     let records_out = BufWriter::new(File::create(records_file_out)?);
     let mut records_ser = serde_json::Serializer::new(records_out);
     let mut records_seq = records_ser.serialize_seq(None).unwrap();
+    let mut num_keys = 0u64;
+    let mut source_key_bytes = 0u64;
+    let mut target_key_bytes = 0u64;
 
     near_chain_configs::stream_records_from_file(reader, |mut r| {
         match &mut r {
             StateRecord::AccessKey { account_id, public_key, access_key } => {
-                let replacement = crate::key_mapping::map_key(&public_key, secret.as_ref());
+                let replacement = crate::key_mapping::map_access_key(
+                    &account_id,
+                    &public_key,
+                    target_key_type,
+                    secret.as_ref(),
+                );
                 let new_record = StateRecord::AccessKey {
                     account_id: crate::key_mapping::map_account(&account_id, secret.as_ref()),
                     public_key: replacement.public_key(),
                     access_key: access_key.clone(),
                 };
+                num_keys += 1;
+                source_key_bytes += public_key.len() as u64;
+                target_key_bytes += replacement.public_key().len() as u64;
                 // TODO: would be nice for stream_records_from_file() to let you return early on error so
                 // we dont have to unwrap here
                 records_seq.serialize_element(&new_record).unwrap();
@@ -79,5 +92,9 @@ pub fn map_records<P: AsRef<Path>>(
         };
     })?;
     records_seq.end()?;
+    tracing::info!(
+        target: "mirror", "mapped {} access keys to {} keys: {} bytes of public keys in the source records, {} in the target ones",
+        num_keys, target_key_type, source_key_bytes, target_key_bytes,
+    );
     Ok(())
 }
@@ -1,8 +1,52 @@
 use borsh::BorshDeserialize;
 use hkdf::Hkdf;
-use near_crypto::{ED25519PublicKey, ED25519SecretKey, PublicKey, Secp256K1PublicKey, SecretKey};
+use near_crypto::{
+    ED25519PublicKey, ED25519SecretKey, Ed25519MlDsaSecretKey, MlDsaPublicKey, MlDsaSecretKey,
+    PublicKey, Secp256K1PublicKey, SecretKey, SlhDsaPublicKey, SlhDsaSecretKey, MLDSA_SEED_LENGTH,
+    SLHDSA_SEED_LENGTH,
+};
 use near_primitives::types::AccountId;
 use sha2::Sha256;
+use std::fmt;
+use std::str::FromStr;
+
+/// Type of the keys the source chain keys are mapped to on the target chain.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TargetKeyType {
+    /// Keep the type of the source key.
+    #[default]
+    #[serde(rename = "source")]
+    Source,
+    #[serde(rename = "mldsa")]
+    MlDsa,
+    #[serde(rename = "ed25519-mldsa")]
+    Ed25519MlDsa,
+}
+
+impl fmt::Display for TargetKeyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Source => "source",
+            Self::MlDsa => "mldsa",
+            Self::Ed25519MlDsa => "ed25519-mldsa",
+        })
+    }
+}
+
+impl FromStr for TargetKeyType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "source" => Ok(Self::Source),
+            "mldsa" => Ok(Self::MlDsa),
+            "ed25519-mldsa" => Ok(Self::Ed25519MlDsa),
+            _ => anyhow::bail!(
+                "unknown target key type {s:?}, expected one of source, mldsa or ed25519-mldsa"
+            ),
+        }
+    }
+}
 
 fn ed25519_map_secret(
     buf: &mut [u8],
@@ -27,7 +71,11 @@ fn map_ed25519(
     let mut buf = [0; ed25519_dalek::KEYPAIR_LENGTH];
 
     ed25519_map_secret(&mut buf[..ed25519_dalek::SECRET_KEY_LENGTH], public, secret);
+    ed25519_from_secret(buf)
+}
 
+// Fills in the public half of `buf`, given the secret half.
+fn ed25519_from_secret(mut buf: [u8; ed25519_dalek::KEYPAIR_LENGTH]) -> ED25519SecretKey {
     let secret_key =
         ed25519_dalek::SecretKey::from_bytes(&buf[..ed25519_dalek::SECRET_KEY_LENGTH]).unwrap();
     let public_key = ed25519_dalek::PublicKey::from(&secret_key);
@@ -69,6 +117,109 @@ fn map_secp256k1(
     secp256k1_from_slice(&mut buf, public)
 }
 
+fn map_mldsa(
+    public: &MlDsaPublicKey,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> MlDsaSecretKey {
+    let mut seed = [0; MLDSA_SEED_LENGTH];
+
+    match secret {
+        Some(secret) => {
+            let hk = Hkdf::<Sha256>::new(None, secret);
+            hk.expand(public.as_ref(), &mut seed).unwrap();
+        }
+        None => {
+            seed.copy_from_slice(&public.as_ref()[..MLDSA_SEED_LENGTH]);
+        }
+    };
+
+    MlDsaSecretKey::from_seed_bytes(seed)
+}
+
+fn map_slhdsa(
+    public: &SlhDsaPublicKey,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> SlhDsaSecretKey {
+    let mut seeds = [0; 3 * SLHDSA_SEED_LENGTH];
+
+    match secret {
+        Some(secret) => {
+            let hk = Hkdf::<Sha256>::new(None, secret);
+            hk.expand(public.as_ref(), &mut seeds).unwrap();
+        }
+        None => {
+            // The public key is only two seeds long, so reuse its first half
+            // for the third one.
+            seeds[..2 * SLHDSA_SEED_LENGTH].copy_from_slice(public.as_ref());
+            seeds[2 * SLHDSA_SEED_LENGTH..].copy_from_slice(&public.as_ref()[..SLHDSA_SEED_LENGTH]);
+        }
+    };
+
+    let (sk_seed, rest) = seeds.split_at(SLHDSA_SEED_LENGTH);
+    let (sk_prf, pk_seed) = rest.split_at(SLHDSA_SEED_LENGTH);
+    SlhDsaSecretKey::from_seeds(
+        sk_seed.try_into().unwrap(),
+        sk_prf.try_into().unwrap(),
+        pk_seed.try_into().unwrap(),
+    )
+}
+
+// Derives `buf` from a public key of a different type than the key being
+// derived. `domain` tells apart the keys of different types derived from
+// the same public key.
+fn derive_from_other_key(
+    buf: &mut [u8],
+    domain: &[u8],
+    public: &PublicKey,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) {
+    match secret {
+        Some(secret) => {
+            let hk = Hkdf::<Sha256>::new(None, secret);
+            hk.expand_multi_info(&[domain, public.key_data()], buf).unwrap();
+        }
+        None => {
+            // All the supported public keys are at least as long as the seeds.
+            buf.copy_from_slice(&public.key_data()[..buf.len()]);
+        }
+    };
+}
+
+fn map_to_mldsa(
+    key: &PublicKey,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> MlDsaSecretKey {
+    match key {
+        PublicKey::MLDSA(k) => map_mldsa(k, secret),
+        PublicKey::ED25519MLDSA(k) => map_mldsa(&k.mldsa(), secret),
+        _ => {
+            let mut seed = [0; MLDSA_SEED_LENGTH];
+            derive_from_other_key(&mut seed, b"mldsa", key, secret);
+            MlDsaSecretKey::from_seed_bytes(seed)
+        }
+    }
+}
+
+fn map_to_ed25519(
+    key: &PublicKey,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> ED25519SecretKey {
+    match key {
+        PublicKey::ED25519(k) => map_ed25519(k, secret),
+        PublicKey::ED25519MLDSA(k) => map_ed25519(&k.ed25519(), secret),
+        _ => {
+            let mut buf = [0; ed25519_dalek::KEYPAIR_LENGTH];
+            derive_from_other_key(
+                &mut buf[..ed25519_dalek::SECRET_KEY_LENGTH],
+                b"ed25519",
+                key,
+                secret,
+            );
+            ed25519_from_secret(buf)
+        }
+    }
+}
+
 // This maps the public key to a secret key so that we can sign
 // transactions on the target chain.  If secret is None, then we just
 // use the bytes of the public key directly, otherwise we feed the
@@ -80,6 +231,48 @@ pub(crate) fn map_key(
     match key {
         PublicKey::ED25519(k) => SecretKey::ED25519(map_ed25519(k, secret)),
         PublicKey::SECP256K1(k) => SecretKey::SECP256K1(map_secp256k1(k, secret)),
+        PublicKey::MLDSA(k) => SecretKey::MLDSA(map_mldsa(k, secret)),
+        PublicKey::SLHDSA(k) => SecretKey::SLHDSA(map_slhdsa(k, secret)),
+        PublicKey::ED25519MLDSA(k) => SecretKey::ED25519MLDSA(Ed25519MlDsaSecretKey {
+            ed25519: map_ed25519(&k.ed25519(), secret),
+            mldsa: map_mldsa(&k.mldsa(), secret),
+        }),
+    }
+}
+
+// Like map_key(), but maps the key to one of the given type. Source keys
+// which are already of that type map to the same keys as with map_key(), and
+// the ed25519 half of a hybrid key derived from an ed25519 key is what
+// map_key() would map that key to.
+pub(crate) fn map_key_to_type(
+    key: &PublicKey,
+    key_type: TargetKeyType,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> SecretKey {
+    match key_type {
+        TargetKeyType::Source => map_key(key, secret),
+        TargetKeyType::MlDsa => SecretKey::MLDSA(map_to_mldsa(key, secret)),
+        TargetKeyType::Ed25519MlDsa => SecretKey::ED25519MLDSA(Ed25519MlDsaSecretKey {
+            ed25519: map_to_ed25519(key, secret),
+            mldsa: map_to_mldsa(key, secret),
+        }),
+    }
+}
+
+// Maps an access key of `account_id`. The runtime gives implicit accounts
+// created by a transfer a full access key whose public key is the account ID,
+// so that key is always mapped with map_key(), the same way map_account()
+// maps the account ID. Every other key is mapped to `key_type`.
+pub(crate) fn map_access_key(
+    account_id: &AccountId,
+    key: &PublicKey,
+    key_type: TargetKeyType,
+    secret: Option<&[u8; crate::secret::SECRET_LEN]>,
+) -> SecretKey {
+    if account_id.is_implicit() && &implicit_account_key(account_id) == key {
+        map_key(key, secret)
+    } else {
+        map_key_to_type(key, key_type, secret)
     }
 }
 
@@ -109,3 +302,45 @@ pub(crate) fn map_account(
         account_id.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+
+    #[test]
+    fn test_map_access_key() {
+        let secret = [7; crate::secret::SECRET_LEN];
+        let named: AccountId = "alice.near".parse().unwrap();
+        let ed25519 = SecretKey::from_seed(KeyType::ED25519, "alice").public_key();
+        let secp256k1 = SecretKey::from_seed(KeyType::SECP256K1, "alice").public_key();
+
+        for secret in [None, Some(&secret)] {
+            let mldsa = map_access_key(&named, &ed25519, TargetKeyType::MlDsa, secret);
+            assert_eq!(mldsa.key_type(), KeyType::MLDSA);
+            assert_eq!(mldsa, map_access_key(&named, &ed25519, TargetKeyType::MlDsa, secret));
+            let from_secp = map_access_key(&named, &secp256k1, TargetKeyType::MlDsa, secret);
+            assert_ne!(mldsa.public_key(), from_secp.public_key());
+
+            let hybrid = map_access_key(&named, &ed25519, TargetKeyType::Ed25519MlDsa, secret);
+            let hybrid = match hybrid {
+                SecretKey::ED25519MLDSA(hybrid) => hybrid,
+                key => panic!("expected a hybrid key, got {:?}", key.key_type()),
+            };
+            assert_eq!(SecretKey::ED25519(hybrid.ed25519), map_key(&ed25519, secret));
+            assert_eq!(SecretKey::MLDSA(hybrid.mldsa), mldsa);
+
+            // The key named after an implicit account keeps being an ed25519 key, so
+            // that it matches the key of the mapped account created by a transfer.
+            let implicit: AccountId = hex::encode(ed25519.key_data()).parse().unwrap();
+            let mapped = map_access_key(&implicit, &ed25519, TargetKeyType::MlDsa, secret);
+            assert_eq!(mapped, map_key(&ed25519, secret));
+            assert_eq!(
+                map_account(&implicit, secret).to_string(),
+                hex::encode(mapped.public_key().key_data())
+            );
+            let other = map_access_key(&implicit, &secp256k1, TargetKeyType::MlDsa, secret);
+            assert_eq!(other.key_type(), KeyType::MLDSA);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::key_mapping::TargetKeyType;
 use actix::Addr;
 use anyhow::Context;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -130,7 +131,54 @@ struct TxMirror {
     target_min_block_production_delay: Duration,
     tracked_shards: Vec<ShardId>,
     secret: Option<[u8; crate::secret::SECRET_LEN]>,
+    target_key_type: TargetKeyType,
     next_source_height: Option<BlockHeight>,
+    send_stats: SendStats,
+}
+
+// Counts the transactions we send and how large they are compared to the
+// source chain transactions they were mapped from, which differ most when
+// mapping to post-quantum keys, and logs it every LOG_INTERVAL.
+struct SendStats {
+    since: Instant,
+    num_txs: u64,
+    source_bytes: u64,
+    target_bytes: u64,
+}
+
+impl SendStats {
+    const LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+    fn new() -> Self {
+        Self { since: Instant::now(), num_txs: 0, source_bytes: 0, target_bytes: 0 }
+    }
+
+    fn record(&mut self, tx: &MappedTx) {
+        let target_size = tx.target_tx.try_to_vec().unwrap().len() as u64;
+        crate::metrics::TRANSACTION_BYTES.with_label_values(&["source"]).inc_by(tx.source_size);
+        crate::metrics::TRANSACTION_BYTES.with_label_values(&["target"]).inc_by(target_size);
+        self.num_txs += 1;
+        self.source_bytes += tx.source_size;
+        self.target_bytes += target_size;
+    }
+
+    fn maybe_log(&mut self, target_key_type: TargetKeyType) {
+        let elapsed = self.since.elapsed();
+        if elapsed < Self::LOG_INTERVAL {
+            return;
+        }
+        if self.num_txs > 0 {
+            tracing::info!(
+                target: "mirror", "sent {} transactions in {:?} ({:.1} tx/s, {:.1} KiB/s) with {} keys. \
+                They take {} bytes vs. {} bytes on the source chain ({:+.1}%)",
+                self.num_txs, elapsed, self.num_txs as f64 / elapsed.as_secs_f64(),
+                self.target_bytes as f64 / 1024. / elapsed.as_secs_f64(), target_key_type,
+                self.target_bytes, self.source_bytes,
+                100. * (self.target_bytes as f64 / self.source_bytes as f64 - 1.),
+            );
+        }
+        *self = Self::new();
+    }
 }
 
 fn open_db<P: AsRef<Path>>(home: P, config: &NearConfig) -> anyhow::Result<DB> {
@@ -156,6 +204,7 @@ struct TxAwaitingNonce {
     source_signer_id: AccountId,
     source_receiver_id: AccountId,
     source_tx_index: usize,
+    source_size: u64,
     target_private: SecretKey,
     target_tx: Transaction,
 }
@@ -172,6 +221,7 @@ impl TxAwaitingNonce {
             source_signer_id: source_tx.signer_id.clone(),
             source_receiver_id: source_tx.receiver_id.clone(),
             source_tx_index,
+            source_size: signed_tx_size(source_tx),
             target_private,
             target_tx,
         }
@@ -186,6 +236,8 @@ struct MappedTx {
     source_signer_id: AccountId,
     source_receiver_id: AccountId,
     source_tx_index: usize,
+    // size of the source chain transaction, to compare with target_tx
+    source_size: u64,
     target_tx: SignedTransaction,
 }
 
@@ -199,11 +251,28 @@ impl MappedTx {
             source_signer_id: source_tx.signer_id.clone(),
             source_receiver_id: source_tx.receiver_id.clone(),
             source_tx_index,
+            source_size: signed_tx_size(source_tx),
             target_tx,
         }
     }
 }
 
+// Returns the serialized size of the transaction as it was sent to the source chain.
+fn signed_tx_size(tx: &SignedTransactionView) -> u64 {
+    let mut transaction = Transaction::new(
+        tx.signer_id.clone(),
+        tx.public_key.clone(),
+        tx.receiver_id.clone(),
+        tx.nonce,
+        // The view doesn't have the block hash, but it has a fixed size anyway.
+        CryptoHash::default(),
+    );
+    // this try_from() won't fail since the ActionView was constructed from the Action
+    transaction.actions = tx.actions.iter().map(|a| Action::try_from(a.clone()).unwrap()).collect();
+    transaction.priority_fee = tx.priority_fee;
+    SignedTransaction::new(tx.signature.clone(), transaction).try_to_vec().unwrap().len() as u64
+}
+
 #[derive(Debug)]
 enum TargetChainTx {
     Ready(MappedTx),
@@ -229,6 +298,7 @@ impl TargetChainTx {
                     source_signer_id: t.source_signer_id.clone(),
                     source_receiver_id: t.source_receiver_id.clone(),
                     source_tx_index: t.source_tx_index,
+                    source_size: t.source_size,
                     target_tx,
                 });
             }
@@ -258,7 +328,7 @@ async fn account_exists(
         .send(
             Query::new(
                 BlockReference::BlockId(BlockId::Hash(prev_block.clone())),
-                QueryRequest::ViewAccount { account_id: account_id.clone() },
+                QueryRequest::ViewAccount { account_id: account_id.clone(), include_proof: false },
             )
             .with_span_context(),
         )
@@ -294,6 +364,7 @@ async fn fetch_access_key_nonce(
                 QueryRequest::ViewAccessKey {
                     account_id: account_id.clone(),
                     public_key: public_key.clone(),
+                    include_proof: false,
                 },
             )
             .with_span_context(),
@@ -420,6 +491,7 @@ impl TxMirror {
         source_home: P,
         target_home: P,
         secret: Option<[u8; crate::secret::SECRET_LEN]>,
+        target_key_type: TargetKeyType,
     ) -> anyhow::Result<Self> {
         let target_config =
             nearcore::config::load_config(target_home.as_ref(), GenesisValidationMode::UnsafeFast)
@@ -459,7 +531,9 @@ impl TxMirror {
                 .min_block_production_delay,
             tracked_shards: target_config.config.tracked_shards.clone(),
             secret,
+            target_key_type,
             next_source_height: None,
+            send_stats: SendStats::new(),
         })
     }
 
@@ -503,6 +577,7 @@ impl TxMirror {
                         {
                             ProcessTxResponse::RequestRouted => {
                                 crate::metrics::TRANSACTIONS_SENT.with_label_values(&["ok"]).inc();
+                                self.send_stats.record(&tx);
                                 txs.push(tx);
                             }
                             ProcessTxResponse::InvalidTx(e) => {
@@ -536,6 +611,7 @@ impl TxMirror {
             }
             sent.push((chunk.shard_id, txs));
         }
+        self.send_stats.maybe_log(self.target_key_type);
         Ok(sent)
     }
 
@@ -731,8 +807,12 @@ impl TxMirror {
                 Action::AddKey(add_key) => {
                     self.store_source_nonce(tx, &add_key.public_key).await?;
 
-                    let replacement =
-                        crate::key_mapping::map_key(&add_key.public_key, self.secret.as_ref());
+                    let replacement = crate::key_mapping::map_access_key(
+                        &tx.receiver_id,
+                        &add_key.public_key,
+                        self.target_key_type,
+                        self.secret.as_ref(),
+                    );
 
                     actions.push(Action::AddKey(AddKeyAction {
                         public_key: replacement.public_key(),
@@ -740,8 +820,12 @@ impl TxMirror {
                     }));
                 }
                 Action::DeleteKey(delete_key) => {
-                    let replacement =
-                        crate::key_mapping::map_key(&delete_key.public_key, self.secret.as_ref());
+                    let replacement = crate::key_mapping::map_access_key(
+                        &tx.receiver_id,
+                        &delete_key.public_key,
+                        self.target_key_type,
+                        self.secret.as_ref(),
+                    );
                     let public_key = replacement.public_key();
 
                     actions.push(Action::DeleteKey(DeleteKeyAction { public_key }));
@@ -821,8 +905,12 @@ impl TxMirror {
                     // If this is a tx containing only stake actions, skip it.
                     continue;
                 }
-                let mapped_key =
-                    crate::key_mapping::map_key(&source_tx.public_key, self.secret.as_ref());
+                let mapped_key = crate::key_mapping::map_access_key(
+                    &source_tx.signer_id,
+                    &source_tx.public_key,
+                    self.target_key_type,
+                    self.secret.as_ref(),
+                );
                 let public_key = mapped_key.public_key();
 
                 let target_signer_id =
@@ -976,8 +1064,13 @@ impl TxMirror {
             let mut diff = self.read_nonce_diff(source_signer_id, source_public_key)?.unwrap();
             let target_signer_id =
                 crate::key_mapping::map_account(source_signer_id, self.secret.as_ref());
-            let target_public_key =
-                crate::key_mapping::map_key(source_public_key, self.secret.as_ref()).public_key();
+            let target_public_key = crate::key_mapping::map_access_key(
+                source_signer_id,
+                source_public_key,
+                self.target_key_type,
+                self.secret.as_ref(),
+            )
+            .public_key();
             self.update_nonces(
                 &source_signer_id,
                 &target_signer_id,
@@ -1105,7 +1198,8 @@ async fn run<P: AsRef<Path>>(
     source_home: P,
     target_home: P,
     secret: Option<[u8; crate::secret::SECRET_LEN]>,
+    target_key_type: TargetKeyType,
 ) -> anyhow::Result<()> {
-    let m = TxMirror::new(source_home, target_home, secret)?;
+    let m = TxMirror::new(source_home, target_home, secret, target_key_type)?;
     m.run().await
 }
@@ -19,3 +19,12 @@ pub static TRANSACTIONS_INCLUDED: Lazy<IntCounter> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static TRANSACTION_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_mirror_transaction_bytes",
+        "Total size of the transactions sent, and of the source chain transactions they were mapped from",
+        &["chain"],
+    )
+    .unwrap()
+});
@@ -1,3 +1,4 @@
+use crate::key_mapping::TargetKeyType;
 use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
@@ -11,6 +12,10 @@ struct KeyMapSecret([u8; SECRET_LEN]);
 #[derive(Serialize, Deserialize)]
 struct MirrorSecretConfig {
     pub key_map_secret: Option<KeyMapSecret>,
+    /// Type of the keys in the target chain records, which the mirrored
+    /// transactions have to be signed with.
+    #[serde(default)]
+    pub target_key_type: TargetKeyType,
 }
 
 impl serde::Serialize for KeyMapSecret {
@@ -59,27 +64,35 @@ impl FromStr for KeyMapSecret {
     }
 }
 
-pub(crate) fn generate<P: AsRef<Path>>(secret_file_out: P) -> anyhow::Result<[u8; SECRET_LEN]> {
+pub(crate) fn generate<P: AsRef<Path>>(
+    secret_file_out: P,
+    target_key_type: TargetKeyType,
+) -> anyhow::Result<[u8; SECRET_LEN]> {
     let mut secret = [0; SECRET_LEN];
     let mut out = File::create(secret_file_out)?;
 
     OsRng.fill_bytes(&mut secret);
-    let config = MirrorSecretConfig { key_map_secret: Some(KeyMapSecret(secret)) };
+    let config = MirrorSecretConfig { key_map_secret: Some(KeyMapSecret(secret)), target_key_type };
     let str = serde_json::to_string_pretty(&config)?;
     out.write_all(str.as_bytes())?;
     Ok(secret)
 }
 
-pub(crate) fn write_empty<P: AsRef<Path>>(secret_file_out: P) -> anyhow::Result<()> {
+pub(crate) fn write_empty<P: AsRef<Path>>(
+    secret_file_out: P,
+    target_key_type: TargetKeyType,
+) -> anyhow::Result<()> {
     let mut out = File::create(secret_file_out)?;
-    let config = MirrorSecretConfig { key_map_secret: None };
+    let config = MirrorSecretConfig { key_map_secret: None, target_key_type };
     let str = serde_json::to_string_pretty(&config)?;
     out.write_all(str.as_bytes())?;
     Ok(())
 }
 
-pub fn load<P: AsRef<Path>>(secret_file: P) -> anyhow::Result<Option<[u8; SECRET_LEN]>> {
+pub fn load<P: AsRef<Path>>(
+    secret_file: P,
+) -> anyhow::Result<(Option<[u8; SECRET_LEN]>, TargetKeyType)> {
     let s = std::fs::read_to_string(secret_file)?;
     let config: MirrorSecretConfig = serde_json::from_str(&s)?;
-    Ok(config.key_map_secret.map(|s| s.0))
+    Ok((config.key_map_secret.map(|s| s.0), config.target_key_type))
 }
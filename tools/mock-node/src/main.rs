@@ -92,6 +92,8 @@ fn main() -> anyhow::Result<()> {
         .context("Error loading config")?;
     near_config.validator_signer = None;
     near_config.client_config.min_num_peers = 1;
+    // Epoch Sync hands off to State Sync, which the mock network doesn't serve.
+    near_config.client_config.epoch_sync_enabled = false;
     let signer = InMemorySigner::from_random("mock_node".parse().unwrap(), KeyType::ED25519);
     near_config.network_config.node_key = signer.secret_key;
     near_config.client_config.tracked_shards =
@@ -278,7 +278,10 @@ fn apply_block_from_range(
             .unwrap()
     };
 
-    let (outcome_root, _) = ApplyTransactionResult::compute_outcomes_proof(&apply_result.outcomes);
+    let protocol_version =
+        runtime_adapter.get_epoch_protocol_version(block.header().epoch_id()).unwrap();
+    let (outcome_root, _) =
+        ApplyTransactionResult::compute_outcomes_proof(protocol_version, &apply_result.outcomes);
     let chunk_extra = ChunkExtra::new(
         &apply_result.new_root,
         outcome_root,
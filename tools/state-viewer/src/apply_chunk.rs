@@ -11,6 +11,7 @@ use near_primitives::shard_layout;
 use near_primitives::sharding::{ChunkHash, ReceiptProof};
 use near_primitives::syncing::ReceiptProofResponse;
 use near_primitives::types::{BlockHeight, ShardId};
+use near_primitives::version::ProtocolVersion;
 use near_primitives_core::hash::hash;
 use near_primitives_core::types::Gas;
 use near_store::DBCol;
@@ -70,14 +71,14 @@ fn get_incoming_receipts(
     Ok(collect_receipts_from_response(&responses))
 }
 
-// returns (apply_result, gas limit)
+// returns (apply_result, gas limit, protocol version)
 pub(crate) fn apply_chunk(
     runtime: &dyn RuntimeAdapter,
     chain_store: &mut ChainStore,
     chunk_hash: ChunkHash,
     target_height: Option<u64>,
     rng: Option<StdRng>,
-) -> anyhow::Result<(ApplyTransactionResult, Gas)> {
+) -> anyhow::Result<(ApplyTransactionResult, Gas, ProtocolVersion)> {
     let chunk = chain_store.get_chunk(&chunk_hash)?;
     let chunk_header = chunk.cloned_header();
 
@@ -113,6 +114,8 @@ pub(crate) fn apply_chunk(
         &prev_block_hash,
         shard_id,
     )?;
+    let epoch_id = runtime.get_epoch_id_from_prev_block(&prev_block_hash)?;
+    let protocol_version = runtime.get_epoch_protocol_version(&epoch_id)?;
 
     Ok((
         runtime.apply_transactions(
@@ -138,6 +141,7 @@ pub(crate) fn apply_chunk(
             false,
         )?,
         chunk_header.gas_limit(),
+        protocol_version,
     ))
 }
 
@@ -250,10 +254,11 @@ fn apply_tx_in_chunk(
     let mut results = Vec::new();
     for chunk_hash in chunk_hashes {
         println!("found tx in chunk {}. Equivalent command (which will run faster than apply_tx):\nview_state apply_chunk --chunk_hash {}\n", &chunk_hash.0, &chunk_hash.0);
-        let (apply_result, gas_limit) = apply_chunk(runtime, chain_store, chunk_hash, None, None)?;
+        let (apply_result, gas_limit, protocol_version) =
+            apply_chunk(runtime, chain_store, chunk_hash, None, None)?;
         println!(
             "resulting chunk extra:\n{:?}",
-            crate::commands::resulting_chunk_extra(&apply_result, gas_limit)
+            crate::commands::resulting_chunk_extra(&apply_result, gas_limit, protocol_version)
         );
         results.push(apply_result);
     }
@@ -379,9 +384,10 @@ fn apply_receipt_in_chunk(
         };
         println!("Applying chunk at height {} in shard {}. Equivalent command (which will run faster than apply_receipt):\nview_state apply_chunk --chunk_hash {}\n",
                  height, shard_id, chunk_hash.0);
-        let (apply_result, gas_limit) =
+        let (apply_result, gas_limit, protocol_version) =
             apply_chunk(runtime.clone(), chain_store, chunk_hash.clone(), None, None)?;
-        let chunk_extra = crate::commands::resulting_chunk_extra(&apply_result, gas_limit);
+        let chunk_extra =
+            crate::commands::resulting_chunk_extra(&apply_result, gas_limit, protocol_version);
         println!("resulting chunk extra:\n{:?}", chunk_extra);
         results.push(apply_result);
     }
@@ -505,7 +511,7 @@ mod test {
                     let chunk_hash = &chunk_hashes[shard];
                     let new_root = new_roots[shard];
 
-                    let (apply_result, _) = crate::apply_chunk::apply_chunk(
+                    let (apply_result, _, _) = crate::apply_chunk::apply_chunk(
                         runtime.as_ref(),
                         &mut chain_store,
                         chunk_hash.clone(),
@@ -40,6 +40,10 @@ pub enum StateViewerSubCommand {
     /// Check whether the node has all the blocks up to its head.
     #[clap(alias = "check_block")]
     CheckBlock,
+    /// Recompute Merkle roots of blocks from start_index to end_index with
+    /// SHA-256 and with the hash function of their protocol version.
+    #[clap(alias = "check_merkle_roots")]
+    CheckMerkleRoots(CheckMerkleRootsCmd),
     /// Dump deployed contract code of given account to wasm file.
     #[clap(alias = "dump_code")]
     DumpCode(DumpCodeCmd),
@@ -92,6 +96,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::Apply(cmd) => cmd.run(home_dir, near_config, hot),
             StateViewerSubCommand::ViewChain(cmd) => cmd.run(near_config, hot),
             StateViewerSubCommand::CheckBlock => check_block_chunk_existence(near_config, hot),
+            StateViewerSubCommand::CheckMerkleRoots(cmd) => cmd.run(home_dir, near_config, hot),
             StateViewerSubCommand::DumpCode(cmd) => cmd.run(home_dir, near_config, hot),
             StateViewerSubCommand::DumpAccountStorage(cmd) => cmd.run(home_dir, near_config, hot),
             StateViewerSubCommand::EpochInfo(cmd) => cmd.run(home_dir, near_config, hot),
@@ -300,6 +305,20 @@ impl ViewChainCmd {
     }
 }
 
+#[derive(Parser)]
+pub struct CheckMerkleRootsCmd {
+    #[clap(long)]
+    start_index: BlockHeight,
+    #[clap(long)]
+    end_index: BlockHeight,
+}
+
+impl CheckMerkleRootsCmd {
+    pub fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        check_merkle_roots(self.start_index, self.end_index, home_dir, near_config, store);
+    }
+}
+
 #[derive(Parser)]
 pub struct DumpCodeCmd {
     #[clap(long)]
@@ -15,13 +15,15 @@ use near_epoch_manager::EpochManagerAdapter;
 use near_network::iter_peers_from_store;
 use near_primitives::account::id::AccountId;
 use near_primitives::block::{Block, BlockHeader};
-use near_primitives::hash::CryptoHash;
+use near_primitives::hash::{CryptoHash, HashAlgorithm};
+use near_primitives::merkle::{merkle_hash_algorithm, merklize_with, PartialMerkleTree};
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::sharding::ChunkHash;
 use near_primitives::state_record::StateRecord;
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{BlockHeight, ShardId, StateRoot};
+use near_primitives::version::ProtocolVersion;
 use near_primitives_core::types::Gas;
 use near_store::test_utils::create_test_store;
 use near_store::Trie;
@@ -357,6 +359,112 @@ pub(crate) fn print_chain(
     }
 }
 
+/// Recomputes the chunk receipts root and the outcome root of every block in
+/// the range with both SHA-256 and the hash function of the block's protocol
+/// version, and reports which of them match the header.  The block Merkle root
+/// is always built with SHA-256 and is checked with it only.
+pub(crate) fn check_merkle_roots(
+    start_height: BlockHeight,
+    end_height: BlockHeight,
+    home_dir: &Path,
+    near_config: NearConfig,
+    store: Store,
+) {
+    let genesis_height = near_config.genesis.config.genesis_height;
+    let chain_store =
+        ChainStore::new(store.clone(), genesis_height, !near_config.client_config.archive);
+    let runtime = NightshadeRuntime::from_config(home_dir, store, &near_config);
+    // SHA-256 followed by `algorithm` unless they are the same.
+    let candidates = |algorithm: HashAlgorithm| {
+        let mut candidates = vec![HashAlgorithm::Sha256];
+        if algorithm != HashAlgorithm::Sha256 {
+            candidates.push(algorithm);
+        }
+        candidates
+    };
+
+    // The block Merkle root commits to all previous blocks, so the trees have to
+    // be rebuilt from genesis: one with SHA-256 only and one with the hash
+    // function of each block's protocol version.
+    let mut sha256_block_merkle_tree = PartialMerkleTree::default();
+    let mut block_merkle_tree = PartialMerkleTree::default();
+    let mut prev_block_hash = None;
+    let mut mismatches = 0;
+    for height in genesis_height..=end_height {
+        let block_hash = match chain_store.get_block_hash_by_height(height) {
+            Ok(block_hash) => block_hash,
+            Err(_) => continue,
+        };
+        let block = chain_store.get_block(&block_hash).unwrap();
+        let header = block.header();
+        let protocol_version = runtime.get_epoch_protocol_version(header.epoch_id()).unwrap();
+        let algorithm = merkle_hash_algorithm(protocol_version);
+        if let Some(prev_block_hash) = prev_block_hash {
+            sha256_block_merkle_tree.insert(prev_block_hash);
+            block_merkle_tree.insert_with(algorithm, prev_block_hash);
+        }
+        if height >= start_height {
+            let chunks = block.chunks();
+            let receipts_roots: Vec<_> =
+                chunks.iter().map(|chunk| chunk.outgoing_receipts_root()).collect();
+            let outcome_roots: Vec<_> = chunks.iter().map(|chunk| chunk.outcome_root()).collect();
+
+            let mut roots = vec![
+                (
+                    "chunk receipts root",
+                    algorithm,
+                    candidates(algorithm)
+                        .into_iter()
+                        .filter(|&candidate| {
+                            merklize_with(candidate, &receipts_roots).0
+                                == *header.chunk_receipts_root()
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                (
+                    "outcome root",
+                    algorithm,
+                    candidates(algorithm)
+                        .into_iter()
+                        .filter(|&candidate| {
+                            merklize_with(candidate, &outcome_roots).0 == *header.outcome_root()
+                        })
+                        .collect(),
+                ),
+            ];
+            if height > genesis_height {
+                let mut matching = vec![];
+                if sha256_block_merkle_tree.root() == *header.block_merkle_root() {
+                    matching.push(HashAlgorithm::Sha256);
+                }
+                if algorithm != HashAlgorithm::Sha256
+                    && block_merkle_tree.root_with(algorithm) == *header.block_merkle_root()
+                {
+                    matching.push(algorithm);
+                }
+                roots.push(("block merkle root", algorithm, matching));
+            }
+            for (name, expected, matching) in roots {
+                let ok = matching == [expected];
+                if !ok {
+                    mismatches += 1;
+                }
+                println!(
+                    "{: >10} {} {}: expected {}, matches {:?}{}",
+                    height,
+                    block_hash,
+                    name,
+                    expected,
+                    matching.iter().map(HashAlgorithm::to_string).collect::<Vec<_>>(),
+                    if ok { "" } else { " MISMATCH" },
+                );
+            }
+        }
+        prev_block_hash = Some(block_hash);
+    }
+    println!("{} mismatching roots", mismatches);
+}
+
 pub(crate) fn replay_chain(
     start_height: BlockHeight,
     end_height: BlockHeight,
@@ -379,6 +487,7 @@ pub(crate) fn replay_chain(
                 .add_validator_proposals(BlockHeaderInfo::new(
                     &header,
                     chain_store.get_block_height(header.last_final_block()).unwrap(),
+                    runtime.get_epoch_protocol_version(header.epoch_id()).unwrap(),
                 ))
                 .unwrap()
                 .commit()
@@ -387,8 +496,13 @@ pub(crate) fn replay_chain(
     }
 }
 
-pub(crate) fn resulting_chunk_extra(result: &ApplyTransactionResult, gas_limit: Gas) -> ChunkExtra {
-    let (outcome_root, _) = ApplyTransactionResult::compute_outcomes_proof(&result.outcomes);
+pub(crate) fn resulting_chunk_extra(
+    result: &ApplyTransactionResult,
+    gas_limit: Gas,
+    protocol_version: ProtocolVersion,
+) -> ChunkExtra {
+    let (outcome_root, _) =
+        ApplyTransactionResult::compute_outcomes_proof(protocol_version, &result.outcomes);
     ChunkExtra::new(
         &result.new_root,
         outcome_root,
@@ -489,11 +603,17 @@ pub(crate) fn print_apply_block_result(
 ) {
     let height = block.header().height();
     let block_hash = block.header().hash();
+    let protocol_version =
+        runtime_adapter.get_epoch_protocol_version(block.header().epoch_id()).unwrap();
     println!(
         "apply chunk for shard {} at height {}, resulting chunk extra {:?}",
         shard_id,
         height,
-        resulting_chunk_extra(apply_result, block.chunks()[shard_id as usize].gas_limit())
+        resulting_chunk_extra(
+            apply_result,
+            block.chunks()[shard_id as usize].gas_limit(),
+            protocol_version
+        )
     );
     let shard_uid = runtime_adapter.shard_id_to_uid(shard_id, block.header().epoch_id()).unwrap();
     if block.chunks()[shard_id as usize].height_included() == height {
@@ -785,9 +905,12 @@ pub(crate) fn apply_chunk(
         near_config.genesis.config.genesis_height,
         !near_config.client_config.archive,
     );
-    let (apply_result, gas_limit) =
+    let (apply_result, gas_limit, protocol_version) =
         apply_chunk::apply_chunk(&runtime, &mut chain_store, chunk_hash, target_height, None)?;
-    println!("resulting chunk extra:\n{:?}", resulting_chunk_extra(&apply_result, gas_limit));
+    println!(
+        "resulting chunk extra:\n{:?}",
+        resulting_chunk_extra(&apply_result, gas_limit, protocol_version)
+    );
     Ok(())
 }
 
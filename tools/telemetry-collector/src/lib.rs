@@ -0,0 +1,135 @@
+//! Server side of the node telemetry.
+//!
+//! Nodes with `telemetry.endpoints` pointing at the collector POST their
+//! `TelemetryInfo` to it.  The collector checks the signatures of the
+//! validators' reports against the trusted keys, keeps the latest reports of
+//! every node in RocksDB and serves summaries of the fleet as JSON:
+//!
+//! * `POST /` accepts a report;
+//! * `GET /nodes` returns a `FleetSummary`;
+//! * `GET /nodes/{node_id}` returns the `NodeSummary` of a node along with
+//!   its stored reports.
+use std::net::SocketAddr;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use near_primitives::time::Clock;
+use tracing::{debug, error};
+
+pub use store::{ReportStore, StoredReport};
+pub use summary::{fleet_summary, FleetSummary, LatencySummary, NodeSummary};
+pub use verify::{verify_report, ReportError, TrustedKeys, VerifiedReport};
+
+mod store;
+mod summary;
+mod verify;
+
+const TARGET: &str = "telemetry-collector";
+
+pub struct Collector {
+    store: ReportStore,
+    trusted_keys: TrustedKeys,
+    require_signature: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CollectorError {
+    #[error(transparent)]
+    Rejected(#[from] ReportError),
+    #[error("storage error: {0}")]
+    Storage(#[from] anyhow::Error),
+}
+
+#[derive(serde::Serialize)]
+struct NodeDetails {
+    summary: NodeSummary,
+    reports: Vec<StoredReport>,
+}
+
+fn now_ms() -> u64 {
+    Clock::utc().timestamp_millis().try_into().unwrap_or(0)
+}
+
+impl Collector {
+    pub fn new(store: ReportStore, trusted_keys: TrustedKeys, require_signature: bool) -> Self {
+        Self { store, trusted_keys, require_signature }
+    }
+
+    /// Verifies the report and stores it as received at `received_at_ms`.
+    pub fn add_report(
+        &self,
+        report: serde_json::Value,
+        received_at_ms: u64,
+    ) -> Result<(), CollectorError> {
+        let VerifiedReport { info, signed } =
+            verify_report(report, &self.trusted_keys, self.require_signature)?;
+        self.store.insert(&StoredReport { received_at_ms, signed, info })?;
+        Ok(())
+    }
+
+    pub fn fleet_summary(&self, now_ms: u64) -> anyhow::Result<FleetSummary> {
+        Ok(fleet_summary(&self.store.all_reports()?, now_ms))
+    }
+}
+
+async fn report_handler(
+    report: web::Json<serde_json::Value>,
+    collector: web::Data<Collector>,
+) -> HttpResponse {
+    match collector.add_report(report.into_inner(), now_ms()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(CollectorError::Rejected(err)) => {
+            debug!(target: TARGET, %err, "Rejected telemetry report");
+            HttpResponse::BadRequest().body(err.to_string())
+        }
+        Err(CollectorError::Storage(err)) => {
+            error!(target: TARGET, ?err, "Failed to store telemetry report");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+async fn nodes_handler(collector: web::Data<Collector>) -> HttpResponse {
+    match collector.fleet_summary(now_ms()) {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(err) => {
+            error!(target: TARGET, ?err, "Failed to read telemetry reports");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+async fn node_handler(path: web::Path<String>, collector: web::Data<Collector>) -> HttpResponse {
+    let node_id = path.into_inner();
+    let details = collector.fleet_summary(now_ms()).and_then(|fleet| {
+        let summary = fleet.nodes.into_iter().find(|node| node.node_id == node_id);
+        Ok(match summary {
+            Some(summary) => {
+                Some(NodeDetails { summary, reports: collector.store.node_reports(&node_id)? })
+            }
+            None => None,
+        })
+    });
+    match details {
+        Ok(Some(details)) => HttpResponse::Ok().json(details),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            error!(target: TARGET, ?err, "Failed to read telemetry reports");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Serves the collector on `addr` until the server is stopped.
+pub async fn run_server(addr: SocketAddr, collector: Collector) -> std::io::Result<()> {
+    let collector = web::Data::new(collector);
+    HttpServer::new(move || {
+        App::new()
+            .app_data(collector.clone())
+            .service(web::resource("/").route(web::post().to(report_handler)))
+            .service(web::resource("/nodes").route(web::get().to(nodes_handler)))
+            .service(web::resource("/nodes/{node_id}").route(web::get().to(node_handler)))
+    })
+    .bind(addr)?
+    .run()
+    .await
+}
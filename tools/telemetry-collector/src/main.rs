@@ -0,0 +1,73 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use near_crypto::PublicKey;
+use near_primitives::types::AccountId;
+use near_telemetry_collector::{Collector, ReportStore, TrustedKeys};
+use tracing::info;
+
+/// Collects the telemetry of the nodes which have the collector among their
+/// `telemetry.endpoints`, and serves a JSON summary of their versions, heights
+/// and latencies on `/nodes`.
+#[derive(Parser)]
+struct Cli {
+    /// Address to listen on
+    #[clap(long, default_value = "0.0.0.0:3040")]
+    addr: SocketAddr,
+    /// Directory of the database with the reports
+    #[clap(long)]
+    db_path: PathBuf,
+    /// Trust the keys of the validators of this genesis file
+    #[clap(long)]
+    genesis: Option<PathBuf>,
+    /// Trust the key of a validator, given as `<account_id>=<public_key>`
+    #[clap(long = "trusted-key")]
+    trusted_keys: Vec<String>,
+    /// Reject the reports of the nodes which aren't validators
+    #[clap(long)]
+    require_signature: bool,
+    /// Number of the latest reports of each node to keep
+    #[clap(long, default_value = "1000")]
+    max_reports_per_node: usize,
+}
+
+fn parse_trusted_key(value: &str) -> anyhow::Result<(AccountId, PublicKey)> {
+    let (account_id, public_key) =
+        value.split_once('=').context("expected <account_id>=<public_key>")?;
+    Ok((account_id.parse()?, public_key.parse()?))
+}
+
+fn main() -> anyhow::Result<()> {
+    let env_filter = near_o11y::tracing_subscriber::EnvFilter::new("telemetry-collector=info");
+    let _subscriber = near_o11y::default_subscriber(env_filter, &Default::default()).global();
+    let cli = Cli::parse();
+
+    let mut trusted_keys = match &cli.genesis {
+        Some(genesis) => TrustedKeys::from_genesis_config(
+            &near_chain_configs::GenesisConfig::from_file(genesis)
+                .with_context(|| format!("failed to read {}", genesis.display()))?,
+        ),
+        None => TrustedKeys::default(),
+    };
+    for value in &cli.trusted_keys {
+        let (account_id, public_key) =
+            parse_trusted_key(value).with_context(|| format!("invalid --trusted-key {}", value))?;
+        trusted_keys.insert(account_id, public_key);
+    }
+    if trusted_keys.is_empty() {
+        tracing::warn!(
+            target: "telemetry-collector",
+            "No trusted keys are given, the reports of validators will be rejected"
+        );
+    }
+    info!(target: "telemetry-collector", "Trusting {} validator keys", trusted_keys.len());
+
+    let store = ReportStore::open(&cli.db_path, cli.max_reports_per_node)
+        .with_context(|| format!("failed to open {}", cli.db_path.display()))?;
+    let collector = Collector::new(store, trusted_keys, cli.require_signature);
+    info!(target: "telemetry-collector", "Listening on {}", cli.addr);
+    actix::System::new().block_on(near_telemetry_collector::run_server(cli.addr, collector))?;
+    Ok(())
+}
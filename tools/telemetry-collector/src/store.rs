@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use near_primitives::telemetry::TelemetryInfo;
+use rocksdb::{IteratorMode, ReadOptions, WriteBatch, DB};
+
+/// A report as it is kept by the collector.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct StoredReport {
+    /// Unix time in milliseconds at which the report was received.
+    pub received_at_ms: u64,
+    /// Whether the report was signed by a trusted validator key.
+    pub signed: bool,
+    pub info: TelemetryInfo,
+}
+
+/// Reports of every node, keyed by the node id followed by a zero byte and
+/// the big-endian time the report was received at, so the reports of a node
+/// are stored together in the order they were received.
+pub struct ReportStore {
+    db: DB,
+    max_reports_per_node: usize,
+}
+
+fn node_prefix(node_id: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(node_id.len() + 1);
+    prefix.extend_from_slice(node_id.as_bytes());
+    prefix.push(0);
+    prefix
+}
+
+fn prefix_read_options(prefix: Vec<u8>) -> ReadOptions {
+    let mut read_options = ReadOptions::default();
+    read_options.set_iterate_range(rocksdb::PrefixRange(prefix));
+    read_options
+}
+
+impl ReportStore {
+    /// Opens the database at `path`, keeping at most `max_reports_per_node`
+    /// latest reports of every node.
+    pub fn open(path: &Path, max_reports_per_node: usize) -> anyhow::Result<Self> {
+        anyhow::ensure!(max_reports_per_node > 0, "max_reports_per_node must be positive");
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+        Ok(Self { db: DB::open(&options, path)?, max_reports_per_node })
+    }
+
+    pub fn insert(&self, report: &StoredReport) -> anyhow::Result<()> {
+        let prefix = node_prefix(&report.info.chain.node_id);
+        let mut key = prefix.clone();
+        key.extend_from_slice(&report.received_at_ms.to_be_bytes());
+
+        let mut batch = WriteBatch::default();
+        batch.put(&key, serde_json::to_vec(report)?);
+        // Drop the oldest reports so that only `max_reports_per_node` are left
+        // after this one is added.
+        let keys = self
+            .db
+            .iterator_opt(IteratorMode::End, prefix_read_options(prefix))
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut kept = 1;
+        for old_key in keys {
+            if *old_key == *key {
+                continue;
+            }
+            if kept < self.max_reports_per_node {
+                kept += 1;
+            } else {
+                batch.delete(old_key);
+            }
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Returns the reports of the node, oldest first.
+    pub fn node_reports(&self, node_id: &str) -> anyhow::Result<Vec<StoredReport>> {
+        self.db
+            .iterator_opt(IteratorMode::Start, prefix_read_options(node_prefix(node_id)))
+            .map(|item| Ok(serde_json::from_slice(&item?.1)?))
+            .collect()
+    }
+
+    /// Returns the reports of all the nodes, oldest first.
+    pub fn all_reports(&self) -> anyhow::Result<BTreeMap<String, Vec<StoredReport>>> {
+        let mut reports = BTreeMap::<String, Vec<StoredReport>>::new();
+        for item in self.db.iterator(IteratorMode::Start) {
+            let report: StoredReport = serde_json::from_slice(&item?.1)?;
+            reports.entry(report.info.chain.node_id.clone()).or_default().push(report);
+        }
+        Ok(reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::tests::test_info;
+
+    fn heights(reports: &[StoredReport]) -> Vec<u64> {
+        reports.iter().map(|report| report.info.chain.latest_block_height).collect()
+    }
+
+    #[test]
+    fn test_keeps_latest_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ReportStore::open(dir.path(), 3).unwrap();
+        for (i, height) in [10, 11, 12, 13, 14].into_iter().enumerate() {
+            let info = test_info("node0", None, "1.0.0", height);
+            store.insert(&StoredReport { received_at_ms: i as u64, signed: false, info }).unwrap();
+        }
+        // A node id which is a prefix of the other one.
+        let info = test_info("node", None, "1.0.0", 100);
+        store.insert(&StoredReport { received_at_ms: 0, signed: false, info }).unwrap();
+
+        assert_eq!(heights(&store.node_reports("node0").unwrap()), vec![12, 13, 14]);
+        assert_eq!(heights(&store.node_reports("node").unwrap()), vec![100]);
+        let all = store.all_reports().unwrap();
+        assert_eq!(all.keys().collect::<Vec<_>>(), vec!["node", "node0"]);
+        assert_eq!(heights(&all["node0"]), vec![12, 13, 14]);
+    }
+}
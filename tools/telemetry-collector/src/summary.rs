@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use near_primitives::types::{AccountId, BlockHeight};
+
+use crate::store::StoredReport;
+
+/// Min, average and max of a series of durations in milliseconds.
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct LatencySummary {
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+    pub samples: usize,
+}
+
+impl LatencySummary {
+    fn from_samples(samples: &[u64]) -> Option<Self> {
+        Some(Self {
+            min_ms: *samples.iter().min()?,
+            avg_ms: samples.iter().sum::<u64>() / samples.len() as u64,
+            max_ms: *samples.iter().max()?,
+            samples: samples.len(),
+        })
+    }
+}
+
+/// State of a node according to its latest report.
+#[derive(serde::Serialize, Debug)]
+pub struct NodeSummary {
+    pub node_id: String,
+    pub account_id: Option<AccountId>,
+    pub is_validator: bool,
+    /// Whether the latest report was signed by a trusted validator key.
+    pub signed: bool,
+    pub version: String,
+    pub build: String,
+    pub status: String,
+    pub num_peers: usize,
+    pub latest_block_height: BlockHeight,
+    /// How many blocks the node is behind the highest node of the fleet.
+    pub blocks_behind: BlockHeight,
+    pub last_report_at_ms: u64,
+    /// Time since the latest report.
+    pub last_report_age_ms: u64,
+    /// Time it took the node to advance by a block, measured between the
+    /// consecutive reports kept by the collector.
+    pub block_latency: Option<LatencySummary>,
+    /// Time between the consecutive reports.
+    pub report_interval: Option<LatencySummary>,
+    pub reports: usize,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct FleetSummary {
+    pub max_block_height: BlockHeight,
+    /// Number of nodes running each version.
+    pub versions: BTreeMap<String, usize>,
+    pub nodes: Vec<NodeSummary>,
+}
+
+fn node_summary(
+    reports: &[StoredReport],
+    max_block_height: BlockHeight,
+    now_ms: u64,
+) -> Option<NodeSummary> {
+    let latest = reports.last()?;
+    let mut block_latencies = vec![];
+    let mut report_intervals = vec![];
+    for pair in reports.windows(2) {
+        let elapsed_ms = pair[1].received_at_ms.saturating_sub(pair[0].received_at_ms);
+        report_intervals.push(elapsed_ms);
+        let blocks = pair[1]
+            .info
+            .chain
+            .latest_block_height
+            .saturating_sub(pair[0].info.chain.latest_block_height);
+        if blocks > 0 {
+            block_latencies.push(elapsed_ms / blocks);
+        }
+    }
+    let chain = &latest.info.chain;
+    Some(NodeSummary {
+        node_id: chain.node_id.clone(),
+        account_id: chain.account_id.clone(),
+        is_validator: chain.is_validator,
+        signed: latest.signed,
+        version: latest.info.agent.version.clone(),
+        build: latest.info.agent.build.clone(),
+        status: chain.status.clone(),
+        num_peers: chain.num_peers,
+        latest_block_height: chain.latest_block_height,
+        blocks_behind: max_block_height.saturating_sub(chain.latest_block_height),
+        last_report_at_ms: latest.received_at_ms,
+        last_report_age_ms: now_ms.saturating_sub(latest.received_at_ms),
+        block_latency: LatencySummary::from_samples(&block_latencies),
+        report_interval: LatencySummary::from_samples(&report_intervals),
+        reports: reports.len(),
+    })
+}
+
+/// Summarizes the reports of every node, oldest first, as of `now_ms`.
+pub fn fleet_summary(reports: &BTreeMap<String, Vec<StoredReport>>, now_ms: u64) -> FleetSummary {
+    let max_block_height = reports
+        .values()
+        .filter_map(|reports| Some(reports.last()?.info.chain.latest_block_height))
+        .max()
+        .unwrap_or(0);
+    let nodes = reports
+        .values()
+        .filter_map(|reports| node_summary(reports, max_block_height, now_ms))
+        .collect::<Vec<_>>();
+    let mut versions = BTreeMap::new();
+    for node in &nodes {
+        *versions.entry(node.version.clone()).or_default() += 1;
+    }
+    FleetSummary { max_block_height, versions, nodes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::tests::test_info;
+
+    fn report(node_id: &str, version: &str, received_at_ms: u64, height: u64) -> StoredReport {
+        StoredReport {
+            received_at_ms,
+            signed: false,
+            info: test_info(node_id, None, version, height),
+        }
+    }
+
+    #[test]
+    fn test_fleet_summary() {
+        let mut reports = BTreeMap::new();
+        reports.insert(
+            "node0".to_string(),
+            vec![
+                report("node0", "1.0.0", 0, 10),
+                report("node0", "1.0.0", 10_000, 20),
+                // Stuck for a while.
+                report("node0", "1.0.0", 20_000, 20),
+                report("node0", "1.0.0", 30_000, 40),
+            ],
+        );
+        reports.insert("node1".to_string(), vec![report("node1", "1.1.0", 25_000, 35)]);
+        reports.insert(
+            "node2".to_string(),
+            vec![report("node2", "1.0.0", 0, 5), report("node2", "1.1.0", 10_000, 10)],
+        );
+
+        let summary = fleet_summary(&reports, 40_000);
+        assert_eq!(summary.max_block_height, 40);
+        assert_eq!(
+            summary.versions,
+            [("1.0.0".to_string(), 1), ("1.1.0".to_string(), 2)].into_iter().collect()
+        );
+
+        let node0 = &summary.nodes[0];
+        assert_eq!(node0.blocks_behind, 0);
+        assert_eq!(node0.last_report_age_ms, 10_000);
+        assert_eq!(
+            node0.block_latency,
+            Some(LatencySummary { min_ms: 500, avg_ms: 750, max_ms: 1000, samples: 2 })
+        );
+        assert_eq!(
+            node0.report_interval,
+            Some(LatencySummary { min_ms: 10_000, avg_ms: 10_000, max_ms: 10_000, samples: 3 })
+        );
+
+        let node1 = &summary.nodes[1];
+        assert_eq!(node1.blocks_behind, 5);
+        assert_eq!(node1.block_latency, None);
+
+        let node2 = &summary.nodes[2];
+        assert_eq!(node2.version, "1.1.0");
+        assert_eq!(node2.blocks_behind, 30);
+        assert_eq!(node2.block_latency.as_ref().unwrap().avg_ms, 2000);
+    }
+}
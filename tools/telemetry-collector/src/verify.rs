@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use near_crypto::{PublicKey, Signature};
+use near_primitives::telemetry::TelemetryInfo;
+use near_primitives::types::AccountId;
+
+/// Why a telemetry report was rejected.
+#[derive(thiserror::Error, Debug)]
+pub enum ReportError {
+    #[error("report is not a JSON object")]
+    NotAnObject,
+    #[error("malformed report: {0}")]
+    Malformed(#[from] serde_json::Error),
+    #[error("malformed signature: {0}")]
+    MalformedSignature(String),
+    #[error("report of {0} is not signed")]
+    Unsigned(AccountId),
+    #[error("signed report has no account_id")]
+    MissingAccountId,
+    #[error("{0} is not a trusted validator")]
+    UntrustedAccount(AccountId),
+    #[error("invalid signature of {0}")]
+    InvalidSignature(AccountId),
+    #[error("only signed reports are accepted")]
+    SignatureRequired,
+}
+
+/// Keys of the validators whose signed reports are accepted.
+#[derive(Default, Clone, Debug)]
+pub struct TrustedKeys {
+    keys: HashMap<AccountId, Vec<PublicKey>>,
+}
+
+impl TrustedKeys {
+    pub fn insert(&mut self, account_id: AccountId, public_key: PublicKey) {
+        let keys = self.keys.entry(account_id).or_default();
+        if !keys.contains(&public_key) {
+            keys.push(public_key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Trusts the keys of the genesis validators.
+    pub fn from_genesis_config(config: &near_chain_configs::GenesisConfig) -> Self {
+        let mut keys = Self::default();
+        for validator in &config.validators {
+            keys.insert(validator.account_id.clone(), validator.public_key.clone());
+        }
+        keys
+    }
+}
+
+/// A report which passed the checks of `verify_report`.
+#[derive(Debug)]
+pub struct VerifiedReport {
+    pub info: TelemetryInfo,
+    /// Whether the report was signed by a trusted validator key. Reports of
+    /// the nodes which aren't validators can't be signed.
+    pub signed: bool,
+}
+
+/// Checks the report as produced by `ValidatorSigner::sign_telemetry`: the
+/// `signature` field signs the JSON of the rest of the report.
+///
+/// A report which claims an `account_id` must be signed by one of the trusted
+/// keys of that account, since the node only sets it when it has a validator
+/// key. Reports without an account are accepted unsigned unless
+/// `require_signature` is set.
+pub fn verify_report(
+    mut value: serde_json::Value,
+    trusted_keys: &TrustedKeys,
+    require_signature: bool,
+) -> Result<VerifiedReport, ReportError> {
+    let signature = match value.as_object_mut().ok_or(ReportError::NotAnObject)?.remove("signature")
+    {
+        Some(signature) => {
+            let signature = signature
+                .as_str()
+                .ok_or_else(|| ReportError::MalformedSignature("not a string".to_string()))?;
+            Some(
+                signature
+                    .parse::<Signature>()
+                    .map_err(|err| ReportError::MalformedSignature(err.to_string()))?,
+            )
+        }
+        None => None,
+    };
+    // The node signs the report before adding the signature, so the content
+    // is what is left once the signature is removed.
+    let content = serde_json::to_string(&value)?;
+    let info: TelemetryInfo = serde_json::from_value(value)?;
+    match (signature, &info.chain.account_id) {
+        (Some(signature), Some(account_id)) => {
+            let keys = trusted_keys
+                .keys
+                .get(account_id)
+                .ok_or_else(|| ReportError::UntrustedAccount(account_id.clone()))?;
+            if !keys.iter().any(|key| signature.verify(content.as_bytes(), key)) {
+                return Err(ReportError::InvalidSignature(account_id.clone()));
+            }
+            Ok(VerifiedReport { info, signed: true })
+        }
+        (Some(_), None) => Err(ReportError::MissingAccountId),
+        (None, Some(account_id)) => Err(ReportError::Unsigned(account_id.clone())),
+        (None, None) if require_signature => Err(ReportError::SignatureRequired),
+        (None, None) => Ok(VerifiedReport { info, signed: false }),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::telemetry::{TelemetryAgentInfo, TelemetryChainInfo, TelemetrySystemInfo};
+    use near_primitives::types::BlockHeight;
+    use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
+
+    pub(crate) fn test_info(
+        node_id: &str,
+        account_id: Option<AccountId>,
+        version: &str,
+        height: BlockHeight,
+    ) -> TelemetryInfo {
+        TelemetryInfo {
+            agent: TelemetryAgentInfo {
+                name: "near-rs".to_string(),
+                version: version.to_string(),
+                build: "test".to_string(),
+            },
+            system: TelemetrySystemInfo {
+                bandwidth_download: 10,
+                bandwidth_upload: 20,
+                cpu_usage: 0.3,
+                memory_usage: 1000,
+                boot_time_seconds: 1,
+            },
+            chain: TelemetryChainInfo {
+                node_id: node_id.to_string(),
+                is_validator: account_id.is_some(),
+                account_id,
+                status: "NoSync".to_string(),
+                latest_block_hash: CryptoHash::default(),
+                latest_block_height: height,
+                num_peers: 3,
+                block_production_tracking_delay: 0.1,
+                min_block_production_delay: 0.6,
+                max_block_production_delay: 2.0,
+                max_block_wait_delay: 6.0,
+            },
+            extra_info: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_verify_report() {
+        let account_id: AccountId = "test0".parse().unwrap();
+        let signer =
+            InMemoryValidatorSigner::from_seed(account_id.clone(), KeyType::ED25519, "test0");
+        let mut trusted_keys = TrustedKeys::default();
+        trusted_keys.insert(account_id.clone(), signer.public_key());
+        let info = test_info("node0", Some(account_id.clone()), "1.0.0", 10);
+        let report = signer.sign_telemetry(&info);
+
+        let verified = verify_report(report.clone(), &trusted_keys, true).unwrap();
+        assert!(verified.signed);
+        assert_eq!(verified.info.chain.latest_block_height, 10);
+
+        let mut tampered = report.clone();
+        tampered["chain"]["latest_block_height"] = 11.into();
+        assert_matches::assert_matches!(
+            verify_report(tampered, &trusted_keys, false),
+            Err(ReportError::InvalidSignature(_))
+        );
+
+        let mut unsigned = report.clone();
+        unsigned.as_object_mut().unwrap().remove("signature");
+        assert_matches::assert_matches!(
+            verify_report(unsigned, &trusted_keys, false),
+            Err(ReportError::Unsigned(_))
+        );
+
+        assert_matches::assert_matches!(
+            verify_report(report, &TrustedKeys::default(), false),
+            Err(ReportError::UntrustedAccount(_))
+        );
+
+        let other = InMemoryValidatorSigner::from_seed(account_id, KeyType::ED25519, "other");
+        assert_matches::assert_matches!(
+            verify_report(other.sign_telemetry(&info), &trusted_keys, false),
+            Err(ReportError::InvalidSignature(_))
+        );
+    }
+
+    #[test]
+    fn test_unsigned_report() {
+        let report = serde_json::to_value(&test_info("node1", None, "1.0.0", 10)).unwrap();
+        let verified = verify_report(report.clone(), &TrustedKeys::default(), false).unwrap();
+        assert!(!verified.signed);
+        assert_matches::assert_matches!(
+            verify_report(report, &TrustedKeys::default(), true),
+            Err(ReportError::SignatureRequired)
+        );
+    }
+}